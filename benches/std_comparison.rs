@@ -0,0 +1,202 @@
+//! Compares the common operations of `vec::Vec` with `std::vec::Vec`, run with `cargo +nightly bench`. Each group
+//! benchmarks both vectors on the same input sizes, so changes to the growth policy or the copy specializations
+//! show up side by side.
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const SIZES: [usize; 3] = [16, 1_000, 100_000];
+
+fn push(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push");
+
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::new("std", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut vec = std::vec::Vec::new();
+                for i in 0..size {
+                    vec.push(black_box(i));
+                }
+                vec
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("vec", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut vec = vec::Vec::new();
+                for i in 0..size {
+                    vec.push(black_box(i));
+                }
+                vec
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn with_capacity_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("with_capacity_fill");
+
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::new("std", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut vec = std::vec::Vec::with_capacity(size);
+                for i in 0..size {
+                    vec.push(black_box(i));
+                }
+                vec
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("vec", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut vec = vec::Vec::with_capacity(size);
+                for i in 0..size {
+                    vec.push(black_box(i));
+                }
+                vec
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// Quadratic, so only the smaller sizes
+fn insert_front(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert_front");
+
+    for size in [16, 1_000] {
+        group.bench_with_input(BenchmarkId::new("std", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut vec = std::vec::Vec::new();
+                for i in 0..size {
+                    vec.insert(0, black_box(i));
+                }
+                vec
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("vec", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut vec = vec::Vec::new();
+                for i in 0..size {
+                    vec.insert(0, black_box(i));
+                }
+                vec
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn drain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("drain");
+
+    for size in SIZES {
+        let source: std::vec::Vec<String> = (0..size).map(|i| i.to_string()).collect();
+
+        group.bench_with_input(BenchmarkId::new("std", size), &source, |b, source| {
+            b.iter_batched_ref(
+                || source.clone(),
+                |vec| vec.drain(..).map(|s| s.len()).sum::<usize>(),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("vec", size), &source, |b, source| {
+            b.iter_batched_ref(
+                || {
+                    let mut vec = vec::Vec::new();
+                    vec.extend(source.iter().cloned());
+                    vec
+                },
+                |vec| vec.drain().map(|s| s.len()).sum::<usize>(),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate");
+
+    for size in SIZES {
+        let std: std::vec::Vec<u64> = (0..size as u64).collect();
+        let mut vec = vec::Vec::new();
+        vec.extend(0..size as u64);
+
+        group.bench_with_input(BenchmarkId::new("std", size), &std, |b, std| {
+            b.iter(|| {
+                black_box(std)
+                    .iter()
+                    .fold(0u64, |acc, &x| acc.wrapping_add(x))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("vec", size), &vec, |b, vec| {
+            b.iter(|| {
+                black_box(vec)
+                    .iter()
+                    .fold(0u64, |acc, &x| acc.wrapping_add(x))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn extend_from_slice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extend_from_slice");
+
+    for size in SIZES {
+        let source: std::vec::Vec<u32> = (0..size as u32).collect();
+
+        group.bench_with_input(BenchmarkId::new("std", size), &source, |b, source| {
+            b.iter(|| {
+                let mut vec = std::vec::Vec::new();
+                vec.extend_from_slice(black_box(source));
+                vec
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("vec", size), &source, |b, source| {
+            b.iter(|| {
+                let mut vec: vec::Vec<u32> = vec::Vec::new();
+                vec.extend(black_box(source).iter());
+                vec
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone");
+
+    for size in SIZES {
+        let std: std::vec::Vec<String> = (0..size).map(|i| i.to_string()).collect();
+        let mut vec = vec::Vec::new();
+        vec.extend(std.iter().cloned());
+
+        group.bench_with_input(BenchmarkId::new("std", size), &std, |b, std| {
+            b.iter(|| black_box(std).clone())
+        });
+        group.bench_with_input(BenchmarkId::new("vec", size), &vec, |b, vec| {
+            b.iter(|| black_box(vec).clone())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    push,
+    with_capacity_fill,
+    insert_front,
+    drain,
+    iterate,
+    extend_from_slice,
+    clone
+);
+criterion_main!(benches);
@@ -0,0 +1,449 @@
+//! Derive macros of the `vec` crate, enabled by its `derive` feature.
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Implements `vec::Soa` for a struct with named fields, so it can be stored column by column in a `vec::SoaVec`.
+///
+/// Next to the struct, generates `<Name>Ref`, `<Name>RefMut`, `<Name>Slices` and `<Name>SlicesMut`, holding a
+/// reference (or a slice) per field, with the visibility of the struct and of each field.
+#[proc_macro_derive(Soa)]
+pub fn derive_soa(input: TokenStream) -> TokenStream {
+    match parse_struct(input) {
+        Ok(def) => expand(&def).parse().unwrap(),
+        Err(message) => format!("compile_error!({:?});", message).parse().unwrap(),
+    }
+}
+
+/// Implements `vec::Idx` for a tuple struct wrapping a single unsigned integer, such as `struct NodeId(u32);`, so it
+/// can index a `vec::IndexVec`.
+///
+/// Converting an index that doesn't fit the integer type panics.
+#[proc_macro_derive(Idx)]
+pub fn derive_idx(input: TokenStream) -> TokenStream {
+    match parse_newtype(input) {
+        Ok((name, ty)) => format!(
+            "
+            impl ::vec::Idx for {name} {{
+                fn new(index: usize) -> Self {{
+                    match <{ty} as ::core::convert::TryFrom<usize>>::try_from(index) {{
+                        Ok(index) => {name}(index),
+                        Err(_) => panic!(\"index {{}} doesn't fit in `{name}`\", index),
+                    }}
+                }}
+
+                fn index(self) -> usize {{
+                    match <usize as ::core::convert::TryFrom<{ty}>>::try_from(self.0) {{
+                        Ok(index) => index,
+                        Err(_) => panic!(\"`{name}` doesn't fit in usize\"),
+                    }}
+                }}
+            }}
+            ",
+            name = name,
+            ty = ty,
+        )
+        .parse()
+        .unwrap(),
+        Err(message) => format!("compile_error!({:?});", message).parse().unwrap(),
+    }
+}
+
+/// Implements `vec::EnumIndex` for an enum without fields, numbering its variants in declaration order (explicit
+/// discriminants are ignored), so it can key a `vec::EnumIndexedVec`.
+#[proc_macro_derive(EnumIndex)]
+pub fn derive_enum_index(input: TokenStream) -> TokenStream {
+    match parse_enum(input) {
+        Ok((name, variants)) => {
+            let to_index: String = variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| format!("{}::{} => {},", name, variant, i))
+                .collect();
+            let from_index: String = variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| format!("{} => {}::{},", i, name, variant))
+                .collect();
+
+            format!(
+                "
+                impl ::vec::EnumIndex for {name} {{
+                    const COUNT: usize = {count};
+
+                    fn index(self) -> usize {{
+                        match self {{ {to_index} }}
+                    }}
+
+                    fn from_index(index: usize) -> Self {{
+                        match index {{
+                            {from_index}
+                            _ => panic!(\"index {{}} out of range for `{name}`\", index),
+                        }}
+                    }}
+                }}
+                ",
+                name = name,
+                count = variants.len(),
+                to_index = to_index,
+                from_index = from_index,
+            )
+            .parse()
+            .unwrap()
+        }
+        Err(message) => format!("compile_error!({:?});", message).parse().unwrap(),
+    }
+}
+
+// Reads the name and variant names of `enum Name { A, B = 2, ... }`, skipping attributes
+fn parse_enum(input: TokenStream) -> Result<(String, Vec<String>), String> {
+    let mut tokens = input.into_iter().peekable();
+
+    skip_attributes(&mut tokens);
+    parse_visibility(&mut tokens);
+
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "enum" => {}
+        _ => return Err("`EnumIndex` can only be derived for enums".into()),
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("expected an enum name".into()),
+    };
+
+    let body = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("`EnumIndex` can't be derived for generic enums".into())
+        }
+        _ => return Err("expected the variants of the enum".into()),
+    };
+
+    let mut variants = Vec::new();
+    let mut tokens = body.into_iter().peekable();
+
+    while tokens.peek().is_some() {
+        skip_attributes(&mut tokens);
+
+        let variant = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident.to_string(),
+            _ => return Err("expected a variant name".into()),
+        };
+
+        match tokens.next() {
+            None => {}
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+            // The discriminant runs up to the next comma, brackets are groups so it can't hold one at this level
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                tokens
+                    .by_ref()
+                    .find(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ','));
+            }
+            Some(_) => {
+                return Err(format!(
+                    "`EnumIndex` can only be derived for enums without fields, `{}` has some",
+                    variant
+                ))
+            }
+        }
+
+        variants.push(variant);
+    }
+
+    Ok((name, variants))
+}
+
+// Reads the name and field type of `struct Name(Type);`, skipping attributes
+fn parse_newtype(input: TokenStream) -> Result<(String, String), String> {
+    let mut tokens = input.into_iter().peekable();
+
+    skip_attributes(&mut tokens);
+    parse_visibility(&mut tokens);
+
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {}
+        _ => return Err("`Idx` can only be derived for structs".into()),
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("expected a struct name".into()),
+    };
+
+    let body = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            group.stream()
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("`Idx` can't be derived for generic structs".into())
+        }
+        _ => return Err("`Idx` can only be derived for tuple structs with a single field".into()),
+    };
+
+    let mut tokens = body.into_iter().peekable();
+    skip_attributes(&mut tokens);
+    parse_visibility(&mut tokens);
+
+    // A trailing comma is allowed, a second field isn't
+    let ty: Vec<TokenTree> = tokens.collect();
+    let ty = match ty.split_last() {
+        Some((TokenTree::Punct(punct), rest)) if punct.as_char() == ',' => rest,
+        _ => &ty[..],
+    };
+
+    if ty.is_empty()
+        || ty
+            .iter()
+            .any(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ','))
+    {
+        return Err("`Idx` can only be derived for tuple structs with a single field".into());
+    }
+
+    Ok((
+        name,
+        ty.iter().cloned().collect::<TokenStream>().to_string(),
+    ))
+}
+
+struct Struct {
+    vis: String,
+    name: String,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    vis: String,
+    name: String,
+    ty: String,
+}
+
+// Reads the visibility, name and fields of `struct Name { field: Type, ... }`, skipping attributes
+fn parse_struct(input: TokenStream) -> Result<Struct, String> {
+    let mut tokens = input.into_iter().peekable();
+
+    skip_attributes(&mut tokens);
+    let vis = parse_visibility(&mut tokens);
+
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {}
+        _ => return Err("`Soa` can only be derived for structs".into()),
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("expected a struct name".into()),
+    };
+
+    let body = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("`Soa` can't be derived for generic structs".into())
+        }
+        _ => return Err("`Soa` can only be derived for structs with named fields".into()),
+    };
+
+    let mut fields = Vec::new();
+    let mut tokens = body.into_iter().peekable();
+
+    while tokens.peek().is_some() {
+        skip_attributes(&mut tokens);
+        let vis = parse_visibility(&mut tokens);
+
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident.to_string(),
+            _ => return Err("expected a field name".into()),
+        };
+
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+            _ => return Err("expected `:` after the field name".into()),
+        }
+
+        // The type runs up to the next comma outside of angle brackets
+        let mut ty = TokenStream::new();
+        let mut depth = 0usize;
+        let mut prev = ' ';
+
+        for token in tokens.by_ref() {
+            if let TokenTree::Punct(punct) = &token {
+                match punct.as_char() {
+                    ',' if depth == 0 => break,
+                    '<' => depth += 1,
+                    // `->` of a function type doesn't close a bracket
+                    '>' if prev != '-' => depth -= 1,
+                    _ => {}
+                }
+                prev = punct.as_char();
+            } else {
+                prev = ' ';
+            }
+
+            ty.extend(Some(token));
+        }
+
+        fields.push(Field {
+            vis,
+            name,
+            ty: ty.to_string(),
+        });
+    }
+
+    if fields.is_empty() {
+        return Err("`Soa` needs at least one field".into());
+    }
+
+    Ok(Struct { vis, name, fields })
+}
+
+fn skip_attributes(tokens: &mut std::iter::Peekable<proc_macro::token_stream::IntoIter>) {
+    while matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+        tokens.next();
+        tokens.next();
+    }
+}
+
+// `pub`, `pub(crate)`, `pub(in path)`, or nothing
+fn parse_visibility(
+    tokens: &mut std::iter::Peekable<proc_macro::token_stream::IntoIter>,
+) -> String {
+    match tokens.peek() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {}
+        _ => return String::new(),
+    }
+
+    let mut vis = tokens.next().unwrap().to_string();
+
+    if let Some(TokenTree::Group(group)) = tokens.peek() {
+        if group.delimiter() == Delimiter::Parenthesis {
+            vis.push_str(&tokens.next().unwrap().to_string());
+        }
+    }
+
+    vis
+}
+
+fn expand(def: &Struct) -> String {
+    let Struct { vis, name, fields } = def;
+
+    // Writes one line per field from a template where `{f}` is the field name, `{t}` its type and `{v}` its
+    // visibility
+    let each = |template: &str| -> String {
+        fields
+            .iter()
+            .map(|field| {
+                template
+                    .replace("{f}", &field.name)
+                    .replace("{t}", &field.ty)
+                    .replace("{v}", &field.vis)
+            })
+            .collect()
+    };
+
+    let columns = each("{f}: ::vec::SoaColumn<{t}>,");
+    let refs = each("{v} {f}: &'a {t},");
+    let refs_mut = each("{v} {f}: &'a mut {t},");
+    let slices = each("{v} {f}: &'a [{t}],");
+    let slices_mut = each("{v} {f}: &'a mut [{t}],");
+
+    let new_columns = each("{f}: ::vec::SoaColumn::new(),");
+    let capacity = each("let cap = cap.min(columns.{f}.capacity());");
+    let reserve = each("columns.{f}.reserve(len, additional);");
+    let write = each("columns.{f}.write(index, value.{f});");
+    let read = each("{f}: columns.{f}.read(index),");
+    let copy = each("columns.{f}.copy(src, dst);");
+    let get = each("{f}: &*columns.{f}.ptr().add(index),");
+    let get_mut = each("{f}: &mut *columns.{f}.ptr().add(index),");
+    let get_slices = each("{f}: columns.{f}.slice(len),");
+    let get_slices_mut = each("{f}: columns.{f}.slice_mut(len),");
+    let drop_in_place = each("columns.{f}.drop_in_place(len);");
+
+    format!(
+        "
+        #[doc(hidden)]
+        {vis} struct {name}Columns {{ {columns} }}
+
+        #[doc = \"References to the fields of a `{name}` stored in a `SoaVec`.\"]
+        {vis} struct {name}Ref<'a> {{ {refs} }}
+
+        #[doc = \"Mutable references to the fields of a `{name}` stored in a `SoaVec`.\"]
+        {vis} struct {name}RefMut<'a> {{ {refs_mut} }}
+
+        #[doc = \"Columns of a `SoaVec<{name}>`, one slice per field.\"]
+        {vis} struct {name}Slices<'a> {{ {slices} }}
+
+        #[doc = \"Columns of a `SoaVec<{name}>`, one mutable slice per field.\"]
+        {vis} struct {name}SlicesMut<'a> {{ {slices_mut} }}
+
+        unsafe impl ::vec::Soa for {name} {{
+            type Columns = {name}Columns;
+            type Ref<'a> = {name}Ref<'a>;
+            type RefMut<'a> = {name}RefMut<'a>;
+            type Slices<'a> = {name}Slices<'a>;
+            type SlicesMut<'a> = {name}SlicesMut<'a>;
+
+            fn columns() -> {name}Columns {{
+                {name}Columns {{ {new_columns} }}
+            }}
+
+            fn capacity(columns: &{name}Columns) -> usize {{
+                let cap = usize::MAX;
+                {capacity}
+                cap
+            }}
+
+            fn reserve(columns: &mut {name}Columns, len: usize, additional: usize) {{
+                {reserve}
+            }}
+
+            unsafe fn write(columns: &mut {name}Columns, index: usize, value: Self) {{
+                unsafe {{ {write} }}
+            }}
+
+            unsafe fn read(columns: &{name}Columns, index: usize) -> Self {{
+                unsafe {{ {name} {{ {read} }} }}
+            }}
+
+            unsafe fn copy(columns: &mut {name}Columns, src: usize, dst: usize) {{
+                unsafe {{ {copy} }}
+            }}
+
+            unsafe fn get(columns: &{name}Columns, index: usize) -> {name}Ref<'_> {{
+                unsafe {{ {name}Ref {{ {get} }} }}
+            }}
+
+            unsafe fn get_mut(columns: &{name}Columns, index: usize) -> {name}RefMut<'_> {{
+                unsafe {{ {name}RefMut {{ {get_mut} }} }}
+            }}
+
+            unsafe fn slices(columns: &{name}Columns, len: usize) -> {name}Slices<'_> {{
+                unsafe {{ {name}Slices {{ {get_slices} }} }}
+            }}
+
+            unsafe fn slices_mut(columns: &mut {name}Columns, len: usize) -> {name}SlicesMut<'_> {{
+                unsafe {{ {name}SlicesMut {{ {get_slices_mut} }} }}
+            }}
+
+            unsafe fn drop_in_place(columns: &mut {name}Columns, len: usize) {{
+                unsafe {{ {drop_in_place} }}
+            }}
+        }}
+        ",
+        vis = vis,
+        name = name,
+        columns = columns,
+        refs = refs,
+        refs_mut = refs_mut,
+        slices = slices,
+        slices_mut = slices_mut,
+        new_columns = new_columns,
+        capacity = capacity,
+        reserve = reserve,
+        write = write,
+        read = read,
+        copy = copy,
+        get = get,
+        get_mut = get_mut,
+        get_slices = get_slices,
+        get_slices_mut = get_slices_mut,
+        drop_in_place = drop_in_place,
+    )
+}
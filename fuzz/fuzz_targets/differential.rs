@@ -0,0 +1,172 @@
+//! Interprets the input as a sequence of operations applied both to `vec::Vec` and to `std::vec::Vec`, and checks
+//! after each one that the two hold the same elements and that no element was leaked or dropped twice.
+//!
+//! Run with `cargo +nightly fuzz run differential` from the repository root.
+#![no_main]
+
+use std::{cell::Cell, rc::Rc};
+
+use libfuzzer_sys::fuzz_target;
+
+// An element that counts how many of its kind are alive, one counter per vector
+#[derive(Debug)]
+struct Counted {
+    value: u8,
+    live: Rc<Cell<isize>>,
+}
+
+impl Counted {
+    fn new(value: u8, live: &Rc<Cell<isize>>) -> Self {
+        live.set(live.get() + 1);
+        Self {
+            value,
+            live: live.clone(),
+        }
+    }
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        Self::new(self.value, &self.live)
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.live.set(self.live.get() - 1);
+    }
+}
+
+impl PartialEq for Counted {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+// Both vectors, with the live element counters of each
+struct Pair {
+    ours: vec::Vec<Counted>,
+    std: Vec<Counted>,
+    ours_live: Rc<Cell<isize>>,
+    std_live: Rc<Cell<isize>>,
+}
+
+impl Pair {
+    fn check(&self) {
+        assert_eq!(&*self.ours, &*self.std);
+        assert_eq!(self.ours_live.get(), self.std_live.get());
+        assert_eq!(self.ours_live.get(), self.ours.len() as isize);
+        assert!(self.ours.capacity() >= self.ours.len());
+    }
+}
+
+// Reads the operations out of the input, one opcode byte followed by its operands
+fn run(mut data: &[u8]) {
+    let ours_live = Rc::new(Cell::new(0));
+    let std_live = Rc::new(Cell::new(0));
+    let mut pair = Pair {
+        ours: vec::Vec::new(),
+        std: Vec::new(),
+        ours_live: ours_live.clone(),
+        std_live: std_live.clone(),
+    };
+
+    let mut next = || {
+        let (&byte, rest) = data.split_first()?;
+        data = rest;
+        Some(byte)
+    };
+
+    while let Some(op) = next() {
+        let arg = next().unwrap_or(0);
+        let len = pair.std.len();
+
+        match op % 11 {
+            0 => {
+                pair.ours.push(Counted::new(arg, &ours_live));
+                pair.std.push(Counted::new(arg, &std_live));
+            }
+            1 => assert_eq!(pair.ours.pop(), pair.std.pop()),
+            2 => {
+                let index = usize::from(next().unwrap_or(0)) % (len + 1);
+                pair.ours.insert(index, Counted::new(arg, &ours_live));
+                pair.std.insert(index, Counted::new(arg, &std_live));
+            }
+            3 if len > 0 => {
+                let index = usize::from(arg) % len;
+                assert_eq!(pair.ours.remove(index), pair.std.remove(index));
+            }
+            // Drain everything but only consume a prefix, the rest is dropped with the iterator
+            4 => {
+                let take = usize::from(arg);
+                let ours: Vec<Counted> = pair.ours.drain().take(take).collect();
+                let std: Vec<Counted> = pair.std.drain(..).take(take).collect();
+                assert_eq!(ours, std);
+            }
+            5 => {
+                assert!(pair.ours.iter().eq(pair.std.iter()));
+                assert!(pair.ours.iter().rev().eq(pair.std.iter().rev()));
+            }
+            6 => {
+                let ours = pair.ours.clone();
+                let std = pair.std.clone();
+                assert_eq!(&*ours, &*std);
+                assert_eq!(ours_live.get(), std_live.get());
+            }
+            7 => {
+                let len = usize::from(arg);
+                pair.ours.truncate(len);
+                pair.std.truncate(len);
+            }
+            8 => {
+                let count = usize::from(arg % 16);
+                pair.ours
+                    .extend((0..count).map(|i| Counted::new(i as u8, &ours_live)));
+                pair.std
+                    .extend((0..count).map(|i| Counted::new(i as u8, &std_live)));
+            }
+            // Move everything out, consuming a few elements from both ends, then rebuild from the rest
+            9 => {
+                let take = usize::from(arg % 8);
+                let mut ours = std::mem::replace(&mut pair.ours, vec::Vec::new()).into_iter();
+                let mut std = std::mem::take(&mut pair.std).into_iter();
+
+                for _ in 0..take {
+                    assert_eq!(ours.next(), std.next());
+                    assert_eq!(ours.next_back(), std.next_back());
+                }
+
+                pair.ours.extend(ours);
+                pair.std.extend(std);
+            }
+            // Drain a range, which the crate's `drain` doesn't take: the bulk removal of the range's indices either
+            // returns the drained elements or drops them in place
+            10 => {
+                let (a, b) = (
+                    usize::from(arg) % (len + 1),
+                    usize::from(next().unwrap_or(0)) % (len + 1),
+                );
+                let (start, end) = (a.min(b), a.max(b));
+                let indices: Vec<usize> = (start..end).collect();
+
+                if arg % 2 == 0 {
+                    let ours = pair.ours.extract_indices(&indices);
+                    let std: Vec<Counted> = pair.std.drain(start..end).collect();
+                    assert_eq!(&*ours, &*std);
+                } else {
+                    pair.ours.remove_indices(&indices);
+                    pair.std.drain(start..end);
+                }
+            }
+            _ => {}
+        }
+
+        pair.check();
+    }
+
+    drop(pair);
+    assert_eq!(ours_live.get(), 0);
+    assert_eq!(std_live.get(), 0);
+}
+
+fuzz_target!(|data: &[u8]| run(data));
@@ -0,0 +1,133 @@
+use std::{
+    alloc::{AllocError, Allocator, Global, Layout},
+    ptr::NonNull,
+};
+
+use crate::Vec;
+
+/// Vector whose buffer is always aligned to at least `ALIGN` bytes (e.g. 64 for SIMD, 4096 for `O_DIRECT`).
+/// # Example
+/// ```
+/// use vec::AlignedVec;
+///
+/// let mut vec: AlignedVec<f32, 64> = AlignedVec::new_aligned();
+/// for i in 0..100 {
+///     vec.push(i as f32);
+/// }
+///
+/// assert_eq!(vec.as_ptr() as usize % 64, 0);
+/// ```
+pub type AlignedVec<T, const ALIGN: usize> = Vec<T, Aligned<ALIGN>>;
+
+/// Allocator adapter raising the alignment of every request made to `A` to at least `ALIGN` bytes.
+/// # Example
+/// ```
+/// #![feature(allocator_api)]
+/// use vec::{Aligned, Vec};
+///
+/// let mut vec = Vec::new_in(Aligned::<4096>::new());
+/// vec.push(1u8);
+/// assert_eq!(vec.as_ptr() as usize % 4096, 0);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Aligned<const ALIGN: usize, A: Allocator = Global> {
+    alloc: A,
+}
+
+impl<const ALIGN: usize> Aligned<ALIGN> {
+    /// Creates an adapter over the global allocator.
+    ///
+    /// `ALIGN` must be a power of two, otherwise this fails to compile.
+    /// # Example
+    /// ```
+    /// use vec::Aligned;
+    /// let _alloc = Aligned::<64>::new();
+    /// ```
+    pub const fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<const ALIGN: usize, A: Allocator> Aligned<ALIGN, A> {
+    /// Creates an adapter over `alloc`.
+    ///
+    /// `ALIGN` must be a power of two, otherwise this fails to compile.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use vec::Aligned;
+    /// let _alloc = Aligned::<32, _>::new_in(Global);
+    /// ```
+    pub const fn new_in(alloc: A) -> Self {
+        const { assert!(ALIGN.is_power_of_two(), "Alignment must be a power of two") };
+
+        Self { alloc }
+    }
+
+    // Raises the alignment of a layout to ALIGN
+    fn align(layout: Layout) -> Result<Layout, AllocError> {
+        layout.align_to(ALIGN).map_err(|_| AllocError)
+    }
+}
+
+impl<T, const ALIGN: usize> Vec<T, Aligned<ALIGN>> {
+    /// Creates a new Vector with size 0 (unallocated) whose buffer will be aligned to `ALIGN` bytes.
+    /// # Example
+    /// ```
+    /// use vec::AlignedVec;
+    /// let mut vec: AlignedVec<u8, 512> = AlignedVec::new_aligned();
+    /// vec.push(1);
+    /// assert_eq!(vec.as_ptr() as usize % 512, 0);
+    /// ```
+    pub fn new_aligned() -> Self {
+        Self::new_in(Aligned::new())
+    }
+}
+
+unsafe impl<const ALIGN: usize, A: Allocator> Allocator for Aligned<ALIGN, A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.alloc.allocate(Self::align(layout)?)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.alloc.allocate_zeroed(Self::align(layout)?)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // Can't fail, the same layout was aligned successfully when allocating
+        if let Ok(layout) = Self::align(layout) {
+            self.alloc.deallocate(ptr, layout)
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.alloc
+            .grow(ptr, Self::align(old_layout)?, Self::align(new_layout)?)
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.alloc
+            .grow_zeroed(ptr, Self::align(old_layout)?, Self::align(new_layout)?)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.alloc
+            .shrink(ptr, Self::align(old_layout)?, Self::align(new_layout)?)
+    }
+}
@@ -0,0 +1,584 @@
+use std::{
+    alloc::{Allocator, Global, Layout},
+    any::{self, TypeId},
+    cmp, fmt, mem,
+    ptr::{self, NonNull},
+    slice,
+};
+
+use crate::{error::TryReserveError, raw::handle_reserve_error};
+
+/// Runtime description of an element type: its layout, identity and how to drop and clone it. Built from a concrete
+/// type once, then passed around as a value to create [`AnyVec`]s of a type picked at runtime.
+/// # Example
+/// ```
+/// use vec::AnyType;
+///
+/// let ty = AnyType::of::<u32>();
+/// assert_eq!(ty.layout().size(), 4);
+/// assert!(ty.is::<u32>());
+/// assert!(!ty.is_cloneable());
+/// ```
+#[derive(Clone, Copy)]
+pub struct AnyType {
+    layout: Layout,
+    id: TypeId,
+    name: &'static str,
+    drop: Option<unsafe fn(*mut u8)>,
+    clone: Option<unsafe fn(*const u8, *mut u8)>,
+}
+
+unsafe fn drop_erased<T>(ptr: *mut u8) {
+    ptr::drop_in_place(ptr as *mut T)
+}
+
+unsafe fn clone_erased<T: Clone>(src: *const u8, dst: *mut u8) {
+    ptr::write(dst as *mut T, (*(src as *const T)).clone())
+}
+
+impl AnyType {
+    /// Describes `T`. Vectors of it can't be cloned; use [`of_cloneable`](AnyType::of_cloneable) for that.
+    /// # Example
+    /// ```
+    /// let ty = vec::AnyType::of::<String>();
+    /// assert!(ty.name().ends_with("String"));
+    /// ```
+    pub fn of<T: 'static>() -> Self {
+        Self {
+            layout: Layout::new::<T>(),
+            id: TypeId::of::<T>(),
+            name: any::type_name::<T>(),
+            drop: if mem::needs_drop::<T>() {
+                Some(drop_erased::<T>)
+            } else {
+                None
+            },
+            clone: None,
+        }
+    }
+
+    /// Describes `T`, including how to clone it.
+    /// # Example
+    /// ```
+    /// let ty = vec::AnyType::of_cloneable::<String>();
+    /// assert!(ty.is_cloneable());
+    /// ```
+    pub fn of_cloneable<T: Clone + 'static>() -> Self {
+        Self {
+            clone: Some(clone_erased::<T>),
+            ..Self::of::<T>()
+        }
+    }
+
+    /// Returns the layout of one element.
+    /// # Example
+    /// ```
+    /// let ty = vec::AnyType::of::<u64>();
+    /// assert_eq!(ty.layout().align(), std::mem::align_of::<u64>());
+    /// ```
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Returns the name of the type, as given by [`std::any::type_name`].
+    /// # Example
+    /// ```
+    /// assert_eq!(vec::AnyType::of::<u8>().name(), "u8");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns `true` if this describes `T`.
+    /// # Example
+    /// ```
+    /// let ty = vec::AnyType::of::<u8>();
+    /// assert!(ty.is::<u8>());
+    /// assert!(!ty.is::<i8>());
+    /// ```
+    pub fn is<T: 'static>(&self) -> bool {
+        self.id == TypeId::of::<T>()
+    }
+
+    /// Returns `true` if the type was described with its clone function.
+    /// # Example
+    /// ```
+    /// assert!(vec::AnyType::of_cloneable::<u8>().is_cloneable());
+    /// ```
+    pub fn is_cloneable(&self) -> bool {
+        self.clone.is_some()
+    }
+}
+
+impl fmt::Debug for AnyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyType")
+            .field("name", &self.name)
+            .field("layout", &self.layout)
+            .finish()
+    }
+}
+
+impl PartialEq for AnyType {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for AnyType {}
+
+/// Vector whose element type is chosen at runtime, through an [`AnyType`].
+///
+/// Elements are stored contiguously like in a [`Vec`](crate::Vec). Typed access checks the requested type
+/// against the stored one, while untyped operations (moving, removing, dropping, cloning) go through the layout
+/// and functions captured in the [`AnyType`].
+/// # Example
+/// ```
+/// use vec::{AnyType, AnyVec};
+///
+/// // Columns whose types come from, e.g., a plugin's schema
+/// let mut columns = vec![AnyVec::new(AnyType::of::<f32>()), AnyVec::new(AnyType::of::<String>())];
+/// columns[0].push(1.5f32);
+/// columns[1].push("one".to_string());
+///
+/// assert_eq!(columns[0].downcast_ref::<f32>(), Some(&[1.5][..]));
+/// assert_eq!(columns[1].get::<String>(0).map(|s| s.as_str()), Some("one"));
+/// assert!(columns[0].downcast_ref::<u32>().is_none());
+///
+/// // Untyped operations work the same on every column
+/// for column in &mut columns {
+///     column.swap_remove(0);
+/// }
+/// assert!(columns.iter().all(|column| column.is_empty()));
+/// ```
+pub struct AnyVec {
+    ptr: NonNull<u8>,
+    cap: usize,
+    len: usize,
+    ty: AnyType,
+}
+
+impl AnyVec {
+    /// Creates an empty vector of elements of type `ty`, without allocating.
+    /// # Example
+    /// ```
+    /// let v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new(ty: AnyType) -> Self {
+        // !0 == usize::MAX
+        let cap = if ty.layout.size() == 0 { !0 } else { 0 };
+
+        Self {
+            // Aligned and non-null, as for an empty Vec
+            ptr: NonNull::new(ptr::without_provenance_mut(ty.layout.align())).unwrap(),
+            cap,
+            len: 0,
+            ty,
+        }
+    }
+
+    /// Creates an empty vector of elements of type `ty` with room for at least `capacity` of them.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v = vec::AnyVec::with_capacity(vec::AnyType::of::<u8>(), 10);
+    /// assert!(v.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(ty: AnyType, capacity: usize) -> Self {
+        let mut vec = Self::new(ty);
+        vec.reserve(capacity);
+        vec
+    }
+
+    /// Returns the element type.
+    /// # Example
+    /// ```
+    /// let v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// assert!(v.element_type().is::<u8>());
+    /// ```
+    pub fn element_type(&self) -> &AnyType {
+        &self.ty
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// assert_eq!(v.capacity(), 0);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns a raw pointer to the buffer, valid for `len` elements of the element type.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u32>());
+    /// v.push(7u32);
+    /// assert_eq!(unsafe { *(v.as_ptr() as *const u32) }, 7);
+    /// ```
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// Reserves room for at least `additional` more elements.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.reserve(10);
+    /// assert!(v.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Tries to reserve room for at least `additional` more elements.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator fails.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u64>());
+    /// assert!(v.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if self.cap - self.len >= additional {
+            return Ok(());
+        }
+
+        // Zero-sized types already have a capacity of usize::MAX
+        if self.ty.layout.size() == 0 {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let new_cap = cmp::max(cmp::max(self.cap * 2, required), 4);
+        let new_layout = self.array(new_cap)?;
+
+        let ptr = unsafe {
+            if self.cap == 0 {
+                Global.allocate(new_layout)
+            } else {
+                Global.grow(self.ptr, self.array(self.cap).unwrap(), new_layout)
+            }
+        };
+
+        self.ptr = ptr
+            .map_err(|_| TryReserveError::AllocError { layout: new_layout })?
+            .cast();
+        self.cap = new_cap;
+        Ok(())
+    }
+
+    /// Appends an element.
+    /// # Panics
+    /// Panics if `T` isn't the element type, or the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// assert_eq!(v.get::<u8>(0), Some(&1));
+    /// ```
+    pub fn push<T: 'static>(&mut self, value: T) {
+        self.assert_type::<T>();
+
+        let value = mem::ManuallyDrop::new(value);
+        unsafe { self.push_raw(&*value as *const T as *const u8) }
+    }
+
+    /// Appends an element by copying its bytes from `src`, taking ownership of it.
+    /// # Safety
+    /// `src` must point to a valid value of the element type, which must not be used or dropped afterwards.
+    /// # Panics
+    /// Panics if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u16>());
+    /// let value = 7u16;
+    /// unsafe { v.push_raw(&value as *const u16 as *const u8) };
+    /// assert_eq!(v.get::<u16>(0), Some(&7));
+    /// ```
+    pub unsafe fn push_raw(&mut self, src: *const u8) {
+        self.reserve(1);
+        ptr::copy_nonoverlapping(src, self.slot(self.len), self.ty.layout.size());
+        self.len += 1;
+    }
+
+    /// Returns the elements as a slice of `T`, or `None` if `T` isn't the element type.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// assert_eq!(v.downcast_ref::<u8>(), Some(&[1][..]));
+    /// assert_eq!(v.downcast_ref::<i8>(), None);
+    /// ```
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&[T]> {
+        if self.ty.is::<T>() {
+            Some(unsafe { slice::from_raw_parts(self.ptr.as_ptr() as *const T, self.len) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the elements as a mutable slice of `T`, or `None` if `T` isn't the element type.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// v.downcast_mut::<u8>().unwrap()[0] = 2;
+    /// assert_eq!(v.get::<u8>(0), Some(&2));
+    /// ```
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut [T]> {
+        if self.ty.is::<T>() {
+            Some(unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr() as *mut T, self.len) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if out of bounds or `T` isn't the element type.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// assert_eq!(v.get::<u8>(0), Some(&1));
+    /// assert_eq!(v.get::<u8>(1), None);
+    /// ```
+    pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.downcast_ref()?.get(index)
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if out of bounds or `T` isn't the element
+    /// type.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// *v.get_mut::<u8>(0).unwrap() = 2;
+    /// assert_eq!(v.get::<u8>(0), Some(&2));
+    /// ```
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+        self.downcast_mut()?.get_mut(index)
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Panics
+    /// Panics if `T` isn't the element type.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// assert_eq!(v.pop::<u8>(), Some(1));
+    /// assert_eq!(v.pop::<u8>(), None);
+    /// ```
+    pub fn pop<T: 'static>(&mut self) -> Option<T> {
+        self.assert_type::<T>();
+
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.slot(self.len) as *const T)) }
+    }
+
+    /// Removes the element at `index` and drops it, replacing it with the last element. Works without knowing the
+    /// element type.
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<String>());
+    /// v.push("a".to_string());
+    /// v.push("b".to_string());
+    /// v.push("c".to_string());
+    ///
+    /// v.swap_remove(0);
+    /// assert_eq!(v.downcast_ref::<String>().unwrap(), ["c", "b"]);
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) {
+        let removed = self.take_swap_remove(index);
+
+        if let Some(drop) = self.ty.drop {
+            unsafe { drop(removed) }
+        }
+    }
+
+    /// Moves the element at `index` to the end of `other`, replacing it with the last element. Works without
+    /// knowing the element type.
+    /// # Panics
+    /// Panics if `index` is out of bounds or `other` doesn't hold the same element type.
+    /// # Example
+    /// ```
+    /// let ty = vec::AnyType::of::<String>();
+    /// let (mut a, mut b) = (vec::AnyVec::new(ty), vec::AnyVec::new(ty));
+    /// a.push("x".to_string());
+    ///
+    /// a.swap_remove_into(0, &mut b);
+    /// assert!(a.is_empty());
+    /// assert_eq!(b.downcast_ref::<String>().unwrap(), ["x"]);
+    /// ```
+    pub fn swap_remove_into(&mut self, index: usize, other: &mut AnyVec) {
+        assert!(self.ty == other.ty, "element types differ");
+
+        // Make room first, so a panic leaves both vectors intact
+        other.reserve(1);
+
+        let removed = self.take_swap_remove(index);
+        unsafe { other.push_raw(removed) }
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if it's not longer.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// v.push(2u8);
+    /// v.truncate(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let old_len = self.len;
+
+        // Shorten first, so a panicking destructor can't cause an element to be dropped twice
+        self.len = len;
+
+        if let Some(drop) = self.ty.drop {
+            for index in len..old_len {
+                unsafe { drop(self.slot(index)) }
+            }
+        }
+    }
+
+    /// Removes every element, keeping the allocation.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of::<u8>());
+    /// v.push(1u8);
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Returns a copy of the vector, or `None` if the element type wasn't described with its clone function.
+    /// # Example
+    /// ```
+    /// let mut v = vec::AnyVec::new(vec::AnyType::of_cloneable::<String>());
+    /// v.push("a".to_string());
+    ///
+    /// let copy = v.try_clone().unwrap();
+    /// assert_eq!(copy.downcast_ref::<String>().unwrap(), ["a"]);
+    /// ```
+    pub fn try_clone(&self) -> Option<Self> {
+        let clone = self.ty.clone?;
+        let mut copy = Self::with_capacity(self.ty, self.len);
+
+        for index in 0..self.len {
+            // Bumping the length after each element drops the clones made so far if one panics
+            unsafe { clone(self.slot(index), copy.slot(index)) };
+            copy.len += 1;
+        }
+
+        Some(copy)
+    }
+
+    // Layout of a buffer of `cap` elements
+    fn array(&self, cap: usize) -> Result<Layout, TryReserveError> {
+        let size = self
+            .ty
+            .layout
+            .size()
+            .checked_mul(cap)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+
+        // Fails for allocations bigger than isize::MAX bytes
+        Layout::from_size_align(size, self.ty.layout.align())
+            .map_err(|_| TryReserveError::CapacityOverflow)
+    }
+
+    // Pointer to slot `index`, which must be within the capacity
+    fn slot(&self, index: usize) -> *mut u8 {
+        unsafe { self.ptr.as_ptr().add(index * self.ty.layout.size()) }
+    }
+
+    // Moves the last element over `index` and returns a pointer to the removed element, now past the end
+    fn take_swap_remove(&mut self, index: usize) -> *mut u8 {
+        assert!(index < self.len, "index out of bounds");
+
+        self.len -= 1;
+        if index != self.len {
+            unsafe {
+                ptr::swap_nonoverlapping(
+                    self.slot(index),
+                    self.slot(self.len),
+                    self.ty.layout.size(),
+                )
+            };
+        }
+
+        self.slot(self.len)
+    }
+
+    fn assert_type<T: 'static>(&self) {
+        assert!(
+            self.ty.is::<T>(),
+            "type mismatch: vector holds {}, got {}",
+            self.ty.name,
+            any::type_name::<T>()
+        );
+    }
+}
+
+impl fmt::Debug for AnyVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyVec")
+            .field("type", &self.ty.name)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Drop for AnyVec {
+    fn drop(&mut self) {
+        self.clear();
+
+        if self.ty.layout.size() != 0 && self.cap != 0 {
+            unsafe { Global.deallocate(self.ptr, self.array(self.cap).unwrap()) }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    mem::MaybeUninit,
+    ops::Index,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+// Bucket `b` holds `FIRST << b` slots, so the buckets double in size and a fixed table of them covers every index
+const FIRST_BITS: u32 = 5;
+const FIRST: usize = 1 << FIRST_BITS;
+const BUCKETS: usize = (usize::BITS - FIRST_BITS) as usize;
+
+/// Vector that many threads can append to concurrently through `&self`, without locks.
+///
+/// Elements live in buckets that are allocated as needed and never move, so pushing never invalidates the references
+/// returned by [`get`](AppendOnlyVec::get). A push reserves its index with a single atomic increment; reads are
+/// wait-free and only see an element once its push has completed.
+/// # Example
+/// ```
+/// use std::thread;
+/// use vec::AppendOnlyVec;
+///
+/// let results = AppendOnlyVec::new();
+///
+/// thread::scope(|s| {
+///     for t in 0..4 {
+///         let results = &results;
+///         s.spawn(move || {
+///             for i in 0..100 {
+///                 results.push(t * 100 + i);
+///             }
+///         });
+///     }
+/// });
+///
+/// assert_eq!(results.len(), 400);
+/// assert_eq!(results.iter().sum::<i32>(), (0..400).sum());
+/// ```
+pub struct AppendOnlyVec<T> {
+    buckets: [AtomicPtr<Slot<T>>; BUCKETS],
+    // indices handed out to pushes, some of which may still be writing
+    reserved: AtomicUsize,
+    // pushes that completed
+    len: AtomicUsize,
+}
+
+struct Slot<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+    // set once the value is written, publishing it to readers
+    ready: AtomicBool,
+}
+
+unsafe impl<T: Send> Send for AppendOnlyVec<T> {}
+unsafe impl<T: Send + Sync> Sync for AppendOnlyVec<T> {}
+
+// Bucket and offset in the bucket of element `index`
+fn location(index: usize) -> (usize, usize) {
+    let i = index + FIRST;
+    let bucket = (usize::BITS - 1 - i.leading_zeros() - FIRST_BITS) as usize;
+
+    (bucket, i - (FIRST << bucket))
+}
+
+fn bucket_len(bucket: usize) -> usize {
+    FIRST << bucket
+}
+
+impl<T> AppendOnlyVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::AppendOnlyVec<i32> = vec::AppendOnlyVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            buckets: [const { AtomicPtr::new(ptr::null_mut()) }; BUCKETS],
+            reserved: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of elements whose push has completed.
+    /// # Example
+    /// ```
+    /// let v = vec::AppendOnlyVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if no push has completed.
+    /// # Example
+    /// ```
+    /// let v: vec::AppendOnlyVec<i32> = vec::AppendOnlyVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an element and returns its index. Concurrent pushes get distinct indices, in no particular order.
+    /// # Panics
+    /// This function will panic if a bucket exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v = vec::AppendOnlyVec::new();
+    /// assert_eq!(v.push('a'), 0);
+    /// assert_eq!(v.push('b'), 1);
+    /// ```
+    pub fn push(&self, value: T) -> usize {
+        let index = self.reserved.fetch_add(1, Ordering::Relaxed);
+        let (bucket, offset) = location(index);
+
+        unsafe {
+            let slot = &*self.bucket_or_allocate(bucket).add(offset);
+
+            (*slot.value.get()).write(value);
+            slot.ready.store(true, Ordering::Release);
+        }
+
+        self.len.fetch_add(1, Ordering::Release);
+        index
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if there is none yet. Elements are published out
+    /// of order, so this may be `None` for an index below [`len`](AppendOnlyVec::len) while its push completes.
+    /// # Example
+    /// ```
+    /// let v = vec::AppendOnlyVec::new();
+    /// let index = v.push(1);
+    /// assert_eq!(v.get(index), Some(&1));
+    /// assert_eq!(v.get(index + 1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.reserved.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let (bucket, offset) = location(index);
+        let ptr = self.buckets[bucket].load(Ordering::Acquire);
+
+        if ptr.is_null() {
+            return None;
+        }
+
+        unsafe {
+            let slot = &*ptr.add(offset);
+
+            if slot.ready.load(Ordering::Acquire) {
+                Some((*slot.value.get()).assume_init_ref())
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns an iterator over the published elements, in index order.
+    /// # Example
+    /// ```
+    /// let v = vec::AppendOnlyVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// assert_eq!(v.iter().copied().collect::<std::vec::Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.reserved.load(Ordering::Acquire)).filter_map(move |i| self.get(i))
+    }
+
+    // Pointer to the slots of `bucket`, allocating them if no push did yet
+    fn bucket_or_allocate(&self, bucket: usize) -> *mut Slot<T> {
+        let ptr = self.buckets[bucket].load(Ordering::Acquire);
+
+        if !ptr.is_null() {
+            return ptr;
+        }
+
+        let slots: Box<[Slot<T>]> = (0..bucket_len(bucket))
+            .map(|_| Slot {
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+                ready: AtomicBool::new(false),
+            })
+            .collect();
+        let new = Box::into_raw(slots) as *mut Slot<T>;
+
+        // Another push may have allocated the bucket in the meantime, keep the first one
+        match self.buckets[bucket].compare_exchange(
+            ptr::null_mut(),
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => new,
+            Err(existing) => {
+                unsafe {
+                    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                        new,
+                        bucket_len(bucket),
+                    )))
+                };
+                existing
+            }
+        }
+    }
+}
+
+impl<T> Default for AppendOnlyVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for AppendOnlyVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> Extend<T> for AppendOnlyVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AppendOnlyVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Drop for AppendOnlyVec<T> {
+    fn drop(&mut self) {
+        for (bucket, ptr) in self.buckets.iter_mut().enumerate() {
+            let ptr = *ptr.get_mut();
+
+            if ptr.is_null() {
+                continue;
+            }
+
+            unsafe {
+                let mut slots =
+                    Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, bucket_len(bucket)));
+
+                for slot in slots.iter_mut() {
+                    if *slot.ready.get_mut() {
+                        slot.value.get_mut().assume_init_drop();
+                    }
+                }
+            }
+        }
+    }
+}
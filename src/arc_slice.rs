@@ -0,0 +1,290 @@
+use std::{
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    io,
+    ops::{Deref, Range, RangeBounds},
+    sync::Arc,
+};
+
+use crate::{raw::resolve_range, Vec};
+
+/// Shared, immutable view of a range of a frozen [`Vec`]. Cloning, slicing and splitting only bump a reference
+/// count, so parsed sub-messages can point into the buffer they were read from without copying it.
+///
+/// The buffer is freed once the last view of it is dropped.
+/// # Example
+/// ```
+/// use vec::{ArcSlice, Vec};
+///
+/// let mut vec = Vec::new();
+/// vec.extend(0..10);
+/// let mut all = ArcSlice::from(vec);
+///
+/// let head = all.split_to(3);
+/// let middle = all.slice(..4);
+///
+/// assert_eq!(&*head, &[0, 1, 2]);
+/// assert_eq!(&*middle, &[3, 4, 5, 6]);
+/// assert_eq!(&*all, &[3, 4, 5, 6, 7, 8, 9]);
+/// assert!(head.ptr_eq(&middle));
+/// ```
+pub struct ArcSlice<T> {
+    vec: Arc<Vec<T>>,
+    // range of `vec` this view covers
+    start: usize,
+    end: usize,
+}
+
+/// [`ArcSlice`] of bytes, with helpers to parse byte streams: [`split_to_byte`](ArcSlice::split_to_byte) and
+/// [`io::Read`].
+pub type ArcBytes = ArcSlice<u8>;
+
+// The buffer is never mutated once shared
+unsafe impl<T: Send + Sync> Send for ArcSlice<T> {}
+unsafe impl<T: Send + Sync> Sync for ArcSlice<T> {}
+
+impl<T> ArcSlice<T> {
+    /// Creates an empty slice, without allocating its buffer.
+    /// # Example
+    /// ```
+    /// let s: vec::ArcSlice<i32> = vec::ArcSlice::new();
+    /// assert!(s.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::from(Vec::new())
+    }
+
+    /// Returns the number of elements in the view.
+    /// # Example
+    /// ```
+    /// let s = vec::ArcBytes::from(&b"abc"[..]);
+    /// assert_eq!(s.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the view holds no elements.
+    /// # Example
+    /// ```
+    /// let s: vec::ArcSlice<i32> = vec::ArcSlice::new();
+    /// assert!(s.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns a view of `range` within this one, sharing the buffer.
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    /// # Example
+    /// ```
+    /// let s = vec::ArcBytes::from(&b"hello world"[..]);
+    /// assert_eq!(&*s.slice(6..), b"world");
+    /// assert_eq!(&*s.slice(..=4), b"hello");
+    /// ```
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let Range { start, end } = resolve_range(range, self.len());
+
+        Self {
+            vec: self.vec.clone(),
+            start: self.start + start,
+            end: self.start + end,
+        }
+    }
+
+    /// Splits the view in two at `at`, returning the elements before it and keeping the ones after.
+    /// # Panics
+    /// Panics if `at > len`.
+    /// # Example
+    /// ```
+    /// let mut s = vec::ArcBytes::from(&b"GET /index"[..]);
+    /// let method = s.split_to(3);
+    /// assert_eq!(&*method, b"GET");
+    /// assert_eq!(&*s, b" /index");
+    /// ```
+    pub fn split_to(&mut self, at: usize) -> Self {
+        let head = self.slice(..at);
+        self.start = head.end;
+        head
+    }
+
+    /// Splits the view in two at `at`, returning the elements after it and keeping the ones before.
+    /// # Panics
+    /// Panics if `at > len`.
+    /// # Example
+    /// ```
+    /// let mut s = vec::ArcBytes::from(&b"key=value"[..]);
+    /// let value = s.split_off(4);
+    /// assert_eq!(&*s, b"key=");
+    /// assert_eq!(&*value, b"value");
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let tail = self.slice(at..);
+        self.end = tail.start;
+        tail
+    }
+
+    /// Shortens the view to its first `len` elements, doing nothing if it's not longer.
+    /// # Example
+    /// ```
+    /// let mut s = vec::ArcBytes::from(&b"abc"[..]);
+    /// s.truncate(1);
+    /// assert_eq!(&*s, b"a");
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.end = self.start + len;
+        }
+    }
+
+    /// Drops the first `count` elements from the view.
+    /// # Panics
+    /// Panics if `count > len`.
+    /// # Example
+    /// ```
+    /// let mut s = vec::ArcBytes::from(&b"abc"[..]);
+    /// s.advance(2);
+    /// assert_eq!(&*s, b"c");
+    /// ```
+    pub fn advance(&mut self, count: usize) {
+        assert!(count <= self.len(), "cannot advance past the end");
+        self.start += count;
+    }
+
+    /// Returns `true` if both views share the same buffer.
+    /// # Example
+    /// ```
+    /// let s = vec::ArcBytes::from(&b"abc"[..]);
+    /// assert!(s.ptr_eq(&s.slice(1..)));
+    /// assert!(!s.ptr_eq(&vec::ArcBytes::from(&b"abc"[..])));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.vec, &other.vec)
+    }
+
+    /// Returns the elements of the view in a vector, reusing the buffer if this is its only view and it covers all of
+    /// it, and cloning the elements otherwise.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut s = vec::ArcBytes::from(&b"abc"[..]);
+    /// let tail = s.split_off(1);
+    /// assert_eq!(&*tail.into_vec(), b"bc");
+    /// ```
+    pub fn into_vec(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let Self {
+            mut vec,
+            start,
+            end,
+        } = self;
+        // Taking the buffer only succeeds if no other view shares it
+        if start == 0 && end == vec.len {
+            match Arc::try_unwrap(vec) {
+                Ok(vec) => return vec,
+                Err(shared) => vec = shared,
+            }
+        }
+
+        let mut out = Vec::with_capacity(end - start);
+        out.extend(vec[start..end].iter().cloned());
+        out
+    }
+}
+
+impl ArcSlice<u8> {
+    /// Returns the bytes before the first `delimiter`, dropping them and the delimiter from the view, or `None` if
+    /// there is no delimiter.
+    /// # Example
+    /// ```
+    /// let mut s = vec::ArcBytes::from(&b"a\nbc\nd"[..]);
+    /// assert_eq!(s.split_to_byte(b'\n').as_deref(), Some(&b"a"[..]));
+    /// assert_eq!(s.split_to_byte(b'\n').as_deref(), Some(&b"bc"[..]));
+    /// assert_eq!(s.split_to_byte(b'\n'), None);
+    /// assert_eq!(&*s, b"d");
+    /// ```
+    pub fn split_to_byte(&mut self, delimiter: u8) -> Option<Self> {
+        let index = self.iter().position(|&b| b == delimiter)?;
+        let head = self.split_to(index);
+        self.advance(1);
+        Some(head)
+    }
+}
+
+impl<T> Default for ArcSlice<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ArcSlice<T> {
+    /// Returns a view of the same range, sharing the buffer.
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for ArcSlice<T> {
+    /// Freezes a vector into a view of all of its elements, without copying them.
+    fn from(vec: Vec<T>) -> Self {
+        Self {
+            end: vec.len,
+            start: 0,
+            vec: Arc::new(vec),
+        }
+    }
+}
+
+impl<T: Clone> From<&[T]> for ArcSlice<T> {
+    /// Copies a slice into a new buffer.
+    fn from(slice: &[T]) -> Self {
+        let mut vec = Vec::with_capacity(slice.len());
+        vec.extend(slice.iter().cloned());
+        Self::from(vec)
+    }
+}
+
+impl<T> Deref for ArcSlice<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec[self.start..self.end]
+    }
+}
+
+impl io::Read for ArcSlice<u8> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = cmp::min(buf.len(), self.len());
+        buf[..count].copy_from_slice(&self[..count]);
+        self.advance(count);
+        Ok(count)
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArcSlice<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq> Eq for ArcSlice<T> {}
+
+impl<T: Hash> Hash for ArcSlice<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self[..].hash(state)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArcSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
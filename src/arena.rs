@@ -0,0 +1,252 @@
+use std::{
+    alloc::{AllocError, Allocator, Global, Layout},
+    cell::{Cell, RefCell},
+    cmp,
+    ptr::{self, NonNull},
+};
+
+use crate::Vec;
+
+// Size of the first chunk requested by `Arena::new`
+const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+// Memory block owned by the arena, returned to the global allocator when the arena is dropped
+struct Chunk {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+/// Bump allocator handing out memory from large chunks, freed all at once when the arena is reset or dropped.
+///
+/// `&Arena` implements [`Allocator`], so any number of vectors can allocate from the same arena.
+/// # Example
+/// ```
+/// #![feature(allocator_api)]
+/// use vec::{Arena, Vec};
+///
+/// let arena = Arena::new();
+/// let mut a = Vec::new_in(&arena);
+/// let mut b = Vec::new_in(&arena);
+///
+/// for i in 0..100 {
+///     a.push(i);
+///     b.push(i * 2);
+/// }
+///
+/// assert_eq!(a[99], 99);
+/// assert_eq!(b[99], 198);
+/// ```
+pub struct Arena {
+    // every chunk allocated so far, the last one being the current one
+    chunks: RefCell<Vec<Chunk>>,
+    // next free byte in the current chunk
+    ptr: Cell<*mut u8>,
+    // end of the current chunk
+    end: Cell<*mut u8>,
+    // size of the next chunk, doubled every time a new chunk is needed
+    next_chunk_size: Cell<usize>,
+}
+
+impl Arena {
+    /// Creates an empty arena, the first chunk is allocated on the first allocation.
+    /// # Example
+    /// ```
+    /// use vec::Arena;
+    /// let arena = Arena::new();
+    /// assert_eq!(arena.allocated_bytes(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates an empty arena whose first chunk will hold `chunk_size` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Arena, Vec};
+    /// let arena = Arena::with_chunk_size(64);
+    /// let mut vec = Vec::new_in(&arena);
+    /// vec.push(1u8);
+    /// assert_eq!(arena.allocated_bytes(), 64);
+    /// ```
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+            ptr: Cell::new(ptr::null_mut()),
+            end: Cell::new(ptr::null_mut()),
+            next_chunk_size: Cell::new(cmp::max(chunk_size, 1)),
+        }
+    }
+
+    /// Returns the total number of bytes the arena has requested from the global allocator.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Arena, Vec};
+    /// let arena = Arena::with_chunk_size(16);
+    /// let mut vec = Vec::new_in(&arena);
+    /// for i in 0..64u8 {
+    ///     vec.push(i);
+    /// }
+    /// assert!(arena.allocated_bytes() >= 64);
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|c| c.layout.size()).sum()
+    }
+
+    /// Frees every allocation made from the arena at once, keeping only the most recent chunk for reuse.
+    ///
+    /// Requires `&mut self`, so no vector can still be borrowing from the arena.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Arena, Vec};
+    /// let mut arena = Arena::with_chunk_size(64);
+    /// for frame in 0..3 {
+    ///     let mut vec = Vec::new_in(&arena);
+    ///     vec.push(frame);
+    ///     drop(vec);
+    ///     arena.reset();
+    /// }
+    /// assert_eq!(arena.allocated_bytes(), 64);
+    /// ```
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+
+        if let Some(last) = chunks.pop() {
+            while let Some(chunk) = chunks.pop() {
+                unsafe { Global.deallocate(chunk.ptr, chunk.layout) }
+            }
+
+            self.ptr.set(last.ptr.as_ptr());
+            self.end
+                .set(last.ptr.as_ptr().wrapping_add(last.layout.size()));
+            chunks.push(last);
+        }
+    }
+
+    // Bumps the current chunk, returning `None` when the layout doesn't fit in it
+    fn bump(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let ptr = self.ptr.get();
+
+        if ptr.is_null() {
+            return None;
+        }
+
+        let start = ptr.wrapping_add(ptr.align_offset(layout.align()));
+        let remaining = self.end.get().addr().checked_sub(start.addr())?;
+
+        if remaining < layout.size() {
+            return None;
+        }
+
+        self.ptr.set(start.wrapping_add(layout.size()));
+        NonNull::new(start)
+    }
+
+    // Allocates a new chunk big enough for `layout` and makes it the current one
+    fn new_chunk(&self, layout: Layout) -> Result<(), AllocError> {
+        let size = cmp::max(
+            self.next_chunk_size.get(),
+            layout
+                .size()
+                .checked_add(layout.align())
+                .ok_or(AllocError)?,
+        );
+        let chunk_layout = Layout::from_size_align(size, 1).map_err(|_| AllocError)?;
+        let ptr = Global.allocate(chunk_layout)?.cast::<u8>();
+
+        self.chunks.borrow_mut().push(Chunk {
+            ptr,
+            layout: chunk_layout,
+        });
+        self.ptr.set(ptr.as_ptr());
+        self.end.set(ptr.as_ptr().wrapping_add(size));
+        self.next_chunk_size.set(size.saturating_mul(2));
+
+        Ok(())
+    }
+
+    // Whether the block at `ptr` is the last one handed out, so it can be resized in place
+    fn is_last(&self, ptr: NonNull<u8>, size: usize) -> bool {
+        ptr.as_ptr().wrapping_add(size) == self.ptr.get()
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Allocator for &Arena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = match self.bump(layout) {
+            Some(ptr) => ptr,
+            None => {
+                self.new_chunk(layout)?;
+                self.bump(layout).ok_or(AllocError)?
+            }
+        };
+
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // Memory is only reclaimed on reset/drop, except for the most recent block
+        if self.is_last(ptr, layout.size()) {
+            self.ptr.set(ptr.as_ptr());
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // The most recent block can be extended in place if the chunk still has room
+        if self.is_last(ptr, old_layout.size())
+            && ptr.as_ptr().align_offset(new_layout.align()) == 0
+        {
+            let available = self.end.get().addr() - ptr.as_ptr().addr();
+
+            if available >= new_layout.size() {
+                self.ptr.set(ptr.as_ptr().add(new_layout.size()));
+                return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+            }
+        }
+
+        let new = self.allocate(new_layout)?;
+        ptr::copy_nonoverlapping(ptr.as_ptr(), new.as_ptr() as *mut u8, old_layout.size());
+        Ok(new)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr.as_ptr().align_offset(new_layout.align()) != 0 {
+            let new = self.allocate(new_layout)?;
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new.as_ptr() as *mut u8, new_layout.size());
+            return Ok(new);
+        }
+
+        if self.is_last(ptr, old_layout.size()) {
+            self.ptr.set(ptr.as_ptr().add(new_layout.size()));
+        }
+
+        Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+    }
+}
+
+// Arena Deallocation, every chunk goes back to the global allocator at once
+impl Drop for Arena {
+    fn drop(&mut self) {
+        while let Some(chunk) = self.chunks.get_mut().pop() {
+            unsafe { Global.deallocate(chunk.ptr, chunk.layout) }
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use std::{
+    fmt, iter,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+use crate::{
+    error::CapacityError,
+    raw::{insert_at, poison, remove_at, truncate_at, RawValIter},
+};
+
+/// Vector with a fixed capacity of `N` elements stored inline, it never allocates.
+///
+/// Handy on the stack in embedded or hot-path code. Going past the capacity panics, or returns a
+/// [`CapacityError`] from the `try_*` methods.
+/// # Example
+/// ```
+/// use vec::ArrayVec;
+///
+/// let mut vec: ArrayVec<u8, 4> = ArrayVec::new();
+/// vec.push(1);
+/// vec.push(2);
+/// assert_eq!(&*vec, &[1, 2]);
+/// assert_eq!(vec.capacity(), 4);
+/// ```
+pub struct ArrayVec<T, const N: usize> {
+    len: usize,
+    data: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    /// Creates an empty vector.
+    /// # Example
+    /// ```
+    /// let vec: vec::ArrayVec<i32, 8> = vec::ArrayVec::new();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            len: 0,
+            data: [const { MaybeUninit::uninit() }; N],
+        }
+    }
+
+    /// Creates a vector holding the elements of an array, behind `array_vec![a, b, c]`. An array longer than the
+    /// capacity fails to build.
+    #[doc(hidden)]
+    pub fn from_array<const M: usize>(array: [T; M]) -> Self {
+        const { assert!(M <= N, "more elements than the capacity of the ArrayVec") };
+
+        let mut vec = Self::new();
+        let array = ManuallyDrop::new(array);
+        unsafe { ptr::copy_nonoverlapping(array.as_ptr(), vec.data.as_mut_ptr().cast(), M) };
+        vec.len = M;
+        vec
+    }
+
+    /// Creates a vector of `M` clones of `elem`, behind `array_vec![elem; M]`. More clones than the capacity fail to
+    /// build.
+    #[doc(hidden)]
+    pub fn from_elem<const M: usize>(elem: T) -> Self
+    where
+        T: Clone,
+    {
+        const { assert!(M <= N, "more elements than the capacity of the ArrayVec") };
+
+        let mut vec = Self::new();
+        vec.extend(iter::repeat_n(elem, M));
+        vec
+    }
+
+    /// Returns the number of elements in the vector.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 8> = vec::ArrayVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let vec: vec::ArrayVec<i32, 8> = vec::ArrayVec::new();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the vector holds `N` elements.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 1> = vec::ArrayVec::new();
+    /// vec.push(1);
+    /// assert!(vec.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns the number of elements the vector can hold, `N`.
+    /// # Example
+    /// ```
+    /// let vec: vec::ArrayVec<i32, 8> = vec::ArrayVec::new();
+    /// assert_eq!(vec.capacity(), 8);
+    /// ```
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Appends an element to the back of the vector.
+    /// # Panics
+    /// Panics if the vector is full.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<char, 2> = vec::ArrayVec::new();
+    /// vec.push('a');
+    /// assert_eq!(vec[0], 'a');
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        if self.try_push(elem).is_err() {
+            panic!("ArrayVec is full (capacity {})", N);
+        }
+    }
+
+    /// Appends an element to the back of the vector, giving it back if the vector is full.
+    /// # Errors
+    /// Returns a [`CapacityError`] holding `elem` if the vector is full.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 1> = vec::ArrayVec::new();
+    /// assert!(vec.try_push(1).is_ok());
+    /// assert_eq!(vec.try_push(2).unwrap_err().element, 2);
+    /// ```
+    pub fn try_push(&mut self, elem: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError { element: elem });
+        }
+
+        unsafe { ptr::write(self.as_mut_ptr().add(self.len), elem) };
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 2> = vec::ArrayVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.pop(), Some(1));
+    /// assert_eq!(vec.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+
+        unsafe {
+            let elem = ptr::read(self.as_mut_ptr().add(self.len));
+            poison(self.as_mut_ptr().add(self.len), 1);
+            Some(elem)
+        }
+    }
+
+    /// Inserts an element at `index`, shifting all the elements after it to the right.
+    /// # Panics
+    /// Panics if `index > len` or if the vector is full.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 4> = vec::ArrayVec::new();
+    /// vec.push(1);
+    /// vec.push(3);
+    /// vec.insert(1, 2);
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        if self.try_insert(index, elem).is_err() {
+            panic!("ArrayVec is full (capacity {})", N);
+        }
+    }
+
+    /// Inserts an element at `index`, shifting all the elements after it to the right, giving it back if the vector
+    /// is full.
+    /// # Errors
+    /// Returns a [`CapacityError`] holding `elem` if the vector is full.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 1> = vec::ArrayVec::new();
+    /// vec.try_insert(0, 2).unwrap();
+    /// assert!(vec.try_insert(0, 1).is_err());
+    /// ```
+    pub fn try_insert(&mut self, index: usize, elem: T) -> Result<(), CapacityError<T>> {
+        assert!(index <= self.len, "Index out of bounds");
+
+        if self.is_full() {
+            return Err(CapacityError { element: elem });
+        }
+
+        unsafe { insert_at(self.as_mut_ptr(), self.len, index, elem) };
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes and returns the element at `index`, shifting all the elements after it to the left.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 4> = vec::ArrayVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.remove(1), 1);
+    /// assert_eq!(&*vec, &[0, 2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        let elem = unsafe { remove_at(self.as_mut_ptr(), self.len, index) };
+        self.len -= 1;
+
+        elem
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if `len` is greater than the length.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 8> = vec::ArrayVec::new();
+    /// vec.extend(0..5);
+    /// vec.truncate(2);
+    /// assert_eq!(&*vec, &[0, 1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        let ptr = self.as_mut_ptr();
+
+        unsafe { truncate_at(ptr, &mut self.len, len) }
+    }
+
+    /// Removes every element.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 8> = vec::ArrayVec::new();
+    /// vec.extend(0..5);
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Removes every element, returning them in an iterator. Elements that aren't consumed are dropped along with
+    /// the iterator.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 8> = vec::ArrayVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.drain().sum::<i32>(), 3);
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, N> {
+        unsafe {
+            let iter = RawValIter::new(ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), self.len));
+
+            self.len = 0;
+
+            Drain {
+                iter,
+                marker: PhantomData,
+            }
+        }
+    }
+
+    fn as_ptr(&self) -> *const T {
+        self.data.as_ptr() as *const T
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr() as *mut T
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Extend<T> for ArrayVec<T, N> {
+    /// Appends every element of an iterator.
+    /// # Panics
+    /// Panics if the iterator yields more elements than there is room left.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 4> = vec::ArrayVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::new();
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        if mem::needs_drop::<T>() {
+            unsafe { ptr::drop_in_place(&mut **self as *mut [T]) }
+        }
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    /// Consumes the vector into an iterator over its elements.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::ArrayVec<i32, 4> = vec::ArrayVec::new();
+    /// vec.extend(0..3);
+    /// let mut iter = vec.into_iter();
+    /// assert_eq!(iter.next(), Some(0));
+    /// assert_eq!(iter.next_back(), Some(2));
+    /// ```
+    fn into_iter(self) -> IntoIter<T, N> {
+        let vec = mem::ManuallyDrop::new(self);
+
+        IntoIter {
+            start: 0,
+            end: vec.len,
+            // The elements are now owned by the iterator
+            data: unsafe { ptr::read(&vec.data) },
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over an [`ArrayVec`], created by its `IntoIterator` implementation.
+pub struct IntoIter<T, const N: usize> {
+    // the elements in `start..end` are initialized
+    start: usize,
+    end: usize,
+    data: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.start += 1;
+        Some(unsafe { self.data[self.start - 1].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { self.data[self.end].assume_init_read() })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining = &mut self.data[self.start..self.end];
+
+        unsafe { ptr::drop_in_place(remaining as *mut [MaybeUninit<T>] as *mut [T]) }
+    }
+}
+
+/// Draining iterator over an [`ArrayVec`], created by [`ArrayVec::drain`].
+pub struct Drain<'a, T, const N: usize> {
+    iter: RawValIter<T>,
+    marker: PhantomData<&'a mut ArrayVec<T, N>>,
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back()
+    }
+}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        self.iter.drop_remaining();
+    }
+}
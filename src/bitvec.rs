@@ -0,0 +1,328 @@
+use std::{
+    fmt,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign},
+};
+
+use crate::Vec;
+
+const BITS: usize = usize::BITS as usize;
+
+/// Vector of bits packed into `usize` blocks, taking one bit of memory per element.
+/// # Example
+/// ```
+/// use vec::BitVec;
+///
+/// let mut bits = BitVec::new();
+/// bits.push(true);
+/// bits.push(false);
+/// bits.push(true);
+///
+/// assert_eq!(bits.get(2), Some(true));
+/// assert_eq!(bits.count_ones(), 2);
+/// assert_eq!(bits.iter_ones().collect::<std::vec::Vec<_>>(), [0, 2]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct BitVec {
+    // bit `i` lives in block `i / BITS` at position `i % BITS`, bits past `len` are always zero
+    blocks: Vec<usize>,
+    len: usize,
+}
+
+impl BitVec {
+    /// Creates an empty bit vector, without allocating.
+    /// # Example
+    /// ```
+    /// let bits = vec::BitVec::new();
+    /// assert!(bits.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates a bit vector of `len` bits, all set to `value`.
+    /// # Example
+    /// ```
+    /// let bits = vec::BitVec::repeat(true, 100);
+    /// assert_eq!(bits.len(), 100);
+    /// assert_eq!(bits.count_ones(), 100);
+    /// ```
+    pub fn repeat(value: bool, len: usize) -> Self {
+        let block = if value { !0 } else { 0 };
+        let mut blocks = Vec::with_capacity(len.div_ceil(BITS));
+        blocks.extend((0..len.div_ceil(BITS)).map(|_| block));
+
+        let mut bits = Self { blocks, len };
+        bits.clear_unused();
+        bits
+    }
+
+    /// Returns the number of bits.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::new();
+    /// bits.push(false);
+    /// assert_eq!(bits.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no bits.
+    /// # Example
+    /// ```
+    /// let bits = vec::BitVec::new();
+    /// assert!(bits.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a bit.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::new();
+    /// bits.push(true);
+    /// assert_eq!(bits.get(0), Some(true));
+    /// ```
+    pub fn push(&mut self, value: bool) {
+        if self.len.is_multiple_of(BITS) {
+            self.blocks.push(0);
+        }
+
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    /// Removes the last bit and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::new();
+    /// bits.push(true);
+    /// assert_eq!(bits.pop(), Some(true));
+    /// assert_eq!(bits.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<bool> {
+        let value = self.get(self.len.checked_sub(1)?)?;
+
+        self.set(self.len - 1, false);
+        self.len -= 1;
+
+        if self.len.is_multiple_of(BITS) {
+            self.blocks.pop();
+        }
+
+        Some(value)
+    }
+
+    /// Returns the bit at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let bits = vec::BitVec::repeat(false, 3);
+    /// assert_eq!(bits.get(2), Some(false));
+    /// assert_eq!(bits.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.len {
+            return None;
+        }
+
+        Some(self.blocks[index / BITS] & (1 << (index % BITS)) != 0)
+    }
+
+    /// Sets the bit at `index` to `value`.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::repeat(false, 3);
+    /// bits.set(1, true);
+    /// assert_eq!(bits.get(1), Some(true));
+    /// ```
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "index out of bounds");
+
+        let block = &mut self.blocks[index / BITS];
+        let mask = 1 << (index % BITS);
+
+        if value {
+            *block |= mask;
+        } else {
+            *block &= !mask;
+        }
+    }
+
+    /// Returns the number of bits set to `true`.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::repeat(false, 200);
+    /// bits.set(0, true);
+    /// bits.set(199, true);
+    /// assert_eq!(bits.count_ones(), 2);
+    /// ```
+    pub fn count_ones(&self) -> usize {
+        self.blocks.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// Returns the number of bits set to `true` before `index` (the rank of `index`).
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::repeat(true, 100);
+    /// bits.set(10, false);
+    /// assert_eq!(bits.rank(10), 10);
+    /// assert_eq!(bits.rank(100), 99);
+    /// ```
+    pub fn rank(&self, index: usize) -> usize {
+        assert!(index <= self.len, "index out of bounds");
+
+        let full: usize = self.blocks[..index / BITS]
+            .iter()
+            .map(|b| b.count_ones() as usize)
+            .sum();
+        let partial = match index % BITS {
+            0 => 0,
+            rem => (self.blocks[index / BITS] & ((1 << rem) - 1)).count_ones() as usize,
+        };
+
+        full + partial
+    }
+
+    /// Returns an iterator over the bits.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::new();
+    /// bits.push(true);
+    /// bits.push(false);
+    /// assert_eq!(bits.iter().collect::<std::vec::Vec<_>>(), [true, false]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |i| self.blocks[i / BITS] & (1 << (i % BITS)) != 0)
+    }
+
+    /// Returns an iterator over the indices of the bits set to `true`, in ascending order.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::repeat(false, 130);
+    /// bits.set(3, true);
+    /// bits.set(129, true);
+    /// assert_eq!(bits.iter_ones().collect::<std::vec::Vec<_>>(), [3, 129]);
+    /// ```
+    pub fn iter_ones(&self) -> IterOnes<'_> {
+        IterOnes {
+            blocks: &self.blocks,
+            index: 0,
+            current: self.blocks.first().copied().unwrap_or(0),
+        }
+    }
+
+    /// Removes every bit, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut bits = vec::BitVec::repeat(true, 10);
+    /// bits.clear();
+    /// assert!(bits.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+        self.len = 0;
+    }
+
+    // Zeroes the bits of the last block past `len`
+    fn clear_unused(&mut self) {
+        if let (Some(last), rem @ 1..) = (self.blocks.last_mut(), self.len % BITS) {
+            *last &= (1 << rem) - 1;
+        }
+    }
+
+    // Combines every block with the matching one of `other`
+    fn zip_blocks(&mut self, other: &BitVec, op: impl Fn(usize, usize) -> usize) {
+        assert_eq!(self.len, other.len, "bit vectors have different lengths");
+
+        for (a, b) in self.blocks.iter_mut().zip(other.blocks.iter()) {
+            *a = op(*a, *b);
+        }
+    }
+}
+
+impl Default for BitVec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in self.iter() {
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Extend<bool> for BitVec {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for bit in iter {
+            self.push(bit);
+        }
+    }
+}
+
+macro_rules! impl_bit_op {
+    ( $( $op:ident, $method:ident, $assign:ident, $assign_method:ident, $f:expr );* ) => {
+        $(
+            impl $assign<&BitVec> for BitVec {
+                /// # Panics
+                /// Panics if the vectors have different lengths.
+                fn $assign_method(&mut self, other: &BitVec) {
+                    self.zip_blocks(other, $f)
+                }
+            }
+
+            impl $op for &BitVec {
+                type Output = BitVec;
+
+                /// # Panics
+                /// Panics if the vectors have different lengths.
+                fn $method(self, other: &BitVec) -> BitVec {
+                    let mut result = self.clone();
+                    result.zip_blocks(other, $f);
+                    result
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+
+/// Iterator over the indices of the set bits of a [`BitVec`], created by [`BitVec::iter_ones`].
+pub struct IterOnes<'a> {
+    blocks: &'a [usize],
+    // block being scanned, and its bits not yielded yet
+    index: usize,
+    current: usize,
+}
+
+impl Iterator for IterOnes<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.blocks.get(self.index)?;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        // Clear the lowest set bit
+        self.current &= self.current - 1;
+
+        Some(self.index * BITS + bit)
+    }
+}
@@ -0,0 +1,412 @@
+use std::{
+    alloc::{Allocator, Global},
+    cmp, fmt,
+    iter::FromIterator,
+    mem,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    raw::{handle_reserve_error, RawVec},
+    TryReserveError, Vec,
+};
+
+/// Contiguous vector that grows at both ends: the elements sit in the middle of the allocation with spare capacity
+/// on each side, so [`push_front`](BiVec::push_front) is amortized O(1) like [`push_back`](BiVec::push_back).
+///
+/// Unlike [`VecDeque`](crate::VecDeque) the elements never wrap around, so the vector always derefs to a single
+/// slice. When one end runs out of room the elements are re-centered, in place if the allocation has room to spare
+/// and after growing it otherwise.
+/// # Example
+/// ```
+/// use vec::BiVec;
+///
+/// let mut v = BiVec::new();
+/// v.push_back(2);
+/// v.push_front(1);
+/// v.push_back(3);
+/// v.push_front(0);
+///
+/// assert_eq!(&*v, &[0, 1, 2, 3]);
+/// assert_eq!(v.pop_front(), Some(0));
+/// assert_eq!(v[..2], [1, 2]);
+/// ```
+pub struct BiVec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    // elements live in `head..head + len`
+    head: usize,
+    len: usize,
+    buf: RawVec<T, A, G>,
+}
+
+impl<T> BiVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::BiVec<i32> = vec::BiVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates an empty vector with room for `capacity` elements, split evenly between the two ends.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v: vec::BiVec<i32> = vec::BiVec::with_capacity(12);
+    /// assert_eq!((v.front_capacity(), v.back_capacity()), (6, 6));
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut v = Self::new();
+        v.relocate(0, 0, capacity);
+        v
+    }
+}
+
+impl<T> Default for BiVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, A: Allocator> BiVec<T, A> {
+    /// Creates an empty vector that will allocate from `alloc`.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Arena, BiVec};
+    /// let arena = Arena::new();
+    /// let mut v = BiVec::new_in(&arena);
+    /// v.push_front(1);
+    /// assert_eq!(v[0], 1);
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            head: 0,
+            len: 0,
+            buf: RawVec::new_in(alloc),
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> BiVec<T, A, G> {
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let mut v = vec::BiVec::new();
+    /// v.push_front(1);
+    /// v.push_back(2);
+    /// assert_eq!(v.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::BiVec<u8> = vec::BiVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the allocation can hold, on both sides of the elements included.
+    /// # Example
+    /// ```
+    /// let v: vec::BiVec<u8> = vec::BiVec::with_capacity(16);
+    /// assert_eq!(v.capacity(), 16);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.buf.cap
+    }
+
+    /// Returns the number of elements that can be pushed to the front without moving the elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::BiVec<u8> = vec::BiVec::with_capacity(16);
+    /// v.push_front(1);
+    /// assert_eq!(v.front_capacity(), 7);
+    /// ```
+    pub fn front_capacity(&self) -> usize {
+        self.head
+    }
+
+    /// Returns the number of elements that can be pushed to the back without moving the elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::BiVec<u8> = vec::BiVec::with_capacity(16);
+    /// v.push_front(1);
+    /// assert_eq!(v.back_capacity(), 8);
+    /// ```
+    pub fn back_capacity(&self) -> usize {
+        self.buf.cap - self.head - self.len
+    }
+
+    /// Prepends an element.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::BiVec::new();
+    /// v.push_front(2);
+    /// v.push_front(1);
+    /// assert_eq!(&*v, &[1, 2]);
+    /// ```
+    pub fn push_front(&mut self, elem: T) {
+        self.reserve_front(1);
+
+        self.head -= 1;
+        unsafe { ptr::write(self.ptr().add(self.head), elem) };
+        self.len += 1;
+    }
+
+    /// Appends an element.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::BiVec::new();
+    /// v.push_back(1);
+    /// v.push_back(2);
+    /// assert_eq!(&*v, &[1, 2]);
+    /// ```
+    pub fn push_back(&mut self, elem: T) {
+        self.reserve_back(1);
+
+        unsafe { ptr::write(self.ptr().add(self.head + self.len), elem) };
+        self.len += 1;
+    }
+
+    /// Removes the first element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::BiVec::new();
+    /// v.push_back(1);
+    /// assert_eq!(v.pop_front(), Some(1));
+    /// assert_eq!(v.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.head += 1;
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.head - 1))) }
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::BiVec::new();
+    /// v.push_front(1);
+    /// assert_eq!(v.pop_back(), Some(1));
+    /// assert_eq!(v.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.head + self.len))) }
+    }
+
+    /// Makes room for at least `additional` more elements at the front.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::BiVec<u8> = vec::BiVec::new();
+    /// v.reserve_front(10);
+    /// assert!(v.front_capacity() >= 10);
+    /// ```
+    pub fn reserve_front(&mut self, additional: usize) {
+        if self.head < additional {
+            self.relocate(additional, 0, 0);
+        }
+    }
+
+    /// Makes room for at least `additional` more elements at the back.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::BiVec<u8> = vec::BiVec::new();
+    /// v.reserve_back(10);
+    /// assert!(v.back_capacity() >= 10);
+    /// ```
+    pub fn reserve_back(&mut self, additional: usize) {
+        if self.back_capacity() < additional {
+            self.relocate(0, additional, 0);
+        }
+    }
+
+    /// Shortens the vector to its first `len` elements, doing nothing if it's not longer.
+    /// # Example
+    /// ```
+    /// let mut v = vec::BiVec::new();
+    /// v.extend([1, 2, 3]);
+    /// v.truncate(1);
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let tail = ptr::slice_from_raw_parts_mut(
+            unsafe { self.ptr().add(self.head + len) },
+            self.len - len,
+        );
+        // Shorten first, so a panicking destructor can't cause an element to be dropped twice
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) }
+    }
+
+    /// Removes every element, keeping the allocated capacity. The free space is split evenly between the two ends.
+    /// # Example
+    /// ```
+    /// let mut v = vec::BiVec::new();
+    /// v.push_back(1);
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = self.buf.cap / 2;
+    }
+
+    fn ptr(&self) -> *mut T {
+        self.buf.ptr.as_ptr()
+    }
+
+    // Moves the elements so at least `front` slots are free before them and `back` after them, growing the buffer
+    // to at least `min_cap` and to twice the room needed, so alternating pushes at both ends stay amortized O(1).
+    // The free space is split evenly between the two ends.
+    fn relocate(&mut self, front: usize, back: usize, min_cap: usize) {
+        let needed = self
+            .len
+            .checked_add(front)
+            .and_then(|n| n.checked_add(back))
+            .unwrap_or_else(|| handle_reserve_error(TryReserveError::CapacityOverflow));
+        let wanted = cmp::max(needed.saturating_mul(2), min_cap);
+
+        if self.buf.cap < wanted {
+            // The reallocation keeps everything up to the end of the elements in place
+            let used = self.head + self.len;
+            if let Err(err) = self.buf.try_reserve(used, wanted - used) {
+                handle_reserve_error(err)
+            }
+        }
+
+        let head = front + (self.buf.cap - needed) / 2;
+        unsafe { ptr::copy(self.ptr().add(self.head), self.ptr().add(head), self.len) };
+        self.head = head;
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Deref for BiVec<T, A, G> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr().add(self.head), self.len) }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for BiVec<T, A, G> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr().add(self.head), self.len) }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<Vec<T, A, G>> for BiVec<T, A, G> {
+    /// Turns a vector into a `BiVec`, reusing its allocation. Its spare capacity is all at the back.
+    /// # Example
+    /// ```
+    /// use vec::{BiVec, Vec};
+    /// let mut vec = Vec::new();
+    /// vec.extend(0..3);
+    /// let mut v = BiVec::from(vec);
+    /// v.push_front(-1);
+    /// assert_eq!(&*v, &[-1, 0, 1, 2]);
+    /// ```
+    fn from(vec: Vec<T, A, G>) -> Self {
+        let (buf, len) = vec.into_raw_parts();
+
+        Self { head: 0, len, buf }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<BiVec<T, A, G>> for Vec<T, A, G> {
+    /// Turns a `BiVec` into a vector, reusing its allocation. The elements are moved to the start of the buffer
+    /// first.
+    /// # Example
+    /// ```
+    /// use vec::{BiVec, Vec};
+    /// let mut v = BiVec::new();
+    /// v.push_front(2);
+    /// v.push_front(1);
+    /// assert_eq!(&*Vec::from(v), &[1, 2]);
+    /// ```
+    fn from(v: BiVec<T, A, G>) -> Self {
+        let v = mem::ManuallyDrop::new(v);
+
+        unsafe {
+            ptr::copy(v.ptr().add(v.head), v.ptr(), v.len);
+            Vec::from_raw_parts(ptr::read(&v.buf), v.len)
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for BiVec<T, A, G> {
+    /// Appends every element of an iterator.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve_back(iter.size_hint().0);
+
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for BiVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T: Clone> Clone for BiVec<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for BiVec<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> PartialEq for BiVec<T, A, G> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for BiVec<T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for BiVec<T, A, G> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(&mut self[..]) }
+        // Deallocation is handled by RawVec
+    }
+}
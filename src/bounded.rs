@@ -0,0 +1,299 @@
+use std::{
+    alloc::{Allocator, Global},
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    error::CapacityError,
+    growth::{Capped, Doubling, GrowthPolicy},
+    IntoIter, Vec,
+};
+
+/// Heap-backed vector refusing to grow past a maximum length set at construction. Its capacity doesn't grow past
+/// it either, apart from the few elements the first allocation always makes room for.
+///
+/// Meant for decoding untrusted input: adding an element past the bound returns a [`CapacityError`] instead of
+/// allocating more memory.
+/// # Example
+/// ```
+/// use vec::BoundedVec;
+///
+/// let mut vec = BoundedVec::new(6);
+/// vec.extend(0..6).unwrap();
+///
+/// let err = vec.push(6).unwrap_err();
+/// assert_eq!(err.element, 6);
+/// assert_eq!(&*vec, &[0, 1, 2, 3, 4, 5]);
+/// assert_eq!(vec.capacity(), 6);
+/// ```
+pub struct BoundedVec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    vec: Vec<T, A, Capped<G>>,
+}
+
+impl<T> BoundedVec<T> {
+    /// Creates an empty vector holding at most `max_len` elements, without allocating.
+    /// # Example
+    /// ```
+    /// let vec: vec::BoundedVec<u8> = vec::BoundedVec::new(16);
+    /// assert_eq!(vec.max_len(), 16);
+    /// assert_eq!(vec.capacity(), 0);
+    /// ```
+    pub fn new(max_len: usize) -> Self {
+        Self::new_in(max_len, Global)
+    }
+}
+
+impl<T, A: Allocator> BoundedVec<T, A> {
+    /// Creates an empty vector holding at most `max_len` elements, that will allocate from `alloc`.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Arena, BoundedVec};
+    /// let arena = Arena::new();
+    /// let mut vec = BoundedVec::new_in(2, &arena);
+    /// vec.push(1).unwrap();
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn new_in(max_len: usize, alloc: A) -> Self {
+        Self::with_growth_policy_in(max_len, Doubling, alloc)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> BoundedVec<T, A, G> {
+    /// Creates an empty vector holding at most `max_len` elements, growing according to `growth` up to that
+    /// length.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use vec::{BoundedVec, FixedStep};
+    /// let mut vec = BoundedVec::with_growth_policy_in(100, FixedStep(8), Global);
+    /// vec.push(1u64).unwrap();
+    /// assert_eq!(vec.capacity(), 8);
+    /// ```
+    pub fn with_growth_policy_in(max_len: usize, growth: G, alloc: A) -> Self {
+        Self {
+            vec: Vec::with_growth_policy_in(
+                Capped {
+                    max: max_len,
+                    growth,
+                },
+                alloc,
+            ),
+        }
+    }
+
+    /// Returns the maximum number of elements the vector accepts.
+    /// # Example
+    /// ```
+    /// let vec: vec::BoundedVec<u8> = vec::BoundedVec::new(16);
+    /// assert_eq!(vec.max_len(), 16);
+    /// ```
+    pub fn max_len(&self) -> usize {
+        self.vec.buf.growth_policy().max
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(6);
+    /// for i in 0..5u32 {
+    ///     vec.push(i).unwrap();
+    /// }
+    /// // Doubling from 4 would give 8
+    /// assert_eq!(vec.capacity(), 6);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns `true` if the vector holds `max_len` elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(1);
+    /// vec.push(1).unwrap();
+    /// assert!(vec.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.vec.len >= self.max_len()
+    }
+
+    /// Appends an element to the back of the vector, giving it back if the vector is full.
+    /// # Errors
+    /// Returns a [`CapacityError`] holding `elem` if the vector already holds `max_len` elements.
+    /// # Panics
+    /// Panics if the capacity overflows, aborts if the allocator fails.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(1);
+    /// assert!(vec.push(1).is_ok());
+    /// assert_eq!(vec.push(2).unwrap_err().element, 2);
+    /// ```
+    pub fn push(&mut self, elem: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError { element: elem });
+        }
+
+        self.vec.push(elem);
+
+        Ok(())
+    }
+
+    /// Inserts an element at `index`, shifting all the elements after it to the right, giving it back if the vector
+    /// is full.
+    /// # Errors
+    /// Returns a [`CapacityError`] holding `elem` if the vector already holds `max_len` elements.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(2);
+    /// vec.push(2).unwrap();
+    /// vec.insert(0, 1).unwrap();
+    /// assert!(vec.insert(0, 0).is_err());
+    /// assert_eq!(&*vec, &[1, 2]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) -> Result<(), CapacityError<T>> {
+        assert!(index <= self.vec.len, "Index out of bounds");
+
+        if self.is_full() {
+            return Err(CapacityError { element: elem });
+        }
+
+        self.vec.insert(index, elem);
+
+        Ok(())
+    }
+
+    /// Appends every element of an iterator, stopping at the first one that doesn't fit.
+    ///
+    /// Elements pushed before the failure are kept, the rest of the iterator is dropped.
+    /// # Errors
+    /// Returns a [`CapacityError`] holding the first element past `max_len`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(3);
+    /// let err = vec.extend(0..10).unwrap_err();
+    /// assert_eq!(err.element, 3);
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), CapacityError<T>> {
+        let mut iter = iter.into_iter();
+
+        // Reserve what the iterator promises, up to the bound
+        let (lower, _) = iter.size_hint();
+        let room = self.max_len().saturating_sub(self.vec.len);
+        self.vec.reserve(lower.min(room));
+
+        iter.try_for_each(|elem| self.push(elem))
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(2);
+    /// vec.push(1).unwrap();
+    /// assert_eq!(vec.pop(), Some(1));
+    /// assert_eq!(vec.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Removes and returns the element at `index`, shifting all the elements after it to the left.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(4);
+    /// vec.extend(0..3).unwrap();
+    /// assert_eq!(vec.remove(1), 1);
+    /// assert_eq!(&*vec, &[0, 2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        self.vec.remove(index)
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if `len` is greater than the length.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(8);
+    /// vec.extend(0..5).unwrap();
+    /// vec.truncate(2);
+    /// assert_eq!(&*vec, &[0, 1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len)
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::BoundedVec::new(8);
+    /// vec.extend(0..5).unwrap();
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Returns the underlying vector, whose growth policy still caps the capacity but no longer the length.
+    /// # Example
+    /// ```
+    /// let mut bounded = vec::BoundedVec::new(2);
+    /// bounded.extend(0..2).unwrap();
+    ///
+    /// let mut vec = bounded.into_inner();
+    /// vec.push(2);
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    pub fn into_inner(self) -> Vec<T, A, Capped<G>> {
+        self.vec
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Deref for BoundedVec<T, A, G> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for BoundedVec<T, A, G> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for BoundedVec<T, A, G> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for BoundedVec<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> PartialEq for BoundedVec<T, A, G> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for BoundedVec<T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for BoundedVec<T, A, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A, Capped<G>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut, Index, IndexMut},
+};
+
+use crate::Vec;
+
+// Invariant in `'id`, so the compiler can't shrink or grow one brand into another
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/// Index proven in bounds of the [`BrandedVec`] of brand `'id`, by [`check`](BrandedVec::check) or
+/// [`push`](BrandedVec::push).
+///
+/// The vector never shrinks, so the proof holds for as long as the brand exists and indexing with it skips the bounds
+/// check.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BrandedIndex<'id> {
+    index: usize,
+    brand: Brand<'id>,
+}
+
+impl BrandedIndex<'_> {
+    /// Returns the position this index stands for.
+    /// # Example
+    /// ```
+    /// vec::BrandedVec::scope(vec::Vec::new(), |mut v| {
+    ///     let i = v.push('a');
+    ///     assert_eq!(i.index(), 0);
+    /// });
+    /// ```
+    pub fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Debug for BrandedIndex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.index.fmt(f)
+    }
+}
+
+/// Vector with a unique brand, the lifetime `'id`, whose indices are checked once and then used without bounds checks,
+/// GhostCell-style.
+///
+/// A `BrandedVec` only exists inside the closure given to [`scope`](BrandedVec::scope), with a brand no other vector
+/// has. [`check`](BrandedVec::check) turns a `usize` into a [`BrandedIndex`] of that brand, and since the vector can
+/// grow but never shrink, every index of its brand stays in bounds: indexing with one is unchecked yet safe. Using an
+/// index of one vector on another is a compile error.
+/// # Example
+/// ```
+/// use vec::{BrandedVec, Vec};
+///
+/// let mut code = Vec::new();
+/// code.extend([1, 2, 3, 4]);
+///
+/// let sum = BrandedVec::scope(code, |code| {
+///     // Checked once, outside of the hot loop
+///     let entry = code.check(0).unwrap();
+///     let mut sum = 0;
+///     for _ in 0..1000 {
+///         sum += code[entry];
+///     }
+///     sum
+/// });
+/// assert_eq!(sum, 1000);
+/// ```
+///
+/// Indices of another vector are rejected at compile time:
+/// ```compile_fail
+/// use vec::{BrandedVec, Vec};
+///
+/// BrandedVec::scope(Vec::<u8>::new(), |mut a| {
+///     BrandedVec::scope(Vec::<u8>::new(), |b| {
+///         let i = a.push(1);
+///         b[i];
+///     });
+/// });
+/// ```
+pub struct BrandedVec<'id, T> {
+    // never shrinks, so indices checked against it stay in bounds
+    vec: Vec<T>,
+    brand: Brand<'id>,
+}
+
+impl<T> BrandedVec<'_, T> {
+    /// Calls `f` with `vec` as a vector of a new brand, and returns its result. Return
+    /// [`into_vec`](BrandedVec::into_vec) from `f` to get the vector back.
+    /// # Example
+    /// ```
+    /// use vec::{BrandedVec, Vec};
+    ///
+    /// let vec = BrandedVec::scope(Vec::new(), |mut v| {
+    ///     v.push(1);
+    ///     v.into_vec()
+    /// });
+    /// assert_eq!(&*vec, &[1]);
+    /// ```
+    pub fn scope<R, F: for<'id> FnOnce(BrandedVec<'id, T>) -> R>(vec: Vec<T>, f: F) -> R {
+        f(BrandedVec {
+            vec,
+            brand: PhantomData,
+        })
+    }
+}
+
+impl<'id, T> BrandedVec<'id, T> {
+    /// Returns the proof that `index` is in bounds, or `None` if it isn't.
+    /// # Example
+    /// ```
+    /// vec::BrandedVec::scope(vec::Vec::new(), |mut v| {
+    ///     v.push('a');
+    ///     assert_eq!(v.check(0).map(|i| v[i]), Some('a'));
+    ///     assert!(v.check(1).is_none());
+    /// });
+    /// ```
+    pub fn check(&self, index: usize) -> Option<BrandedIndex<'id>> {
+        if index < self.vec.len {
+            Some(BrandedIndex {
+                index,
+                brand: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Appends an element and returns its index.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// vec::BrandedVec::scope(vec::Vec::new(), |mut v| {
+    ///     let i = v.push('a');
+    ///     v.push('b');
+    ///     assert_eq!(v[i], 'a');
+    /// });
+    /// ```
+    pub fn push(&mut self, elem: T) -> BrandedIndex<'id> {
+        self.vec.push(elem);
+
+        BrandedIndex {
+            index: self.vec.len - 1,
+            brand: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the element at `index`, without checking bounds.
+    /// # Example
+    /// ```
+    /// vec::BrandedVec::scope(vec::Vec::new(), |mut v| {
+    ///     let i = v.push('a');
+    ///     assert_eq!(v.get(i), &'a');
+    /// });
+    /// ```
+    pub fn get(&self, index: BrandedIndex<'id>) -> &T {
+        // The brand proves `index` was in bounds, and the vector never shrinks
+        unsafe { self.vec.get_unchecked(index.index) }
+    }
+
+    /// Returns a mutable reference to the element at `index`, without checking bounds.
+    /// # Example
+    /// ```
+    /// vec::BrandedVec::scope(vec::Vec::new(), |mut v| {
+    ///     let i = v.push(1);
+    ///     *v.get_mut(i) += 1;
+    ///     assert_eq!(v[i], 2);
+    /// });
+    /// ```
+    pub fn get_mut(&mut self, index: BrandedIndex<'id>) -> &mut T {
+        // The brand proves `index` was in bounds, and the vector never shrinks
+        unsafe { self.vec.get_unchecked_mut(index.index) }
+    }
+
+    /// Returns an iterator over the indices of every element, in order.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([1, 2, 3]);
+    ///
+    /// vec::BrandedVec::scope(vec, |mut v| {
+    ///     for i in v.indices() {
+    ///         v[i] *= 10;
+    ///     }
+    ///     assert_eq!(&*v, &[10, 20, 30]);
+    /// });
+    /// ```
+    pub fn indices(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = BrandedIndex<'id>> + ExactSizeIterator {
+        (0..self.vec.len).map(|index| BrandedIndex {
+            index,
+            brand: PhantomData,
+        })
+    }
+
+    /// Returns the underlying vector, ending the brand.
+    /// # Example
+    /// ```
+    /// let vec = vec::BrandedVec::scope(vec::Vec::new(), |mut v| {
+    ///     v.push(1);
+    ///     v.into_vec()
+    /// });
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+}
+
+impl<T> Deref for BrandedVec<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+// Slices can't change their length, so this can't break a proof
+impl<T> DerefMut for BrandedVec<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+
+impl<'id, T> Index<BrandedIndex<'id>> for BrandedVec<'id, T> {
+    type Output = T;
+
+    fn index(&self, index: BrandedIndex<'id>) -> &T {
+        self.get(index)
+    }
+}
+
+impl<'id, T> IndexMut<BrandedIndex<'id>> for BrandedVec<'id, T> {
+    fn index_mut(&mut self, index: BrandedIndex<'id>) -> &mut T {
+        self.get_mut(index)
+    }
+}
+
+impl<T> Extend<T> for BrandedVec<'_, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.vec.extend(iter);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BrandedVec<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,464 @@
+use std::{cmp, convert::TryInto, fmt, io};
+
+use crate::{error::ByteBufError, Vec};
+
+/// Growable byte buffer for encoding and decoding binary formats.
+///
+/// Writes (`put_*`) append at the end, growing the buffer as needed. Reads (`get_*`) consume bytes from a cursor
+/// at the front and fail with [`ByteBufError::Underflow`] when not enough bytes are left, without moving the
+/// cursor.
+/// # Example
+/// ```
+/// use vec::ByteBuf;
+///
+/// let mut buf = ByteBuf::new();
+/// buf.put_u16_be(0xCAFE);
+/// buf.put_var_int(300);
+/// buf.put_slice(b"hi");
+///
+/// assert_eq!(buf.get_u16_be(), Ok(0xCAFE));
+/// assert_eq!(buf.get_var_int(), Ok(300));
+/// assert_eq!(buf.remaining(), 2);
+/// assert!(buf.get_u32_le().is_err());
+/// ```
+#[derive(Clone)]
+pub struct ByteBuf {
+    vec: Vec<u8>,
+    // read cursor, never past the end
+    pos: usize,
+}
+
+impl ByteBuf {
+    /// Creates an empty buffer, without allocating.
+    /// # Example
+    /// ```
+    /// let buf = vec::ByteBuf::new();
+    /// assert_eq!(buf.remaining(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            vec: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Creates an empty buffer with room for `capacity` bytes.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let buf = vec::ByteBuf::with_capacity(64);
+    /// assert!(buf.capacity() >= 64);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(capacity),
+            pos: 0,
+        }
+    }
+
+    /// Returns the number of bytes the buffer can hold without reallocating.
+    /// # Example
+    /// ```
+    /// assert_eq!(vec::ByteBuf::new().capacity(), 0);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the position of the read cursor, the number of bytes consumed since the last
+    /// [`compact`](ByteBuf::compact).
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_u32_le(1);
+    /// buf.get_u8().unwrap();
+    /// assert_eq!(buf.position(), 1);
+    /// ```
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bytes left to read.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_u32_le(1);
+    /// buf.get_u8().unwrap();
+    /// assert_eq!(buf.remaining(), 3);
+    /// ```
+    pub fn remaining(&self) -> usize {
+        self.vec.len - self.pos
+    }
+
+    /// Returns `true` if there are bytes left to read.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// assert!(!buf.has_remaining());
+    /// buf.put_u8(1);
+    /// assert!(buf.has_remaining());
+    /// ```
+    pub fn has_remaining(&self) -> bool {
+        self.remaining() > 0
+    }
+
+    /// Returns the bytes left to read.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_slice(b"abc");
+    /// buf.get_u8().unwrap();
+    /// assert_eq!(buf.chunk(), b"bc");
+    /// ```
+    pub fn chunk(&self) -> &[u8] {
+        &self.vec[self.pos..]
+    }
+
+    /// Appends a byte.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_u8(7);
+    /// assert_eq!(buf.chunk(), &[7]);
+    /// ```
+    pub fn put_u8(&mut self, n: u8) {
+        self.vec.push(n)
+    }
+
+    /// Appends a signed byte.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_i8(-1);
+    /// assert_eq!(buf.chunk(), &[0xFF]);
+    /// ```
+    pub fn put_i8(&mut self, n: i8) {
+        self.vec.push(n as u8)
+    }
+
+    /// Appends the bytes of `src`.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_slice(b"ab");
+    /// buf.put_slice(b"c");
+    /// assert_eq!(buf.chunk(), b"abc");
+    /// ```
+    pub fn put_slice(&mut self, src: &[u8]) {
+        self.vec.insert_slice(self.vec.len, src)
+    }
+
+    /// Appends `n` as a variable-length integer (unsigned LEB128): 7 bits per byte, least significant first, with
+    /// the high bit set on every byte but the last. Values below 128 take a single byte.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_var_int(300);
+    /// assert_eq!(buf.chunk(), &[0xAC, 0x02]);
+    /// ```
+    pub fn put_var_int(&mut self, n: u64) {
+        write_var_int(&mut self.vec, n)
+    }
+
+    /// Reads a byte.
+    /// # Errors
+    /// Returns [`ByteBufError::Underflow`] if no bytes are left.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_u8(7);
+    /// assert_eq!(buf.get_u8(), Ok(7));
+    /// assert!(buf.get_u8().is_err());
+    /// ```
+    pub fn get_u8(&mut self) -> Result<u8, ByteBufError> {
+        self.get_array().map(|[n]| n)
+    }
+
+    /// Reads a signed byte.
+    /// # Errors
+    /// Returns [`ByteBufError::Underflow`] if no bytes are left.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_u8(0xFF);
+    /// assert_eq!(buf.get_i8(), Ok(-1));
+    /// ```
+    pub fn get_i8(&mut self) -> Result<i8, ByteBufError> {
+        self.get_array().map(|[n]| n as i8)
+    }
+
+    /// Fills `dst` with the next bytes.
+    /// # Errors
+    /// Returns [`ByteBufError::Underflow`] if fewer than `dst.len()` bytes are left, without reading any.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_slice(b"abc");
+    ///
+    /// let mut dst = [0; 2];
+    /// buf.copy_to_slice(&mut dst).unwrap();
+    /// assert_eq!(&dst, b"ab");
+    /// ```
+    pub fn copy_to_slice(&mut self, dst: &mut [u8]) -> Result<(), ByteBufError> {
+        dst.copy_from_slice(self.take(dst.len())?);
+        Ok(())
+    }
+
+    /// Reads a variable-length integer written by [`put_var_int`](ByteBuf::put_var_int).
+    /// # Errors
+    /// Returns [`ByteBufError::Underflow`] if the buffer ends before the last byte of the integer, and
+    /// [`ByteBufError::Overflow`] if it doesn't fit in a `u64`. The cursor doesn't move on error.
+    /// # Example
+    /// ```
+    /// use vec::{ByteBuf, ByteBufError};
+    ///
+    /// let mut buf = ByteBuf::new();
+    /// buf.put_slice(&[0xAC, 0x02, 0x80]);
+    /// assert_eq!(buf.get_var_int(), Ok(300));
+    /// assert_eq!(buf.get_var_int(), Err(ByteBufError::Underflow { needed: 2, remaining: 1 }));
+    /// ```
+    pub fn get_var_int(&mut self) -> Result<u64, ByteBufError> {
+        let (n, size) = read_var_int(self.chunk())?;
+        self.pos += size;
+        Ok(n)
+    }
+
+    /// Skips the next `count` bytes.
+    /// # Errors
+    /// Returns [`ByteBufError::Underflow`] if fewer than `count` bytes are left.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_slice(b"abc");
+    /// buf.advance(2).unwrap();
+    /// assert_eq!(buf.chunk(), b"c");
+    /// ```
+    pub fn advance(&mut self, count: usize) -> Result<(), ByteBufError> {
+        self.take(count).map(drop)
+    }
+
+    /// Removes the next `count` bytes from the buffer and returns them in a new one, e.g. to hand a complete frame
+    /// to a decoder.
+    /// # Errors
+    /// Returns [`ByteBufError::Underflow`] if fewer than `count` bytes are left.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_slice(b"headbody");
+    ///
+    /// let head = buf.split_to(4).unwrap();
+    /// assert_eq!(head.chunk(), b"head");
+    /// assert_eq!(buf.chunk(), b"body");
+    /// ```
+    pub fn split_to(&mut self, count: usize) -> Result<ByteBuf, ByteBufError> {
+        let bytes = self.take(count)?;
+        let mut split = Self::with_capacity(bytes.len());
+        split.put_slice(bytes);
+        Ok(split)
+    }
+
+    /// Drops the bytes already read and moves the rest to the front, so the space they took can be written to
+    /// again. Resets the [`position`](ByteBuf::position).
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_slice(b"abc");
+    /// buf.advance(2).unwrap();
+    /// buf.compact();
+    /// assert_eq!((buf.position(), buf.chunk()), (0, &b"c"[..]));
+    /// ```
+    pub fn compact(&mut self) {
+        let len = self.vec.len;
+
+        self.vec.copy_within(self.pos.., 0);
+        self.vec.truncate(len - self.pos);
+        self.pos = 0;
+    }
+
+    /// Removes every byte and resets the cursor, keeping the allocation.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_u8(1);
+    /// buf.clear();
+    /// assert!(!buf.has_remaining());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear();
+        self.pos = 0;
+    }
+
+    /// Returns the bytes left to read, without copying if none were read.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::ByteBuf::new();
+    /// buf.put_slice(b"abc");
+    /// buf.advance(1).unwrap();
+    /// assert_eq!(&*buf.into_vec(), b"bc");
+    /// ```
+    pub fn into_vec(mut self) -> Vec<u8> {
+        self.compact();
+        self.vec
+    }
+
+    // Consumes the next `count` bytes
+    fn take(&mut self, count: usize) -> Result<&[u8], ByteBufError> {
+        let remaining = self.remaining();
+        if count > remaining {
+            return Err(ByteBufError::Underflow {
+                needed: count,
+                remaining,
+            });
+        }
+
+        self.pos += count;
+        Ok(&self.vec[self.pos - count..self.pos])
+    }
+
+    // Consumes the next `N` bytes
+    fn get_array<const N: usize>(&mut self) -> Result<[u8; N], ByteBufError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+}
+
+// Appends `n` as unsigned LEB128
+pub(crate) fn write_var_int(vec: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        vec.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    vec.push(n as u8)
+}
+
+// Decodes the unsigned LEB128 integer at the start of `bytes`, returning it and its size in bytes
+pub(crate) fn read_var_int(bytes: &[u8]) -> Result<(u64, usize), ByteBufError> {
+    let mut n = 0u64;
+
+    for (i, &byte) in bytes.iter().enumerate() {
+        let bits = u64::from(byte & 0x7F);
+        let shift = 7 * i as u32;
+
+        // The tenth byte can only hold the top bit of a u64
+        if shift >= u64::BITS || bits << shift >> shift != bits {
+            return Err(ByteBufError::Overflow);
+        }
+        n |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok((n, i + 1));
+        }
+    }
+
+    Err(ByteBufError::Underflow {
+        needed: bytes.len() + 1,
+        remaining: bytes.len(),
+    })
+}
+
+macro_rules! impl_int {
+    ( $( $t:ty, $put_be:ident, $put_le:ident, $get_be:ident, $get_le:ident );* ) => {
+        impl ByteBuf {
+            $(
+                #[doc = concat!("Appends a `", stringify!($t), "` in big-endian byte order.")]
+                /// # Panics
+                /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+                pub fn $put_be(&mut self, n: $t) {
+                    self.put_slice(&n.to_be_bytes())
+                }
+
+                #[doc = concat!("Appends a `", stringify!($t), "` in little-endian byte order.")]
+                /// # Panics
+                /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+                pub fn $put_le(&mut self, n: $t) {
+                    self.put_slice(&n.to_le_bytes())
+                }
+
+                #[doc = concat!("Reads a `", stringify!($t), "` in big-endian byte order.")]
+                /// # Errors
+                /// Returns [`ByteBufError::Underflow`] if not enough bytes are left, without reading any.
+                pub fn $get_be(&mut self) -> Result<$t, ByteBufError> {
+                    self.get_array().map(<$t>::from_be_bytes)
+                }
+
+                #[doc = concat!("Reads a `", stringify!($t), "` in little-endian byte order.")]
+                /// # Errors
+                /// Returns [`ByteBufError::Underflow`] if not enough bytes are left, without reading any.
+                pub fn $get_le(&mut self) -> Result<$t, ByteBufError> {
+                    self.get_array().map(<$t>::from_le_bytes)
+                }
+            )*
+        }
+    };
+}
+
+impl_int!(
+    u16, put_u16_be, put_u16_le, get_u16_be, get_u16_le;
+    u32, put_u32_be, put_u32_le, get_u32_be, get_u32_le;
+    u64, put_u64_be, put_u64_le, get_u64_be, get_u64_le;
+    u128, put_u128_be, put_u128_le, get_u128_be, get_u128_le;
+    i16, put_i16_be, put_i16_le, get_i16_be, get_i16_le;
+    i32, put_i32_be, put_i32_le, get_i32_be, get_i32_le;
+    i64, put_i64_be, put_i64_le, get_i64_be, get_i64_le;
+    i128, put_i128_be, put_i128_le, get_i128_be, get_i128_le;
+    f32, put_f32_be, put_f32_le, get_f32_be, get_f32_le;
+    f64, put_f64_be, put_f64_le, get_f64_be, get_f64_le
+);
+
+impl Default for ByteBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Vec<u8>> for ByteBuf {
+    /// Wraps a vector, with the cursor at its start.
+    fn from(vec: Vec<u8>) -> Self {
+        Self { vec, pos: 0 }
+    }
+}
+
+impl io::Write for ByteBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.put_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Read for ByteBuf {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = cmp::min(buf.len(), self.remaining());
+        self.copy_to_slice(&mut buf[..count]).unwrap();
+        Ok(count)
+    }
+}
+
+// Only the bytes left to read count, not the ones already consumed
+impl PartialEq for ByteBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.chunk() == other.chunk()
+    }
+}
+
+impl Eq for ByteBuf {}
+
+impl fmt::Debug for ByteBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ByteBuf")
+            .field("read", &&self.vec[..self.pos])
+            .field("remaining", &self.chunk())
+            .finish()
+    }
+}
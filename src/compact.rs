@@ -0,0 +1,439 @@
+use std::{
+    alloc::{Allocator, Global, Layout},
+    cmp, fmt,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+};
+
+#[cfg(feature = "stats")]
+use crate::stats::VecStats;
+use crate::{
+    growth::Doubling,
+    raw::{
+        grown_capacity, handle_reserve_error, insert_at, poison, realloc_buffer,
+        record_deallocation, record_growth, remove_at, truncate_at,
+    },
+    TryReserveError,
+};
+
+// Biggest length and capacity a `CompactVec` can reach
+const MAX: usize = u32::MAX as usize;
+
+/// Vector storing its length and capacity as `u32`, taking two words instead of three on 64-bit targets.
+///
+/// Meant for cache-dense containers of vectors. Going over `u32::MAX` elements is a capacity overflow: the
+/// fallible methods return [`TryReserveError::CapacityOverflow`] and the others panic.
+/// # Example
+/// ```
+/// use std::mem;
+/// use vec::CompactVec;
+///
+/// # #[cfg(not(feature = "stats"))]
+/// assert_eq!(mem::size_of::<CompactVec<u64>>(), mem::size_of::<usize>() + 8);
+///
+/// let mut vec = CompactVec::new();
+/// vec.extend(0..10);
+/// assert_eq!(vec.len(), 10);
+/// ```
+pub struct CompactVec<T> {
+    // pointer to the allocation
+    ptr: NonNull<T>,
+    len: u32,
+    // size of allocation, `u32::MAX` for zero-sized types
+    cap: u32,
+    #[cfg(feature = "stats")]
+    stats: VecStats,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for CompactVec<T> {}
+unsafe impl<T: Sync> Sync for CompactVec<T> {}
+
+impl<T> CompactVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let vec: vec::CompactVec<i32> = vec::CompactVec::new();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: if mem::size_of::<T>() == 0 {
+                u32::MAX
+            } else {
+                0
+            },
+            #[cfg(feature = "stats")]
+            stats: VecStats::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates an empty vector with room for `capacity` elements.
+    /// # Panics
+    /// Panics if `capacity` exceeds `u32::MAX`, aborts if the allocator fails.
+    /// # Example
+    /// ```
+    /// let vec: vec::CompactVec<u8> = vec::CompactVec::with_capacity(100);
+    /// assert_eq!(vec.capacity(), 100);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::try_with_capacity(capacity).unwrap_or_else(|err| handle_reserve_error(err))
+    }
+
+    /// Creates an empty vector with room for `capacity` elements, returning an error instead of panicking.
+    /// # Errors
+    /// Returns [`TryReserveError::CapacityOverflow`] if `capacity` exceeds `u32::MAX`, or the allocator's failure.
+    /// # Example
+    /// ```
+    /// use vec::{CompactVec, TryReserveError};
+    /// let err = CompactVec::<u8>::try_with_capacity(u32::MAX as usize + 1).unwrap_err();
+    /// assert_eq!(err, TryReserveError::CapacityOverflow);
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        let mut vec = Self::new();
+
+        if capacity > MAX {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        if capacity > vec.capacity() {
+            vec.try_grow_to(capacity, capacity)?;
+        }
+
+        Ok(vec)
+    }
+
+    /// Returns the number of elements in the vector.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let vec: vec::CompactVec<u8> = vec::CompactVec::new();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.push(1u32);
+    /// assert_eq!(vec.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.cap as usize
+    }
+
+    /// Returns how many times the buffer was reallocated and the peak capacity it reached, like
+    /// [`Vec::stats`](crate::Vec::stats).
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.extend(0..5u32);
+    /// assert_eq!(vec.stats().reallocations, 2);
+    /// assert_eq!(vec.stats().peak_capacity, 8);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> VecStats {
+        self.stats
+    }
+
+    /// Appends an element to the back of the vector.
+    /// # Panics
+    /// Panics if the vector already holds `u32::MAX` elements, aborts if the allocator fails.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.push('a');
+    /// assert_eq!(vec[0], 'a');
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        if let Err(err) = self.try_push(elem) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Appends an element to the back of the vector, returning an error instead of panicking if it can't grow.
+    /// # Errors
+    /// Returns [`TryReserveError::CapacityOverflow`] if the vector already holds `u32::MAX` elements, or the
+    /// allocator's failure. The element is dropped.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.try_push(1).unwrap();
+    /// assert_eq!(&*vec, &[1]);
+    /// ```
+    pub fn try_push(&mut self, elem: T) -> Result<(), TryReserveError> {
+        self.reserve_one()?;
+
+        unsafe { ptr::write(self.ptr.as_ptr().add(self.len()), elem) };
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.pop(), Some(1));
+    /// assert_eq!(vec.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+
+        unsafe {
+            let elem = ptr::read(self.ptr.as_ptr().add(self.len()));
+            poison(self.ptr.as_ptr().add(self.len()), 1);
+            Some(elem)
+        }
+    }
+
+    /// Inserts an element at `index`, shifting all the elements after it to the right.
+    /// # Panics
+    /// Panics if `index > len` or if the vector already holds `u32::MAX` elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.push(1);
+    /// vec.push(3);
+    /// vec.insert(1, 2);
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        if let Err(err) = self.try_insert(index, elem) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Inserts an element at `index`, returning an error instead of panicking if the vector can't grow.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Errors
+    /// Returns [`TryReserveError::CapacityOverflow`] if the vector already holds `u32::MAX` elements, or the
+    /// allocator's failure. The element is dropped.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.try_insert(0, 2).unwrap();
+    /// vec.try_insert(0, 1).unwrap();
+    /// assert_eq!(&*vec, &[1, 2]);
+    /// ```
+    pub fn try_insert(&mut self, index: usize, elem: T) -> Result<(), TryReserveError> {
+        assert!(index <= self.len(), "Index out of bounds");
+
+        self.reserve_one()?;
+
+        unsafe { insert_at(self.ptr.as_ptr(), self.len(), index, elem) };
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes and returns the element at `index`, shifting all the elements after it to the left.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.remove(1), 1);
+    /// assert_eq!(&*vec, &[0, 2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "index out of bounds");
+
+        let elem = unsafe { remove_at(self.ptr.as_ptr(), self.len(), index) };
+        self.len -= 1;
+
+        elem
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if `len` is greater than the length.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.extend(0..5);
+    /// vec.truncate(2);
+    /// assert_eq!(&*vec, &[0, 1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+
+        let mut old_len = self.len();
+
+        // Update the length first, so a panicking destructor can't cause the tail to be dropped twice
+        self.len = len as u32;
+
+        unsafe { truncate_at(self.ptr.as_ptr(), &mut old_len, len) }
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.extend(0..5);
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    // Makes room for one more element
+    fn reserve_one(&mut self) -> Result<(), TryReserveError> {
+        if self.len < self.cap {
+            return Ok(());
+        }
+
+        // Zero-sized types already have a capacity of u32::MAX
+        if self.len == u32::MAX || mem::size_of::<T>() == 0 {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        let (new_cap, exact) = grown_capacity::<T, _>(&Doubling, self.capacity(), self.len(), 1)?;
+
+        self.try_grow_to(cmp::min(new_cap, MAX), exact)
+    }
+
+    // Reallocates the buffer to hold `new_cap` elements, or `exact` if the allocator can't fit that
+    fn try_grow_to(&mut self, new_cap: usize, exact: usize) -> Result<(), TryReserveError> {
+        let old_cap = self.capacity();
+        let (ptr, cap) = realloc_buffer(new_cap, exact, |cap| {
+            let new_layout =
+                Layout::array::<T>(cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+
+            let ptr = unsafe {
+                if old_cap == 0 {
+                    Global.allocate(new_layout)
+                } else {
+                    let old_layout = Layout::array::<T>(old_cap).unwrap();
+                    Global.grow(self.ptr.cast(), old_layout, new_layout)
+                }
+            };
+
+            ptr.map_err(|_| TryReserveError::AllocError { layout: new_layout })
+        })?;
+
+        record_growth::<T>(
+            old_cap,
+            cap,
+            #[cfg(feature = "stats")]
+            &mut self.stats,
+        );
+
+        self.ptr = ptr.cast();
+        self.cap = cap as u32;
+
+        Ok(())
+    }
+}
+
+impl<T> Default for CompactVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for CompactVec<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl<T> DerefMut for CompactVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl<T> Extend<T> for CompactVec<T> {
+    /// Appends every element of an iterator.
+    /// # Panics
+    /// Panics if the length goes over `u32::MAX`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::CompactVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Clone> Clone for CompactVec<T> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::with_capacity(self.len());
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CompactVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for CompactVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for CompactVec<T> {}
+
+impl<T> Drop for CompactVec<T> {
+    fn drop(&mut self) {
+        // Deallocates even if a destructor panics
+        struct Dealloc(NonNull<u8>, Option<Layout>);
+
+        impl Drop for Dealloc {
+            fn drop(&mut self) {
+                if let Some(layout) = self.1 {
+                    unsafe { Global.deallocate(self.0, layout) }
+                }
+            }
+        }
+
+        // Don't free zero-sized allocations
+        let layout = if self.cap != 0 && mem::size_of::<T>() != 0 {
+            Layout::array::<T>(self.capacity()).ok()
+        } else {
+            None
+        };
+        let _dealloc = Dealloc(self.ptr.cast(), layout);
+        if layout.is_some() {
+            record_deallocation::<T>(self.capacity());
+        }
+
+        unsafe { ptr::drop_in_place(&mut **self) }
+    }
+}
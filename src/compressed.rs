@@ -0,0 +1,354 @@
+use std::{cmp, fmt, iter::FromIterator, marker::PhantomData, mem};
+
+use crate::{
+    byte_buf::{read_var_int, write_var_int},
+    Vec,
+};
+
+// Number of values in a compressed block
+const BLOCK_LEN: usize = 128;
+
+/// Integer type a [`CompressedVec`] can hold, converted to and from 64 bits to compute deltas.
+///
+/// Signed integers are sign-extended so small negative deltas stay small.
+pub trait Compressible: Copy {
+    /// Returns the value widened to 64 bits.
+    fn to_bits(self) -> u64;
+    /// Returns the value whose [`to_bits`](Compressible::to_bits) is `bits`.
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! impl_compressible {
+    ( $( $t:ty, $wide:ty );* ) => {
+        $(
+            impl Compressible for $t {
+                fn to_bits(self) -> u64 {
+                    self as $wide as u64
+                }
+
+                fn from_bits(bits: u64) -> Self {
+                    bits as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_compressible!(u8, u64; u16, u64; u32, u64; u64, u64; usize, u64);
+impl_compressible!(i8, i64; i16, i64; i32, i64; i64, i64; isize, i64);
+
+/// Vector of integers compressed in blocks, for large columns of slowly changing values such as time series.
+///
+/// Values are grouped in blocks of 128: each block stores its first value, then runs of equal consecutive deltas as
+/// zig-zag encoded variable-length integers. Repeated values and evenly spaced ones (timestamps, counters) shrink
+/// to a few bytes per block. The last, incomplete block is kept uncompressed so pushing stays cheap.
+///
+/// Indexing decodes the runs of one block, iterating decodes them in order.
+/// # Example
+/// ```
+/// use vec::CompressedVec;
+///
+/// // A timestamp every second
+/// let timestamps: CompressedVec<i64> = (0..10_000).map(|i| 1_600_000_000 + i).collect();
+///
+/// assert_eq!(timestamps.get(1234), Some(1_600_001_234));
+/// assert!(timestamps.compressed_bytes() < 10_000);
+/// assert_eq!(timestamps.iter().last(), Some(1_600_009_999));
+/// ```
+#[derive(Clone)]
+pub struct CompressedVec<T: Compressible> {
+    // encoded runs of every full block, one after another
+    data: Vec<u8>,
+    // offset in `data` and first value of every full block
+    blocks: Vec<(usize, u64)>,
+    // values after the last full block, fewer than `BLOCK_LEN`
+    tail: Vec<T>,
+}
+
+impl<T: Compressible> CompressedVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::CompressedVec<u32> = vec::CompressedVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            blocks: Vec::new(),
+            tail: Vec::new(),
+        }
+    }
+
+    /// Returns the number of values.
+    /// # Example
+    /// ```
+    /// let v: vec::CompressedVec<u32> = (0..1000).collect();
+    /// assert_eq!(v.len(), 1000);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.blocks.len * BLOCK_LEN + self.tail.len
+    }
+
+    /// Returns `true` if the vector holds no values.
+    /// # Example
+    /// ```
+    /// let v: vec::CompressedVec<u32> = vec::CompressedVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of bytes used by the values: the encoded blocks, their headers and the uncompressed last
+    /// block.
+    /// # Example
+    /// ```
+    /// let v: vec::CompressedVec<u64> = std::iter::repeat(7).take(1024).collect();
+    /// assert!(v.compressed_bytes() < 1024);
+    /// ```
+    pub fn compressed_bytes(&self) -> usize {
+        self.data.len
+            + self.blocks.len * mem::size_of::<(usize, u64)>()
+            + self.tail.len * mem::size_of::<T>()
+    }
+
+    /// Appends a value, compressing the last block once it's full.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CompressedVec::new();
+    /// v.push(-3i32);
+    /// assert_eq!(v.get(0), Some(-3));
+    /// ```
+    pub fn push(&mut self, value: T) {
+        self.tail.push(value);
+
+        if self.tail.len == BLOCK_LEN {
+            self.compress_tail();
+        }
+    }
+
+    /// Removes the last value and returns it, or `None` if the vector is empty. Decompresses the last block if the
+    /// uncompressed one is empty.
+    /// # Example
+    /// ```
+    /// let mut v: vec::CompressedVec<u8> = (0..=200).collect();
+    /// assert_eq!(v.pop(), Some(200));
+    /// assert_eq!(v.len(), 200);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.tail.len == 0 {
+            let (offset, first) = self.blocks.pop()?;
+            self.tail
+                .extend(Runs::<T>::new(&self.data[offset..], first, BLOCK_LEN));
+            self.data.truncate(offset);
+        }
+
+        self.tail.pop()
+    }
+
+    /// Returns the value at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let v: vec::CompressedVec<u16> = (0..1000).map(|i| i / 10).collect();
+    /// assert_eq!(v.get(555), Some(55));
+    /// assert_eq!(v.get(1000), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<T> {
+        let (block, pos) = (index / BLOCK_LEN, index % BLOCK_LEN);
+
+        let &(offset, first) = match self.blocks.get(block) {
+            Some(header) => header,
+            None if block == self.blocks.len => return self.tail.get(pos).copied(),
+            None => return None,
+        };
+
+        // Skip whole runs instead of decoding every value before `pos`
+        let (mut value, mut left, mut bytes) = (first, pos, &self.data[offset..]);
+        while left > 0 {
+            let (delta, count, size) = read_run(bytes);
+            let taken = cmp::min(count, left);
+
+            value = value.wrapping_add(delta.wrapping_mul(taken as u64));
+            left -= taken;
+            bytes = &bytes[size..];
+        }
+
+        Some(T::from_bits(value))
+    }
+
+    /// Returns an iterator over the values, decoding the blocks in order.
+    /// # Example
+    /// ```
+    /// let v: vec::CompressedVec<i32> = [5, 5, 5, -1].iter().copied().collect();
+    /// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [5, 5, 5, -1]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.blocks
+            .iter()
+            .flat_map(move |&(offset, first)| Runs::new(&self.data[offset..], first, BLOCK_LEN))
+            .chain(self.tail.iter().copied())
+    }
+
+    /// Returns every value in a plain vector.
+    /// # Example
+    /// ```
+    /// let v: vec::CompressedVec<u32> = (0..300).collect();
+    /// let plain = v.decompress();
+    /// assert_eq!(plain[299], 299);
+    /// ```
+    pub fn decompress(&self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len());
+        vec.extend(self.iter());
+        vec
+    }
+
+    /// Removes every value.
+    /// # Example
+    /// ```
+    /// let mut v: vec::CompressedVec<u32> = (0..300).collect();
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.blocks.clear();
+        self.tail.clear();
+    }
+
+    // Encodes the full uncompressed block as runs of equal deltas
+    fn compress_tail(&mut self) {
+        let first = self.tail[0].to_bits();
+        self.blocks.push((self.data.len, first));
+
+        let (mut prev, mut run_delta, mut run) = (first, 0, 0);
+        for value in self.tail.iter().skip(1) {
+            let delta = value.to_bits().wrapping_sub(prev);
+            prev = value.to_bits();
+
+            if run > 0 && delta != run_delta {
+                write_run(&mut self.data, run_delta, run);
+                run = 0;
+            }
+            run_delta = delta;
+            run += 1;
+        }
+        if run > 0 {
+            write_run(&mut self.data, run_delta, run);
+        }
+
+        self.tail.clear();
+    }
+}
+
+// Zig-zag encodes `delta` as a signed value, so small negative deltas take few bytes
+fn write_run(data: &mut Vec<u8>, delta: u64, count: usize) {
+    let delta = delta as i64;
+    write_var_int(data, ((delta << 1) ^ (delta >> 63)) as u64);
+    write_var_int(data, count as u64);
+}
+
+// Decodes the run at the start of `bytes`, returning its delta, length and size in bytes
+fn read_run(bytes: &[u8]) -> (u64, usize, usize) {
+    let (zigzag, delta_size) = read_var_int(bytes).expect("corrupted block");
+    let (count, count_size) = read_var_int(&bytes[delta_size..]).expect("corrupted block");
+    let delta = (zigzag >> 1) ^ (zigzag & 1).wrapping_neg();
+
+    (delta, count as usize, delta_size + count_size)
+}
+
+// Iterator decoding the values of a block
+struct Runs<'a, T> {
+    bytes: &'a [u8],
+    // last value yielded, or the first one if nothing was yielded yet
+    value: u64,
+    delta: u64,
+    // values left in the current run, and in the block
+    run: usize,
+    left: usize,
+    started: bool,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T> Runs<'a, T> {
+    fn new(bytes: &'a [u8], first: u64, len: usize) -> Self {
+        Self {
+            bytes,
+            value: first,
+            delta: 0,
+            run: 0,
+            left: len,
+            started: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Compressible> Iterator for Runs<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.left == 0 {
+            return None;
+        }
+        self.left -= 1;
+
+        if !self.started {
+            self.started = true;
+            return Some(T::from_bits(self.value));
+        }
+
+        if self.run == 0 {
+            let (delta, count, size) = read_run(self.bytes);
+            self.bytes = &self.bytes[size..];
+            self.delta = delta;
+            self.run = count;
+        }
+
+        self.run -= 1;
+        self.value = self.value.wrapping_add(self.delta);
+        Some(T::from_bits(self.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left, Some(self.left))
+    }
+}
+
+impl<T: Compressible> Default for CompressedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Compressible> Extend<T> for CompressedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Compressible> FromIterator<T> for CompressedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T: Compressible + PartialEq> PartialEq for CompressedVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Compressible + Eq> Eq for CompressedVec<T> {}
+
+impl<T: Compressible + fmt::Debug> fmt::Debug for CompressedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,331 @@
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        PoisonError, RwLock, RwLockReadGuard,
+    },
+    thread,
+};
+
+use crate::Vec;
+
+/// Vector shared between threads, split into shards that each have their own lock. Element `i` lives in shard
+/// `i % shards`, so concurrent pushes and reads mostly contend on different locks.
+///
+/// Suited to collecting results from many threads where a parallel fold doesn't fit. Iteration can run one thread
+/// per shard with [`par_for_each`](ConcurrentVec::par_for_each).
+/// # Example
+/// ```
+/// use std::thread;
+/// use vec::ConcurrentVec;
+///
+/// let results = ConcurrentVec::new();
+///
+/// thread::scope(|s| {
+///     for t in 0..4 {
+///         let results = &results;
+///         s.spawn(move || {
+///             for i in 0..100 {
+///                 results.push(t * 100 + i);
+///             }
+///         });
+///     }
+/// });
+///
+/// assert_eq!(results.len(), 400);
+/// let mut all = results.into_vec();
+/// all.sort();
+/// assert!(all.iter().copied().eq(0..400));
+/// ```
+pub struct ConcurrentVec<T> {
+    // element `i` is at position `i / shards.len` of shard `i % shards.len`, `None` until its push completes
+    shards: Vec<RwLock<Vec<Option<T>>>>,
+    // indices handed out to pushes
+    reserved: AtomicUsize,
+    // pushes that completed
+    len: AtomicUsize,
+}
+
+// Every shard is only reached through its lock
+unsafe impl<T: Send> Send for ConcurrentVec<T> {}
+unsafe impl<T: Send + Sync> Sync for ConcurrentVec<T> {}
+
+impl<T> ConcurrentVec<T> {
+    /// Creates an empty vector with 16 shards, without allocating their storage.
+    /// # Example
+    /// ```
+    /// let v: vec::ConcurrentVec<i32> = vec::ConcurrentVec::new();
+    /// assert_eq!(v.shards(), 16);
+    /// ```
+    pub fn new() -> Self {
+        Self::with_shards(16)
+    }
+
+    /// Creates an empty vector with `shards` shards.
+    /// # Panics
+    /// Panics if `shards` is zero.
+    /// # Example
+    /// ```
+    /// let v: vec::ConcurrentVec<i32> = vec::ConcurrentVec::with_shards(4);
+    /// assert_eq!(v.shards(), 4);
+    /// ```
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0, "shard count must be non-zero");
+
+        let mut locks = Vec::with_capacity(shards);
+        locks.extend((0..shards).map(|_| RwLock::new(Vec::new())));
+
+        Self {
+            shards: locks,
+            reserved: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of shards.
+    /// # Example
+    /// ```
+    /// let v: vec::ConcurrentVec<i32> = vec::ConcurrentVec::with_shards(4);
+    /// assert_eq!(v.shards(), 4);
+    /// ```
+    pub fn shards(&self) -> usize {
+        self.shards.len
+    }
+
+    /// Returns the number of elements whose push has completed.
+    /// # Example
+    /// ```
+    /// let v = vec::ConcurrentVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if no push has completed.
+    /// # Example
+    /// ```
+    /// let v: vec::ConcurrentVec<i32> = vec::ConcurrentVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an element and returns its index, locking only the shard it goes to.
+    /// # Panics
+    /// This function will panic if the capacity of a shard exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v = vec::ConcurrentVec::new();
+    /// assert_eq!(v.push('a'), 0);
+    /// assert_eq!(v.push('b'), 1);
+    /// ```
+    pub fn push(&self, value: T) -> usize {
+        let index = self.reserved.fetch_add(1, Ordering::Relaxed);
+        let (shard, pos) = self.location(index);
+
+        {
+            let mut shard = self.shards[shard]
+                .write()
+                .unwrap_or_else(PoisonError::into_inner);
+
+            // Pushes with a greater index may have reached the shard first
+            while shard.len <= pos {
+                shard.push(None);
+            }
+            shard[pos] = Some(value);
+        }
+
+        self.len.fetch_add(1, Ordering::Release);
+        index
+    }
+
+    /// Returns a guard holding the element at `index` under its shard's read lock, or `None` if there is none yet.
+    /// # Deadlocks
+    /// Pushing to the shard from the thread holding the guard waits for the guard to be dropped, so it deadlocks (or
+    /// panics, depending on the platform). Copy the element out, or drop the guard, before pushing.
+    /// # Example
+    /// ```
+    /// let v = vec::ConcurrentVec::new();
+    /// let index = v.push(1);
+    /// assert_eq!(*v.get(index).unwrap(), 1);
+    /// assert!(v.get(index + 1).is_none());
+    ///
+    /// // The guard is dropped at the end of the statement, before the push
+    /// let first = *v.get(index).unwrap();
+    /// v.push(first + 1);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<Ref<'_, T>> {
+        let (shard, pos) = self.location(index);
+        let guard = self.shards[shard]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        guard.get(pos)?.as_ref()?;
+
+        Some(Ref { guard, pos })
+    }
+
+    /// Calls `f` with the index and a reference to every element, in index order, locking one shard at a time.
+    /// # Deadlocks
+    /// `f` runs under the read lock of the element's shard, so pushing to the vector from `f` deadlocks (or panics,
+    /// depending on the platform). Gather what to push and push it once `for_each` returns.
+    /// # Example
+    /// ```
+    /// let v = vec::ConcurrentVec::with_shards(2);
+    /// v.push(10);
+    /// v.push(20);
+    ///
+    /// let mut seen = std::vec::Vec::new();
+    /// v.for_each(|index, value| seen.push((index, *value)));
+    /// assert_eq!(seen, [(0, 10), (1, 20)]);
+    ///
+    /// for (_, value) in seen {
+    ///     v.push(value + 1);
+    /// }
+    /// assert_eq!(v.len(), 4);
+    /// ```
+    pub fn for_each<F: FnMut(usize, &T)>(&self, mut f: F) {
+        for index in 0..self.reserved.load(Ordering::Acquire) {
+            if let Some(value) = self.get(index) {
+                f(index, &value);
+            }
+        }
+    }
+
+    /// Calls `f` with the index and a reference to every element, running one thread per shard. Each thread holds
+    /// the read lock of its shard and visits its elements in index order.
+    ///
+    /// The threads are spawned on every call, not pooled, so this only pays off when the work on every shard
+    /// outweighs starting a thread. All shards are read-locked for the whole call, so pushes wait until it returns.
+    /// # Deadlocks
+    /// Pushing to the vector from `f` deadlocks (or panics, depending on the platform).
+    /// # Example
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let v = vec::ConcurrentVec::with_shards(4);
+    /// for i in 0..100 {
+    ///     v.push(i);
+    /// }
+    ///
+    /// let sum = AtomicUsize::new(0);
+    /// v.par_for_each(|_, value| {
+    ///     sum.fetch_add(*value, Ordering::Relaxed);
+    /// });
+    /// assert_eq!(sum.into_inner(), 4950);
+    /// ```
+    pub fn par_for_each<F: Fn(usize, &T) + Sync>(&self, f: F)
+    where
+        T: Sync,
+    {
+        let shards = self.shards.len;
+        let mut guards = Vec::with_capacity(shards);
+        guards.extend(
+            self.shards
+                .iter()
+                .map(|lock| lock.read().unwrap_or_else(PoisonError::into_inner)),
+        );
+
+        thread::scope(|s| {
+            for (shard, guard) in guards.iter().enumerate() {
+                let (f, slots) = (&f, &guard[..]);
+
+                s.spawn(move || {
+                    for (pos, value) in slots.iter().enumerate() {
+                        if let Some(value) = value {
+                            f(pos * shards + shard, value);
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Gathers the elements into a vector, in index order.
+    /// # Example
+    /// ```
+    /// let v = vec::ConcurrentVec::with_shards(2);
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// assert_eq!(&*v.into_vec(), &[1, 2, 3]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len.into_inner());
+        let mut shards = Vec::with_capacity(self.shards.len);
+        shards.extend(self.shards.into_iter().map(|lock| {
+            lock.into_inner()
+                .unwrap_or_else(PoisonError::into_inner)
+                .into_iter()
+        }));
+
+        // Take one element per shard in turn, as they were striped
+        let mut remaining = shards.len;
+        while remaining > 0 {
+            remaining = 0;
+
+            for shard in shards.iter_mut() {
+                if let Some(slot) = shard.next() {
+                    remaining += 1;
+                    vec.extend(slot);
+                }
+            }
+        }
+
+        vec
+    }
+
+    // Shard of element `index`, and its position there
+    fn location(&self, index: usize) -> (usize, usize) {
+        (index % self.shards.len, index / self.shards.len)
+    }
+}
+
+impl<T> Default for ConcurrentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for ConcurrentVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ConcurrentVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        self.for_each(|_, value| {
+            list.entry(value);
+        });
+        list.finish()
+    }
+}
+
+/// Element of a [`ConcurrentVec`], keeping its shard read-locked while alive, so pushes to that shard wait for it to
+/// be dropped. Created by [`ConcurrentVec::get`].
+pub struct Ref<'a, T> {
+    guard: RwLockReadGuard<'a, Vec<Option<T>>>,
+    pos: usize,
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard[self.pos].as_ref().unwrap()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
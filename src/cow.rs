@@ -0,0 +1,274 @@
+use std::{fmt, ops::Deref, sync::Arc};
+
+use crate::Vec;
+
+/// Vector whose clones share one buffer until either of them is mutated. Cloning only bumps a reference count;
+/// the first mutation of a shared buffer clones its elements into a buffer of its own.
+///
+/// Suited to snapshots read by many threads and occasionally changed by one writer.
+/// # Example
+/// ```
+/// use vec::CowVec;
+///
+/// let mut current = CowVec::new();
+/// current.extend(0..1000);
+///
+/// let snapshot = current.clone();
+/// assert!(current.ptr_eq(&snapshot));
+///
+/// // The writer gets its own copy, the snapshot is unaffected
+/// current.push(1000);
+/// assert!(!current.ptr_eq(&snapshot));
+/// assert_eq!((current.len(), snapshot.len()), (1001, 1000));
+/// ```
+pub struct CowVec<T> {
+    vec: Arc<Vec<T>>,
+}
+
+// The shared buffer is only mutated once no other handle can see it
+unsafe impl<T: Send + Sync> Send for CowVec<T> {}
+unsafe impl<T: Send + Sync> Sync for CowVec<T> {}
+
+impl<T> CowVec<T> {
+    /// Creates an empty vector, without allocating its buffer.
+    /// # Example
+    /// ```
+    /// let v: vec::CowVec<i32> = vec::CowVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            vec: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Returns `true` if this handle is the only one to its buffer, so mutating it won't clone.
+    /// # Example
+    /// ```
+    /// let v: vec::CowVec<i32> = vec::CowVec::new();
+    /// let w = v.clone();
+    /// assert!(!v.is_unique());
+    /// drop(w);
+    /// assert!(v.is_unique());
+    /// ```
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.vec) == 1
+    }
+
+    /// Returns `true` if both handles share the same buffer.
+    /// # Example
+    /// ```
+    /// let v: vec::CowVec<i32> = vec::CowVec::new();
+    /// assert!(v.ptr_eq(&v.clone()));
+    /// assert!(!v.ptr_eq(&vec::CowVec::new()));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.vec, &other.vec)
+    }
+
+    /// Returns the elements as a slice, never cloning.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.as_slice(), &[1]);
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T: Clone> CowVec<T> {
+    /// Returns the underlying vector for mutation, first cloning the elements if the buffer is shared.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.extend([3, 1, 2]);
+    /// v.make_mut().sort();
+    /// assert_eq!(v.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn make_mut(&mut self) -> &mut Vec<T> {
+        Arc::make_mut(&mut self.vec)
+    }
+
+    /// Appends an element, first cloning the elements if the buffer is shared.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.push(1);
+    /// assert_eq!(v[0], 1);
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.make_mut().push(elem)
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty. Clones the elements first if the
+    /// buffer is shared and not empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.pop(), Some(1));
+    /// assert_eq!(v.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.make_mut().pop()
+    }
+
+    /// Inserts an element at `index`, first cloning the elements if the buffer is shared.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.extend([1, 3]);
+    /// v.insert(1, 2);
+    /// assert_eq!(v.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        self.make_mut().insert(index, elem)
+    }
+
+    /// Removes the element at `index` and returns it, first cloning the elements if the buffer is shared.
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.extend([1, 2, 3]);
+    /// assert_eq!(v.remove(1), 2);
+    /// assert_eq!(v.as_slice(), &[1, 3]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        self.make_mut().remove(index)
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if out of bounds. Clones the elements
+    /// first if the buffer is shared and `index` is in bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.push(1);
+    /// let snapshot = v.clone();
+    ///
+    /// *v.get_mut(0).unwrap() = 2;
+    /// assert_eq!((v[0], snapshot[0]), (2, 1));
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        self.make_mut().get_mut(index)
+    }
+
+    /// Shortens the vector to `len` elements, doing nothing if it's not longer. A shared buffer is replaced by a
+    /// copy of the kept elements, instead of cloning them all.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.extend([1, 2, 3]);
+    /// v.truncate(1);
+    /// assert_eq!(v.as_slice(), &[1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+
+        match Arc::get_mut(&mut self.vec) {
+            Some(vec) => vec.truncate(len),
+            None => self.vec = Arc::new(copied(&self.vec[..len])),
+        }
+    }
+
+    /// Removes every element. A shared buffer is left to its other handles instead of being cloned.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.push(1);
+    /// let snapshot = v.clone();
+    ///
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// assert_eq!(snapshot.len(), 1);
+    /// ```
+    pub fn clear(&mut self) {
+        match Arc::get_mut(&mut self.vec) {
+            Some(vec) => vec.clear(),
+            None => self.vec = Arc::new(Vec::new()),
+        }
+    }
+
+    /// Returns the underlying vector, cloning the elements only if the buffer is shared.
+    /// # Example
+    /// ```
+    /// let mut v = vec::CowVec::new();
+    /// v.push(1);
+    /// assert_eq!(&*v.into_vec(), &[1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        Arc::try_unwrap(self.vec).unwrap_or_else(|vec| (*vec).clone())
+    }
+}
+
+// Clones `slice` into a vector of its own
+fn copied<T: Clone>(slice: &[T]) -> Vec<T> {
+    let mut vec = Vec::with_capacity(slice.len());
+    vec.extend(slice.iter().cloned());
+    vec
+}
+
+impl<T> Deref for CowVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T> Clone for CowVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<T> Default for CowVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<T>> for CowVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Self { vec: Arc::new(vec) }
+    }
+}
+
+impl<T: Clone> Extend<T> for CowVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.make_mut().extend(iter)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CowVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for CowVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for CowVec<T> {}
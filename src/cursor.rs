@@ -0,0 +1,264 @@
+use std::{
+    alloc::{Allocator, Global},
+    ptr,
+};
+
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    raw::handle_reserve_error,
+    Vec,
+};
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Returns a cursor on the element at `index`, or past the last element if `index` is the length, to insert and
+    /// remove elements around it without shifting the rest of the vector on every edit.
+    /// # Panics
+    /// This function will panic if `index` is greater than the length.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([1, 2, 4]);
+    ///
+    /// let mut cursor = vec.cursor_mut(2);
+    /// cursor.insert_before(3);
+    /// drop(cursor);
+    /// assert_eq!(&*vec, &[1, 2, 3, 4]);
+    /// ```
+    pub fn cursor_mut(&mut self, index: usize) -> CursorMut<'_, T, A, G> {
+        assert!(index <= self.len, "index out of bounds");
+
+        // The elements from the cursor on belong to the cursor until it's dropped
+        let end = self.len;
+        self.len = index;
+
+        CursorMut {
+            vec: self,
+            tail: index,
+            end,
+        }
+    }
+}
+
+/// Cursor over a [`Vec`], pointing at an element or past the last one, returned by [`Vec::cursor_mut`].
+///
+/// The cursor keeps a gap of free slots before the element it's on, so inserting and removing there is O(1) and
+/// moving by one element moves a single element across the gap. The elements after the gap are shifted back in place
+/// once, when the cursor is dropped. Leaking the cursor leaks them too.
+/// # Example
+/// ```
+/// let mut vec = vec::Vec::new();
+/// vec.extend(0..6);
+///
+/// // Doubles the even numbers and drops the odd ones
+/// let mut cursor = vec.cursor_mut(0);
+/// while let Some(&mut x) = cursor.current() {
+///     if x % 2 == 0 {
+///         cursor.insert_before(x);
+///         cursor.move_next();
+///     } else {
+///         cursor.remove_current();
+///     }
+/// }
+/// drop(cursor);
+/// assert_eq!(&*vec, &[0, 0, 2, 2, 4, 4]);
+/// ```
+pub struct CursorMut<'a, T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    // elements before the cursor live in `..vec.len`, the element it's on and the ones after in `tail..end`
+    vec: &'a mut Vec<T, A, G>,
+    tail: usize,
+    end: usize,
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> CursorMut<'_, T, A, G> {
+    /// Returns the position of the cursor, the number of elements before it.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..3);
+    /// let mut cursor = vec.cursor_mut(1);
+    /// cursor.insert_before(5);
+    /// assert_eq!(cursor.index(), 2);
+    /// ```
+    pub fn index(&self) -> usize {
+        self.vec.len
+    }
+
+    /// Returns the number of elements in the vector, on both sides of the cursor.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..3);
+    /// let mut cursor = vec.cursor_mut(1);
+    /// cursor.remove_current();
+    /// assert_eq!(cursor.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.vec.len + (self.end - self.tail)
+    }
+
+    /// Returns `true` if the vector holds no elements, on either side of the cursor.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(1);
+    /// let mut cursor = vec.cursor_mut(0);
+    /// cursor.remove_current();
+    /// assert!(cursor.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element the cursor is on, or `None` past the last element.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..3);
+    /// let mut cursor = vec.cursor_mut(1);
+    /// *cursor.current().unwrap() = 10;
+    /// drop(cursor);
+    /// assert_eq!(&*vec, &[0, 10, 2]);
+    /// ```
+    pub fn current(&mut self) -> Option<&mut T> {
+        if self.tail == self.end {
+            return None;
+        }
+
+        unsafe { Some(&mut *self.vec.ptr().add(self.tail)) }
+    }
+
+    /// Returns the element before the cursor, or `None` on the first element.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.cursor_mut(3).peek_prev(), Some(&2));
+    /// assert_eq!(vec.cursor_mut(0).peek_prev(), None);
+    /// ```
+    pub fn peek_prev(&self) -> Option<&T> {
+        self.vec.last()
+    }
+
+    /// Moves to the next element, or past the last element. Does nothing if the cursor is already past the last
+    /// element.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..2);
+    /// let mut cursor = vec.cursor_mut(0);
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&mut 1));
+    /// cursor.move_next();
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), None);
+    /// ```
+    pub fn move_next(&mut self) {
+        if self.tail == self.end {
+            return;
+        }
+
+        // Moves the current element across the gap
+        unsafe {
+            let ptr = self.vec.ptr();
+            ptr::copy(ptr.add(self.tail), ptr.add(self.vec.len), 1);
+        }
+        self.vec.len += 1;
+        self.tail += 1;
+    }
+
+    /// Moves to the previous element. Does nothing if the cursor is on the first element.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..2);
+    /// let mut cursor = vec.cursor_mut(2);
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), Some(&mut 1));
+    /// ```
+    pub fn move_prev(&mut self) {
+        if self.vec.len == 0 {
+            return;
+        }
+
+        // Moves the previous element across the gap
+        self.vec.len -= 1;
+        self.tail -= 1;
+        unsafe {
+            let ptr = self.vec.ptr();
+            ptr::copy(ptr.add(self.vec.len), ptr.add(self.tail), 1);
+        }
+    }
+
+    /// Inserts an element before the cursor, which stays on the same element.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([1, 3]);
+    /// let mut cursor = vec.cursor_mut(1);
+    /// cursor.insert_before(2);
+    /// assert_eq!(cursor.current(), Some(&mut 3));
+    /// drop(cursor);
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn insert_before(&mut self, elem: T) {
+        if self.vec.len == self.tail {
+            self.grow();
+        }
+
+        unsafe { self.vec.ptr().add(self.vec.len).write(elem) };
+        self.vec.len += 1;
+    }
+
+    /// Removes the element the cursor is on and returns it, moving the cursor to the next element. Returns `None`
+    /// past the last element.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..3);
+    /// let mut cursor = vec.cursor_mut(1);
+    /// assert_eq!(cursor.remove_current(), Some(1));
+    /// assert_eq!(cursor.current(), Some(&mut 2));
+    /// drop(cursor);
+    /// assert_eq!(&*vec, &[0, 2]);
+    /// ```
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.tail == self.end {
+            return None;
+        }
+
+        self.tail += 1;
+        unsafe { Some(self.vec.ptr().add(self.tail - 1).read()) }
+    }
+
+    // Grows the buffer once the gap is closed, moving the elements after it to the end of the new buffer
+    fn grow(&mut self) {
+        let tail_len = self.end - self.tail;
+
+        // Growing keeps every slot of the old buffer, not only the first `len`
+        if let Err(err) = self.vec.buf.try_reserve(self.vec.len + tail_len, 1) {
+            handle_reserve_error(err)
+        }
+
+        let new_tail = self.vec.buf.cap - tail_len;
+        unsafe {
+            let ptr = self.vec.ptr();
+            ptr::copy(ptr.add(self.tail), ptr.add(new_tail), tail_len);
+        }
+        self.tail = new_tail;
+        self.end = self.vec.buf.cap;
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for CursorMut<'_, T, A, G> {
+    fn drop(&mut self) {
+        // Closes the gap, the only shift of the elements after the cursor
+        let tail_len = self.end - self.tail;
+        unsafe {
+            let ptr = self.vec.ptr();
+            ptr::copy(ptr.add(self.tail), ptr.add(self.vec.len), tail_len);
+        }
+        self.vec.len += tail_len;
+    }
+}
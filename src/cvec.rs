@@ -0,0 +1,192 @@
+use std::{
+    convert::TryFrom,
+    ffi::c_void,
+    fmt, mem,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+    slice,
+};
+
+use crate::{error::TryReserveError, Vec};
+
+/// Function freeing a buffer allocated by foreign code, such as C's `free`.
+pub type CFree = unsafe extern "C" fn(*mut c_void);
+
+/// Owned array allocated by foreign code (e.g. `malloc`'d by a C library), freed with the foreign library's own
+/// deallocator on drop instead of Rust's allocator.
+///
+/// Behaves like a slice through `Deref`. Moving the elements into a [`Vec`] needs a copy, as the buffer can't be
+/// handed to Rust's allocator.
+/// # Example
+/// ```
+/// use std::{ffi::c_void, mem};
+/// use vec::CVec;
+///
+/// extern "C" {
+///     fn malloc(size: usize) -> *mut c_void;
+///     fn free(ptr: *mut c_void);
+/// }
+///
+/// // Stand-in for a C function returning a malloc'd array
+/// unsafe fn squares(n: usize) -> *mut u32 {
+///     let ptr = malloc(n * mem::size_of::<u32>()) as *mut u32;
+///     for i in 0..n {
+///         ptr.add(i).write((i * i) as u32);
+///     }
+///     ptr
+/// }
+///
+/// let mut v = unsafe { CVec::new(squares(4), 4, free) };
+/// assert_eq!(&*v, &[0, 1, 4, 9]);
+///
+/// v[0] = 7;
+/// let owned = v.try_into_vec().unwrap(); // copies, then frees the C buffer
+/// assert_eq!(&*owned, &[7, 1, 4, 9]);
+/// ```
+pub struct CVec<T> {
+    // null when the foreign code gave no buffer, in which case `len` is 0
+    ptr: *mut T,
+    len: usize,
+    free: CFree,
+}
+
+// The elements are owned like in a `Box<[T]>`, and `new` requires `free` to work from any thread
+unsafe impl<T: Send> Send for CVec<T> {}
+unsafe impl<T: Sync> Sync for CVec<T> {}
+
+impl<T> CVec<T> {
+    /// Takes ownership of `len` elements at `ptr`, to be passed to `free` when the vector is dropped.
+    /// # Safety
+    /// `ptr` must point to `len` initialized, properly aligned values of `T`, valid for reads and writes and not
+    /// used elsewhere until `free` is called. It may be null only if `len` is 0, in which case `free` is never
+    /// called. `free` must be the right deallocator for `ptr`, and callable from whichever thread drops the vector.
+    /// # Example
+    /// ```
+    /// extern "C" {
+    ///     fn free(ptr: *mut std::ffi::c_void);
+    /// }
+    ///
+    /// let v = unsafe { vec::CVec::<u8>::new(std::ptr::null_mut(), 0, free) };
+    /// assert!(v.is_empty());
+    /// ```
+    pub unsafe fn new(ptr: *mut T, len: usize, free: CFree) -> Self {
+        debug_assert!(!ptr.is_null() || len == 0, "null pointer with a length");
+        Self { ptr, len, free }
+    }
+
+    /// Returns a pointer to the buffer, null if the foreign code gave none.
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    /// Returns a mutable pointer to the buffer, null if the foreign code gave none.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Moves the elements into a [`Vec`], copying them, then frees the foreign buffer.
+    /// # Errors
+    /// Returns an error if the allocation fails, in which case the elements are dropped and the buffer is freed.
+    /// # Example
+    /// ```
+    /// # extern "C" {
+    /// #     fn malloc(size: usize) -> *mut std::ffi::c_void;
+    /// #     fn free(ptr: *mut std::ffi::c_void);
+    /// # }
+    /// let v = unsafe {
+    ///     let ptr = malloc(2) as *mut u8;
+    ///     ptr.write_bytes(1, 2);
+    ///     vec::CVec::new(ptr, 2, free)
+    /// };
+    /// assert_eq!(&*v.try_into_vec().unwrap(), &[1, 1]);
+    /// ```
+    pub fn try_into_vec(self) -> Result<Vec<T>, TryReserveError> {
+        let mut vec = Vec::try_with_capacity(self.len)?;
+
+        let this = mem::ManuallyDrop::new(self);
+        unsafe {
+            // The elements now belong to `vec`, only the buffer is left to free
+            ptr::copy_nonoverlapping(this.as_ptr(), vec.as_mut_ptr(), this.len);
+            vec.set_len(this.len);
+            this.free_buffer();
+        }
+
+        Ok(vec)
+    }
+
+    /// Gives up ownership of the buffer, returning its pointer, length and deallocator.
+    /// # Example
+    /// ```
+    /// # extern "C" {
+    /// #     fn free(ptr: *mut std::ffi::c_void);
+    /// # }
+    /// let v = unsafe { vec::CVec::<u8>::new(std::ptr::null_mut(), 0, free) };
+    /// let (ptr, len, _free) = v.into_raw_parts();
+    /// assert!(ptr.is_null() && len == 0);
+    /// ```
+    pub fn into_raw_parts(self) -> (*mut T, usize, CFree) {
+        let this = mem::ManuallyDrop::new(self);
+        (this.ptr, this.len, this.free)
+    }
+
+    // Frees the buffer without dropping the elements
+    unsafe fn free_buffer(&self) {
+        if !self.ptr.is_null() {
+            (self.free)(self.ptr as *mut c_void)
+        }
+    }
+
+    // Pointer usable for slices, which must be non-null even when empty
+    fn slice_ptr(&self) -> *mut T {
+        if self.ptr.is_null() {
+            NonNull::dangling().as_ptr()
+        } else {
+            self.ptr
+        }
+    }
+}
+
+impl<T> Deref for CVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.slice_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for CVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.slice_ptr(), self.len) }
+    }
+}
+
+impl<T> TryFrom<CVec<T>> for Vec<T> {
+    type Error = TryReserveError;
+
+    fn try_from(v: CVec<T>) -> Result<Self, TryReserveError> {
+        v.try_into_vec()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Drop for CVec<T> {
+    fn drop(&mut self) {
+        // Frees the buffer even if a destructor panics
+        struct Free<'a, T>(&'a CVec<T>);
+
+        impl<T> Drop for Free<'_, T> {
+            fn drop(&mut self) {
+                unsafe { self.0.free_buffer() }
+            }
+        }
+
+        let slice: *mut [T] = &mut **self;
+        let _free = Free(self);
+        unsafe { ptr::drop_in_place(slice) }
+    }
+}
@@ -0,0 +1,645 @@
+use std::{
+    alloc::{Allocator, Global},
+    fmt,
+    iter::FusedIterator,
+    mem::{self, MaybeUninit},
+    ops::{Index, IndexMut},
+    ptr, slice,
+};
+
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    raw::{handle_reserve_error, RawVec},
+    Vec,
+};
+
+/// Double-ended queue implemented as a ring buffer over the same growable buffer as [`Vec`], pushing and popping at
+/// both ends in O(1).
+/// # Example
+/// ```
+/// use vec::VecDeque;
+///
+/// let mut deque = VecDeque::new();
+/// deque.push_back(2);
+/// deque.push_back(3);
+/// deque.push_front(1);
+///
+/// assert_eq!(deque.pop_front(), Some(1));
+/// assert_eq!(deque.pop_back(), Some(3));
+/// assert_eq!(deque.len(), 1);
+/// ```
+pub struct VecDeque<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    // index of the front element in the buffer, always less than the capacity (or 0 when unallocated)
+    head: usize,
+    len: usize,
+    buf: RawVec<T, A, G>,
+}
+
+impl<T> VecDeque<T> {
+    /// Creates an empty deque, without allocating.
+    /// # Example
+    /// ```
+    /// let deque: vec::VecDeque<u8> = vec::VecDeque::new();
+    /// assert!(deque.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates an empty deque with room for `capacity` elements.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let deque: vec::VecDeque<u8> = vec::VecDeque::with_capacity(10);
+    /// assert_eq!(deque.capacity(), 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity).into()
+    }
+}
+
+impl<T> Default for VecDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, A: Allocator> VecDeque<T, A> {
+    /// Creates an empty deque that will allocate from `alloc`.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Arena, VecDeque};
+    /// let arena = Arena::new();
+    /// let mut deque = VecDeque::new_in(&arena);
+    /// deque.push_back(1);
+    /// assert_eq!(deque[0], 1);
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            head: 0,
+            len: 0,
+            buf: RawVec::new_in(alloc),
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> VecDeque<T, A, G> {
+    /// Returns the number of elements in the deque.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_front(1);
+    /// assert_eq!(deque.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the deque holds no elements.
+    /// # Example
+    /// ```
+    /// let deque: vec::VecDeque<u8> = vec::VecDeque::new();
+    /// assert!(deque.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the deque can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1u32);
+    /// assert_eq!(deque.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.buf.cap
+    }
+
+    /// Appends an element to the back of the deque.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// assert_eq!(deque.back(), Some(&2));
+    /// ```
+    pub fn push_back(&mut self, elem: T) {
+        self.grow_if_full();
+
+        unsafe { ptr::write(self.ptr().add(self.to_physical(self.len)), elem) };
+        self.len += 1;
+    }
+
+    /// Prepends an element to the front of the deque.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_front(1);
+    /// deque.push_front(2);
+    /// assert_eq!(deque.front(), Some(&2));
+    /// ```
+    pub fn push_front(&mut self, elem: T) {
+        self.grow_if_full();
+
+        self.head = self.to_physical(self.capacity() - 1);
+        unsafe { ptr::write(self.ptr().add(self.head), elem) };
+        self.len += 1;
+    }
+
+    /// Removes the last element and returns it, or `None` if the deque is empty.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// assert_eq!(deque.pop_back(), Some(1));
+    /// assert_eq!(deque.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.to_physical(self.len)))) }
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is empty.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// assert_eq!(deque.pop_front(), Some(1));
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let elem = unsafe { ptr::read(self.ptr().add(self.head)) };
+        self.head = self.to_physical(1);
+        self.len -= 1;
+
+        Some(elem)
+    }
+
+    /// Returns a reference to the element at `index` (counted from the front), or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(2);
+    /// deque.push_front(1);
+    /// assert_eq!(deque.get(1), Some(&2));
+    /// assert_eq!(deque.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            unsafe { Some(&*self.ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index` (counted from the front), or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// *deque.get_mut(0).unwrap() = 2;
+    /// assert_eq!(deque[0], 2);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            unsafe { Some(&mut *self.ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the front element, or `None` if the deque is empty.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// assert_eq!(deque.front(), Some(&1));
+    /// ```
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a reference to the back element, or `None` if the deque is empty.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// assert_eq!(deque.back(), Some(&2));
+    /// ```
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// Returns the elements as two slices: the front part, then the part that wrapped around to the start of the
+    /// buffer (empty if the elements are contiguous).
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::with_capacity(4);
+    /// deque.push_back(2);
+    /// deque.push_back(3);
+    /// deque.push_front(1);
+    /// assert_eq!(deque.as_slices(), (&[1][..], &[2, 3][..]));
+    /// ```
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front, back) = self.slice_ranges();
+
+        unsafe {
+            (
+                slice::from_raw_parts(self.ptr().add(self.head), front),
+                slice::from_raw_parts(self.ptr(), back),
+            )
+        }
+    }
+
+    /// Returns the elements as two mutable slices, see [`VecDeque::as_slices`].
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// deque.as_mut_slices().0[0] = 2;
+    /// assert_eq!(deque[0], 2);
+    /// ```
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front, back) = self.slice_ranges();
+
+        unsafe {
+            (
+                slice::from_raw_parts_mut(self.ptr().add(self.head), front),
+                slice::from_raw_parts_mut(self.ptr(), back),
+            )
+        }
+    }
+
+    /// Moves the elements so they are contiguous in the buffer, and returns them as a single slice.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::with_capacity(4);
+    /// deque.push_back(2);
+    /// deque.push_front(1);
+    /// assert_eq!(deque.make_contiguous(), &[1, 2]);
+    /// assert_eq!(deque.as_slices(), (&[1, 2][..], &[][..]));
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let (_, back) = self.slice_ranges();
+
+        if back > 0 {
+            // Rotating the whole buffer keeps the cyclic order, bringing the front element to the start
+            unsafe {
+                let buf =
+                    slice::from_raw_parts_mut(self.ptr() as *mut MaybeUninit<T>, self.capacity());
+                buf.rotate_left(self.head);
+            }
+
+            self.head = 0;
+        }
+
+        self.as_mut_slices().0
+    }
+
+    /// Returns a front-to-back iterator over the elements.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(2);
+    /// deque.push_front(1);
+    /// let items: std::vec::Vec<_> = deque.iter().copied().collect();
+    /// assert_eq!(items, [1, 2]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+
+        Iter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    /// Returns a front-to-back iterator over mutable references to the elements.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_front(0);
+    /// deque.iter_mut().for_each(|x| *x *= 10);
+    /// assert_eq!(deque.as_slices().0.iter().chain(deque.as_slices().1).sum::<i32>(), 10);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+
+        IterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(1);
+    /// deque.clear();
+    /// assert!(deque.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        let (front, back) = (front as *mut [T], back as *mut [T]);
+
+        // Reset first, so a panicking destructor can't cause an element to be dropped twice
+        self.head = 0;
+        self.len = 0;
+
+        unsafe { drop_slices(front, back) }
+    }
+
+    fn ptr(&self) -> *mut T {
+        self.buf.ptr.as_ptr()
+    }
+
+    // Buffer index of the element at logical `index`, which is at most the capacity
+    fn to_physical(&self, index: usize) -> usize {
+        let index = self.head.wrapping_add(index);
+
+        if index >= self.capacity() {
+            index.wrapping_sub(self.capacity())
+        } else {
+            index
+        }
+    }
+
+    // Lengths of the front (from `head`) and back (from the start of the buffer) parts
+    fn slice_ranges(&self) -> (usize, usize) {
+        let to_end = self.capacity() - self.head;
+
+        if self.len <= to_end {
+            (self.len, 0)
+        } else {
+            (to_end, self.len - to_end)
+        }
+    }
+
+    fn grow_if_full(&mut self) {
+        if self.len < self.capacity() {
+            return;
+        }
+
+        let old_cap = self.capacity();
+
+        if let Err(err) = self.buf.try_grow() {
+            handle_reserve_error(err)
+        }
+
+        // The elements that wrapped around the old end must move to keep the ring in order
+        let (front, back) = (old_cap - self.head, self.len - (old_cap - self.head));
+
+        if back > 0 {
+            unsafe {
+                if back <= self.capacity() - old_cap {
+                    // [B . . F F F] -> [. . . F F F B . .]
+                    ptr::copy_nonoverlapping(self.ptr(), self.ptr().add(old_cap), back);
+                } else {
+                    // [B B B . F] -> [B B B . . . . F]
+                    let new_head = self.capacity() - front;
+                    ptr::copy(self.ptr().add(self.head), self.ptr().add(new_head), front);
+                    self.head = new_head;
+                }
+            }
+        }
+    }
+}
+
+// Drops the elements of two slices, still dropping the second if a destructor of the first panics
+pub(crate) unsafe fn drop_slices<T>(front: *mut [T], back: *mut [T]) {
+    struct Dropper<T>(*mut [T]);
+
+    impl<T> Drop for Dropper<T> {
+        fn drop(&mut self) {
+            unsafe { ptr::drop_in_place(self.0) }
+        }
+    }
+
+    let _back = Dropper(back);
+    ptr::drop_in_place(front);
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<Vec<T, A, G>> for VecDeque<T, A, G> {
+    /// Turns a vector into a deque, reusing its allocation.
+    /// # Example
+    /// ```
+    /// use vec::{Vec, VecDeque};
+    /// let mut vec = Vec::new();
+    /// vec.extend(0..3);
+    /// let deque = VecDeque::from(vec);
+    /// assert_eq!(deque.front(), Some(&0));
+    /// ```
+    fn from(vec: Vec<T, A, G>) -> Self {
+        let (buf, len) = vec.into_raw_parts();
+
+        Self { head: 0, len, buf }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<VecDeque<T, A, G>> for Vec<T, A, G> {
+    /// Turns a deque into a vector, reusing its allocation. The elements are moved to the start of the buffer if
+    /// needed.
+    /// # Example
+    /// ```
+    /// use vec::{Vec, VecDeque};
+    /// let mut deque = VecDeque::new();
+    /// deque.push_back(2);
+    /// deque.push_front(1);
+    /// let vec = Vec::from(deque);
+    /// assert_eq!(&*vec, &[1, 2]);
+    /// ```
+    fn from(mut deque: VecDeque<T, A, G>) -> Self {
+        deque.make_contiguous();
+
+        unsafe {
+            if deque.head != 0 {
+                ptr::copy(deque.ptr().add(deque.head), deque.ptr(), deque.len);
+            }
+
+            let buf = ptr::read(&deque.buf);
+            let len = deque.len;
+            mem::forget(deque);
+
+            Vec::from_raw_parts(buf, len)
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Index<usize> for VecDeque<T, A, G> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IndexMut<usize> for VecDeque<T, A, G> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for VecDeque<T, A, G> {
+    /// Appends every element of an iterator to the back.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.extend(0..3);
+    /// assert_eq!(deque.back(), Some(&2));
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for VecDeque<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> PartialEq for VecDeque<T, A, G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for VecDeque<T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for VecDeque<T, A, G> {
+    fn drop(&mut self) {
+        let (front, back) = self.as_mut_slices();
+
+        unsafe { drop_slices(front, back) }
+        // Deallocation is handled by RawVec
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for VecDeque<T, A, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A, G>;
+
+    /// Consumes the deque into a front-to-back iterator.
+    /// # Example
+    /// ```
+    /// let mut deque = vec::VecDeque::new();
+    /// deque.push_back(2);
+    /// deque.push_front(1);
+    /// let mut iter = deque.into_iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next_back(), Some(2));
+    /// ```
+    fn into_iter(self) -> IntoIter<T, A, G> {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a VecDeque<T, A, G> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Borrowing iterator over a [`VecDeque`], created by [`VecDeque::iter`].
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Mutable borrowing iterator over a [`VecDeque`], created by [`VecDeque::iter_mut`].
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// Owning iterator over a [`VecDeque`], created by its `IntoIterator` implementation.
+pub struct IntoIter<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    deque: VecDeque<T, A, G>,
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Iterator for IntoIter<T, A, G> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len, Some(self.deque.len))
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for IntoIter<T, A, G> {
+    fn next_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> ExactSizeIterator for IntoIter<T, A, G> {}
@@ -0,0 +1,380 @@
+use std::{
+    cmp, fmt, mem,
+    ops::{Deref, Index, IndexMut, Range, RangeBounds},
+};
+
+use crate::{raw::resolve_range, Vec};
+
+/// Vector remembering which index ranges changed since they were last taken, so a mirror of it (e.g. a vertex
+/// buffer on the GPU) can be updated by uploading only those spans.
+///
+/// Writing an element marks it dirty; inserting or removing one marks everything after it, as those elements
+/// moved. Shrinking the vector only shortens the ranges, the mirror is expected to follow the length.
+/// # Example
+/// ```
+/// use vec::DirtyRangeVec;
+///
+/// let mut v = DirtyRangeVec::new();
+/// v.extend(0..10);
+/// v.take_dirty_ranges(); // initial upload
+///
+/// v[2] = 20;
+/// v[3] = 30;
+/// v[8] = 80;
+/// assert_eq!(&*v.take_dirty_ranges(), &[2..4, 8..9]);
+///
+/// v.remove(5);
+/// assert_eq!(&*v.take_dirty_ranges(), &[5..9]);
+/// assert!(v.take_dirty_ranges().is_empty());
+/// ```
+pub struct DirtyRangeVec<T> {
+    vec: Vec<T>,
+    // ranges marked since the last take, possibly overlapping and out of order
+    dirty: Vec<Range<usize>>,
+    // number of ranges in `dirty` after it was last coalesced
+    coalesced: usize,
+}
+
+impl<T> DirtyRangeVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::DirtyRangeVec<f32> = vec::DirtyRangeVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::from(Vec::new())
+    }
+
+    /// Returns a mutable reference to the element at `index` and marks it dirty, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.push(1);
+    /// v.take_dirty_ranges();
+    ///
+    /// *v.get_mut(0).unwrap() = 2;
+    /// assert_eq!(&*v.take_dirty_ranges(), &[0..1]);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.vec.len {
+            return None;
+        }
+
+        self.mark(index..index + 1);
+        Some(&mut self.vec[index])
+    }
+
+    /// Returns the elements in `range` mutably, marking them dirty.
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([1, 2, 3, 4]);
+    /// v.take_dirty_ranges();
+    ///
+    /// v.slice_mut(1..3).fill(0);
+    /// assert_eq!(&*v, &[1, 0, 0, 4]);
+    /// assert_eq!(&*v.take_dirty_ranges(), &[1..3]);
+    /// ```
+    pub fn slice_mut<R: RangeBounds<usize>>(&mut self, range: R) -> &mut [T] {
+        let Range { start, end } = resolve_range(range, self.vec.len);
+
+        self.mark(start..end);
+        &mut self.vec[start..end]
+    }
+
+    /// Returns every element mutably, marking them all dirty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([3, 1, 2]);
+    /// v.take_dirty_ranges();
+    ///
+    /// v.as_mut_slice().sort();
+    /// assert_eq!(&*v.take_dirty_ranges(), &[0..3]);
+    /// ```
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.slice_mut(..)
+    }
+
+    /// Appends an element, marking it dirty.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// assert_eq!(&*v.take_dirty_ranges(), &[0..2]);
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.vec.push(elem);
+        self.mark(self.vec.len - 1..self.vec.len);
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.pop(), Some(1));
+    /// assert!(v.take_dirty_ranges().is_empty());
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Inserts an element at `index`, marking it and the ones after it dirty.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([1, 2, 3]);
+    /// v.take_dirty_ranges();
+    ///
+    /// v.insert(1, 9);
+    /// assert_eq!(&*v.take_dirty_ranges(), &[1..4]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        self.vec.insert(index, elem);
+        self.mark(index..self.vec.len);
+    }
+
+    /// Removes and returns the element at `index`, marking the ones after it dirty.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([1, 2, 3]);
+    /// v.take_dirty_ranges();
+    ///
+    /// v.remove(0);
+    /// assert_eq!(&*v.take_dirty_ranges(), &[0..2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        let elem = self.vec.remove(index);
+        self.mark(index..self.vec.len);
+        elem
+    }
+
+    /// Removes and returns the element at `index`, replacing it with the last one, which marks only `index`
+    /// dirty.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([1, 2, 3, 4]);
+    /// v.take_dirty_ranges();
+    ///
+    /// assert_eq!(v.swap_remove(1), 2);
+    /// assert_eq!(&*v, &[1, 4, 3]);
+    /// assert_eq!(&*v.take_dirty_ranges(), &[1..2]);
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.vec.len, "index out of bounds");
+
+        let last = self.vec.len - 1;
+        self.vec.swap(index, last);
+
+        let elem = self.vec.pop().unwrap();
+        self.mark(index..cmp::min(index + 1, self.vec.len));
+        elem
+    }
+
+    /// Shortens the vector to `len` elements, doing nothing if it's not longer.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([1, 2, 3]);
+    /// v.truncate(1);
+    /// assert_eq!(&*v.take_dirty_ranges(), &[0..1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len)
+    }
+
+    /// Removes every element.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([1, 2, 3]);
+    /// v.clear();
+    /// assert!(v.take_dirty_ranges().is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear();
+        self.dirty.clear();
+        self.coalesced = 0;
+    }
+
+    /// Marks the elements in `range` dirty, clipped to the length.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend([1, 2, 3]);
+    /// v.take_dirty_ranges();
+    ///
+    /// v.mark_dirty(1..10);
+    /// assert_eq!(&*v.take_dirty_ranges(), &[1..3]);
+    /// ```
+    pub fn mark_dirty(&mut self, range: Range<usize>) {
+        self.mark(range.start..cmp::min(range.end, self.vec.len));
+    }
+
+    /// Returns `true` if any element was marked dirty since the ranges were last taken.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.push(1);
+    /// assert!(v.is_dirty());
+    /// v.take_dirty_ranges();
+    /// assert!(!v.is_dirty());
+    /// ```
+    pub fn is_dirty(&self) -> bool {
+        let len = self.vec.len;
+        self.dirty.iter().any(|range| range.start < len)
+    }
+
+    /// Returns the dirty ranges in order, merging the overlapping and adjacent ones, and marks every element clean.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.extend(0..10);
+    /// v.take_dirty_ranges();
+    ///
+    /// v[7] = 0;
+    /// v[1] = 0;
+    /// v[2] = 0;
+    /// assert_eq!(&*v.take_dirty_ranges(), &[1..3, 7..8]);
+    /// ```
+    pub fn take_dirty_ranges(&mut self) -> Vec<Range<usize>> {
+        self.coalesce();
+        self.coalesced = 0;
+
+        mem::replace(&mut self.dirty, Vec::new())
+    }
+
+    /// Returns the elements, dropping the dirty ranges.
+    /// # Example
+    /// ```
+    /// let mut v = vec::DirtyRangeVec::new();
+    /// v.push(1);
+    /// assert_eq!(&*v.into_vec(), &[1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+
+    fn mark(&mut self, range: Range<usize>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        // Writes to consecutive elements extend the last range instead of adding one each
+        if let Some(last) = self.dirty.last_mut() {
+            if range.start <= last.end && last.start <= range.end {
+                last.start = cmp::min(last.start, range.start);
+                last.end = cmp::max(last.end, range.end);
+                return;
+            }
+        }
+
+        self.dirty.push(range);
+        self.coalesce_if_needed();
+    }
+
+    // Keeps scattered writes from growing the list without bound
+    fn coalesce_if_needed(&mut self) {
+        if self.dirty.len > cmp::max(2 * self.coalesced, 64) {
+            self.coalesce();
+        }
+    }
+
+    // Sorts the ranges, merges the ones touching, and clips them to the length
+    fn coalesce(&mut self) {
+        let len = self.vec.len;
+
+        self.dirty.sort_unstable_by_key(|range| range.start);
+
+        let mut merged = 0;
+        for i in 0..self.dirty.len {
+            let range = self.dirty[i].start..cmp::min(self.dirty[i].end, len);
+            if range.start >= range.end {
+                continue;
+            }
+
+            if merged > 0 && range.start <= self.dirty[merged - 1].end {
+                let last = &mut self.dirty[merged - 1];
+                last.end = cmp::max(last.end, range.end);
+            } else {
+                self.dirty[merged] = range;
+                merged += 1;
+            }
+        }
+
+        self.dirty.truncate(merged);
+        self.coalesced = merged;
+    }
+}
+
+impl<T> Default for DirtyRangeVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<T>> for DirtyRangeVec<T> {
+    /// Wraps a vector, marking every element dirty.
+    fn from(vec: Vec<T>) -> Self {
+        let mut v = Self {
+            vec,
+            dirty: Vec::new(),
+            coalesced: 0,
+        };
+        v.mark(0..v.vec.len);
+        v
+    }
+}
+
+impl<T> Deref for DirtyRangeVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T> Index<usize> for DirtyRangeVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.vec[index]
+    }
+}
+
+impl<T> IndexMut<usize> for DirtyRangeVec<T> {
+    /// Marks the element dirty.
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T> Extend<T> for DirtyRangeVec<T> {
+    /// Appends the elements, marking them dirty.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let start = self.vec.len;
+        self.vec.extend(iter);
+        self.mark(start..self.vec.len);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DirtyRangeVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
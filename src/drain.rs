@@ -1,13 +1,23 @@
-use std::marker::PhantomData;
+use std::{
+    alloc::{Allocator, Global},
+    marker::PhantomData,
+    ptr::NonNull,
+};
 
-use crate::raw::RawValIter;
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    raw::RawValIter,
+    Vec,
+};
 
-pub struct Drain<'a, T: 'a> {
-    pub vec: PhantomData<&'a mut Vec<T>>,
+pub struct Drain<'a, T: 'a, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    // drained vector, given back to the growth policy once the drain is over
+    pub vec: NonNull<Vec<T, A, G>>,
     pub iter: RawValIter<T>,
+    pub marker: PhantomData<&'a mut Vec<T, A, G>>,
 }
 
-impl<'a, T> Iterator for Drain<'a, T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> Iterator for Drain<'a, T, A, G> {
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
@@ -18,14 +28,16 @@ impl<'a, T> Iterator for Drain<'a, T> {
     }
 }
 
-impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Drain<'a, T, A, G> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back()
     }
 }
 
-impl<'a, T> Drop for Drain<'a, T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> Drop for Drain<'a, T, A, G> {
     fn drop(&mut self) {
-        for _ in &mut *self {}
+        self.iter.drop_remaining();
+
+        unsafe { self.vec.as_mut().shrink_by_policy() }
     }
 }
@@ -0,0 +1,308 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// Fieldless enum whose variants index an [`EnumIndexedVec`], numbered from 0 in declaration order. With the
+/// `derive` feature, `#[derive(EnumIndex)]` implements it for enums without fields.
+/// # Example
+/// ```
+/// use vec::EnumIndex;
+///
+/// #[derive(Clone, Copy, PartialEq, Debug)]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// impl EnumIndex for Color {
+///     const COUNT: usize = 2;
+///
+///     fn index(self) -> usize {
+///         self as usize
+///     }
+///
+///     fn from_index(index: usize) -> Self {
+///         [Color::Red, Color::Green][index]
+///     }
+/// }
+///
+/// assert_eq!(Color::from_index(Color::Green.index()), Color::Green);
+/// ```
+pub trait EnumIndex: Copy {
+    /// Number of variants.
+    const COUNT: usize;
+
+    /// Returns the position of the variant, less than [`COUNT`](EnumIndex::COUNT).
+    fn index(self) -> usize;
+
+    /// Returns the variant at position `index`.
+    /// # Panics
+    /// Implementations panic if `index >= COUNT`.
+    fn from_index(index: usize) -> Self;
+}
+
+impl EnumIndex for bool {
+    const COUNT: usize = 2;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => false,
+            1 => true,
+            _ => panic!("index {} out of range for `bool`", index),
+        }
+    }
+}
+
+/// Table holding a value for every variant of the enum `E`, such as per-opcode counters.
+///
+/// It's always fully populated, so lookups can't fail, and it iterates in variant order. The values sit in a
+/// [`Vec`] of exactly `E::COUNT` elements.
+/// # Example
+/// ```
+/// use vec::{EnumIndex, EnumIndexedVec};
+///
+/// #[derive(Clone, Copy, PartialEq, Debug)]
+/// enum Op {
+///     Load,
+///     Store,
+///     Jump,
+/// }
+///
+/// impl EnumIndex for Op {
+///     const COUNT: usize = 3;
+///
+///     fn index(self) -> usize {
+///         self as usize
+///     }
+///
+///     fn from_index(index: usize) -> Self {
+///         [Op::Load, Op::Store, Op::Jump][index]
+///     }
+/// }
+///
+/// let mut counts: EnumIndexedVec<Op, u64> = EnumIndexedVec::new();
+/// for op in [Op::Load, Op::Jump, Op::Load].iter() {
+///     counts[*op] += 1;
+/// }
+///
+/// assert_eq!(counts[Op::Load], 2);
+/// assert_eq!(counts[Op::Store], 0);
+/// assert_eq!(
+///     counts.iter().collect::<std::vec::Vec<_>>(),
+///     [(Op::Load, &2), (Op::Store, &0), (Op::Jump, &1)]
+/// );
+/// ```
+pub struct EnumIndexedVec<E: EnumIndex, T> {
+    // `E::COUNT` values, the one of variant `e` at `e.index()`
+    values: Vec<T>,
+    marker: PhantomData<fn(E)>,
+}
+
+impl<E: EnumIndex, T> EnumIndexedVec<E, T> {
+    /// Creates a table with the default value for every variant.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let flags: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// assert_eq!(flags[true], 0);
+    /// ```
+    pub fn new() -> Self
+    where
+        T: Default,
+    {
+        Self::from_fn(|_| T::default())
+    }
+
+    /// Creates a table with the value `f` returns for every variant, called in variant order.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let names = vec::EnumIndexedVec::from_fn(|b: bool| if b { "yes" } else { "no" });
+    /// assert_eq!(names[false], "no");
+    /// ```
+    pub fn from_fn<F: FnMut(E) -> T>(mut f: F) -> Self {
+        let mut values = Vec::with_capacity(E::COUNT);
+        for index in 0..E::COUNT {
+            values.push(f(E::from_index(index)));
+        }
+
+        Self {
+            values,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of values, which is the number of variants.
+    /// # Example
+    /// ```
+    /// let flags: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// assert_eq!(flags.len(), 2);
+    /// ```
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        E::COUNT
+    }
+
+    /// Returns a reference to the value of `key`.
+    /// # Example
+    /// ```
+    /// let flags: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// assert_eq!(flags.get(false), &0);
+    /// ```
+    pub fn get(&self, key: E) -> &T {
+        &self.values[key.index()]
+    }
+
+    /// Returns a mutable reference to the value of `key`.
+    /// # Example
+    /// ```
+    /// let mut flags: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// *flags.get_mut(true) = 3;
+    /// assert_eq!(flags[true], 3);
+    /// ```
+    pub fn get_mut(&mut self, key: E) -> &mut T {
+        &mut self.values[key.index()]
+    }
+
+    /// Replaces the value of `key` and returns the old one.
+    /// # Example
+    /// ```
+    /// let mut flags: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// assert_eq!(flags.set(true, 3), 0);
+    /// assert_eq!(flags[true], 3);
+    /// ```
+    pub fn set(&mut self, key: E, value: T) -> T {
+        mem::replace(self.get_mut(key), value)
+    }
+
+    /// Returns an iterator over the variants, in order.
+    /// # Example
+    /// ```
+    /// let flags: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// assert_eq!(flags.keys().collect::<std::vec::Vec<_>>(), [false, true]);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = E> {
+        (0..E::COUNT).map(E::from_index)
+    }
+
+    /// Returns the values in variant order.
+    /// # Example
+    /// ```
+    /// let names = vec::EnumIndexedVec::from_fn(|b: bool| b as u8 * 10);
+    /// assert_eq!(names.values(), &[0, 10]);
+    /// ```
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the values in variant order, mutably.
+    /// # Example
+    /// ```
+    /// let mut counts: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// counts.values_mut().fill(1);
+    /// assert_eq!(counts[false], 1);
+    /// ```
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Returns an iterator over the variants and their values, in variant order.
+    /// # Example
+    /// ```
+    /// let names = vec::EnumIndexedVec::from_fn(|b: bool| b as u8);
+    /// assert_eq!(names.iter().collect::<std::vec::Vec<_>>(), [(false, &0), (true, &1)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (E, &T)> + '_ {
+        self.keys().zip(self.values.iter())
+    }
+
+    /// Returns an iterator over the variants and mutable references to their values, in variant order.
+    /// # Example
+    /// ```
+    /// let mut counts: vec::EnumIndexedVec<bool, u8> = vec::EnumIndexedVec::new();
+    /// for (key, count) in counts.iter_mut() {
+    ///     *count = key as u8 + 1;
+    /// }
+    /// assert_eq!(counts.values(), &[1, 2]);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (E, &mut T)> + '_ {
+        (0..E::COUNT).map(E::from_index).zip(self.values.iter_mut())
+    }
+
+    /// Returns a table with `f` applied to the value of every variant.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let counts = vec::EnumIndexedVec::from_fn(|b: bool| b as u32 + 1);
+    /// let halves = counts.map(|_, &count| count as f32 / 2.0);
+    /// assert_eq!(halves[true], 1.0);
+    /// ```
+    pub fn map<U, F: FnMut(E, &T) -> U>(&self, mut f: F) -> EnumIndexedVec<E, U> {
+        EnumIndexedVec::from_fn(|key| f(key, self.get(key)))
+    }
+
+    /// Returns the values, in variant order.
+    /// # Example
+    /// ```
+    /// let names = vec::EnumIndexedVec::from_fn(|b: bool| b as u8);
+    /// assert_eq!(&*names.into_vec(), &[0, 1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<E: EnumIndex, T: Default> Default for EnumIndexedVec<E, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: EnumIndex, T: Clone> Clone for EnumIndexedVec<E, T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<E: EnumIndex, T> Index<E> for EnumIndexedVec<E, T> {
+    type Output = T;
+
+    fn index(&self, key: E) -> &T {
+        self.get(key)
+    }
+}
+
+impl<E: EnumIndex, T> IndexMut<E> for EnumIndexedVec<E, T> {
+    fn index_mut(&mut self, key: E) -> &mut T {
+        self.get_mut(key)
+    }
+}
+
+impl<E: EnumIndex, T: PartialEq> PartialEq for EnumIndexedVec<E, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values[..] == other.values[..]
+    }
+}
+
+impl<E: EnumIndex, T: Eq> Eq for EnumIndexedVec<E, T> {}
+
+impl<E: EnumIndex + fmt::Debug, T: fmt::Debug> fmt::Debug for EnumIndexedVec<E, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    alloc::{Allocator, Global, Layout},
+    error::Error,
+    fmt, str,
+};
+
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    Vec,
+};
+
+/// Error returned by the fallible (`try_*`) allocating methods.
+/// # Example
+/// ```
+/// use vec::{TryReserveError, Vec};
+/// let err = Vec::<u64>::try_with_capacity(usize::MAX).unwrap_err();
+/// assert_eq!(err, TryReserveError::CapacityOverflow);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity exceeds `isize::MAX` bytes or overflows `usize`.
+    CapacityOverflow,
+    /// The allocator couldn't serve the request.
+    AllocError {
+        /// Layout of the failed allocation request.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => write!(f, "capacity overflow"),
+            TryReserveError::AllocError { layout } => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+        }
+    }
+}
+
+impl Error for TryReserveError {}
+
+/// Error returned when adding an element to a vector that is already at its maximum length, giving the element
+/// back.
+/// # Example
+/// ```
+/// use vec::ArrayVec;
+/// let mut vec: ArrayVec<i32, 1> = ArrayVec::new();
+/// vec.push(1);
+///
+/// let err = vec.try_push(2).unwrap_err();
+/// assert_eq!(err.element, 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    /// Element that couldn't be added.
+    pub element: T,
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "insufficient capacity")
+    }
+}
+
+impl<T: fmt::Debug> Error for CapacityError<T> {}
+
+/// Error returned by [`String::from_utf8`](crate::String::from_utf8) when the bytes aren't valid UTF-8, giving
+/// them back.
+/// # Example
+/// ```
+/// use vec::{String, Vec};
+/// let mut bytes = Vec::new();
+/// bytes.extend([b'a', 0xFF]);
+///
+/// let err = String::from_utf8(bytes).unwrap_err();
+/// assert_eq!(err.utf8_error().valid_up_to(), 1);
+/// assert_eq!(&*err.into_bytes(), &[b'a', 0xFF]);
+/// ```
+pub struct FromUtf8Error<A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    pub(crate) bytes: Vec<u8, A, G>,
+    pub(crate) error: str::Utf8Error,
+}
+
+impl<A: Allocator, G: GrowthPolicy> FromUtf8Error<A, G> {
+    /// Returns the bytes that failed the conversion.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the bytes that failed the conversion, without copying.
+    pub fn into_bytes(self) -> Vec<u8, A, G> {
+        self.bytes
+    }
+
+    /// Returns where and why the bytes aren't valid UTF-8.
+    pub fn utf8_error(&self) -> str::Utf8Error {
+        self.error
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> fmt::Debug for FromUtf8Error<A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromUtf8Error")
+            .field("bytes", &self.as_bytes())
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> fmt::Display for FromUtf8Error<A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> Error for FromUtf8Error<A, G> {}
+
+/// Error returned when converting an empty vector into a [`NonEmptyVec`](crate::NonEmptyVec).
+/// # Example
+/// ```
+/// use std::convert::TryFrom;
+/// use vec::{EmptyError, NonEmptyVec, Vec};
+///
+/// let err = NonEmptyVec::try_from(Vec::<i32>::new()).unwrap_err();
+/// assert_eq!(err, EmptyError);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyError;
+
+impl fmt::Display for EmptyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "vector is empty")
+    }
+}
+
+impl Error for EmptyError {}
+
+/// Error returned by the reading methods of a [`ByteBuf`](crate::ByteBuf).
+/// # Example
+/// ```
+/// use vec::{ByteBuf, ByteBufError};
+/// let mut buf = ByteBuf::new();
+/// buf.put_u8(1);
+///
+/// let err = buf.get_u32_be().unwrap_err();
+/// assert_eq!(err, ByteBufError::Underflow { needed: 4, remaining: 1 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteBufError {
+    /// Fewer bytes are left than the read needs.
+    Underflow {
+        /// Number of bytes the read needs, at least.
+        needed: usize,
+        /// Number of bytes left.
+        remaining: usize,
+    },
+    /// A variable-length integer doesn't fit in 64 bits.
+    Overflow,
+}
+
+impl fmt::Display for ByteBufError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ByteBufError::Underflow { needed, remaining } => {
+                write!(f, "needed {} bytes but only {} are left", needed, remaining)
+            }
+            ByteBufError::Overflow => write!(f, "variable-length integer overflows 64 bits"),
+        }
+    }
+}
+
+impl Error for ByteBufError {}
@@ -0,0 +1,223 @@
+use std::{
+    alloc::{AllocError, Allocator, Layout},
+    cell::{Cell, UnsafeCell},
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ptr::NonNull,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::Vec;
+
+/// Allocator handing out a single caller-provided buffer, so a vector can live on the stack, in a `static` or in any
+/// memory it doesn't own, without ever touching the heap.
+///
+/// The buffer is lent to one vector at a time and never moves: the vector can grow until it fills the buffer, after
+/// which pushing is an allocation error (see [`Vec::try_push`]).
+///
+/// Where the storage of a vector comes from is chosen through its allocator rather than a separate storage trait
+/// over `RawVec`: the heap is [`Global`](std::alloc::Global), a borrowed or `static` buffer is `Fixed` (see
+/// [`StaticBuffer`]), and inline storage is [`ArrayVec`](crate::ArrayVec) or [`SmallVec`](crate::SmallVec).
+/// # Example
+/// ```
+/// use std::mem::MaybeUninit;
+/// use vec::Vec;
+///
+/// let mut buf = [MaybeUninit::uninit(); 16];
+/// let mut vec = Vec::from_buffer(&mut buf);
+///
+/// vec.extend(0..16u32);
+/// assert_eq!(vec.capacity(), 16);
+/// assert!(vec.try_push(16).is_err());
+/// ```
+///
+/// A `static` buffer works the same way, claimed through a [`StaticBuffer`]:
+/// ```
+/// use vec::{StaticBuffer, Vec};
+///
+/// static BUF: StaticBuffer<u8, 1024> = StaticBuffer::new();
+///
+/// let mut vec = Vec::from_buffer(BUF.take().unwrap());
+/// vec.extend(b"hello".iter().copied());
+/// assert_eq!(&*vec, b"hello");
+/// ```
+pub struct Fixed<'a, T> {
+    // start of the lent buffer
+    ptr: NonNull<T>,
+    // length of the buffer, in elements
+    len: usize,
+    // whether the buffer is currently handed out
+    in_use: Cell<bool>,
+    _buf: PhantomData<&'a mut [MaybeUninit<T>]>,
+}
+
+impl<'a, T> Fixed<'a, T> {
+    /// Creates an allocator lending `buf`.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::mem::MaybeUninit;
+    /// use vec::{Fixed, Vec};
+    ///
+    /// let mut buf: [MaybeUninit<u64>; 4] = [MaybeUninit::uninit(); 4];
+    /// let mut vec = Vec::new_in(Fixed::new(&mut buf));
+    /// vec.push(1u64);
+    /// assert_eq!(vec.capacity(), 4);
+    /// ```
+    pub fn new(buf: &'a mut [MaybeUninit<T>]) -> Self {
+        Self {
+            ptr: NonNull::from(&mut *buf).cast(),
+            len: buf.len(),
+            in_use: Cell::new(false),
+            _buf: PhantomData,
+        }
+    }
+
+    /// Returns the size of the buffer, in elements.
+    /// # Example
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use vec::Fixed;
+    /// let mut buf = [MaybeUninit::<u16>::uninit(); 10];
+    /// assert_eq!(Fixed::new(&mut buf).len(), 10);
+    /// ```
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    // Whether the buffer can hold the given layout
+    fn fits(&self, layout: Layout) -> bool {
+        layout.size() <= self.len * mem::size_of::<T>()
+            && self.ptr.as_ptr().addr().is_multiple_of(layout.align())
+    }
+
+    // The whole buffer, as bytes
+    fn block(&self) -> NonNull<[u8]> {
+        NonNull::slice_from_raw_parts(self.ptr.cast(), self.len * mem::size_of::<T>())
+    }
+}
+
+impl<'a, T> Vec<T, Fixed<'a, T>> {
+    /// Creates an empty vector storing its elements in `buf`, its capacity is the length of `buf`.
+    /// # Example
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use vec::Vec;
+    /// let mut buf = [MaybeUninit::uninit(); 3];
+    /// let mut vec = Vec::from_buffer(&mut buf);
+    /// vec.push('a');
+    /// assert_eq!(vec.capacity(), 3);
+    /// ```
+    pub fn from_buffer(buf: &'a mut [MaybeUninit<T>]) -> Self {
+        let len = buf.len();
+
+        Self::try_with_capacity_in(len, Fixed::new(buf))
+            .expect("An unused buffer can always serve its own length")
+    }
+}
+
+unsafe impl<T> Allocator for Fixed<'_, T> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if self.in_use.get() || !self.fits(layout) {
+            return Err(AllocError);
+        }
+
+        self.in_use.set(true);
+
+        // The whole buffer is usable, no matter how much was requested
+        Ok(self.block())
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        self.in_use.set(false);
+    }
+
+    unsafe fn grow(
+        &self,
+        _ptr: NonNull<u8>,
+        _old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // The buffer never moves: either the new size still fits in it or the request can't be served
+        if self.fits(new_layout) {
+            Ok(self.block())
+        } else {
+            Err(AllocError)
+        }
+    }
+
+    unsafe fn shrink(
+        &self,
+        _ptr: NonNull<u8>,
+        _old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // Shrinking keeps the whole buffer, as long as it can still serve the new layout
+        if self.fits(new_layout) {
+            Ok(self.block())
+        } else {
+            Err(AllocError)
+        }
+    }
+}
+
+/// Buffer of `N` elements meant for a `static`, handing out a mutable reference to its memory once, so a
+/// [`Fixed`] vector can live in a `static` without any `unsafe`.
+/// # Example
+/// ```
+/// use vec::{StaticBuffer, Vec};
+///
+/// static BUF: StaticBuffer<u32, 8> = StaticBuffer::new();
+///
+/// let mut vec = Vec::from_buffer(BUF.take().unwrap());
+/// vec.extend(0..8);
+/// assert_eq!(vec.capacity(), 8);
+/// assert!(BUF.take().is_none());
+/// ```
+pub struct StaticBuffer<T, const N: usize> {
+    buf: UnsafeCell<[MaybeUninit<T>; N]>,
+    // whether the buffer was handed out
+    taken: AtomicBool,
+}
+
+// The buffer is only ever reached through the single reference `take` hands out
+unsafe impl<T: Send, const N: usize> Sync for StaticBuffer<T, N> {}
+
+impl<T, const N: usize> StaticBuffer<T, N> {
+    /// Creates an uninitialized buffer, usable as the initializer of a `static`.
+    /// # Example
+    /// ```
+    /// static BUF: vec::StaticBuffer<u8, 64> = vec::StaticBuffer::new();
+    /// assert_eq!(BUF.take().unwrap().len(), 64);
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            buf: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+            taken: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the buffer the first time it's called, and `None` afterwards.
+    /// # Example
+    /// ```
+    /// static BUF: vec::StaticBuffer<u8, 64> = vec::StaticBuffer::new();
+    /// assert!(BUF.take().is_some());
+    /// assert!(BUF.take().is_none());
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn take(&'static self) -> Option<&'static mut [MaybeUninit<T>]> {
+        if self.taken.swap(true, Ordering::Acquire) {
+            return None;
+        }
+
+        // Only the first call gets here, so the reference is unique
+        Some(unsafe { &mut *self.buf.get() })
+    }
+}
+
+impl<T, const N: usize> Default for StaticBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    fmt,
+    iter::FromIterator,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// List of lists stored in two buffers, like an Arrow `ListArray`: every element of every list in one values buffer,
+/// and an offsets buffer where list `i` spans `offsets[i]..offsets[i + 1]`.
+///
+/// Unlike `Vec<Vec<T>>` it makes two allocations in total instead of one per list, and keeps the elements of
+/// consecutive lists next to each other. Lists can only be appended at the end, but their elements can be mutated.
+/// # Example
+/// ```
+/// use vec::FlatListVec;
+///
+/// let mut words = FlatListVec::new();
+/// words.push_list("hello".chars());
+/// words.push_list("world".chars());
+/// words.push_list(None);
+///
+/// assert_eq!(words.len(), 3);
+/// assert_eq!(words[1], ['w', 'o', 'r', 'l', 'd']);
+/// assert!(words[2].is_empty());
+/// assert_eq!(words.offsets(), &[0, 5, 10, 10]);
+/// assert_eq!(words.iter().map(|w| w.len()).sum::<usize>(), 10);
+/// ```
+#[derive(Clone)]
+pub struct FlatListVec<T> {
+    values: Vec<T>,
+    // `len + 1` ascending offsets into `values`, starting with 0 and ending with `values.len`
+    offsets: Vec<usize>,
+}
+
+impl<T> FlatListVec<T> {
+    /// Creates an empty list of lists.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v: vec::FlatListVec<i32> = vec::FlatListVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::with_capacity(0, 0)
+    }
+
+    /// Creates an empty list of lists with room for `lists` lists holding `values` elements in total.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::with_capacity(2, 10);
+    /// v.push_list(0..5);
+    /// v.push_list(5..10);
+    /// assert_eq!(v.values_len(), 10);
+    /// ```
+    pub fn with_capacity(lists: usize, values: usize) -> Self {
+        let mut offsets = Vec::with_capacity(lists + 1);
+        offsets.push(0);
+
+        Self {
+            values: Vec::with_capacity(values),
+            offsets,
+        }
+    }
+
+    /// Returns the number of lists.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list([1, 2].iter().copied());
+    /// v.push_list(None);
+    /// assert_eq!(v.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.offsets.len - 1
+    }
+
+    /// Returns `true` if there are no lists.
+    /// # Example
+    /// ```
+    /// let v: vec::FlatListVec<i32> = vec::FlatListVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements of every list together.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..3);
+    /// v.push_list(0..2);
+    /// assert_eq!(v.values_len(), 5);
+    /// ```
+    pub fn values_len(&self) -> usize {
+        self.values.len
+    }
+
+    /// Appends a list with the elements of an iterator and returns its index.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// assert_eq!(v.push_list(0..3), 0);
+    /// assert_eq!(v.push_list(3..4), 1);
+    /// assert_eq!(v[1], [3]);
+    /// ```
+    pub fn push_list<I: IntoIterator<Item = T>>(&mut self, list: I) -> usize {
+        self.values.extend(list);
+        self.offsets.push(self.values.len);
+        self.len() - 1
+    }
+
+    /// Appends a list with a copy of the elements of `slice` and returns its index.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_slice(b"abc");
+    /// assert_eq!(v[0], *b"abc");
+    /// ```
+    pub fn push_slice(&mut self, slice: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        self.values.insert_slice(self.values.len, slice);
+        self.offsets.push(self.values.len);
+        self.len() - 1
+    }
+
+    /// Appends an element to the last list.
+    /// # Panics
+    /// Panics if there are no lists, or if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(None);
+    /// v.push_to_last(1);
+    /// v.push_to_last(2);
+    /// assert_eq!(v[0], [1, 2]);
+    /// ```
+    pub fn push_to_last(&mut self, elem: T) {
+        assert!(!self.is_empty(), "no list to push to");
+
+        self.values.push(elem);
+        *self.offsets.last_mut().unwrap() += 1;
+    }
+
+    /// Removes the last list and returns its elements, or `None` if there are no lists.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..2);
+    /// assert_eq!(v.pop_list().as_deref(), Some(&[0, 1][..]));
+    /// assert_eq!(v.pop_list(), None);
+    /// ```
+    pub fn pop_list(&mut self) -> Option<Vec<T>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.offsets.pop();
+        let start = *self.offsets.last().unwrap();
+
+        let mut list = Vec::with_capacity(self.values.len - start);
+        while self.values.len > start {
+            list.push(self.values.pop().unwrap());
+        }
+        list.reverse();
+
+        Some(list)
+    }
+
+    /// Returns the list at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..2);
+    /// assert_eq!(v.get(0), Some(&[0, 1][..]));
+    /// assert_eq!(v.get(1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&[T]> {
+        if index < self.len() {
+            Some(&self.values[self.offsets[index]..self.offsets[index + 1]])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the list at `index` mutably, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..2);
+    /// v.get_mut(0).unwrap().reverse();
+    /// assert_eq!(v[0], [1, 0]);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut [T]> {
+        if index < self.len() {
+            Some(&mut self.values[self.offsets[index]..self.offsets[index + 1]])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the elements of every list, one list after the other.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..2);
+    /// v.push_list(5..6);
+    /// assert_eq!(v.values(), &[0, 1, 5]);
+    /// ```
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the `len + 1` offsets of the lists in [`values`](FlatListVec::values): list `i` spans
+    /// `offsets[i]..offsets[i + 1]`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..2);
+    /// v.push_list(5..6);
+    /// assert_eq!(v.offsets(), &[0, 2, 3]);
+    /// ```
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Returns an iterator over the lists.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..2);
+    /// v.push_list(None);
+    /// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [&[0, 1][..], &[]]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator + '_ {
+        self.offsets
+            .windows(2)
+            .map(move |w| &self.values[w[0]..w[1]])
+    }
+
+    /// Calls `f` with every list, mutably.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list([3, 1, 2].iter().copied());
+    /// v.push_list([5, 4].iter().copied());
+    /// v.for_each_mut(|list| list.sort());
+    /// assert_eq!(v.values(), &[1, 2, 3, 4, 5]);
+    /// ```
+    pub fn for_each_mut<F: FnMut(&mut [T])>(&mut self, mut f: F) {
+        let mut rest = &mut self.values[..];
+
+        for w in self.offsets.windows(2) {
+            let (list, tail) = rest.split_at_mut(w[1] - w[0]);
+            f(list);
+            rest = tail;
+        }
+    }
+
+    /// Removes every list, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FlatListVec::new();
+    /// v.push_list(0..2);
+    /// v.clear();
+    /// assert!(v.is_empty() && v.values().is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.offsets.truncate(1);
+    }
+}
+
+impl<T> Default for FlatListVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for FlatListVec<T> {
+    type Output = [T];
+
+    fn index(&self, index: usize) -> &[T] {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for FlatListVec<T> {
+    fn index_mut(&mut self, index: usize) -> &mut [T] {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T, L: IntoIterator<Item = T>> Extend<L> for FlatListVec<T> {
+    /// Appends every list of an iterator.
+    fn extend<I: IntoIterator<Item = L>>(&mut self, iter: I) {
+        for list in iter {
+            self.push_list(list);
+        }
+    }
+}
+
+impl<T, L: IntoIterator<Item = T>> FromIterator<L> for FlatListVec<T> {
+    fn from_iter<I: IntoIterator<Item = L>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T: PartialEq> PartialEq for FlatListVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offsets == other.offsets && self.values == other.values
+    }
+}
+
+impl<T: Eq> Eq for FlatListVec<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for FlatListVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
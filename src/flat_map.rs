@@ -0,0 +1,415 @@
+use std::{
+    borrow::Borrow,
+    fmt,
+    iter::FromIterator,
+    mem,
+    ops::{Bound, Index, RangeBounds},
+};
+
+use crate::Vec;
+
+/// Map keeping its keys sorted in one vector and their values in another, so lookups are binary searches over
+/// contiguous keys.
+///
+/// For small or read-heavy maps it beats a `BTreeMap` on memory and lookup speed, but inserting or removing moves
+/// every entry after the changed one. Build large maps in bulk with [`FlatMap::from_sorted_iter`] or
+/// [`collect`](Iterator::collect), which sort once.
+/// # Example
+/// ```
+/// use vec::FlatMap;
+///
+/// let mut ports = FlatMap::new();
+/// ports.insert("https", 443);
+/// ports.insert("http", 80);
+/// ports.insert("ssh", 22);
+///
+/// assert_eq!(ports.get("http"), Some(&80));
+/// assert_eq!(ports.keys(), &["http", "https", "ssh"]);
+/// assert_eq!(ports.range("i".."z").count(), 1);
+/// ```
+#[derive(Clone)]
+pub struct FlatMap<K, V> {
+    // strictly ascending
+    keys: Vec<K>,
+    // value of `keys[i]` at `values[i]`
+    values: Vec<V>,
+}
+
+impl<K: Ord, V> FlatMap<K, V> {
+    /// Creates an empty map, without allocating.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<u32, &str> = vec::FlatMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Creates an empty map with room for `capacity` entries.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<u32, &str> = vec::FlatMap::with_capacity(10);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a map from entries already sorted by key, in O(n).
+    /// # Panics
+    /// Panics if the keys aren't strictly ascending, or if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let map = vec::FlatMap::from_sorted_iter((0..100).map(|i| (i, i * i)));
+    /// assert_eq!(map.get(&9), Some(&81));
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut map = Self::with_capacity(iter.size_hint().0);
+
+        for (key, value) in iter {
+            if let Some(last) = map.keys.last() {
+                assert!(last < &key, "keys must be strictly ascending");
+            }
+
+            map.keys.push(key);
+            map.values.push(value);
+        }
+
+        map
+    }
+
+    /// Returns the number of entries.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.keys.len
+    }
+
+    /// Returns `true` if the map has no entries.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<u32, char> = vec::FlatMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.keys.len == 0
+    }
+
+    /// Inserts a value for `key`, and returns the one it replaced if any.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// assert_eq!(map.insert(1, 'a'), None);
+    /// assert_eq!(map.insert(1, 'b'), Some('a'));
+    /// assert_eq!(map[&1], 'b');
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.keys.binary_search(&key) {
+            Ok(index) => Some(mem::replace(&mut self.values[index], value)),
+            Err(index) => {
+                self.keys.insert(index, key);
+                self.values.insert(index, value);
+                None
+            }
+        }
+    }
+
+    /// Removes the entry of `key` and returns its value, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.remove(&1), Some('a'));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.find(key)?;
+        self.keys.remove(index);
+        Some(self.values.remove(index))
+    }
+
+    /// Returns `true` if the map has an entry for `key`.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// map.insert(1, 'a');
+    /// assert!(map.contains_key(&1));
+    /// assert!(!map.contains_key(&2));
+    /// ```
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns a reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// map.insert(String::from("a"), 1);
+    /// assert_eq!(map.get("a"), Some(&1));
+    /// ```
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        Some(&self.values[self.find(key)?])
+    }
+
+    /// Returns a mutable reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// map.insert(1, 10);
+    /// *map.get_mut(&1).unwrap() += 1;
+    /// assert_eq!(map[&1], 11);
+    /// ```
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.find(key)?;
+        Some(&mut self.values[index])
+    }
+
+    /// Returns the stored key equal to `key` and its value, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// map.insert(String::from("a"), 1);
+    /// assert_eq!(map.get_key_value("a"), Some((&String::from("a"), &1)));
+    /// ```
+    pub fn get_key_value<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.find(key)?;
+        Some((&self.keys[index], &self.values[index]))
+    }
+
+    /// Returns the entry with the smallest key, or `None` if the map is empty.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<_, _> = [(2, 'b'), (1, 'a')].iter().copied().collect();
+    /// assert_eq!(map.first(), Some((&1, &'a')));
+    /// ```
+    pub fn first(&self) -> Option<(&K, &V)> {
+        Some((self.keys.first()?, self.values.first()?))
+    }
+
+    /// Returns the entry with the greatest key, or `None` if the map is empty.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<_, _> = [(2, 'b'), (1, 'a')].iter().copied().collect();
+    /// assert_eq!(map.last(), Some((&2, &'b')));
+    /// ```
+    pub fn last(&self) -> Option<(&K, &V)> {
+        Some((self.keys.last()?, self.values.last()?))
+    }
+
+    /// Returns an iterator over the entries with a key within `range`, in key order, found by binary search.
+    /// # Example
+    /// ```
+    /// let map = vec::FlatMap::from_sorted_iter((0..10).map(|i| (i, i * 10)));
+    /// assert_eq!(map.range(3..6).map(|(_, v)| *v).collect::<std::vec::Vec<_>>(), [30, 40, 50]);
+    /// assert_eq!(map.range(..=1).count(), 2);
+    /// ```
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator + '_
+    where
+        K: Borrow<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.keys.partition_point(|k| k.borrow() < start),
+            Bound::Excluded(start) => self.keys.partition_point(|k| k.borrow() <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.keys.partition_point(|k| k.borrow() <= end),
+            Bound::Excluded(end) => self.keys.partition_point(|k| k.borrow() < end),
+            Bound::Unbounded => self.keys.len,
+        };
+
+        // An empty range (e.g. `3..1`) would end before it starts
+        let end = end.max(start);
+        self.keys[start..end].iter().zip(&self.values[start..end])
+    }
+
+    /// Returns an iterator over the entries, in key order.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<_, _> = [(2, 'b'), (1, 'a')].iter().copied().collect();
+    /// assert_eq!(map.iter().collect::<std::vec::Vec<_>>(), [(&1, &'a'), (&2, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator + '_ {
+        self.keys.iter().zip(self.values.iter())
+    }
+
+    /// Returns an iterator over the entries with mutable references to the values, in key order.
+    /// # Example
+    /// ```
+    /// let mut map: vec::FlatMap<_, _> = [(1, 10), (2, 20)].iter().copied().collect();
+    /// map.iter_mut().for_each(|(_, v)| *v += 1);
+    /// assert_eq!(map.values(), &[11, 21]);
+    /// ```
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator + '_ {
+        self.keys.iter().zip(self.values.iter_mut())
+    }
+
+    /// Returns the keys, in ascending order.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<_, _> = [(2, 'b'), (1, 'a')].iter().copied().collect();
+    /// assert_eq!(map.keys(), &[1, 2]);
+    /// ```
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+
+    /// Returns the values, in key order.
+    /// # Example
+    /// ```
+    /// let map: vec::FlatMap<_, _> = [(2, 'b'), (1, 'a')].iter().copied().collect();
+    /// assert_eq!(map.values(), &['a', 'b']);
+    /// ```
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns the values mutably, in key order.
+    /// # Example
+    /// ```
+    /// let mut map: vec::FlatMap<_, _> = [(2, 0), (1, 0)].iter().copied().collect();
+    /// map.values_mut()[1] = 5;
+    /// assert_eq!(map[&2], 5);
+    /// ```
+    pub fn values_mut(&mut self) -> &mut [V] {
+        &mut self.values
+    }
+
+    /// Removes every entry, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut map = vec::FlatMap::new();
+    /// map.insert(1, 'a');
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+    }
+
+    fn find<Q: Ord + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.keys.binary_search_by(|k| k.borrow().cmp(key)).ok()
+    }
+}
+
+impl<K: Ord, V> Default for FlatMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Borrow<Q>, Q: Ord + ?Sized, V> Index<&Q> for FlatMap<K, V> {
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry for key")
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for FlatMap<K, V> {
+    /// Inserts every entry of an iterator, a later value replacing an earlier one of the same key.
+    ///
+    /// Entries are sorted and merged with the existing ones in a single pass, rather than inserted one by one.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let mut new = Vec::new();
+        new.extend(iter);
+        if new.len == 0 {
+            return;
+        }
+        // Stable, so the last of equal keys stays last
+        new.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let old = mem::take(self);
+        self.keys.reserve(old.len() + new.len);
+        self.values.reserve(old.len() + new.len);
+
+        let mut old = old.keys.into_iter().zip(old.values.into_iter()).peekable();
+        let mut new = new.into_iter().peekable();
+
+        loop {
+            let entry = match (old.peek(), new.peek()) {
+                (Some(a), Some(b)) if a.0 < b.0 => old.next(),
+                (Some(a), Some(b)) if a.0 == b.0 => {
+                    old.next();
+                    new.next()
+                }
+                (_, Some(_)) => new.next(),
+                (Some(_), None) => old.next(),
+                (None, None) => break,
+            };
+            let (key, value) = entry.unwrap();
+
+            match self.keys.last() {
+                Some(last) if *last == key => *self.values.last_mut().unwrap() = value,
+                _ => {
+                    self.keys.push(key);
+                    self.values.push(value);
+                }
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for FlatMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for FlatMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys && self.values == other.values
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for FlatMap<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for FlatMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.keys.iter().zip(self.values.iter()))
+            .finish()
+    }
+}
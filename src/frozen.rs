@@ -0,0 +1,175 @@
+use std::{cell::UnsafeCell, fmt, ops::Index};
+
+use crate::SegmentedVec;
+
+/// Single-threaded vector that can be pushed to through `&self` while references to its elements are held. Elements
+/// are stored in a [`SegmentedVec`], so they never move once pushed.
+///
+/// Suited to interners and arenas, which hand out `&T` to earlier items while adding new ones.
+/// # Example
+/// ```
+/// use vec::FrozenVec;
+///
+/// let names = FrozenVec::new();
+/// let alice: &str = names.push_get("alice".to_string());
+/// let bob: &str = names.push_get("bob".to_string());
+///
+/// // Both references stay valid across the pushes
+/// assert_eq!((alice, bob), ("alice", "bob"));
+/// assert_eq!(names.len(), 2);
+/// ```
+pub struct FrozenVec<T> {
+    // only ever mutated by appending, which doesn't move existing elements
+    vec: UnsafeCell<SegmentedVec<T>>,
+}
+
+impl<T> FrozenVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::FrozenVec<i32> = vec::FrozenVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            vec: UnsafeCell::new(SegmentedVec::new()),
+        }
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let v = vec::FrozenVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        unsafe { (*self.vec.get()).len() }
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::FrozenVec<i32> = vec::FrozenVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an element and returns its index.
+    /// # Panics
+    /// This function will panic if a chunk exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v = vec::FrozenVec::new();
+    /// assert_eq!(v.push('a'), 0);
+    /// assert_eq!(v.push('b'), 1);
+    /// ```
+    pub fn push(&self, value: T) -> usize {
+        // No reference to the SegmentedVec itself outlives a call, and pushing doesn't touch the memory of the
+        // elements already handed out
+        let vec = unsafe { &mut *self.vec.get() };
+
+        vec.push(value);
+        vec.len() - 1
+    }
+
+    /// Appends an element and returns a reference to it, valid for as long as the vector is borrowed.
+    /// # Panics
+    /// This function will panic if a chunk exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v = vec::FrozenVec::new();
+    /// let a = v.push_get(1);
+    /// let b = v.push_get(2);
+    /// assert_eq!(*a + *b, 3);
+    /// ```
+    pub fn push_get(&self, value: T) -> &T {
+        let index = self.push(value);
+        &self[index]
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let v = vec::FrozenVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.get(0), Some(&1));
+    /// assert_eq!(v.get(1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        unsafe { (*self.vec.get()).get(index) }
+    }
+
+    /// Returns an iterator over the elements. Elements pushed while iterating are yielded too.
+    /// # Example
+    /// ```
+    /// let v = vec::FrozenVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// assert_eq!(v.iter().sum::<i32>(), 3);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        // Index by index, as a push may reallocate the table of chunks under a borrowing iterator
+        (0..).map_while(move |i| self.get(i))
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if out of bounds. Needs exclusive access,
+    /// as no other reference can be alive then.
+    /// # Example
+    /// ```
+    /// let mut v = vec::FrozenVec::new();
+    /// v.push(1);
+    /// *v.get_mut(0).unwrap() = 2;
+    /// assert_eq!(v[0], 2);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.vec.get_mut().get_mut(index)
+    }
+
+    /// Returns the underlying segmented vector.
+    /// # Example
+    /// ```
+    /// let v = vec::FrozenVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.into_inner()[0], 1);
+    /// ```
+    pub fn into_inner(self) -> SegmentedVec<T> {
+        self.vec.into_inner()
+    }
+}
+
+impl<T> Default for FrozenVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for FrozenVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> Extend<T> for FrozenVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.vec.get_mut().extend(iter)
+    }
+}
+
+impl<T> From<SegmentedVec<T>> for FrozenVec<T> {
+    fn from(vec: SegmentedVec<T>) -> Self {
+        Self {
+            vec: UnsafeCell::new(vec),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FrozenVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
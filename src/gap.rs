@@ -0,0 +1,535 @@
+use std::{
+    alloc::{Allocator, Global},
+    fmt, mem,
+    ops::{Index, IndexMut},
+    ptr, slice,
+};
+
+use crate::{
+    deque::drop_slices,
+    growth::{Doubling, GrowthPolicy},
+    raw::{handle_reserve_error, RawVec},
+    Vec,
+};
+
+/// Gap buffer over the same growable buffer as [`Vec`]: the elements sit at both ends of the allocation with the free
+/// space (the gap) between them, so inserting and deleting at the gap is O(1).
+///
+/// Suited to text editors, where edits cluster around a cursor: [`move_gap_to`](GapBuffer::move_gap_to) only moves
+/// the elements between the old and new positions.
+/// # Example
+/// ```
+/// use vec::GapBuffer;
+///
+/// let mut buf = GapBuffer::new();
+/// buf.extend("hllo".chars());
+///
+/// buf.move_gap_to(1);
+/// buf.insert('e');
+/// assert_eq!(buf.as_slices(), (&['h', 'e'][..], &['l', 'l', 'o'][..]));
+///
+/// assert_eq!(buf.delete_forward(), Some('l'));
+/// assert_eq!(buf.iter().collect::<String>(), "helo");
+/// ```
+pub struct GapBuffer<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    // elements before the gap live in `..gap_start`, elements after it in `gap_end..capacity`
+    gap_start: usize,
+    gap_end: usize,
+    buf: RawVec<T, A, G>,
+}
+
+impl<T> GapBuffer<T> {
+    /// Creates an empty gap buffer, without allocating.
+    /// # Example
+    /// ```
+    /// let buf: vec::GapBuffer<char> = vec::GapBuffer::new();
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates an empty gap buffer with room for `capacity` elements.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let buf: vec::GapBuffer<char> = vec::GapBuffer::with_capacity(10);
+    /// assert_eq!(buf.capacity(), 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity).into()
+    }
+}
+
+impl<T> Default for GapBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, A: Allocator> GapBuffer<T, A> {
+    /// Creates an empty gap buffer that will allocate from `alloc`.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Arena, GapBuffer};
+    /// let arena = Arena::new();
+    /// let mut buf = GapBuffer::new_in(&arena);
+    /// buf.insert(1);
+    /// assert_eq!(buf[0], 1);
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        let buf = RawVec::new_in(alloc);
+
+        Self {
+            gap_start: 0,
+            gap_end: buf.cap,
+            buf,
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> GapBuffer<T, A, G> {
+    /// Returns the number of elements, on both sides of the gap.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// buf.move_gap_to(1);
+    /// assert_eq!(buf.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.gap_start + (self.capacity() - self.gap_end)
+    }
+
+    /// Returns `true` if the buffer holds no elements.
+    /// # Example
+    /// ```
+    /// let buf: vec::GapBuffer<u8> = vec::GapBuffer::new();
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the buffer can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.insert(1u32);
+    /// assert_eq!(buf.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.buf.cap
+    }
+
+    /// Returns the position of the gap, which is the number of elements before it.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2, 3]);
+    /// assert_eq!(buf.gap_position(), 3);
+    /// buf.move_gap_to(1);
+    /// assert_eq!(buf.gap_position(), 1);
+    /// ```
+    pub fn gap_position(&self) -> usize {
+        self.gap_start
+    }
+
+    /// Moves the gap so it's preceded by `index` elements, shifting the elements in between to the other side.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2, 3]);
+    /// buf.move_gap_to(1);
+    /// assert_eq!(buf.as_slices(), (&[1][..], &[2, 3][..]));
+    /// ```
+    pub fn move_gap_to(&mut self, index: usize) {
+        assert!(index <= self.len(), "index out of bounds");
+
+        unsafe {
+            if index < self.gap_start {
+                // [a b c _ _ d] -> [a _ _ b c d]
+                let count = self.gap_start - index;
+                ptr::copy(
+                    self.ptr().add(index),
+                    self.ptr().add(self.gap_end - count),
+                    count,
+                );
+                self.gap_start = index;
+                self.gap_end -= count;
+            } else {
+                // [a _ _ b c d] -> [a b c _ _ d]
+                let count = index - self.gap_start;
+                ptr::copy(
+                    self.ptr().add(self.gap_end),
+                    self.ptr().add(self.gap_start),
+                    count,
+                );
+                self.gap_start = index;
+                self.gap_end += count;
+            }
+        }
+    }
+
+    /// Inserts an element at the gap, before the elements after it. The gap moves past the new element, like a
+    /// cursor after typing.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.insert('a');
+    /// buf.insert('c');
+    /// buf.move_gap_to(1);
+    /// buf.insert('b');
+    /// assert_eq!(buf.iter().collect::<String>(), "abc");
+    /// ```
+    pub fn insert(&mut self, elem: T) {
+        self.reserve(1);
+
+        unsafe { ptr::write(self.ptr().add(self.gap_start), elem) };
+        self.gap_start += 1;
+    }
+
+    /// Inserts a copy of every element of `slice` at the gap, moving the gap past them.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.insert_slice(b"held");
+    /// buf.move_gap_to(2);
+    /// buf.insert_slice(b"llo wor");
+    /// assert_eq!(buf.iter().copied().collect::<std::vec::Vec<_>>(), b"hello world");
+    /// ```
+    pub fn insert_slice(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(slice.len());
+
+        for elem in slice {
+            // Bump the gap after each write, so a panicking clone leaves the buffer consistent
+            unsafe { ptr::write(self.ptr().add(self.gap_start), elem.clone()) };
+            self.gap_start += 1;
+        }
+    }
+
+    /// Removes the element right before the gap and returns it, or `None` if there is none (backspace).
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// assert_eq!(buf.delete_backward(), Some(2));
+    /// buf.move_gap_to(0);
+    /// assert_eq!(buf.delete_backward(), None);
+    /// ```
+    pub fn delete_backward(&mut self) -> Option<T> {
+        if self.gap_start == 0 {
+            return None;
+        }
+
+        self.gap_start -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.gap_start))) }
+    }
+
+    /// Removes the element right after the gap and returns it, or `None` if there is none (delete).
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// assert_eq!(buf.delete_forward(), None);
+    /// buf.move_gap_to(0);
+    /// assert_eq!(buf.delete_forward(), Some(1));
+    /// ```
+    pub fn delete_forward(&mut self) -> Option<T> {
+        if self.gap_end == self.capacity() {
+            return None;
+        }
+
+        self.gap_end += 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.gap_end - 1))) }
+    }
+
+    /// Makes room for at least `additional` more elements in the gap. The elements after the gap are moved to the
+    /// end of the new buffer.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf: vec::GapBuffer<u8> = vec::GapBuffer::new();
+    /// buf.reserve(10);
+    /// assert!(buf.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        if self.gap_end - self.gap_start >= additional {
+            return;
+        }
+
+        let old_cap = self.capacity();
+
+        if let Err(err) = self.buf.try_reserve(self.len(), additional) {
+            handle_reserve_error(err)
+        }
+
+        // [a _ b] -> [a _ _ _ _ b]
+        let after = old_cap - self.gap_end;
+        let new_gap_end = self.capacity() - after;
+        unsafe {
+            ptr::copy(
+                self.ptr().add(self.gap_end),
+                self.ptr().add(new_gap_end),
+                after,
+            )
+        };
+        self.gap_end = new_gap_end;
+    }
+
+    /// Returns a reference to the element at `index`, counting both sides of the gap, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2, 3]);
+    /// buf.move_gap_to(1);
+    /// assert_eq!(buf.get(2), Some(&3));
+    /// assert_eq!(buf.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() {
+            unsafe { Some(&*self.ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, counting both sides of the gap, or `None` if out of
+    /// bounds.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// buf.move_gap_to(0);
+    /// *buf.get_mut(1).unwrap() = 3;
+    /// assert_eq!(buf[1], 3);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len() {
+            unsafe { Some(&mut *self.ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns the elements before and after the gap as two slices.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2, 3]);
+    /// buf.move_gap_to(2);
+    /// assert_eq!(buf.as_slices(), (&[1, 2][..], &[3][..]));
+    /// ```
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        unsafe {
+            (
+                slice::from_raw_parts(self.ptr(), self.gap_start),
+                slice::from_raw_parts(self.ptr().add(self.gap_end), self.capacity() - self.gap_end),
+            )
+        }
+    }
+
+    /// Returns the elements before and after the gap as two mutable slices.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// buf.move_gap_to(1);
+    /// buf.as_mut_slices().1[0] = 3;
+    /// assert_eq!(buf[1], 3);
+    /// ```
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        unsafe {
+            (
+                slice::from_raw_parts_mut(self.ptr(), self.gap_start),
+                slice::from_raw_parts_mut(
+                    self.ptr().add(self.gap_end),
+                    self.capacity() - self.gap_end,
+                ),
+            )
+        }
+    }
+
+    /// Moves the gap to the end, and returns the elements as a single slice.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// buf.move_gap_to(0);
+    /// assert_eq!(buf.make_contiguous(), &[1, 2]);
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        self.move_gap_to(self.len());
+        self.as_mut_slices().0
+    }
+
+    /// Returns an iterator over the elements, in order.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2, 3]);
+    /// buf.move_gap_to(1);
+    /// assert_eq!(buf.iter().sum::<i32>(), 6);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        let (before, after) = self.as_slices();
+        before.iter().chain(after)
+    }
+
+    /// Returns an iterator over mutable references to the elements, in order.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// buf.move_gap_to(1);
+    /// buf.iter_mut().for_each(|x| *x *= 10);
+    /// assert_eq!(buf[1], 20);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + '_ {
+        let (before, after) = self.as_mut_slices();
+        before.iter_mut().chain(after)
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// buf.clear();
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        let (before, after) = self.as_mut_slices();
+        let (before, after) = (before as *mut [T], after as *mut [T]);
+
+        // Reset first, so a panicking destructor can't cause an element to be dropped twice
+        self.gap_start = 0;
+        self.gap_end = self.capacity();
+
+        unsafe { drop_slices(before, after) }
+    }
+
+    fn ptr(&self) -> *mut T {
+        self.buf.ptr.as_ptr()
+    }
+
+    // Buffer index of the element at logical `index`
+    fn to_physical(&self, index: usize) -> usize {
+        if index < self.gap_start {
+            index
+        } else {
+            index - self.gap_start + self.gap_end
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<Vec<T, A, G>> for GapBuffer<T, A, G> {
+    /// Turns a vector into a gap buffer with the gap at the end, reusing its allocation.
+    /// # Example
+    /// ```
+    /// use vec::{GapBuffer, Vec};
+    /// let mut vec = Vec::new();
+    /// vec.extend(0..3);
+    /// let buf = GapBuffer::from(vec);
+    /// assert_eq!(buf.gap_position(), 3);
+    /// ```
+    fn from(vec: Vec<T, A, G>) -> Self {
+        let (buf, len) = vec.into_raw_parts();
+
+        Self {
+            gap_start: len,
+            gap_end: buf.cap,
+            buf,
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<GapBuffer<T, A, G>> for Vec<T, A, G> {
+    /// Turns a gap buffer into a vector, reusing its allocation. The gap is moved to the end first.
+    /// # Example
+    /// ```
+    /// use vec::{GapBuffer, Vec};
+    /// let mut buf = GapBuffer::new();
+    /// buf.extend([1, 2]);
+    /// buf.move_gap_to(0);
+    /// let vec = Vec::from(buf);
+    /// assert_eq!(&*vec, &[1, 2]);
+    /// ```
+    fn from(mut buf: GapBuffer<T, A, G>) -> Self {
+        buf.move_gap_to(buf.len());
+
+        unsafe {
+            let raw = ptr::read(&buf.buf);
+            let len = buf.gap_start;
+            mem::forget(buf);
+
+            Vec::from_raw_parts(raw, len)
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Index<usize> for GapBuffer<T, A, G> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IndexMut<usize> for GapBuffer<T, A, G> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for GapBuffer<T, A, G> {
+    /// Inserts every element of an iterator at the gap, moving the gap past them.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::GapBuffer::new();
+    /// buf.extend([1, 3]);
+    /// buf.move_gap_to(1);
+    /// buf.extend([2]);
+    /// assert_eq!(buf.iter().copied().collect::<std::vec::Vec<_>>(), [1, 2, 3]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for GapBuffer<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> PartialEq for GapBuffer<T, A, G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for GapBuffer<T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for GapBuffer<T, A, G> {
+    fn drop(&mut self) {
+        let (before, after) = self.as_mut_slices();
+
+        unsafe { drop_slices(before, after) }
+        // Deallocation is handled by RawVec
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// Two-dimensional grid stored row after row in a single [`Vec`], for game maps and simple numeric tables.
+/// # Example
+/// ```
+/// use vec::Grid;
+///
+/// let mut grid = Grid::new(2, 3);
+/// grid[(0, 2)] = 1;
+/// grid[(1, 0)] = 2;
+///
+/// assert_eq!(grid.row(0), &[0, 0, 1]);
+/// assert_eq!(grid.col(0).copied().collect::<std::vec::Vec<_>>(), [0, 2]);
+///
+/// let t = grid.transpose();
+/// assert_eq!((t.rows(), t.cols()), (3, 2));
+/// assert_eq!(t[(2, 0)], 1);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    // cell `(r, c)` lives at `r * cols + c`
+    data: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of `rows` by `cols` cells, all set to the default value.
+    /// # Panics
+    /// Panics if the number of cells overflows `usize`, or the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// assert_eq!(grid.len(), 6);
+    /// ```
+    pub fn new(rows: usize, cols: usize) -> Self
+    where
+        T: Default,
+    {
+        let len = rows.checked_mul(cols).expect("grid size overflows usize");
+
+        let mut data = Vec::with_capacity(len);
+        data.extend((0..len).map(|_| T::default()));
+
+        Self { data, rows, cols }
+    }
+
+    /// Turns a vector holding the cells row after row into a grid of `cols` columns, without copying.
+    /// # Panics
+    /// Panics if `cols` is zero or the length isn't a multiple of `cols`.
+    /// # Example
+    /// ```
+    /// let mut data = vec::Vec::new();
+    /// data.extend(1..=6);
+    /// let grid = vec::Grid::from_vec(data, 3);
+    /// assert_eq!(grid.row(1), &[4, 5, 6]);
+    /// ```
+    pub fn from_vec(data: Vec<T>, cols: usize) -> Self {
+        assert!(
+            cols > 0 && data.len.is_multiple_of(cols),
+            "length isn't a multiple of the column count"
+        );
+
+        Self {
+            rows: data.len / cols,
+            data,
+            cols,
+        }
+    }
+
+    /// Returns the number of rows.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// assert_eq!(grid.rows(), 2);
+    /// ```
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// assert_eq!(grid.cols(), 3);
+    /// ```
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of cells.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// assert_eq!(grid.len(), 6);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.data.len
+    }
+
+    /// Returns `true` if the grid has no cells.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(0, 3);
+    /// assert!(grid.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.data.len == 0
+    }
+
+    /// Returns a reference to the cell at row `row` and column `col`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// assert_eq!(grid.get(1, 2), Some(&0));
+    /// assert_eq!(grid.get(2, 0), None);
+    /// ```
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            Some(&self.data[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the cell at row `row` and column `col`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// *grid.get_mut(1, 2).unwrap() = 7;
+    /// assert_eq!(grid[(1, 2)], 7);
+    /// ```
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.rows && col < self.cols {
+            Some(&mut self.data[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cells of row `row`.
+    /// # Panics
+    /// Panics if `row` is out of bounds.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// assert_eq!(grid.row(0), &[0, 0, 0]);
+    /// ```
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.rows, "row out of bounds");
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Returns the cells of row `row` as a mutable slice.
+    /// # Panics
+    /// Panics if `row` is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// grid.row_mut(1).fill(1);
+    /// assert_eq!(grid.row(1), &[1, 1, 1]);
+    /// ```
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        assert!(row < self.rows, "row out of bounds");
+        &mut self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Returns an iterator over the cells of column `col`, top to bottom.
+    /// # Panics
+    /// Panics if `col` is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// grid[(1, 1)] = 5;
+    /// assert_eq!(grid.col(1).copied().collect::<std::vec::Vec<_>>(), [0, 5]);
+    /// ```
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        assert!(col < self.cols, "column out of bounds");
+        self.data[col..].iter().step_by(self.cols)
+    }
+
+    /// Returns an iterator over mutable references to the cells of column `col`, top to bottom.
+    /// # Panics
+    /// Panics if `col` is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut grid: vec::Grid<u8> = vec::Grid::new(2, 3);
+    /// grid.col_mut(2).for_each(|cell| *cell = 9);
+    /// assert_eq!(grid.row(1), &[0, 0, 9]);
+    /// ```
+    pub fn col_mut(&mut self, col: usize) -> impl DoubleEndedIterator<Item = &mut T> + '_ {
+        assert!(col < self.cols, "column out of bounds");
+        self.data[col..].iter_mut().step_by(self.cols)
+    }
+
+    /// Returns an iterator over the rows, top to bottom, each one as a slice.
+    /// # Example
+    /// ```
+    /// let mut grid: vec::Grid<u8> = vec::Grid::new(2, 2);
+    /// grid[(1, 0)] = 1;
+    /// let rows: std::vec::Vec<_> = grid.iter_rows().collect();
+    /// assert_eq!(rows, [&[0, 0], &[1, 0]]);
+    /// ```
+    pub fn iter_rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + '_ {
+        // `chunks_exact` rejects a zero chunk size, so zero columns yields empty rows directly
+        let cols = self.cols;
+        (0..self.rows).map(move |row| &self.data[row * cols..(row + 1) * cols])
+    }
+
+    /// Returns an iterator over every cell, row after row.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 2);
+    /// assert_eq!(grid.iter().count(), 4);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Inserts a row at `index`, moving the rows below it down.
+    /// # Panics
+    /// Panics if `index > rows` or the row doesn't have exactly `cols` cells.
+    /// # Example
+    /// ```
+    /// let mut grid: vec::Grid<u8> = vec::Grid::new(2, 2);
+    /// grid.insert_row(1, [1, 2]);
+    /// assert_eq!(grid.rows(), 3);
+    /// assert_eq!(grid.row(1), &[1, 2]);
+    /// ```
+    pub fn insert_row<I: IntoIterator<Item = T>>(&mut self, index: usize, row: I) {
+        assert!(index <= self.rows, "row out of bounds");
+
+        let old_len = self.data.len;
+        self.data.extend(row);
+
+        if self.data.len - old_len != self.cols {
+            self.data.truncate(old_len);
+            panic!("row doesn't have {} cells", self.cols);
+        }
+
+        // The new row was appended, bring it up to its place
+        self.data[index * self.cols..].rotate_right(self.cols);
+        self.rows += 1;
+    }
+
+    /// Removes row `index` and returns its cells, moving the rows below it up.
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut data = vec::Vec::new();
+    /// data.extend(1..=4);
+    /// let mut grid = vec::Grid::from_vec(data, 2);
+    ///
+    /// assert_eq!(&*grid.remove_row(0), &[1, 2]);
+    /// assert_eq!(grid.rows(), 1);
+    /// assert_eq!(grid.row(0), &[3, 4]);
+    /// ```
+    pub fn remove_row(&mut self, index: usize) -> Vec<T> {
+        assert!(index < self.rows, "row out of bounds");
+
+        // Send the row to the end, then pop its cells off
+        self.data[index * self.cols..].rotate_left(self.cols);
+
+        let mut row = Vec::with_capacity(self.cols);
+        for _ in 0..self.cols {
+            row.push(self.data.pop().unwrap());
+        }
+        row.reverse();
+
+        self.rows -= 1;
+        row
+    }
+
+    /// Returns a copy of the grid with rows and columns swapped.
+    /// # Example
+    /// ```
+    /// let mut data = vec::Vec::new();
+    /// data.extend(1..=6);
+    /// let grid = vec::Grid::from_vec(data, 3);
+    ///
+    /// let t = grid.transpose();
+    /// assert_eq!(t.row(0), &[1, 4]);
+    /// assert_eq!(t.row(2), &[3, 6]);
+    /// ```
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let mut data = Vec::with_capacity(self.data.len);
+        data.extend((0..self.cols).flat_map(|col| self.col(col).cloned()));
+
+        Grid {
+            data,
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+
+    /// Returns the cells row after row, without copying.
+    /// # Example
+    /// ```
+    /// let grid: vec::Grid<u8> = vec::Grid::new(2, 2);
+    /// assert_eq!(grid.into_vec().len(), 4);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get(row, col).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        self.get_mut(row, col).expect("index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter_rows()).finish()
+    }
+}
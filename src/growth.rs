@@ -0,0 +1,198 @@
+/// Strategy deciding how much a buffer's capacity grows once it runs out of room.
+/// # Example
+/// ```
+/// use vec::GrowthPolicy;
+///
+/// // Grows to exactly the required capacity
+/// #[derive(Default)]
+/// struct Exact;
+///
+/// impl GrowthPolicy for Exact {
+///     fn next_capacity(&self, _cap: usize, required: usize) -> usize {
+///         required
+///     }
+/// }
+///
+/// let mut vec = vec::Vec::with_growth_policy(Exact);
+/// vec.push(1);
+/// vec.push(2);
+/// assert_eq!(&*vec, &[1, 2]);
+/// ```
+pub trait GrowthPolicy {
+    /// Returns the new capacity for a buffer currently holding `cap` slots that needs at least
+    /// `required` slots. The returned value must be greater than or equal to `required`.
+    fn next_capacity(&self, cap: usize, required: usize) -> usize;
+
+    /// Returns the capacity to shrink a buffer of `cap` slots holding `len` elements to, or `None` to keep it.
+    /// Consulted after `pop`, `truncate`, `clear` and `drain`, values below `len` are raised to `len`.
+    ///
+    /// Never shrinks by default.
+    fn shrink_capacity(&self, _cap: usize, _len: usize) -> Option<usize> {
+        None
+    }
+
+    /// Returns the capacity the buffer shouldn't grow past when the allocator has room for more than
+    /// `next_capacity` asked for, in the size class it rounds the buffer up to. A capacity required by the length
+    /// is always granted.
+    ///
+    /// Unbounded by default.
+    fn max_capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// Amortized doubling: the capacity is multiplied by two on every growth (default policy).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Doubling;
+
+/// Grows the capacity by a factor of 1.5, trading more reallocations for less wasted memory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OneAndHalf;
+
+/// Grows the capacity by a fixed number of elements, for buffers with a predictable append rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedStep(pub usize);
+
+/// Wraps a growth policy to give memory back once the vector empties out: when the length drops below
+/// `cap / divisor`, the capacity shrinks to twice the length.
+///
+/// Meant for long-lived queues that spike and then drain. The divisor should be greater than 2, so a push right
+/// after shrinking doesn't grow the buffer again. A divisor of 0 never shrinks.
+/// # Example
+/// ```
+/// use vec::{Doubling, ShrinkBelow, Vec};
+///
+/// let mut vec = Vec::with_growth_policy(ShrinkBelow { divisor: 4, growth: Doubling });
+/// vec.extend(0..1024);
+/// assert_eq!(vec.capacity(), 1024);
+///
+/// vec.truncate(100);
+/// assert_eq!(vec.capacity(), 200);
+///
+/// vec.clear();
+/// assert_eq!(vec.capacity(), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShrinkBelow<G: GrowthPolicy = Doubling> {
+    /// Shrink once the length is smaller than the capacity divided by this, never if it's 0.
+    pub divisor: usize,
+    /// Policy used to grow the buffer.
+    pub growth: G,
+}
+
+impl Default for ShrinkBelow {
+    /// Shrinks below a quarter of the capacity, and grows by doubling.
+    fn default() -> Self {
+        Self {
+            divisor: 4,
+            growth: Doubling,
+        }
+    }
+}
+
+impl<G: GrowthPolicy> GrowthPolicy for ShrinkBelow<G> {
+    fn next_capacity(&self, cap: usize, required: usize) -> usize {
+        self.growth.next_capacity(cap, required)
+    }
+
+    /// # Example
+    /// ```
+    /// use vec::{GrowthPolicy, ShrinkBelow};
+    /// assert_eq!(ShrinkBelow::default().shrink_capacity(64, 20), None);
+    /// assert_eq!(ShrinkBelow::default().shrink_capacity(64, 10), Some(20));
+    ///
+    /// let never = ShrinkBelow { divisor: 0, growth: vec::Doubling };
+    /// assert_eq!(never.shrink_capacity(64, 0), None);
+    /// ```
+    fn shrink_capacity(&self, cap: usize, len: usize) -> Option<usize> {
+        match cap.checked_div(self.divisor) {
+            Some(threshold) if len < threshold => Some(len.saturating_mul(2)),
+            _ => None,
+        }
+    }
+
+    fn max_capacity(&self) -> usize {
+        self.growth.max_capacity()
+    }
+}
+
+/// Wraps a growth policy so the capacity never grows past `max` elements, unless more room than that is required.
+///
+/// Used by [`BoundedVec`](crate::BoundedVec) so a vector close to its maximum length doesn't allocate room it
+/// can never use.
+/// # Example
+/// ```
+/// use vec::{Capped, Doubling, Vec};
+///
+/// let mut vec = Vec::with_growth_policy(Capped { max: 10, growth: Doubling });
+/// vec.extend(0..10);
+/// assert_eq!(vec.capacity(), 10);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capped<G: GrowthPolicy = Doubling> {
+    /// Capacity the buffer doesn't grow past.
+    pub max: usize,
+    /// Policy used to grow the buffer, up to `max`.
+    pub growth: G,
+}
+
+impl<G: GrowthPolicy> GrowthPolicy for Capped<G> {
+    /// # Example
+    /// ```
+    /// use vec::{Capped, Doubling, GrowthPolicy};
+    /// let policy = Capped { max: 10, growth: Doubling };
+    /// assert_eq!(policy.next_capacity(4, 5), 8);
+    /// assert_eq!(policy.next_capacity(8, 9), 10);
+    /// assert_eq!(policy.next_capacity(10, 12), 12);
+    /// ```
+    fn next_capacity(&self, cap: usize, required: usize) -> usize {
+        self.growth
+            .next_capacity(cap, required)
+            .min(self.max)
+            .max(required)
+    }
+
+    fn shrink_capacity(&self, cap: usize, len: usize) -> Option<usize> {
+        self.growth.shrink_capacity(cap, len)
+    }
+
+    fn max_capacity(&self) -> usize {
+        self.max.min(self.growth.max_capacity())
+    }
+}
+
+impl GrowthPolicy for Doubling {
+    /// # Example
+    /// ```
+    /// use vec::{Doubling, GrowthPolicy};
+    /// assert_eq!(Doubling.next_capacity(4, 5), 8);
+    /// assert_eq!(Doubling.next_capacity(4, 20), 20);
+    /// ```
+    fn next_capacity(&self, cap: usize, required: usize) -> usize {
+        cap.saturating_mul(2).max(required)
+    }
+}
+
+impl GrowthPolicy for OneAndHalf {
+    /// # Example
+    /// ```
+    /// use vec::{GrowthPolicy, OneAndHalf};
+    /// assert_eq!(OneAndHalf.next_capacity(4, 5), 6);
+    /// assert_eq!(OneAndHalf.next_capacity(1, 2), 2);
+    /// ```
+    fn next_capacity(&self, cap: usize, required: usize) -> usize {
+        cap.saturating_add(cap / 2).max(required)
+    }
+}
+
+impl GrowthPolicy for FixedStep {
+    /// # Example
+    /// ```
+    /// use vec::{FixedStep, GrowthPolicy};
+    /// assert_eq!(FixedStep(16).next_capacity(16, 17), 32);
+    /// assert_eq!(FixedStep(16).next_capacity(16, 40), 40);
+    /// ```
+    fn next_capacity(&self, cap: usize, required: usize) -> usize {
+        cap.saturating_add(self.0).max(required)
+    }
+}
@@ -0,0 +1,403 @@
+use std::{
+    alloc::{Allocator, Global},
+    fmt, mem,
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    Vec,
+};
+
+/// Priority queue returning its greatest element first, implemented as a binary max-heap over a [`Vec`].
+/// # Example
+/// ```
+/// use vec::BinaryHeap;
+///
+/// let mut heap = BinaryHeap::new();
+/// heap.push(2);
+/// heap.push(5);
+/// heap.push(1);
+///
+/// assert_eq!(heap.peek(), Some(&5));
+/// assert_eq!(heap.pop(), Some(5));
+/// assert_eq!(heap.pop(), Some(2));
+/// ```
+pub struct BinaryHeap<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    // every element is greater than or equal to its children, at `2i + 1` and `2i + 2`
+    data: Vec<T, A, G>,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    /// Creates an empty heap, without allocating.
+    /// # Example
+    /// ```
+    /// let heap: vec::BinaryHeap<i32> = vec::BinaryHeap::new();
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    /// Creates an empty heap with room for `capacity` elements.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let heap: vec::BinaryHeap<i32> = vec::BinaryHeap::with_capacity(10);
+    /// assert_eq!(heap.capacity(), 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> BinaryHeap<T, A, G> {
+    /// Adds an element to the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.push(1);
+    /// heap.push(3);
+    /// assert_eq!(heap.peek(), Some(&3));
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.data.push(elem);
+        self.sift_up(self.data.len - 1);
+    }
+
+    /// Removes the greatest element and returns it, or `None` if the heap is empty.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.push(1);
+    /// heap.push(3);
+    /// assert_eq!(heap.pop(), Some(3));
+    /// assert_eq!(heap.pop(), Some(1));
+    /// assert_eq!(heap.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        self.data.pop().map(|mut last| {
+            if !self.data.is_empty() {
+                mem::swap(&mut last, &mut self.data[0]);
+                self.sift_down(0, self.data.len);
+            }
+
+            last
+        })
+    }
+
+    /// Returns a guard giving mutable access to the greatest element, the heap is fixed up once it's dropped.
+    /// Returns `None` if the heap is empty.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.extend([1, 5, 3]);
+    ///
+    /// *heap.peek_mut().unwrap() = 0;
+    /// assert_eq!(heap.peek(), Some(&3));
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, A, G>> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self })
+        }
+    }
+
+    /// Returns the elements sorted in ascending order, reusing the heap's buffer.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.extend([3, 1, 4, 1, 5]);
+    /// assert_eq!(&*heap.into_sorted_vec(), &[1, 1, 3, 4, 5]);
+    /// ```
+    pub fn into_sorted_vec(mut self) -> Vec<T, A, G> {
+        // Move the greatest element past the shrinking heap, one at a time
+        let mut end = self.data.len;
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            self.sift_down(0, end);
+        }
+
+        self.data
+    }
+
+    /// Appends the elements of an iterator.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.extend([2, 7, 1]);
+    /// assert_eq!(heap.peek(), Some(&7));
+    /// ```
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+
+    // Moves the element at `pos` up while it is greater than its parent
+    fn sift_up(&mut self, pos: usize) {
+        unsafe {
+            let mut hole = Hole::new(&mut self.data, pos);
+
+            while hole.pos > 0 {
+                let parent = (hole.pos - 1) / 2;
+                if hole.elem() <= hole.get(parent) {
+                    break;
+                }
+
+                hole.move_to(parent);
+            }
+        }
+    }
+
+    // Moves the element at `pos` down while it is smaller than one of its children, within the first `end` elements
+    fn sift_down(&mut self, pos: usize, end: usize) {
+        unsafe {
+            let mut hole = Hole::new(&mut self.data, pos);
+            let mut child = 2 * hole.pos + 1;
+
+            while child < end {
+                // Pick the greater child
+                if child + 1 < end && hole.get(child) <= hole.get(child + 1) {
+                    child += 1;
+                }
+
+                if hole.elem() >= hole.get(child) {
+                    return;
+                }
+
+                hole.move_to(child);
+                child = 2 * hole.pos + 1;
+            }
+        }
+    }
+
+    // Restores the heap after the greatest element was changed through `PeekMut`
+    fn rebuild_top(&mut self) {
+        let end = self.data.len;
+        self.sift_down(0, end);
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> BinaryHeap<T, A, G> {
+    /// Returns the greatest element, or `None` if the heap is empty.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// assert_eq!(heap.peek(), None);
+    /// heap.push(1);
+    /// assert_eq!(heap.peek(), Some(&1));
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns the number of elements in the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.push(1);
+    /// assert_eq!(heap.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.data.len
+    }
+
+    /// Returns `true` if the heap holds no elements.
+    /// # Example
+    /// ```
+    /// let heap: vec::BinaryHeap<i32> = vec::BinaryHeap::new();
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.data.len == 0
+    }
+
+    /// Returns the number of elements the heap can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.push(1u32);
+    /// assert_eq!(heap.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Returns the elements in heap order, which is unspecified apart from the greatest one coming first.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.extend([1, 3, 2]);
+    /// assert_eq!(heap.as_slice()[0], 3);
+    /// assert_eq!(heap.as_slice().len(), 3);
+    /// ```
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.extend([1, 3, 2]);
+    /// heap.clear();
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+
+    /// Returns the underlying vector, in heap order.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::BinaryHeap::new();
+    /// heap.extend([1, 3, 2]);
+    /// let vec = heap.into_vec();
+    /// assert_eq!(vec[0], 3);
+    /// ```
+    pub fn into_vec(self) -> Vec<T, A, G> {
+        self.data
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> From<Vec<T, A, G>> for BinaryHeap<T, A, G> {
+    /// Turns a vector into a heap in place, in O(n).
+    /// # Example
+    /// ```
+    /// use vec::{BinaryHeap, Vec};
+    /// let mut vec = Vec::new();
+    /// vec.extend([1, 4, 2]);
+    ///
+    /// let mut heap = BinaryHeap::from(vec);
+    /// assert_eq!(heap.pop(), Some(4));
+    /// ```
+    fn from(vec: Vec<T, A, G>) -> Self {
+        let mut heap = Self { data: vec };
+
+        // Leaves are already heaps, fix up every parent from the last one to the root
+        let end = heap.data.len;
+        for pos in (0..end / 2).rev() {
+            heap.sift_down(pos, end);
+        }
+
+        heap
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for BinaryHeap<T, A, G> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for BinaryHeap<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+/// Mutable access to the greatest element of a [`BinaryHeap`], created by [`BinaryHeap::peek_mut`]. The element is
+/// moved to its new place when the guard is dropped.
+pub struct PeekMut<'a, T: Ord, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    heap: &'a mut BinaryHeap<T, A, G>,
+}
+
+impl<'a, T: Ord, A: Allocator, G: GrowthPolicy> PeekMut<'a, T, A, G> {
+    /// Removes the peeked element from the heap and returns it.
+    /// # Example
+    /// ```
+    /// use vec::{BinaryHeap, PeekMut};
+    /// let mut heap = BinaryHeap::new();
+    /// heap.extend([1, 5, 3]);
+    ///
+    /// let top = heap.peek_mut().unwrap();
+    /// assert_eq!(PeekMut::pop(top), 5);
+    /// assert_eq!(heap.len(), 2);
+    /// ```
+    pub fn pop(this: Self) -> T {
+        let heap = unsafe { ptr::read(&this.heap) };
+        mem::forget(this);
+
+        heap.pop().unwrap()
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Deref for PeekMut<'_, T, A, G> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.heap.data[0]
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> DerefMut for PeekMut<'_, T, A, G> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.heap.data[0]
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Drop for PeekMut<'_, T, A, G> {
+    fn drop(&mut self) {
+        self.heap.rebuild_top();
+    }
+}
+
+// Element taken out of the heap while it is moved around, leaving a hole the other elements are shifted into. The
+// element is written back into the hole on drop, so a panicking comparison leaves a valid heap behind
+struct Hole<'a, T> {
+    data: &'a mut [T],
+    elem: mem::ManuallyDrop<T>,
+    pos: usize,
+}
+
+impl<'a, T> Hole<'a, T> {
+    // `pos` must be in bounds
+    unsafe fn new(data: &'a mut [T], pos: usize) -> Self {
+        let elem = ptr::read(data.as_ptr().add(pos));
+
+        Self {
+            data,
+            elem: mem::ManuallyDrop::new(elem),
+            pos,
+        }
+    }
+
+    fn elem(&self) -> &T {
+        &self.elem
+    }
+
+    // `index` must be in bounds and not the hole
+    unsafe fn get(&self, index: usize) -> &T {
+        &*self.data.as_ptr().add(index)
+    }
+
+    // Moves the element at `index` into the hole, the hole moves to `index`
+    unsafe fn move_to(&mut self, index: usize) {
+        let ptr = self.data.as_mut_ptr();
+        ptr::copy_nonoverlapping(ptr.add(index), ptr.add(self.pos), 1);
+        self.pos = index;
+    }
+}
+
+impl<T> Drop for Hole<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let pos = self.pos;
+            ptr::copy_nonoverlapping(&*self.elem, self.data.as_mut_ptr().add(pos), 1);
+        }
+    }
+}
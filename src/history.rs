@@ -0,0 +1,331 @@
+use std::{fmt, mem, ops::Deref};
+
+use crate::Vec;
+
+/// Vector recording every change made to it so they can be undone and redone, e.g. for the data model of an
+/// editor.
+///
+/// Changes are grouped into steps by [`checkpoint`](HistoryVec::checkpoint): [`undo`](HistoryVec::undo) reverts
+/// everything done since the last one, or the whole previous step if nothing was. Making a change after undoing
+/// discards the steps that could have been redone.
+/// # Example
+/// ```
+/// use vec::HistoryVec;
+///
+/// let mut v = HistoryVec::new();
+/// v.push('a');
+/// v.push('b');
+/// v.checkpoint();
+///
+/// v.set(0, 'z');
+/// v.remove(1);
+/// assert_eq!(&*v, &['z']);
+///
+/// assert!(v.undo());
+/// assert_eq!(&*v, &['a', 'b']);
+/// assert!(v.undo());
+/// assert!(v.is_empty());
+///
+/// assert!(v.redo());
+/// assert_eq!(&*v, &['a', 'b']);
+/// ```
+#[derive(Clone)]
+pub struct HistoryVec<T> {
+    vec: Vec<T>,
+    // changes since the last checkpoint, in order
+    pending: Vec<Change<T>>,
+    // steps that can be undone, last done at the end
+    undo: Vec<Vec<Change<T>>>,
+    // steps that can be redone, last undone at the end
+    redo: Vec<Vec<Change<T>>>,
+}
+
+// A change that was made, with what's needed to revert it
+#[derive(Clone)]
+enum Change<T> {
+    Inserted(usize),
+    Removed(usize, T),
+    // holds the value that was replaced
+    Set(usize, T),
+}
+
+impl<T> HistoryVec<T> {
+    /// Creates an empty vector with no history, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::HistoryVec<i32> = vec::HistoryVec::new();
+    /// assert!(!v.can_undo());
+    /// ```
+    pub fn new() -> Self {
+        Self::from(Vec::new())
+    }
+
+    /// Appends an element.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.undo();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.insert(self.vec.len, elem)
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty. The element is kept by the history,
+    /// so only a reference to it is returned.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.checkpoint();
+    ///
+    /// assert_eq!(v.pop(), Some(&1));
+    /// v.undo();
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn pop(&mut self) -> Option<&T> {
+        let last = self.vec.len.checked_sub(1)?;
+        Some(self.remove(last))
+    }
+
+    /// Inserts an element at `index`, moving the ones after it.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.checkpoint();
+    ///
+    /// v.insert(0, 0);
+    /// assert_eq!(&*v, &[0, 1]);
+    /// v.undo();
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        self.vec.insert(index, elem);
+        self.record(Change::Inserted(index));
+    }
+
+    /// Removes and returns the element at `index`, moving the ones after it. The element is kept by the history, so
+    /// only a reference to it is returned.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// v.checkpoint();
+    ///
+    /// assert_eq!(v.remove(0), &1);
+    /// v.undo();
+    /// assert_eq!(&*v, &[1, 2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> &T {
+        let elem = self.vec.remove(index);
+
+        match self.record(Change::Removed(index, elem)) {
+            Change::Removed(_, elem) => elem,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Replaces the element at `index` and returns the old one. The old element is kept by the history, so only a
+    /// reference to it is returned.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.checkpoint();
+    ///
+    /// assert_eq!(v.set(0, 5), &1);
+    /// v.undo();
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn set(&mut self, index: usize, elem: T) -> &T {
+        let old = mem::replace(&mut self.vec[index], elem);
+
+        match self.record(Change::Set(index, old)) {
+            Change::Set(_, old) => old,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Ends the current step, so the changes made from now on are undone separately from the previous ones.
+    /// Does nothing if there were no changes since the last checkpoint.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.checkpoint();
+    /// v.push(2);
+    /// v.push(3);
+    ///
+    /// v.undo();
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn checkpoint(&mut self) {
+        if self.pending.len > 0 {
+            let step = mem::replace(&mut self.pending, Vec::new());
+            self.undo.push(step);
+        }
+    }
+
+    /// Reverts the changes made since the last checkpoint, or the previous step if there are none. Returns `false`
+    /// if there was nothing to undo.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// assert!(!v.undo());
+    /// v.push(1);
+    /// assert!(v.undo());
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        self.checkpoint();
+
+        match self.undo.pop() {
+            Some(step) => {
+                let step = self.revert(step);
+                self.redo.push(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes again the changes of the last step undone. Returns `false` if there was nothing to redo.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.undo();
+    /// assert!(v.redo());
+    /// assert_eq!(&*v, &[1]);
+    /// assert!(!v.redo());
+    /// ```
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(step) => {
+                let step = self.revert(step);
+                self.undo.push(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if there are changes to undo.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// assert!(v.can_undo());
+    /// ```
+    pub fn can_undo(&self) -> bool {
+        self.pending.len > 0 || self.undo.len > 0
+    }
+
+    /// Returns `true` if there are undone changes to redo.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.undo();
+    /// assert!(v.can_redo());
+    /// ```
+    pub fn can_redo(&self) -> bool {
+        self.redo.len > 0
+    }
+
+    /// Forgets every recorded change, keeping the elements as they are.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// v.clear_history();
+    /// assert!(!v.undo());
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn clear_history(&mut self) {
+        self.pending.clear();
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Returns the elements, dropping the history.
+    /// # Example
+    /// ```
+    /// let mut v = vec::HistoryVec::new();
+    /// v.push(1);
+    /// assert_eq!(&*v.into_vec(), &[1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+
+    // Logs a change made to the vector, which makes the undone steps unreachable
+    fn record(&mut self, change: Change<T>) -> &Change<T> {
+        self.redo.clear();
+        self.pending.push(change);
+        &self.pending[self.pending.len - 1]
+    }
+
+    // Reverts the changes of `step` last to first, and returns the step that reverts them back
+    fn revert(&mut self, mut step: Vec<Change<T>>) -> Vec<Change<T>> {
+        let mut reverted = Vec::with_capacity(step.len);
+
+        while let Some(change) = step.pop() {
+            reverted.push(match change {
+                Change::Inserted(index) => Change::Removed(index, self.vec.remove(index)),
+                Change::Removed(index, elem) => {
+                    self.vec.insert(index, elem);
+                    Change::Inserted(index)
+                }
+                Change::Set(index, elem) => {
+                    Change::Set(index, mem::replace(&mut self.vec[index], elem))
+                }
+            });
+        }
+
+        reverted
+    }
+}
+
+impl<T> Default for HistoryVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<T>> for HistoryVec<T> {
+    /// Wraps a vector, with no history.
+    fn from(vec: Vec<T>) -> Self {
+        Self {
+            vec,
+            pending: Vec::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl<T> Deref for HistoryVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HistoryVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,81 @@
+use std::{mem, sync::RwLock};
+
+// Hook installed by `set_alloc_hook`, called on every buffer allocation, reallocation and free
+static HOOK: RwLock<Option<fn(&AllocEvent)>> = RwLock::new(None);
+
+/// Kind of buffer operation reported to the allocation hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocKind {
+    /// A new buffer was allocated (`old_cap` is 0).
+    Allocate,
+    /// The buffer was grown in place or moved to a bigger allocation.
+    Grow,
+    /// The buffer was shrunk to a smaller, non-empty allocation.
+    Shrink,
+    /// The buffer was freed (`new_cap` is 0).
+    Deallocate,
+}
+
+/// Buffer operation reported to the hook installed with [`set_alloc_hook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocEvent {
+    /// What happened to the buffer.
+    pub kind: AllocKind,
+    /// Capacity, in elements, before the operation.
+    pub old_cap: usize,
+    /// Capacity, in elements, after the operation.
+    pub new_cap: usize,
+    /// Size in bytes of a single element.
+    pub elem_size: usize,
+}
+
+impl AllocEvent {
+    pub(crate) fn new<T>(kind: AllocKind, old_cap: usize, new_cap: usize) -> Self {
+        Self {
+            kind,
+            old_cap,
+            new_cap,
+            elem_size: mem::size_of::<T>(),
+        }
+    }
+}
+
+/// Installs a process-wide hook called on every allocation, growth, shrink and free of a vector's buffer, replacing
+/// the previous one. Zero-sized types never allocate, so they are never reported.
+///
+/// The hook runs inside the allocating call, it must not panic nor install another hook.
+/// # Example
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use vec::{set_alloc_hook, AllocEvent, AllocKind, Vec};
+///
+/// static GROWS: AtomicUsize = AtomicUsize::new(0);
+///
+/// fn count_grows(event: &AllocEvent) {
+///     if event.kind == AllocKind::Grow && event.elem_size == 8 {
+///         GROWS.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// set_alloc_hook(Some(count_grows));
+///
+/// let mut vec = Vec::new();
+/// for i in 0..100u64 {
+///     vec.push(i);
+/// }
+///
+/// set_alloc_hook(None);
+/// assert!(GROWS.load(Ordering::Relaxed) >= 4);
+/// ```
+pub fn set_alloc_hook(hook: Option<fn(&AllocEvent)>) {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = hook;
+}
+
+// Reports an event to the installed hook, if any
+pub(crate) fn emit(event: AllocEvent) {
+    let hook = *HOOK.read().unwrap_or_else(|err| err.into_inner());
+
+    if let Some(hook) = hook {
+        hook(&event);
+    }
+}
@@ -0,0 +1,380 @@
+use std::{
+    fmt,
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// Index type of an [`IndexVec`], usually a newtype around an integer so that indices of different vectors can't
+/// be mixed up. With the `derive` feature, `#[derive(Idx)]` implements it for `struct Name(u32);`-like types.
+/// # Example
+/// ```
+/// use vec::Idx;
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// struct BlockId(u32);
+///
+/// impl Idx for BlockId {
+///     fn new(index: usize) -> Self {
+///         BlockId(index as u32)
+///     }
+///
+///     fn index(self) -> usize {
+///         self.0 as usize
+///     }
+/// }
+///
+/// assert_eq!(BlockId::new(3).index(), 3);
+/// ```
+pub trait Idx: Copy + Eq {
+    /// Creates the index of position `index`.
+    fn new(index: usize) -> Self;
+
+    /// Returns the position this index stands for.
+    fn index(self) -> usize;
+}
+
+impl Idx for usize {
+    fn new(index: usize) -> Self {
+        index
+    }
+
+    fn index(self) -> usize {
+        self
+    }
+}
+
+/// Vector indexed by a typed index `I` instead of `usize`, so indexing it with the index of another vector is a
+/// type error.
+/// # Example
+/// ```
+/// use vec::{Idx, IndexVec};
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// struct NodeId(u32);
+///
+/// impl Idx for NodeId {
+///     fn new(index: usize) -> Self {
+///         NodeId(index as u32)
+///     }
+///
+///     fn index(self) -> usize {
+///         self.0 as usize
+///     }
+/// }
+///
+/// let mut nodes: IndexVec<NodeId, &str> = IndexVec::new();
+/// let root = nodes.push("root");
+/// let leaf = nodes.push("leaf");
+///
+/// assert_eq!(leaf, NodeId(1));
+/// assert_eq!(nodes[root], "root");
+/// // nodes[1] doesn't compile, only a NodeId can index `nodes`
+/// ```
+pub struct IndexVec<I: Idx, T> {
+    raw: Vec<T>,
+    marker: PhantomData<fn(&I)>,
+}
+
+impl<I: Idx, T> IndexVec<I, T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, i32> = vec::IndexVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::from_raw(Vec::new())
+    }
+
+    /// Creates an empty vector with room for at least `capacity` elements.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, i32> = vec::IndexVec::with_capacity(10);
+    /// assert!(v.as_raw().capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_raw(Vec::with_capacity(capacity))
+    }
+
+    /// Wraps a vector, whose element at position `i` gets index `I::new(i)`.
+    /// # Example
+    /// ```
+    /// let mut raw = vec::Vec::new();
+    /// raw.push('a');
+    /// let v: vec::IndexVec<usize, char> = vec::IndexVec::from_raw(raw);
+    /// assert_eq!(v[0], 'a');
+    /// ```
+    pub fn from_raw(raw: Vec<T>) -> Self {
+        Self {
+            raw,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, i32> = vec::IndexVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.raw.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, i32> = vec::IndexVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.raw.len == 0
+    }
+
+    /// Returns the index the next pushed element will get.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, i32> = vec::IndexVec::new();
+    /// assert_eq!(v.next_index(), 0);
+    /// v.push(1);
+    /// assert_eq!(v.next_index(), 1);
+    /// ```
+    pub fn next_index(&self) -> I {
+        I::new(self.raw.len)
+    }
+
+    /// Appends an element and returns its index.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes, or if `I` can't represent the index.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, char> = vec::IndexVec::new();
+    /// assert_eq!(v.push('a'), 0);
+    /// assert_eq!(v.push('b'), 1);
+    /// ```
+    pub fn push(&mut self, elem: T) -> I {
+        let index = self.next_index();
+        self.raw.push(elem);
+        index
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, char> = vec::IndexVec::new();
+    /// v.push('a');
+    /// assert_eq!(v.pop(), Some('a'));
+    /// assert_eq!(v.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        self.raw.pop()
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, char> = vec::IndexVec::new();
+    /// let a = v.push('a');
+    /// assert_eq!(v.get(a), Some(&'a'));
+    /// assert_eq!(v.get(1), None);
+    /// ```
+    pub fn get(&self, index: I) -> Option<&T> {
+        self.raw.get(index.index())
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, char> = vec::IndexVec::new();
+    /// let a = v.push('a');
+    /// *v.get_mut(a).unwrap() = 'b';
+    /// assert_eq!(v[a], 'b');
+    /// ```
+    pub fn get_mut(&mut self, index: I) -> Option<&mut T> {
+        self.raw.get_mut(index.index())
+    }
+
+    /// Returns mutable references to the elements at two distinct indices.
+    /// # Panics
+    /// Panics if the indices are equal or either is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, i32> = vec::IndexVec::new();
+    /// let (a, b) = (v.push(1), v.push(2));
+    ///
+    /// let (x, y) = v.pick2_mut(a, b);
+    /// std::mem::swap(x, y);
+    /// assert_eq!((v[a], v[b]), (2, 1));
+    /// ```
+    pub fn pick2_mut(&mut self, a: I, b: I) -> (&mut T, &mut T) {
+        let (a, b) = (a.index(), b.index());
+        assert!(a != b, "indices must be distinct");
+
+        if a < b {
+            let (head, tail) = self.raw.split_at_mut(b);
+            (&mut head[a], &mut tail[0])
+        } else {
+            let (head, tail) = self.raw.split_at_mut(a);
+            (&mut tail[0], &mut head[b])
+        }
+    }
+
+    /// Returns an iterator over the elements.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, i32> = (1..=3).collect();
+    /// assert_eq!(v.iter().sum::<i32>(), 6);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.raw.iter()
+    }
+
+    /// Returns an iterator over mutable references to the elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, i32> = (1..=3).collect();
+    /// v.iter_mut().for_each(|x| *x *= 10);
+    /// assert_eq!(v[2], 30);
+    /// ```
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.raw.iter_mut()
+    }
+
+    /// Returns an iterator over the elements along with their index.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, char> = "ab".chars().collect();
+    /// let pairs: std::vec::Vec<_> = v.iter_enumerated().collect();
+    /// assert_eq!(pairs, [(0, &'a'), (1, &'b')]);
+    /// ```
+    pub fn iter_enumerated(&self) -> impl DoubleEndedIterator<Item = (I, &T)> + ExactSizeIterator {
+        self.raw
+            .iter()
+            .enumerate()
+            .map(|(i, elem)| (I::new(i), elem))
+    }
+
+    /// Returns an iterator over mutable references to the elements along with their index.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, usize> = vec::IndexVec::new();
+    /// v.extend([0, 0]);
+    /// v.iter_enumerated_mut().for_each(|(i, x)| *x = i * 10);
+    /// assert_eq!(v[1], 10);
+    /// ```
+    pub fn iter_enumerated_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (I, &mut T)> + ExactSizeIterator {
+        self.raw
+            .iter_mut()
+            .enumerate()
+            .map(|(i, elem)| (I::new(i), elem))
+    }
+
+    /// Returns an iterator over the indices of the elements.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, char> = "ab".chars().collect();
+    /// assert_eq!(v.indices().collect::<std::vec::Vec<_>>(), [0, 1]);
+    /// ```
+    pub fn indices(&self) -> impl DoubleEndedIterator<Item = I> + ExactSizeIterator {
+        (0..self.raw.len).map(I::new)
+    }
+
+    /// Returns the underlying vector, indexed by `usize`.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, char> = "ab".chars().collect();
+    /// assert_eq!(v.as_raw()[1], 'b');
+    /// ```
+    pub fn as_raw(&self) -> &Vec<T> {
+        &self.raw
+    }
+
+    /// Returns the underlying vector mutably, indexed by `usize`.
+    /// # Example
+    /// ```
+    /// let mut v: vec::IndexVec<usize, char> = "ab".chars().collect();
+    /// v.as_raw_mut().sort_by(|a, b| b.cmp(a));
+    /// assert_eq!(v[0], 'b');
+    /// ```
+    pub fn as_raw_mut(&mut self) -> &mut Vec<T> {
+        &mut self.raw
+    }
+
+    /// Returns the underlying vector.
+    /// # Example
+    /// ```
+    /// let v: vec::IndexVec<usize, char> = "ab".chars().collect();
+    /// assert_eq!(&*v.into_raw(), &['a', 'b']);
+    /// ```
+    pub fn into_raw(self) -> Vec<T> {
+        self.raw
+    }
+}
+
+impl<I: Idx, T> Default for IndexVec<I, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Idx, T> Index<I> for IndexVec<I, T> {
+    type Output = T;
+
+    fn index(&self, index: I) -> &T {
+        &self.raw[index.index()]
+    }
+}
+
+impl<I: Idx, T> IndexMut<I> for IndexVec<I, T> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.raw[index.index()]
+    }
+}
+
+impl<I: Idx, T> Extend<T> for IndexVec<I, T> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        self.raw.extend(iter)
+    }
+}
+
+impl<I: Idx, T> FromIterator<T> for IndexVec<I, T> {
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        let mut raw = Vec::new();
+        raw.extend(iter);
+        Self::from_raw(raw)
+    }
+}
+
+impl<I: Idx, T> From<Vec<T>> for IndexVec<I, T> {
+    fn from(raw: Vec<T>) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl<I: Idx, T: Clone> Clone for IndexVec<I, T> {
+    fn clone(&self) -> Self {
+        Self::from_raw(self.raw.clone())
+    }
+}
+
+impl<I: Idx + fmt::Debug, T: fmt::Debug> fmt::Debug for IndexVec<I, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter_enumerated()).finish()
+    }
+}
+
+impl<I: Idx, T: PartialEq> PartialEq for IndexVec<I, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<I: Idx, T: Eq> Eq for IndexVec<I, T> {}
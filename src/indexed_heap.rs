@@ -0,0 +1,391 @@
+use std::{fmt, mem};
+
+use crate::Vec;
+
+// Generation of a handle slot that has run out of generations, it's never handed out again
+const RETIRED: u32 = u32::MAX;
+
+/// Handle to an entry of an [`IndexedHeap`], returned by [`push`](IndexedHeap::push). Once the entry leaves the heap
+/// its slot is reused by later entries, under a new generation the old handle doesn't match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle {
+    index: usize,
+    generation: u32,
+}
+
+/// Priority queue of values with priorities that can be changed after insertion, returning the smallest priority
+/// first, as path-finding algorithms (Dijkstra, A*) want. Wrap priorities in [`Reverse`](std::cmp::Reverse) to get
+/// the greatest first instead.
+///
+/// A binary heap over a [`Vec`], plus a map from every [`Handle`] to its entry's position in the heap, so an entry
+/// can be found, re-prioritized or removed in O(log n). The handles of entries that left the heap are recycled, so
+/// memory follows the number of entries in the heap rather than the number ever pushed.
+/// # Example
+/// ```
+/// use vec::IndexedHeap;
+///
+/// let mut heap = IndexedHeap::new();
+/// let a = heap.push('a', 10);
+/// let b = heap.push('b', 5);
+/// heap.push('c', 7);
+///
+/// heap.decrease_priority(a, 1);
+/// assert_eq!(heap.pop(), Some(('a', 1)));
+/// assert_eq!(heap.priority(b), Some(&5));
+/// assert_eq!(heap.pop(), Some(('b', 5)));
+/// assert!(!heap.contains(b));
+/// ```
+#[derive(Clone)]
+pub struct IndexedHeap<T, P> {
+    // every entry's priority is less than or equal to its children's, at `2i + 1` and `2i + 2`
+    heap: Vec<Entry<T, P>>,
+    // state of every handle slot, by handle index
+    slots: Vec<Slot>,
+    // first vacant slot, `slots.len` if there is none
+    next: usize,
+}
+
+#[derive(Clone)]
+struct Slot {
+    generation: u32,
+    state: SlotState,
+}
+
+#[derive(Clone)]
+enum SlotState {
+    // position in `heap` of the slot's entry
+    InHeap(usize),
+    // index of the next vacant slot in the free list
+    Vacant(usize),
+}
+
+#[derive(Clone)]
+struct Entry<T, P> {
+    priority: P,
+    value: T,
+    handle: usize,
+}
+
+impl<T, P: Ord> IndexedHeap<T, P> {
+    /// Creates an empty heap, without allocating.
+    /// # Example
+    /// ```
+    /// let heap: vec::IndexedHeap<char, u32> = vec::IndexedHeap::new();
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            heap: Vec::new(),
+            slots: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Returns the number of entries.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// heap.push('a', 1);
+    /// assert_eq!(heap.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.heap.len
+    }
+
+    /// Returns `true` if the heap has no entries.
+    /// # Example
+    /// ```
+    /// let heap: vec::IndexedHeap<char, u32> = vec::IndexedHeap::new();
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.heap.len == 0
+    }
+
+    /// Adds a value with `priority`, returning the handle to update or remove it later.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let handle = heap.push("node", 3);
+    /// assert_eq!(heap.get(handle), Some((&"node", &3)));
+    /// ```
+    pub fn push(&mut self, value: T, priority: P) -> Handle {
+        let index = self.next;
+        let position = SlotState::InHeap(self.heap.len);
+
+        let generation = if index == self.slots.len {
+            self.slots.push(Slot {
+                generation: 0,
+                state: position,
+            });
+            self.next = index + 1;
+
+            0
+        } else {
+            let slot = &mut self.slots[index];
+
+            match mem::replace(&mut slot.state, position) {
+                SlotState::Vacant(next) => self.next = next,
+                SlotState::InHeap(_) => unreachable!("free list points to a handle in use"),
+            }
+
+            slot.generation
+        };
+
+        self.heap.push(Entry {
+            priority,
+            value,
+            handle: index,
+        });
+        self.sift_up(self.heap.len - 1);
+
+        Handle { index, generation }
+    }
+
+    /// Returns the value with the smallest priority and its priority, or `None` if the heap is empty.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// heap.push('a', 2);
+    /// heap.push('b', 1);
+    /// assert_eq!(heap.peek(), Some((&'b', &1)));
+    /// ```
+    pub fn peek(&self) -> Option<(&T, &P)> {
+        let entry = self.heap.first()?;
+        Some((&entry.value, &entry.priority))
+    }
+
+    /// Removes the value with the smallest priority and returns it with its priority, or `None` if the heap is
+    /// empty.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// heap.push('a', 2);
+    /// heap.push('b', 1);
+    /// assert_eq!(heap.pop(), Some(('b', 1)));
+    /// assert_eq!(heap.pop(), Some(('a', 2)));
+    /// assert_eq!(heap.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        if self.heap.len == 0 {
+            return None;
+        }
+
+        Some(self.remove_at(0))
+    }
+
+    /// Returns `true` if the entry of `handle` is still in the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let handle = heap.push('a', 1);
+    /// assert!(heap.contains(handle));
+    /// heap.pop();
+    /// assert!(!heap.contains(handle));
+    /// ```
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    /// Returns the value and priority of the entry of `handle`, or `None` if it left the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let handle = heap.push('a', 1);
+    /// assert_eq!(heap.get(handle), Some((&'a', &1)));
+    /// ```
+    pub fn get(&self, handle: Handle) -> Option<(&T, &P)> {
+        let entry = &self.heap[self.position(handle)?];
+        Some((&entry.value, &entry.priority))
+    }
+
+    /// Returns the priority of the entry of `handle`, or `None` if it left the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let handle = heap.push('a', 1);
+    /// assert_eq!(heap.priority(handle), Some(&1));
+    /// ```
+    pub fn priority(&self, handle: Handle) -> Option<&P> {
+        self.get(handle).map(|(_, priority)| priority)
+    }
+
+    /// Sets the priority of the entry of `handle`, and returns the old one or `None` if the entry left the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let a = heap.push('a', 1);
+    /// heap.push('b', 2);
+    ///
+    /// assert_eq!(heap.change_priority(a, 3), Some(1));
+    /// assert_eq!(heap.peek(), Some((&'b', &2)));
+    /// ```
+    pub fn change_priority(&mut self, handle: Handle, priority: P) -> Option<P> {
+        let index = self.position(handle)?;
+        let old = mem::replace(&mut self.heap[index].priority, priority);
+
+        if self.heap[index].priority < old {
+            self.sift_up(index);
+        } else {
+            self.sift_down(index);
+        }
+
+        Some(old)
+    }
+
+    /// Lowers the priority of the entry of `handle` to `priority` if it's smaller than the current one, returning
+    /// `true` if it was. Does nothing if the entry left the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let handle = heap.push('a', 5);
+    ///
+    /// assert!(heap.decrease_priority(handle, 3));
+    /// assert!(!heap.decrease_priority(handle, 4));
+    /// assert_eq!(heap.priority(handle), Some(&3));
+    /// ```
+    pub fn decrease_priority(&mut self, handle: Handle, priority: P) -> bool {
+        match self.position(handle) {
+            Some(index) if priority < self.heap[index].priority => {
+                self.heap[index].priority = priority;
+                self.sift_up(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes the entry of `handle` and returns its value and priority, or `None` if it already left the heap.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let a = heap.push('a', 1);
+    /// heap.push('b', 2);
+    ///
+    /// assert_eq!(heap.remove(a), Some(('a', 1)));
+    /// assert_eq!(heap.remove(a), None);
+    /// assert_eq!(heap.pop(), Some(('b', 2)));
+    /// ```
+    pub fn remove(&mut self, handle: Handle) -> Option<(T, P)> {
+        let index = self.position(handle)?;
+        Some(self.remove_at(index))
+    }
+
+    /// Removes every entry, invalidating every handle returned before.
+    /// # Example
+    /// ```
+    /// let mut heap = vec::IndexedHeap::new();
+    /// let handle = heap.push('a', 1);
+    /// heap.clear();
+    /// assert!(heap.is_empty() && !heap.contains(handle));
+    ///
+    /// // The slot is reused, but the old handle doesn't reach the new entry
+    /// let other = heap.push('b', 2);
+    /// assert_eq!((heap.get(handle), heap.get(other)), (None, Some((&'b', &2))));
+    /// ```
+    pub fn clear(&mut self) {
+        for index in 0..self.heap.len {
+            let handle = self.heap[index].handle;
+            self.release(handle);
+        }
+        self.heap.clear();
+    }
+
+    fn position(&self, handle: Handle) -> Option<usize> {
+        match self.slots.get(handle.index)? {
+            Slot {
+                generation,
+                state: SlotState::InHeap(index),
+            } if *generation == handle.generation => Some(*index),
+            _ => None,
+        }
+    }
+
+    // Frees the slot of an entry that left the heap, moving it to a generation its handles don't match
+    fn release(&mut self, index: usize) {
+        let slot = &mut self.slots[index];
+        // Slots in use are never retired, so this can't overflow
+        slot.generation += 1;
+        slot.state = SlotState::Vacant(self.next);
+
+        if slot.generation != RETIRED {
+            self.next = index;
+        }
+    }
+
+    // Takes the entry at `index` out, moving the last one into its place
+    fn remove_at(&mut self, index: usize) -> (T, P) {
+        let last = self.heap.len - 1;
+        self.swap(index, last);
+
+        let entry = self.heap.pop().unwrap();
+        self.release(entry.handle);
+
+        if index < self.heap.len {
+            // The moved entry can belong above or below its new position
+            self.sift_up(index);
+            self.sift_down(index);
+        }
+
+        (entry.value, entry.priority)
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.heap[index].priority >= self.heap[parent].priority {
+                break;
+            }
+
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let (left, right) = (2 * index + 1, 2 * index + 2);
+            let mut smallest = index;
+
+            if left < self.heap.len && self.heap[left].priority < self.heap[smallest].priority {
+                smallest = left;
+            }
+            if right < self.heap.len && self.heap[right].priority < self.heap[smallest].priority {
+                smallest = right;
+            }
+            if smallest == index {
+                break;
+            }
+
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+
+    // Swaps two entries, keeping the position map in sync
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.slots[self.heap[a].handle].state = SlotState::InHeap(a);
+        self.slots[self.heap[b].handle].state = SlotState::InHeap(b);
+    }
+}
+
+impl<T, P: Ord> Default for IndexedHeap<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, P: fmt::Debug> fmt::Debug for IndexedHeap<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.heap
+                    .iter()
+                    .map(|entry| (&entry.value, &entry.priority)),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::BuildHasher,
+    iter::{self, FromIterator},
+    ops::Index,
+};
+
+use crate::{String, Vec};
+
+/// Id of a string stored in an [`Interner`], standing for it in 4 bytes. Ids are handed out in insertion order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the position of the string among the strings of its interner.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// interner.intern("a");
+    /// assert_eq!(interner.intern("b").index(), 1);
+    /// ```
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// String interner, storing every distinct string once in a single growing byte buffer and handing out compact
+/// [`Symbol`]s for them.
+///
+/// Interning a string it already holds returns the same symbol, found through a hash table of symbols, so symbols can
+/// be compared instead of strings. Strings are never removed.
+/// # Example
+/// ```
+/// use vec::Interner;
+///
+/// let mut interner = Interner::new();
+/// let foo = interner.intern("foo");
+/// let bar = interner.intern("bar");
+///
+/// assert_eq!(interner.intern("foo"), foo);
+/// assert_ne!(foo, bar);
+/// assert_eq!(interner.resolve(bar), "bar");
+/// assert_eq!(interner.len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct Interner<S = RandomState> {
+    // every string, one after the other
+    buf: String,
+    // `len + 1` ascending offsets into `buf`, string `i` spans `offsets[i]..offsets[i + 1]`
+    offsets: Vec<usize>,
+    // open addressing table of symbols plus one, 0 for an empty bucket, with a power of two length
+    table: Vec<u32>,
+    hasher: S,
+}
+
+impl Interner {
+    /// Creates an empty interner, allocating only its offsets buffer.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let interner = vec::Interner::new();
+    /// assert!(interner.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    /// Creates an empty interner with room for `symbols` strings of `bytes` bytes in total.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::with_capacity(2, 6);
+    /// interner.intern("foo");
+    /// interner.intern("bar");
+    /// assert_eq!(interner.len(), 2);
+    /// ```
+    pub fn with_capacity(symbols: usize, bytes: usize) -> Self {
+        let mut interner = Self::new();
+        interner.reserve(symbols, bytes);
+        interner
+    }
+}
+
+impl<S: BuildHasher> Interner<S> {
+    /// Creates an empty interner hashing strings with `hasher`.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut interner = vec::Interner::with_hasher(RandomState::new());
+    /// let a = interner.intern("a");
+    /// assert_eq!(interner.get("a"), Some(a));
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self {
+        let mut offsets = Vec::with_capacity(1);
+        offsets.push(0);
+
+        Self {
+            buf: String::new(),
+            offsets,
+            table: Vec::new(),
+            hasher,
+        }
+    }
+
+    /// Returns the number of distinct strings.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// interner.intern("a");
+    /// interner.intern("a");
+    /// assert_eq!(interner.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.offsets.len - 1
+    }
+
+    /// Returns `true` if the interner holds no strings.
+    /// # Example
+    /// ```
+    /// let interner = vec::Interner::new();
+    /// assert!(interner.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reserves room for at least `symbols` more strings of `bytes` more bytes in total.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// interner.reserve(10, 100);
+    /// ```
+    pub fn reserve(&mut self, symbols: usize, bytes: usize) {
+        self.buf.reserve(bytes);
+        self.offsets.reserve(symbols);
+
+        let needed = self.len() + symbols;
+        if needed * 2 > self.table.len {
+            self.rehash(needed);
+        }
+    }
+
+    /// Returns the symbol of `string`, storing it first if it's new.
+    /// # Panics
+    /// Panics if the interner already holds `u32::MAX` strings, or if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// let a = interner.intern("a");
+    /// assert_eq!(interner.intern(&std::string::String::from("a")), a);
+    /// ```
+    pub fn intern(&mut self, string: &str) -> Symbol {
+        if (self.len() + 1) * 2 > self.table.len {
+            self.rehash(self.len() + 1);
+        }
+
+        let hash = self.hash(string);
+        let bucket = match self.find(hash, string) {
+            Ok(symbol) => return symbol,
+            Err(bucket) => bucket,
+        };
+
+        let index = self.len();
+        assert!(index < u32::MAX as usize, "Interner is full");
+
+        self.buf.push_str(string);
+        self.offsets.push(self.buf.len());
+        self.table[bucket] = index as u32 + 1;
+
+        Symbol(index as u32)
+    }
+
+    /// Returns the symbol of `string`, or `None` if it was never interned.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// let a = interner.intern("a");
+    /// assert_eq!(interner.get("a"), Some(a));
+    /// assert_eq!(interner.get("b"), None);
+    /// ```
+    pub fn get(&self, string: &str) -> Option<Symbol> {
+        if self.table.is_empty() {
+            return None;
+        }
+
+        self.find(self.hash(string), string).ok()
+    }
+
+    /// Returns the string of `symbol`.
+    /// # Panics
+    /// Panics if `symbol` comes from an interner holding more strings.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// let hello = interner.intern("hello");
+    /// assert_eq!(interner.resolve(hello), "hello");
+    /// ```
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.try_resolve(symbol).expect("symbol out of bounds")
+    }
+
+    /// Returns the string of `symbol`, or `None` if it's out of bounds.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// let a = interner.intern("a");
+    /// assert_eq!(interner.try_resolve(a), Some("a"));
+    /// assert_eq!(vec::Interner::new().try_resolve(a), None);
+    /// ```
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&str> {
+        let index = symbol.index();
+
+        if index < self.len() {
+            Some(&self.buf[self.offsets[index]..self.offsets[index + 1]])
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the symbols and their strings, in insertion order.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// let a = interner.intern("a");
+    /// let b = interner.intern("b");
+    /// assert_eq!(interner.iter().collect::<std::vec::Vec<_>>(), [(a, "a"), (b, "b")]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Symbol, &str)> + ExactSizeIterator + '_ {
+        self.offsets
+            .windows(2)
+            .enumerate()
+            .map(move |(i, w)| (Symbol(i as u32), &self.buf[w[0]..w[1]]))
+    }
+
+    /// Returns every string one after the other, as stored in the byte buffer.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// interner.intern("foo");
+    /// interner.intern("bar");
+    /// interner.intern("foo");
+    /// assert_eq!(interner.as_str(), "foobar");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    /// Removes every string, keeping the allocated capacity. Symbols handed out before are reused for new strings.
+    /// # Example
+    /// ```
+    /// let mut interner = vec::Interner::new();
+    /// interner.intern("a");
+    /// interner.clear();
+    /// assert!(interner.is_empty() && interner.get("a").is_none());
+    /// ```
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.offsets.truncate(1);
+        self.table.fill(0);
+    }
+
+    fn hash(&self, string: &str) -> u64 {
+        self.hasher.hash_one(string)
+    }
+
+    // Returns the symbol of `string`, or the empty bucket it would go in. The table must not be full.
+    fn find(&self, hash: u64, string: &str) -> Result<Symbol, usize> {
+        let mask = self.table.len - 1;
+        let mut bucket = hash as usize & mask;
+
+        loop {
+            match self.table[bucket] {
+                0 => return Err(bucket),
+                id => {
+                    let symbol = Symbol(id - 1);
+                    if self.resolve(symbol) == string {
+                        return Ok(symbol);
+                    }
+                }
+            }
+            bucket = (bucket + 1) & mask;
+        }
+    }
+
+    // Rebuilds the table with room for `symbols` symbols, keeping it at most half full
+    fn rehash(&mut self, symbols: usize) {
+        let buckets = (symbols * 2).next_power_of_two().max(8);
+
+        let mut table = Vec::with_capacity(buckets);
+        table.extend(iter::repeat_n(0, buckets));
+        self.table = table;
+
+        for index in 0..self.len() {
+            let string = &self.buf[self.offsets[index]..self.offsets[index + 1]];
+            let mut bucket = self.hash(string) as usize & (buckets - 1);
+
+            while self.table[bucket] != 0 {
+                bucket = (bucket + 1) & (buckets - 1);
+            }
+            self.table[bucket] = index as u32 + 1;
+        }
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: BuildHasher> Index<Symbol> for Interner<S> {
+    type Output = str;
+
+    fn index(&self, symbol: Symbol) -> &str {
+        self.resolve(symbol)
+    }
+}
+
+impl<'a, S: BuildHasher> Extend<&'a str> for Interner<S> {
+    /// Interns every string of an iterator.
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for string in iter {
+            self.intern(string);
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Interner {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        interner.extend(iter);
+        interner
+    }
+}
+
+impl<S: BuildHasher> fmt::Debug for Interner<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -1,29 +1,228 @@
-#![feature(ptr_internals)]
-#![feature(allocator_api)]
+#![feature(
+    allocator_api,
+    array_try_from_fn,
+    min_specialization,
+    portable_simd,
+    ptr_metadata,
+    trusted_len,
+    unsize
+)]
+
+// Asserts an internal invariant when the `debug-invariants` feature is enabled, and compiles to nothing otherwise
+macro_rules! invariant {
+    ( $( $arg:tt )* ) => {
+        if cfg!(feature = "debug-invariants") {
+            assert!($( $arg )*);
+        }
+    };
+}
+
+mod aligned;
+mod any;
+mod append_only;
+mod arc_slice;
+mod arena;
+mod array;
+mod bitvec;
+mod bivec;
+mod bounded;
+mod branded;
+mod byte_buf;
+mod compact;
+mod compressed;
+mod concurrent;
+mod cow;
+mod cursor;
+mod cvec;
+mod deque;
+mod dirty;
 mod drain;
+mod enum_indexed;
+mod error;
+mod fixed;
+mod flat_list;
+mod flat_map;
+mod frozen;
+mod gap;
+mod grid;
+mod growth;
+mod heap;
+mod history;
+#[cfg(feature = "alloc-hooks")]
+mod hooks;
+mod index_vec;
+mod indexed_heap;
+mod interner;
+#[cfg(all(feature = "mmap", target_os = "linux"))]
+mod mmap;
+mod non_empty;
+mod nullable;
+mod observable;
+mod oom;
+mod ordered_map;
+mod ordered_set;
+#[cfg(feature = "parallel")]
+mod par;
+mod persistent;
+mod pool;
 mod raw;
+#[cfg(feature = "simd-reductions")]
+mod reduce;
+mod repeat;
+mod rope;
+mod segmented;
+mod segmented_bytes;
+mod simd;
+mod slab;
+mod slotmap;
+mod small;
+mod small_string;
+mod snapshot;
+#[cfg(feature = "derive")]
+mod soa;
+mod sort;
+mod sorted;
+mod sparse;
+mod sparse_set;
+mod spec_extend;
+mod spill;
+mod spsc;
+mod stable;
+#[cfg(feature = "stats")]
+mod stats;
+mod string;
+mod thin;
+mod unsized_vec;
+mod vec_list;
+mod vec_map;
+#[cfg(all(kani, feature = "verification"))]
+mod verification;
+mod zeroed;
 
+pub use aligned::{Aligned, AlignedVec};
+pub use any::{AnyType, AnyVec};
+pub use append_only::AppendOnlyVec;
+pub use arc_slice::{ArcBytes, ArcSlice};
+pub use arena::Arena;
+pub use array::ArrayVec;
+pub use bitvec::BitVec;
+pub use bivec::BiVec;
+pub use bounded::BoundedVec;
+pub use branded::{BrandedIndex, BrandedVec};
+pub use byte_buf::ByteBuf;
+pub use compact::CompactVec;
+pub use compressed::{CompressedVec, Compressible};
+pub use concurrent::{ConcurrentVec, Ref as ConcurrentRef};
+pub use cow::CowVec;
+pub use cursor::CursorMut;
+pub use cvec::{CFree, CVec};
+pub use deque::VecDeque;
+pub use dirty::DirtyRangeVec;
 use drain::Drain;
-use raw::{RawValIter, RawVec};
+pub use enum_indexed::{EnumIndex, EnumIndexedVec};
+pub use error::{ByteBufError, CapacityError, EmptyError, FromUtf8Error, TryReserveError};
+pub use fixed::{Fixed, StaticBuffer};
+pub use flat_list::FlatListVec;
+pub use flat_map::FlatMap;
+pub use frozen::FrozenVec;
+pub use gap::GapBuffer;
+pub use grid::Grid;
+pub use growth::{Capped, Doubling, FixedStep, GrowthPolicy, OneAndHalf, ShrinkBelow};
+pub use heap::{BinaryHeap, PeekMut};
+pub use history::HistoryVec;
+#[cfg(feature = "alloc-hooks")]
+pub use hooks::{set_alloc_hook, AllocEvent, AllocKind};
+pub use index_vec::{Idx, IndexVec};
+pub use indexed_heap::{Handle as HeapHandle, IndexedHeap};
+pub use interner::{Interner, Symbol};
+#[cfg(all(feature = "mmap", target_os = "linux"))]
+pub use mmap::{Mmap, MmapBuilder};
+pub use non_empty::NonEmptyVec;
+pub use nullable::NullableVec;
+pub use observable::{
+    Event as ObservableEvent, ObservableVec, Subscription as ObservableSubscription,
+};
+pub use oom::{set_oom_handler, OomAction, OomHandler};
+pub use ordered_map::OrderedMap;
+pub use ordered_set::OrderedSet;
+pub use persistent::PersistentVec;
+pub use pool::Pool;
+use raw::{
+    handle_reserve_error, insert_at, poison, remove_at, remove_where, truncate_at, RawValIter,
+    RawVec, SetLenOnDrop,
+};
+#[cfg(feature = "simd-reductions")]
+pub use reduce::Numeric;
+#[doc(hidden)]
+pub use repeat::from_elem;
+pub use rope::Rope;
+pub use segmented::SegmentedVec;
+pub use segmented_bytes::SegmentedBytes;
+pub use simd::Primitive;
+pub use slab::Slab;
+pub use slotmap::{Key, SecondaryMap, SlotMap};
+pub use small::SmallVec;
+pub use small_string::SmallString;
+pub use snapshot::{Snapshot, SnapshotVec};
+#[cfg(feature = "derive")]
+pub use soa::{Column as SoaColumn, Soa, SoaVec};
+pub use sorted::SortedVec;
+pub use sparse::SparseVec;
+pub use sparse_set::SparseSet;
+use spec_extend::SpecExtend;
+pub use spill::{Spill, SpillRef, SpillVec};
+pub use spsc::{spsc_ring, Consumer as SpscConsumer, Producer as SpscProducer};
+pub use stable::StableVec;
+#[cfg(feature = "stats")]
+pub use stats::VecStats;
 use std::{
+    alloc::{Allocator, Global},
+    cmp,
+    collections::HashSet,
+    hash::Hash,
+    iter::FromIterator,
     marker::PhantomData,
-    mem,
+    mem::{self, MaybeUninit},
     ops::{Deref, DerefMut},
-    ptr,
+    ptr::{self, NonNull},
 };
+pub use string::String;
+pub use thin::ThinVec;
+pub use unsized_vec::UnsizedVec;
+#[cfg(feature = "derive")]
+pub use vec_derive::{EnumIndex, Idx, Soa};
+pub use vec_list::{
+    CursorMut as VecListCursorMut, Handle as ListHandle, Iter as VecListIter, VecList,
+};
+pub use vec_map::{
+    Entry as VecMapEntry, OccupiedEntry as VecMapOccupiedEntry, VacantEntry as VecMapVacantEntry,
+    VecMap,
+};
+pub use zeroed::Zeroable;
 
-/// Simplified macro for vec creation.
+/// Simplified macro for vec creation, building a [`Vec`] of this crate whatever `Vec` is in scope.
+///
+/// The list form allocates once for all the elements. `custom_vec![elem; n]` creates a vector of `n` clones of
+/// `elem`, like [`std::vec!`].
 /// # Example
 /// ```
 /// use vec::custom_vec;
-/// let vec = custom_vec![1, 2, 3];
+/// let vec = custom_vec![1, 2, 3,];
 /// assert_eq!(vec.len(), 3);
+/// assert_eq!(vec.capacity(), 3);
+///
+/// let zeroes = custom_vec![0u8; 1024];
+/// assert_eq!(zeroes.len(), 1024);
+/// assert!(zeroes.iter().all(|&x| x == 0));
 /// ```
 #[macro_export]
 macro_rules! custom_vec {
-    ( $( $x:expr ),* ) => {
+    ( $elem:expr; $n:expr ) => {
+        $crate::from_elem($elem, $n)
+    };
+    ( $( $x:expr ),* $(,)? ) => {
         {
-            let mut temp_vec = Vec::new();
+            let mut temp_vec = $crate::Vec::with_capacity(<[()]>::len(&[$( $crate::__unit!($x) ),*]));
             $(
                 temp_vec.push($x);
             )*
@@ -32,23 +231,239 @@ macro_rules! custom_vec {
     };
 }
 
+/// Creates an [`ArrayVec`] from a list of elements, or of `n` clones of an element with `array_vec![elem; n]`.
+///
+/// The capacity is checked at compile time: more elements than the capacity of the vector fail to build, so `n` must
+/// be a constant.
+/// # Example
+/// ```
+/// use vec::{array_vec, ArrayVec};
+/// let vec: ArrayVec<i32, 4> = array_vec![1, 2, 3];
+/// assert_eq!(&*vec, &[1, 2, 3]);
+///
+/// let zeroes: ArrayVec<u8, 8> = array_vec![0; 8];
+/// assert!(zeroes.is_full());
+/// ```
+/// ```compile_fail
+/// let vec: vec::ArrayVec<i32, 2> = vec::array_vec![1, 2, 3];
+/// ```
+#[macro_export]
+macro_rules! array_vec {
+    ( $elem:expr; $n:expr ) => {
+        $crate::ArrayVec::from_elem::<{ $n }>($elem)
+    };
+    ( $( $x:expr ),* $(,)? ) => {
+        $crate::ArrayVec::from_array([$( $x ),*])
+    };
+}
+
+/// Creates a [`SmallVec`] from a list of elements, or of `n` clones of an element with `small_vec![elem; n]`. The
+/// vector only allocates if there are more elements than its inline capacity.
+/// # Example
+/// ```
+/// use vec::{small_vec, SmallVec};
+/// let vec: SmallVec<i32, 4> = small_vec![1, 2, 3];
+/// assert!(!vec.spilled());
+///
+/// let ones: SmallVec<i32, 4> = small_vec![1; 6];
+/// assert!(ones.spilled());
+/// assert_eq!(&*ones, &[1; 6]);
+/// ```
+#[macro_export]
+macro_rules! small_vec {
+    ( $elem:expr; $n:expr ) => {
+        $crate::SmallVec::from_elem($elem, $n)
+    };
+    ( $( $x:expr ),* $(,)? ) => {
+        $crate::SmallVec::from_array([$( $x ),*])
+    };
+}
+
+/// Creates a [`SortedVec`] from a list of elements, or of `n` clones of an element with `sorted_vec![elem; n]`. The
+/// elements are collected into a [`Vec`] by [`custom_vec!`] and sorted once.
+/// # Example
+/// ```
+/// use vec::sorted_vec;
+/// let vec = sorted_vec![3, 1, 2];
+/// assert_eq!(&*vec, &[1, 2, 3]);
+/// ```
+#[macro_export]
+macro_rules! sorted_vec {
+    ( $( $tt:tt )* ) => {
+        $crate::SortedVec::from($crate::custom_vec![$( $tt )*])
+    };
+}
+
+/// Creates a [`VecDeque`] from a list of elements, or of `n` clones of an element with `vec_deque![elem; n]`. The
+/// elements are collected into a [`Vec`] by [`custom_vec!`], whose allocation the deque reuses.
+/// # Example
+/// ```
+/// use vec::vec_deque;
+/// let mut deque = vec_deque![1, 2, 3];
+/// deque.push_front(0);
+/// assert_eq!(deque.front(), Some(&0));
+/// assert_eq!(deque.back(), Some(&3));
+/// ```
+#[macro_export]
+macro_rules! vec_deque {
+    ( $( $tt:tt )* ) => {
+        $crate::VecDeque::from($crate::custom_vec![$( $tt )*])
+    };
+}
+
+// Stands for one element of a list in `custom_vec!`, to count them in a constant expression
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __unit {
+    ( $x:tt ) => {
+        ()
+    };
+}
+
 /// Contiguous, dynamically-sized set of elements of any type.
 #[derive(Debug)]
-pub struct Vec<T> {
+pub struct Vec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
     /// Items in the vector
     pub len: usize,
     /// Pointer to Vector's RawPointer
-    buf: RawVec<T>,
+    buf: RawVec<T, A, G>,
 }
 
 /// Coerces a `Vec` into an iterator.
-pub struct IntoIter<T> {
-    _buf: RawVec<T>,
+pub struct IntoIter<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    _buf: RawVec<T, A, G>,
     iter: RawValIter<T>,
 }
 
 impl<T> Vec<T> {
+    /// Creates a new Vector with size 0 (unallocated).
+    /// # Example
+    /// ```
+    /// let vec: Vec<i32> = Vec::new();
+    /// assert_eq!(vec.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            buf: RawVec::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates a new Vector with room for at least `capacity` elements without reallocating.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::with_capacity(10);
+    /// assert_eq!(vec.capacity(), 10);
+    /// vec.push(1);
+    /// assert_eq!(vec.capacity(), 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    /// Creates a new Vector with room for at least `capacity` elements, returning an error if the allocation fails.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// # Example
+    /// ```
+    /// use vec::{TryReserveError, Vec};
+    /// let vec: Vec<u8> = Vec::try_with_capacity(16).unwrap();
+    /// assert_eq!(vec.capacity(), 16);
+    ///
+    /// let err = Vec::<u32>::try_with_capacity(usize::MAX).unwrap_err();
+    /// assert_eq!(err, TryReserveError::CapacityOverflow);
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, G: GrowthPolicy> Vec<T, Global, G> {
+    /// Creates a new Vector with size 0 (unallocated) whose capacity grows according to `growth`.
+    /// # Example
+    /// ```
+    /// use vec::{FixedStep, Vec};
+    /// let mut vec = Vec::with_growth_policy(FixedStep(8));
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn with_growth_policy(growth: G) -> Self {
+        Self::with_growth_policy_in(growth, Global)
+    }
+}
+
+impl<T, A: Allocator> Vec<T, A> {
+    /// Creates a new Vector with size 0 (unallocated) that will allocate from `alloc`.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// let vec: vec::Vec<i32, _> = vec::Vec::new_in(Global);
+    /// assert_eq!(vec.len(), 0);
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            buf: RawVec::new_in(alloc),
+            len: 0,
+        }
+    }
+
+    /// Creates a new Vector with room for at least `capacity` elements, allocated from `alloc`.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// let vec: vec::Vec<i32, _> = vec::Vec::with_capacity_in(8, Global);
+    /// assert_eq!(vec.capacity(), 8);
+    /// ```
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        match Self::try_with_capacity_in(capacity, alloc) {
+            Ok(vec) => vec,
+            Err(err) => handle_reserve_error(err),
+        }
+    }
+
+    /// Creates a new Vector with room for at least `capacity` elements allocated from `alloc`, returning an error if
+    /// the allocation fails.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Pool, TryReserveError, Vec};
+    /// let pool: Pool<16, 1> = Pool::new();
+    /// let vec = Vec::<u8, _>::try_with_capacity_in(16, &pool).unwrap();
+    /// assert_eq!(vec.capacity(), 16);
+    ///
+    /// // The pool has no blocks left
+    /// let result = Vec::<u8, _>::try_with_capacity_in(16, &pool);
+    /// assert!(matches!(result, Err(TryReserveError::AllocError { .. })));
+    /// ```
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            buf: RawVec::try_with_capacity_in(capacity, Doubling, alloc)?,
+            len: 0,
+        })
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
     fn ptr(&self) -> *mut T {
+        invariant!(
+            self.buf.ptr.as_ptr().is_aligned(),
+            "Vector buffer is misaligned"
+        );
+        invariant!(
+            self.len <= self.buf.cap,
+            "Vector length {} exceeds its capacity {}",
+            self.len,
+            self.buf.cap
+        );
+
         self.buf.ptr.as_ptr()
     }
 
@@ -56,19 +471,179 @@ impl<T> Vec<T> {
         self.buf.cap
     }
 
-    /// Creates a new Vector with size 0 (unallocated).
+    /// Returns the number of elements the vector can hold without reallocating.
     /// # Example
     /// ```
-    /// let vec: Vec<i32> = Vec::new();
-    /// assert_eq!(vec.len(), 0);
+    /// let mut vec = vec::Vec::new();
+    /// assert_eq!(vec.capacity(), 0);
+    /// vec.push(1u32);
+    /// // The first allocation already makes room for a few elements
+    /// assert_eq!(vec.capacity(), 4);
     /// ```
-    pub fn new() -> Self {
+    pub fn capacity(&self) -> usize {
+        self.cap()
+    }
+
+    /// Creates a new Vector with size 0 (unallocated) that will allocate from `alloc`, growing according to `growth`.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use vec::{OneAndHalf, Vec};
+    /// let mut vec = Vec::with_growth_policy_in(OneAndHalf, Global);
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn with_growth_policy_in(growth: G, alloc: A) -> Self {
         Self {
-            buf: RawVec::new(),
+            buf: RawVec::with_growth_policy_in(growth, alloc),
             len: 0,
         }
     }
 
+    /// Returns a reference to the underlying allocator.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// let vec: vec::Vec<i32, _> = vec::Vec::new_in(Global);
+    /// let _alloc: &Global = vec.allocator();
+    /// ```
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    /// Returns how many times the buffer was reallocated and the peak capacity it reached, to find vectors worth
+    /// creating with [`Vec::with_capacity`].
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::with_capacity(100);
+    /// vec.extend(0..100u32);
+    /// assert_eq!(vec.stats().reallocations, 1);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> VecStats {
+        self.buf.stats()
+    }
+
+    /// Returns a raw pointer to the vector's buffer, valid for reads of `capacity` elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(1);
+    /// assert_eq!(unsafe { *vec.as_ptr() }, 1);
+    /// ```
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr()
+    }
+
+    /// Returns a raw mutable pointer to the vector's buffer, valid for writes of `capacity` elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(1);
+    /// unsafe { *vec.as_mut_ptr() = 2 };
+    /// assert_eq!(vec[0], 2);
+    /// ```
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr()
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    /// # Safety
+    /// `new_len` must be less than or equal to the capacity, and the elements at `old_len..new_len` must be
+    /// initialized.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::Vec<u8> = vec::Vec::with_capacity(3);
+    /// unsafe {
+    ///     vec.as_mut_ptr().write_bytes(7, 3);
+    ///     vec.set_len(3);
+    /// }
+    /// assert_eq!(&*vec, &[7, 7, 7]);
+    /// ```
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        invariant!(
+            new_len <= self.cap(),
+            "set_len: new length {} exceeds capacity {}",
+            new_len,
+            self.cap()
+        );
+
+        self.len = new_len;
+    }
+
+    /// Returns the spare capacity of the vector, past its length, as a slice of uninitialized elements to fill
+    /// before calling [`Vec::set_len`].
+    /// # Example
+    /// ```
+    /// let mut vec: vec::Vec<u32> = vec::Vec::with_capacity(4);
+    /// for (i, slot) in vec.spare_capacity_mut().iter_mut().enumerate() {
+    ///     slot.write(i as u32);
+    /// }
+    /// unsafe { vec.set_len(4) };
+    /// assert_eq!(&*vec, &[0, 1, 2, 3]);
+    /// ```
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.ptr().add(self.len) as *mut MaybeUninit<T>,
+                self.cap() - self.len,
+            )
+        }
+    }
+
+    /// Builds a vector reusing the allocation of `buf`, whose first `len` elements are initialized. Only the
+    /// capacity of `buf` matters, its length is ignored.
+    /// # Safety
+    /// `len` must be less than or equal to the capacity of `buf`, and its first `len` elements must be initialized.
+    /// # Example
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use vec::Vec;
+    ///
+    /// // Stand-in for a C function filling a buffer, returning how many bytes it wrote
+    /// unsafe fn read_into(buf: *mut u8, cap: usize) -> usize {
+    ///     let n = cap.min(3);
+    ///     buf.write_bytes(b'x', n);
+    ///     n
+    /// }
+    ///
+    /// let mut buf: Vec<MaybeUninit<u8>> = Vec::with_capacity(16);
+    /// let written = unsafe { read_into(buf.as_mut_ptr().cast(), buf.capacity()) };
+    ///
+    /// let vec = unsafe { Vec::from_uninit_parts(buf, written) };
+    /// assert_eq!(&*vec, b"xxx");
+    /// assert_eq!(vec.capacity(), 16);
+    /// ```
+    pub unsafe fn from_uninit_parts(buf: Vec<MaybeUninit<T>, A, G>, len: usize) -> Self {
+        invariant!(
+            len <= buf.cap(),
+            "from_uninit_parts: length {} exceeds capacity {}",
+            len,
+            buf.cap()
+        );
+
+        // The elements of `buf` are `MaybeUninit`, dropping nothing is fine
+        let buf = mem::ManuallyDrop::new(buf);
+
+        Self {
+            buf: ptr::read(&buf.buf).assume_init(),
+            len,
+        }
+    }
+
+    // Splits the vector into its buffer and length, for the containers reusing its allocation
+    pub(crate) fn into_raw_parts(self) -> (RawVec<T, A, G>, usize) {
+        let vec = mem::ManuallyDrop::new(self);
+        (unsafe { ptr::read(&vec.buf) }, vec.len)
+    }
+
+    // Inverse of `into_raw_parts`, the first `len` elements of `buf` must be initialized
+    pub(crate) unsafe fn from_raw_parts(buf: RawVec<T, A, G>, len: usize) -> Self {
+        Self { buf, len }
+    }
+
     /// Pushes an element to the end of the vector.
     /// # Example
     /// ```
@@ -77,16 +652,43 @@ impl<T> Vec<T> {
     /// vec.push(1);
     /// assert_eq!(custom_vec![5, 4, 3, 2, 1], vec);
     /// ```
+    #[inline]
     pub fn push(&mut self, elem: T) {
+        if let Err(err) = self.try_push(elem) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Pushes an element to the end of the vector, returning an error instead of aborting if the vector can't grow.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure, the element is dropped.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Pool, Vec};
+    /// let pool: Pool<8, 1> = Pool::new();
+    /// let mut vec = Vec::new_in(&pool);
+    /// for i in 0..8u8 {
+    ///     vec.try_push(i).unwrap();
+    /// }
+    /// // A block holds only 8 bytes
+    /// assert!(vec.try_push(8).is_err());
+    /// assert_eq!(vec.len(), 8);
+    /// ```
+    #[inline]
+    pub fn try_push(&mut self, elem: T) -> Result<(), TryReserveError> {
+        // Also guards zero-sized types, whose capacity is usize::MAX: a full vector fails instead of overflowing `len`
         if self.len == self.cap() {
-            self.buf.grow()
+            self.buf.try_grow()?;
         };
 
         unsafe {
-            ptr::write(self.ptr().offset(self.len as isize), elem);
+            ptr::write(self.ptr().add(self.len), elem);
         }
 
         self.len += 1;
+
+        Ok(())
     }
 
     /// Removes the last element of the vector and returns it, or `None` if the vector is empty.
@@ -103,7 +705,15 @@ impl<T> Vec<T> {
             None
         } else {
             self.len -= 1;
-            unsafe { Some(ptr::read(self.ptr().offset(self.len as isize))) }
+
+            let elem = unsafe {
+                let elem = ptr::read(self.ptr().add(self.len));
+                poison(self.ptr().add(self.len), 1);
+                elem
+            };
+            self.shrink_by_policy();
+
+            Some(elem)
         }
     }
 
@@ -117,28 +727,277 @@ impl<T> Vec<T> {
     /// vec.insert(1, 3);
     /// assert_eq!(custom_vec![1, 3, 2], vec);
     /// ```
+    #[inline]
     pub fn insert(&mut self, index: usize, elem: T) {
+        if let Err(err) = self.try_insert(index, elem) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Inserts an element at a given index, returning an error instead of aborting if the vector can't grow.
+    /// # Panics
+    /// This function will panic if the index is out of bounds (> length).
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure, the element is dropped.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.try_insert(0, 2).unwrap();
+    /// vec.try_insert(0, 1).unwrap();
+    /// assert_eq!(&*vec, &[1, 2]);
+    /// ```
+    #[inline]
+    pub fn try_insert(&mut self, index: usize, elem: T) -> Result<(), TryReserveError> {
         assert!(index <= self.len, "Index out of bounds");
 
         if self.cap() == self.len {
-            self.buf.grow();
+            self.buf.try_grow()?;
         }
 
-        unsafe {
-            if index < self.len {
-                // ptr::copy(source, dest, count) > Copy from 'source' to 'dest' 'count' elements
-                ptr::copy(
-                    self.ptr().offset(index as isize),
-                    self.ptr().offset(index as isize + 1),
-                    self.len - index,
-                );
+        invariant!(
+            index < self.cap() && self.len < self.cap(),
+            "insert: no room for index {} (length {}, capacity {})",
+            index,
+            self.len,
+            self.cap()
+        );
+
+        unsafe { insert_at(self.ptr(), self.len, index, elem) };
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Appends every element of an iterator, returning an error instead of aborting if the vector can't grow.
+    ///
+    /// Elements pushed before the failure are kept, the rest of the iterator is dropped.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.try_extend(0..3).unwrap();
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    pub fn try_extend<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), TryReserveError> {
+        self.spec_extend(iter.into_iter())
+    }
+
+    // Generic path of `try_extend`, for iterators of unknown length
+    fn extend_desugared<I: Iterator<Item = T>>(
+        &mut self,
+        mut iter: I,
+    ) -> Result<(), TryReserveError> {
+        loop {
+            // Fill the spare capacity, the guard keeps the length right if the iterator panics
+            let ptr = self.ptr();
+            let cap = self.cap();
+            let mut len = SetLenOnDrop::new(&mut self.len);
+
+            while len.current_len() < cap {
+                match iter.next() {
+                    Some(elem) => unsafe {
+                        ptr::write(ptr.add(len.current_len()), elem);
+                        len.increment_len(1);
+                    },
+                    None => return Ok(()),
+                }
             }
 
-            ptr::write(self.ptr().offset(index as isize), elem);
-            self.len += 1;
+            drop(len);
+
+            match iter.next() {
+                Some(elem) => {
+                    // Make room for everything the iterator promises at once, rather than growing element by element
+                    let (lower, _) = iter.size_hint();
+                    self.buf.try_reserve(self.len, lower.saturating_add(1))?;
+                    self.try_push(elem)?
+                }
+                None => return Ok(()),
+            }
         }
     }
 
+    /// Reserves room for at least `additional` more elements in a single reallocation.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(1);
+    /// vec.reserve(100);
+    /// assert!(vec.capacity() >= 101);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Reserves room for at least `additional` more elements in a single reallocation, returning an error instead
+    /// of aborting if the vector can't grow.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// # Example
+    /// ```
+    /// use vec::{TryReserveError, Vec};
+    /// let mut vec: Vec<u32> = Vec::new();
+    /// vec.try_reserve(10).unwrap();
+    /// assert!(vec.capacity() >= 10);
+    /// assert_eq!(vec.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buf.try_reserve(self.len, additional)
+    }
+
+    /// Moves every element of `other` to the back of the vector, leaving `other` empty.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut a = vec::Vec::new();
+    /// a.extend(0..3);
+    /// let mut b = vec::Vec::new();
+    /// b.extend(3..6);
+    ///
+    /// a.append(&mut b);
+    /// assert_eq!(&*a, &[0, 1, 2, 3, 4, 5]);
+    /// assert!(b.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        if let Err(err) = self.try_append(other) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Moves every element of `other` to the back of the vector, leaving `other` empty, returning an error instead
+    /// of aborting if the vector can't grow.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure, both vectors are left
+    /// unchanged.
+    /// # Example
+    /// ```
+    /// use vec::Vec;
+    /// let mut a = Vec::new();
+    /// a.extend(0..3);
+    /// let mut b = Vec::new();
+    /// b.extend(3..6);
+    ///
+    /// a.try_append(&mut b).unwrap();
+    /// assert_eq!(&*a, &[0, 1, 2, 3, 4, 5]);
+    /// assert!(b.is_empty());
+    /// ```
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), TryReserveError> {
+        let count = other.len;
+        self.try_reserve(count)?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(other.ptr(), self.ptr().add(self.len), count);
+        }
+
+        // The elements now belong to `self`
+        other.len = 0;
+        self.len += count;
+
+        Ok(())
+    }
+
+    /// Inserts a copy of every element of `slice` at `index`, shifting all the elements after it to the right.
+    /// # Panics
+    /// This function will panic if the index is out of bounds (> length) or the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([1, 5]);
+    /// vec.insert_slice(1, &[2, 3, 4]);
+    /// assert_eq!(&*vec, &[1, 2, 3, 4, 5]);
+    /// ```
+    pub fn insert_slice(&mut self, index: usize, slice: &[T])
+    where
+        T: Copy,
+    {
+        if let Err(err) = self.try_insert_slice(index, slice) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Inserts a copy of every element of `slice` at `index`, shifting all the elements after it to the right,
+    /// returning an error instead of aborting if the vector can't grow.
+    /// # Panics
+    /// This function will panic if the index is out of bounds (> length).
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure, the vector is left unchanged.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([1, 5]);
+    /// vec.try_insert_slice(1, &[2, 3, 4]).unwrap();
+    /// assert_eq!(&*vec, &[1, 2, 3, 4, 5]);
+    /// ```
+    pub fn try_insert_slice(&mut self, index: usize, slice: &[T]) -> Result<(), TryReserveError>
+    where
+        T: Copy,
+    {
+        assert!(index <= self.len, "Index out of bounds");
+
+        self.try_reserve(slice.len())?;
+
+        unsafe {
+            let gap = self.ptr().add(index);
+
+            ptr::copy(gap, gap.add(slice.len()), self.len - index);
+            ptr::copy_nonoverlapping(slice.as_ptr(), gap, slice.len());
+        }
+
+        self.len += slice.len();
+
+        Ok(())
+    }
+
+    /// Clones every element into a new vector using a clone of the allocator and growth policy, returning an error
+    /// instead of aborting if the new vector can't be allocated.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Pool, Vec};
+    /// let pool: Pool<8, 1> = Pool::new();
+    /// let mut vec = Vec::new_in(&pool);
+    /// vec.push(1u8);
+    ///
+    /// // The only block of the pool is taken
+    /// assert!(vec.try_clone().is_err());
+    /// ```
+    pub fn try_clone(&self) -> Result<Self, TryReserveError>
+    where
+        T: Clone,
+        A: Clone,
+        G: Clone,
+    {
+        let buf = RawVec::try_with_capacity_in(
+            self.len,
+            self.buf.growth_policy().clone(),
+            self.allocator().clone(),
+        )?;
+        let mut vec = Self { buf, len: 0 };
+
+        // If a clone panics, the guard sets the length so the elements cloned so far are dropped
+        let ptr = vec.ptr();
+        let mut len = SetLenOnDrop::new(&mut vec.len);
+
+        for elem in self.iter() {
+            unsafe { ptr::write(ptr.add(len.current_len()), elem.clone()) }
+            len.increment_len(1);
+        }
+
+        drop(len);
+        Ok(vec)
+    }
+
     /// Removes an element from a given index, shifting all the elements to the left.
     /// # Panics
     /// This function will panic if the index is out of bounds.
@@ -154,15 +1013,240 @@ impl<T> Vec<T> {
     pub fn remove(&mut self, index: usize) -> T {
         assert!(index < self.len, "index out of bounds");
 
+        invariant!(
+            self.len <= self.cap(),
+            "remove: the tail after index {} overruns the capacity {}",
+            index,
+            self.cap()
+        );
+
+        let result = unsafe { remove_at(self.ptr(), self.len, index) };
+        self.len -= 1;
+
+        result
+    }
+
+    /// Removes the elements at the given indices, which must be sorted in strictly increasing order, dropping them.
+    /// The remaining elements keep their order and are shifted left in a single pass, instead of once per removed
+    /// element as with repeated calls to [`Vec::remove`].
+    /// # Panics
+    /// This function will panic if the indices are not strictly increasing or if one is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..8);
+    /// vec.remove_indices(&[1, 4, 5, 7]);
+    /// assert_eq!(&*vec, &[0, 2, 3, 6]);
+    /// ```
+    pub fn remove_indices(&mut self, indices: &[usize]) {
+        self.remove_sorted(indices, drop)
+    }
+
+    /// Removes the elements at the given indices, which must be sorted in strictly increasing order, and returns
+    /// them in that order. The remaining elements are compacted in a single pass, like with
+    /// [`Vec::remove_indices`].
+    /// # Panics
+    /// This function will panic if the indices are not strictly increasing or if one is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(["a", "b", "c", "d"]);
+    /// let removed = vec.extract_indices(&[0, 2]);
+    /// assert_eq!(&*removed, &["a", "c"]);
+    /// assert_eq!(&*vec, &["b", "d"]);
+    /// ```
+    pub fn extract_indices(&mut self, indices: &[usize]) -> Vec<T> {
+        let mut removed = Vec::with_capacity(indices.len());
+        self.remove_sorted(indices, |elem| removed.push(elem));
+        removed
+    }
+
+    /// Removes, in a single pass, the elements whose bit is set in `mask`, dropping them. The remaining elements
+    /// keep their order.
+    /// # Panics
+    /// This function will panic if the mask doesn't have exactly one bit per element.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..5);
+    /// let mut mask = vec::BitVec::repeat(false, 5);
+    /// mask.set(0, true);
+    /// mask.set(3, true);
+    /// vec.remove_mask(&mask);
+    /// assert_eq!(&*vec, &[1, 2, 4]);
+    /// ```
+    pub fn remove_mask(&mut self, mask: &BitVec) {
+        assert_eq!(
+            mask.len(),
+            self.len,
+            "mask length doesn't match the vector length"
+        );
+
+        let ptr = self.ptr();
+        let len = self.len;
+        unsafe { remove_where(ptr, &mut self.len, len, |i| mask.get(i) == Some(true), drop) };
+        self.shrink_by_policy();
+    }
+
+    /// Moves the elements for which `pred` returns `true` into a new vector, in their order, and keeps the others
+    /// in place. Each element is visited and moved at most once.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(1..=6);
+    /// let even = vec.drain_partition(|x| x % 2 == 0);
+    /// assert_eq!(&*even, &[2, 4, 6]);
+    /// assert_eq!(&*vec, &[1, 3, 5]);
+    /// ```
+    pub fn drain_partition<F: FnMut(&T) -> bool>(&mut self, mut pred: F) -> Vec<T> {
+        let mut matching = Vec::new();
+        let ptr = self.ptr();
+        let len = self.len;
+
         unsafe {
-            self.len -= 1;
-            ptr::copy(
-                self.ptr().offset(index as isize + 1),
-                self.ptr().offset(index as isize),
-                self.len - index,
-            );
-            ptr::read(self.ptr().offset(index as isize))
+            remove_where(
+                ptr,
+                &mut self.len,
+                len,
+                |i| pred(&*ptr.add(i)),
+                |elem| matching.push(elem),
+            )
+        };
+        self.shrink_by_policy();
+
+        matching
+    }
+
+    /// Removes every element equal to an earlier one, keeping the first occurrences in their order. Unlike a dedup
+    /// of consecutive duplicates, the vector doesn't need to be sorted: the elements seen so far are tracked in a
+    /// hash set.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([3, 1, 3, 2, 1, 3]);
+    /// vec.unique();
+    /// assert_eq!(&*vec, &[3, 1, 2]);
+    /// ```
+    pub fn unique(&mut self)
+    where
+        T: Hash + Eq,
+    {
+        // The set borrows the elements, so the duplicates are only marked here and removed once it is dropped
+        let mut duplicates = BitVec::new();
+        {
+            let mut seen = HashSet::with_capacity(self.len);
+            duplicates.extend(self.iter().map(|elem| !seen.insert(elem)));
+        }
+
+        self.remove_mask(&duplicates)
+    }
+
+    /// Removes every element whose key is equal to the key of an earlier one, keeping the first occurrences in
+    /// their order. The key is extracted once per element. See [`Vec::unique`].
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(["apple", "avocado", "banana", "blueberry", "cherry"]);
+    /// vec.unique_by_key(|s| s.as_bytes()[0]);
+    /// assert_eq!(&*vec, &["apple", "banana", "cherry"]);
+    /// ```
+    pub fn unique_by_key<K: Hash + Eq, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        let mut seen = HashSet::new();
+        let ptr = self.ptr();
+        let len = self.len;
+
+        unsafe {
+            remove_where(
+                ptr,
+                &mut self.len,
+                len,
+                |i| !seen.insert(f(&*ptr.add(i))),
+                drop,
+            )
+        };
+        self.shrink_by_policy();
+    }
+
+    /// Returns clones of the elements at the given indices, in the order of the indices, which may repeat.
+    /// # Panics
+    /// This function will panic if an index is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(["a", "b", "c"]);
+    /// assert_eq!(&*vec.select(&[2, 0, 2]), &["c", "a", "c"]);
+    /// ```
+    pub fn select(&self, indices: &[usize]) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut selected = Vec::with_capacity(indices.len());
+        selected.extend(indices.iter().map(|&i| self[i].clone()));
+        selected
+    }
+
+    /// Consumes the vector, moving the elements at the given indices out in the order of the indices and dropping
+    /// the others. Applying a permutation this way reorders the vector without cloning.
+    /// # Panics
+    /// This function will panic if an index is out of bounds or appears twice, before any element is moved.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([String::from("a"), String::from("b"), String::from("c")]);
+    /// assert_eq!(&*vec.take_indices(&[2, 0]), &["c", "a"]);
+    /// ```
+    pub fn take_indices(mut self, indices: &[usize]) -> Vec<T> {
+        let mut taken = BitVec::repeat(false, self.len);
+        for &i in indices {
+            assert!(i < self.len, "index out of bounds");
+            assert!(taken.get(i) == Some(false), "index {} taken twice", i);
+            taken.set(i, true);
+        }
+
+        // Forget the elements first: each one is either moved out or dropped below, exactly once
+        let len = mem::replace(&mut self.len, 0);
+        let ptr = self.ptr();
+
+        let mut selected = Vec::with_capacity(indices.len());
+        selected.extend(indices.iter().map(|&i| unsafe { ptr::read(ptr.add(i)) }));
+
+        for i in (0..len).filter(|&i| taken.get(i) == Some(false)) {
+            unsafe { ptr::drop_in_place(ptr.add(i)) };
         }
+
+        selected
+    }
+
+    // Validates sorted `indices` up front, so the vector is untouched if they are wrong, then removes them
+    fn remove_sorted(&mut self, indices: &[usize], removed: impl FnMut(T)) {
+        assert!(
+            indices.windows(2).all(|w| w[0] < w[1]),
+            "indices are not strictly increasing"
+        );
+
+        let end = match indices.last() {
+            Some(&last) => {
+                assert!(last < self.len, "index out of bounds");
+                last + 1
+            }
+            None => return,
+        };
+
+        // Indices are visited in order, so each lookup only has to check the next one
+        let mut next = indices.iter().copied().peekable();
+        let ptr = self.ptr();
+        unsafe {
+            remove_where(
+                ptr,
+                &mut self.len,
+                end,
+                |i| next.next_if_eq(&i).is_some(),
+                removed,
+            )
+        };
+        self.shrink_by_policy();
     }
 
     /// Consumes Self into an iterator.
@@ -176,9 +1260,9 @@ impl<T> Vec<T> {
     /// assert_eq!(Some(3), iter.next());
     /// assert_eq!(None, iter.next());
     /// ```
-    pub fn into_iter(self) -> IntoIter<T> {
+    pub fn into_iter(self) -> IntoIter<T, A, G> {
         unsafe {
-            let iter = RawValIter::new(&self);
+            let iter = RawValIter::new(ptr::slice_from_raw_parts_mut(self.ptr(), self.len));
 
             let buf = ptr::read(&self.buf);
             mem::forget(self);
@@ -192,42 +1276,193 @@ impl<T> Vec<T> {
     /// ```
     /// use vec::custom_vec;
     /// let mut vec = custom_vec![1, 2, 3];
-    /// let mut iter = vec.drain(..);
+    /// let mut iter = vec.drain();
     /// assert_eq!(Some(1), iter.next());
     /// assert_eq!(Some(2), iter.next());
     /// assert_eq!(Some(3), iter.next());
     /// assert_eq!(None, iter.next());
     /// ```
-    pub fn drain(&mut self) -> Drain<T> {
+    pub fn drain(&mut self) -> Drain<'_, T, A, G> {
         unsafe {
-            let iter = RawValIter::new(&self);
+            let iter = RawValIter::new(ptr::slice_from_raw_parts_mut(self.ptr(), self.len));
 
             self.len = 0;
 
             Drain {
+                vec: NonNull::from(self),
                 iter,
-                vec: PhantomData,
+                marker: PhantomData,
+            }
+        }
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if `len` is greater than the current
+    /// length. The capacity is kept, unless the growth policy shrinks it (see [`GrowthPolicy::shrink_capacity`]).
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..5);
+    /// vec.truncate(2);
+    /// assert_eq!(&*vec, &[0, 1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        let ptr = self.ptr();
+
+        unsafe { truncate_at(ptr, &mut self.len, len) }
+        self.shrink_by_policy();
+    }
+
+    /// Removes every element, keeping the allocated capacity unless the growth policy shrinks it.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..5);
+    /// vec.clear();
+    /// assert_eq!(vec.len(), 0);
+    /// assert!(vec.capacity() >= 5);
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Shrinks the capacity of the vector as much as possible, releasing the spare memory to its allocator.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(1);
+    /// vec.push(2);
+    /// vec.push(3);
+    /// vec.shrink_to_fit();
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        if self.cap() > self.len {
+            self.buf.shrink_to(self.len);
+        }
+    }
+
+    // Gives the spare capacity back if the growth policy asks for it, after elements were removed
+    fn shrink_by_policy(&mut self) {
+        // Zero-sized types never allocate
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
+
+        if let Some(cap) = self
+            .buf
+            .growth_policy()
+            .shrink_capacity(self.cap(), self.len)
+        {
+            let cap = cmp::max(cap, self.len);
+
+            if cap < self.cap() {
+                self.buf.shrink_to(cap);
             }
         }
     }
 }
 
+impl<T, A: Allocator, G: GrowthPolicy> Vec<MaybeUninit<T>, A, G> {
+    /// Converts a vector of `MaybeUninit<T>` into a vector of `T`, reusing the allocation.
+    /// # Safety
+    /// Every element up to the length of the vector must be initialized.
+    /// # Example
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use vec::Vec;
+    ///
+    /// let mut buf = Vec::new();
+    /// for i in 0..3 {
+    ///     buf.push(MaybeUninit::new(i));
+    /// }
+    ///
+    /// let vec: Vec<i32> = unsafe { buf.assume_init() };
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    pub unsafe fn assume_init(self) -> Vec<T, A, G> {
+        let len = self.len;
+        Vec::from_uninit_parts(self, len)
+    }
+}
+
 // Deref coertion (so our vector can be 'sliced')
-impl<T> Deref for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Deref for Vec<T, A, G> {
     type Target = [T];
     fn deref(&self) -> &[T] {
         unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
     }
 }
 
-impl<T> DerefMut for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for Vec<T, A, G> {
     fn deref_mut(&mut self) -> &mut [T] {
         unsafe { std::slice::from_raw_parts_mut(self.ptr(), self.len) }
     }
 }
 
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for Vec<T, A, G> {
+    /// Appends every element of an iterator.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(0);
+    /// vec.extend(1..4);
+    /// assert_eq!(&*vec, &[0, 1, 2, 3]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if let Err(err) = self.try_extend(iter) {
+            handle_reserve_error(err)
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator, G: GrowthPolicy> Extend<&'a T> for Vec<T, A, G> {
+    /// Appends a copy of every element of an iterator, with a single copy for slice iterators.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::Vec<i32> = vec::Vec::new();
+    /// vec.extend(&[1, 2]);
+    /// vec.extend([3, 4].iter());
+    /// assert_eq!(&*vec, &[1, 2, 3, 4]);
+    /// ```
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        if let Err(err) = self.spec_extend(iter.into_iter()) {
+            handle_reserve_error(err)
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Vec<T> {
+    /// Collects an iterator into a vector, allocating once for iterators of known length.
+    /// # Example
+    /// ```
+    /// let vec: vec::Vec<_> = (0..4).map(|x| x * 2).collect();
+    /// assert_eq!(&*vec, &[0, 2, 4, 6]);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Vec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for Vec<T, A, G> {
+    /// Clones every element into a new vector using a clone of the allocator and growth policy.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(String::from("a"));
+    /// vec.push(String::from("b"));
+    /// let copy = vec.clone();
+    /// assert_eq!(copy, vec);
+    /// ```
+    fn clone(&self) -> Self {
+        self.try_clone()
+            .unwrap_or_else(|err| handle_reserve_error(err))
+    }
+}
+
 // Iterators
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Iterator for IntoIter<T, A, G> {
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
@@ -239,42 +1474,35 @@ impl<T> Iterator for IntoIter<T> {
     }
 }
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for IntoIter<T, A, G> {
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back()
     }
 }
 
 // Deallocation (Drop trait -> https://doc.rust-lang.org/1.9.0/book/drop.html)
-impl<T> Drop for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Vec<T, A, G> {
     fn drop(&mut self) {
-        if self.cap() != 0 {
-            while let Some(_) = self.pop() {}
-            // Deallocation is handled by RawVec
+        // Trivially droppable elements don't need any work
+        if mem::needs_drop::<T>() {
+            unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr(), self.len)) }
         }
+        // Deallocation is handled by RawVec
     }
 }
 
-impl<T> Drop for IntoIter<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for IntoIter<T, A, G> {
     fn drop(&mut self) {
-        // Ensure all elements are read
-        for _ in &mut *self {}
+        // Ensure all elements are dropped, even if one of them panics
+        self.iter.drop_remaining();
     }
 }
 
-impl<T: PartialEq> PartialEq for Vec<T> {
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> PartialEq for Vec<T, A, G> {
     fn eq(&self, other: &Self) -> bool {
-        if self.len() != other.len() {
-            return false;
-        }
-        for (i, el) in self.iter().enumerate() {
-            if *other.get(i).unwrap() == *el {
-                continue;
-            } else {
-                return false;
-            }
-        }
-        return true;
+        // Slices of integers, `bool` and `char` are compared with `memcmp`
+        self.len() == other.len() && self[..] == other[..]
     }
 }
-impl<T: PartialEq> Eq for Vec<T> {}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> Eq for Vec<T, A, G> {}
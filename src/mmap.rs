@@ -0,0 +1,336 @@
+use std::{
+    alloc::{AllocError, Allocator, Layout},
+    fs::File,
+    os::unix::io::AsRawFd,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Allocator backing every allocation with its own memory mapping, resized in place with `mremap`.
+///
+/// Growing a mapping never copies the elements, the kernel just remaps the pages, which makes it a good fit
+/// for append-heavy buffers of several gigabytes. Sizes are rounded up to the page size.
+/// # Example
+/// ```
+/// #![feature(allocator_api)]
+/// use vec::{Mmap, Vec};
+///
+/// let mut vec = Vec::new_in(Mmap::anonymous());
+/// for i in 0..100_000u64 {
+///     vec.push(i);
+/// }
+/// assert_eq!(vec[99_999], 99_999);
+/// ```
+#[derive(Debug, Default)]
+pub struct Mmap {
+    // file mirrored by the mapping, anonymous memory when `None`
+    file: Option<File>,
+    // whether a live allocation maps the file, every mapping of it starts at offset 0
+    file_in_use: AtomicBool,
+    // ask for transparent huge pages on every mapping
+    huge_pages: bool,
+    // tell the kernel the mapping will be accessed sequentially
+    sequential: bool,
+}
+
+/// Builder for an [`Mmap`] allocator with extra hints for the kernel.
+/// # Example
+/// ```
+/// #![feature(allocator_api)]
+/// use vec::{Mmap, Vec};
+///
+/// let alloc = Mmap::builder().huge_pages(true).sequential(true).anonymous();
+/// let mut vec = Vec::new_in(alloc);
+/// for i in 0..1_000_000u32 {
+///     vec.push(i);
+/// }
+/// assert_eq!(vec.len(), 1_000_000);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MmapBuilder {
+    huge_pages: bool,
+    sequential: bool,
+}
+
+impl MmapBuilder {
+    /// Requests transparent huge pages (`MADV_HUGEPAGE`) for every mapping, reducing TLB misses on big buffers.
+    /// # Example
+    /// ```
+    /// use vec::Mmap;
+    /// let _alloc = Mmap::builder().huge_pages(true).anonymous();
+    /// ```
+    pub fn huge_pages(mut self, enabled: bool) -> Self {
+        self.huge_pages = enabled;
+        self
+    }
+
+    /// Hints that the buffer will be read sequentially (`MADV_SEQUENTIAL`), enabling aggressive read-ahead.
+    /// # Example
+    /// ```
+    /// use vec::Mmap;
+    /// let _alloc = Mmap::builder().sequential(true).anonymous();
+    /// ```
+    pub fn sequential(mut self, enabled: bool) -> Self {
+        self.sequential = enabled;
+        self
+    }
+
+    /// Builds an allocator handing out anonymous, private mappings.
+    /// # Example
+    /// ```
+    /// use vec::Mmap;
+    /// let _alloc = Mmap::builder().anonymous();
+    /// ```
+    pub fn anonymous(self) -> Mmap {
+        self.build(None)
+    }
+
+    /// Builds an allocator mapping `file`, see [`Mmap::file`].
+    /// # Example
+    /// ```
+    /// use std::fs::OpenOptions;
+    /// use vec::Mmap;
+    ///
+    /// let path = std::env::temp_dir().join("vec-mmap-builder-doctest");
+    /// let file = OpenOptions::new().read(true).write(true).create(true).open(&path).unwrap();
+    /// let _alloc = Mmap::builder().sequential(true).file(file);
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn file(self, file: File) -> Mmap {
+        self.build(Some(file))
+    }
+
+    fn build(self, file: Option<File>) -> Mmap {
+        Mmap {
+            file,
+            file_in_use: AtomicBool::new(false),
+            huge_pages: self.huge_pages,
+            sequential: self.sequential,
+        }
+    }
+}
+
+impl Mmap {
+    /// Creates an allocator handing out anonymous, private mappings.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Mmap, Vec};
+    /// let mut vec = Vec::new_in(Mmap::anonymous());
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn anonymous() -> Self {
+        Self::builder().anonymous()
+    }
+
+    /// Creates an allocator mapping `file`, so the buffer's contents are written through to it.
+    ///
+    /// The file is resized to the size of the allocation, hence it can only back one buffer at a time: allocating
+    /// again while a buffer is live fails, which makes the methods of [`Vec`](crate::Vec) needing a second buffer
+    /// from the same allocator (like the scratch space of [`Vec::sort`](crate::Vec::sort)) fail too. Debug builds
+    /// poison vacated memory, so the file doesn't keep the elements popped or truncated away, nor the contents of a
+    /// dropped vector.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::fs::OpenOptions;
+    /// use vec::{Mmap, Vec};
+    ///
+    /// let path = std::env::temp_dir().join("vec-mmap-doctest");
+    /// let file = OpenOptions::new().read(true).write(true).create(true).open(&path).unwrap();
+    ///
+    /// let mut vec = Vec::new_in(Mmap::file(file));
+    /// vec.push(42u8);
+    /// assert_eq!(vec[0], 42);
+    /// # drop(vec);
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn file(file: File) -> Self {
+        Self::builder().file(file)
+    }
+
+    /// Returns a builder to opt into huge pages or access pattern hints.
+    /// # Example
+    /// ```
+    /// use vec::Mmap;
+    /// let _alloc = Mmap::builder().huge_pages(true).anonymous();
+    /// ```
+    pub fn builder() -> MmapBuilder {
+        MmapBuilder::default()
+    }
+
+    // Applies the configured hints to a mapping, failures are ignored as they are only hints
+    fn advise(&self, ptr: NonNull<u8>, size: usize) {
+        unsafe {
+            if self.huge_pages {
+                libc::madvise(ptr.as_ptr() as *mut _, size, libc::MADV_HUGEPAGE);
+            }
+
+            if self.sequential {
+                libc::madvise(ptr.as_ptr() as *mut _, size, libc::MADV_SEQUENTIAL);
+            }
+        }
+    }
+
+    // Rounds a size up to a multiple of the page size
+    fn page_align(size: usize) -> Result<usize, AllocError> {
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        size.checked_add(page - 1)
+            .map(|s| s & !(page - 1))
+            .ok_or(AllocError)
+    }
+
+    // Sets the length of the backing file, if any
+    fn set_len(&self, size: usize) -> Result<(), AllocError> {
+        match &self.file {
+            Some(file) => file.set_len(size as u64).map_err(|_| AllocError),
+            None => Ok(()),
+        }
+    }
+
+    fn map(&self, size: usize) -> Result<NonNull<u8>, AllocError> {
+        // Two mappings of the file would overlap, and resizing one would cut the other short
+        if self.file.is_some() && self.file_in_use.swap(true, Ordering::Acquire) {
+            return Err(AllocError);
+        }
+
+        let ptr = self.map_unique(size);
+        if ptr.is_err() {
+            self.release_file();
+        }
+        ptr
+    }
+
+    // Maps `size` bytes, the file being free for this mapping
+    fn map_unique(&self, size: usize) -> Result<NonNull<u8>, AllocError> {
+        self.set_len(size)?;
+
+        let (flags, fd) = match &self.file {
+            Some(file) => (libc::MAP_SHARED, file.as_raw_fd()),
+            None => (libc::MAP_PRIVATE | libc::MAP_ANONYMOUS, -1),
+        };
+
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                flags,
+                fd,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(AllocError);
+        }
+
+        let ptr = NonNull::new(ptr as *mut u8).ok_or(AllocError)?;
+        self.advise(ptr, size);
+
+        Ok(ptr)
+    }
+
+    // Lets the file back a new allocation once the mapping of the previous one is gone
+    fn release_file(&self) {
+        if self.file.is_some() {
+            self.file_in_use.store(false, Ordering::Release);
+        }
+    }
+
+    unsafe fn remap(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let old_size = Self::page_align(old_layout.size())?;
+        let new_size = Self::page_align(new_layout.size())?;
+
+        if old_size == 0 {
+            return self.allocate(new_layout);
+        }
+
+        if new_size == 0 {
+            self.deallocate(ptr, old_layout);
+            return self.allocate(new_layout);
+        }
+
+        // The file must be big enough before the mapping grows over it
+        if new_size > old_size {
+            self.set_len(new_size)?;
+        }
+
+        let new = libc::mremap(
+            ptr.as_ptr() as *mut _,
+            old_size,
+            new_size,
+            libc::MREMAP_MAYMOVE,
+        );
+
+        if new == libc::MAP_FAILED {
+            // The old mapping is still there, give back the room the file was grown by for it
+            if new_size > old_size {
+                let _ = self.set_len(old_size);
+            }
+            return Err(AllocError);
+        }
+
+        // The old block is gone, so this can't fail anymore. A file longer than its mapping only wastes disk space
+        if new_size < old_size {
+            let _ = self.set_len(new_size);
+        }
+
+        let new = NonNull::new(new as *mut u8).ok_or(AllocError)?;
+        self.advise(new, new_size);
+
+        Ok(NonNull::slice_from_raw_parts(new, new_size))
+    }
+}
+
+unsafe impl Allocator for Mmap {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let size = Self::page_align(layout.size())?;
+
+        // Mappings are page aligned, bigger alignments can't be honored
+        if layout.align() > Self::page_align(1)? {
+            return Err(AllocError);
+        }
+
+        if size == 0 {
+            let dangling =
+                NonNull::new(ptr::without_provenance_mut(layout.align())).ok_or(AllocError)?;
+            return Ok(NonNull::slice_from_raw_parts(dangling, 0));
+        }
+
+        Ok(NonNull::slice_from_raw_parts(self.map(size)?, size))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if let Ok(size) = Self::page_align(layout.size()) {
+            if size != 0 {
+                libc::munmap(ptr.as_ptr() as *mut _, size);
+                self.release_file();
+            }
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.remap(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.remap(ptr, old_layout, new_layout)
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    convert::TryFrom,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{error::EmptyError, Vec};
+
+/// Vector that always holds at least one element, so [`first`](NonEmptyVec::first) and
+/// [`last`](NonEmptyVec::last) never fail and removing the final element is refused.
+/// # Example
+/// ```
+/// use std::convert::TryFrom;
+/// use vec::{NonEmptyVec, Vec};
+///
+/// let mut v = NonEmptyVec::new(1);
+/// v.push(2);
+/// assert_eq!((v.first(), v.last()), (&1, &2));
+///
+/// assert_eq!(v.pop(), Some(2));
+/// assert_eq!(v.pop(), None); // the last element stays
+///
+/// assert!(NonEmptyVec::try_from(Vec::<i32>::new()).is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct NonEmptyVec<T> {
+    // never empty
+    vec: Vec<T>,
+}
+
+impl<T> NonEmptyVec<T> {
+    /// Creates a vector holding `head`.
+    /// # Example
+    /// ```
+    /// let v = vec::NonEmptyVec::new(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn new(head: T) -> Self {
+        let mut vec = Vec::new();
+        vec.push(head);
+        Self { vec }
+    }
+
+    /// Creates a vector holding `head` followed by the elements of `tail`.
+    /// # Example
+    /// ```
+    /// let v = vec::NonEmptyVec::from_parts(1, [2, 3]);
+    /// assert_eq!(&*v, &[1, 2, 3]);
+    /// ```
+    pub fn from_parts<I: IntoIterator<Item = T>>(head: T, tail: I) -> Self {
+        let mut v = Self::new(head);
+        v.vec.extend(tail);
+        v
+    }
+
+    /// Returns the number of elements, which is never zero.
+    /// # Example
+    /// ```
+    /// let v = vec::NonEmptyVec::from_parts(1, [2]);
+    /// assert_eq!(v.len(), 2);
+    /// ```
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.vec.len
+    }
+
+    /// Returns the first element.
+    /// # Example
+    /// ```
+    /// let v = vec::NonEmptyVec::from_parts(1, [2]);
+    /// assert_eq!(v.first(), &1);
+    /// ```
+    pub fn first(&self) -> &T {
+        &self.vec[0]
+    }
+
+    /// Returns a mutable reference to the first element.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NonEmptyVec::new(1);
+    /// *v.first_mut() = 2;
+    /// assert_eq!(v.first(), &2);
+    /// ```
+    pub fn first_mut(&mut self) -> &mut T {
+        &mut self.vec[0]
+    }
+
+    /// Returns the last element.
+    /// # Example
+    /// ```
+    /// let v = vec::NonEmptyVec::from_parts(1, [2]);
+    /// assert_eq!(v.last(), &2);
+    /// ```
+    pub fn last(&self) -> &T {
+        &self.vec[self.vec.len - 1]
+    }
+
+    /// Returns a mutable reference to the last element.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NonEmptyVec::from_parts(1, [2]);
+    /// *v.last_mut() = 3;
+    /// assert_eq!(v.last(), &3);
+    /// ```
+    pub fn last_mut(&mut self) -> &mut T {
+        let last = self.vec.len - 1;
+        &mut self.vec[last]
+    }
+
+    /// Returns the first element and the rest.
+    /// # Example
+    /// ```
+    /// let v = vec::NonEmptyVec::from_parts(1, [2, 3]);
+    /// assert_eq!(v.split_first(), (&1, &[2, 3][..]));
+    /// ```
+    pub fn split_first(&self) -> (&T, &[T]) {
+        self.vec.split_first().unwrap()
+    }
+
+    /// Appends an element.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NonEmptyVec::new(1);
+    /// v.push(2);
+    /// assert_eq!(v.last(), &2);
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.vec.push(elem)
+    }
+
+    /// Removes the last element and returns it, or `None` if it's the only one left.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NonEmptyVec::from_parts(1, [2]);
+    /// assert_eq!(v.pop(), Some(2));
+    /// assert_eq!(v.pop(), None);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.vec.len == 1 {
+            return None;
+        }
+
+        self.vec.pop()
+    }
+
+    /// Inserts an element at `index`, moving the ones after it.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NonEmptyVec::from_parts(1, [3]);
+    /// v.insert(1, 2);
+    /// assert_eq!(&*v, &[1, 2, 3]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        self.vec.insert(index, elem)
+    }
+
+    /// Removes the element at `index` and returns it, or `None` if it's the only one left.
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NonEmptyVec::from_parts(1, [2]);
+    /// assert_eq!(v.remove(0), Some(1));
+    /// assert_eq!(v.remove(0), None);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        assert!(index < self.vec.len, "index out of bounds");
+
+        if self.vec.len == 1 {
+            return None;
+        }
+
+        Some(self.vec.remove(index))
+    }
+
+    /// Shortens the vector to `len` elements, doing nothing if it's not longer.
+    /// # Panics
+    /// Panics if `len` is zero.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NonEmptyVec::from_parts(1, [2, 3]);
+    /// v.truncate(1);
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        assert!(
+            len > 0,
+            "can't truncate a non-empty vector to zero elements"
+        );
+        self.vec.truncate(len)
+    }
+
+    /// Returns the underlying vector.
+    /// # Example
+    /// ```
+    /// let v = vec::NonEmptyVec::new(1);
+    /// assert_eq!(&*v.into_vec(), &[1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+}
+
+impl<T> Deref for NonEmptyVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T> DerefMut for NonEmptyVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.vec
+    }
+}
+
+impl<T> Extend<T> for NonEmptyVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.vec.extend(iter)
+    }
+}
+
+impl<T> TryFrom<Vec<T>> for NonEmptyVec<T> {
+    type Error = EmptyError;
+
+    fn try_from(vec: Vec<T>) -> Result<Self, EmptyError> {
+        if vec.len == 0 {
+            Err(EmptyError)
+        } else {
+            Ok(Self { vec })
+        }
+    }
+}
+
+impl<T> From<NonEmptyVec<T>> for Vec<T> {
+    fn from(v: NonEmptyVec<T>) -> Self {
+        v.vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for NonEmptyVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,336 @@
+use std::{fmt, iter::FromIterator, mem};
+
+use crate::{BitVec, Vec};
+
+/// Vector of optional values stored Arrow-style: a dense buffer with a slot for every element, plus a packed
+/// validity bitmap telling which slots hold a value.
+///
+/// Unlike `Vec<Option<T>>` the values stay contiguous and unpadded, so kernels can run over
+/// [`values`](NullableVec::values) directly and use the bitmap to skip nulls. Null slots hold `T::default()`.
+/// # Example
+/// ```
+/// use vec::NullableVec;
+///
+/// let mut v = NullableVec::new();
+/// v.push(Some(1.5));
+/// v.push(None);
+/// v.push(Some(2.5));
+///
+/// assert_eq!(v.get(0), Some(&1.5));
+/// assert_eq!(v.get(1), None);
+/// assert_eq!(v.null_count(), 1);
+/// assert_eq!(v.values(), &[1.5, 0.0, 2.5]);
+/// assert_eq!(v.iter().flatten().sum::<f64>(), 4.0);
+/// ```
+#[derive(Clone)]
+pub struct NullableVec<T> {
+    values: Vec<T>,
+    // bit `i` is set if slot `i` holds a value
+    validity: BitVec,
+    null_count: usize,
+}
+
+impl<T> NullableVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::NullableVec<i32> = vec::NullableVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            validity: BitVec::new(),
+            null_count: 0,
+        }
+    }
+
+    /// Returns the number of elements, nulls included.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// v.push(None);
+    /// assert_eq!(v.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::NullableVec<i32> = vec::NullableVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.values.len == 0
+    }
+
+    /// Returns the number of nulls.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// v.push(None);
+    /// assert_eq!(v.null_count(), 1);
+    /// ```
+    pub fn null_count(&self) -> usize {
+        self.null_count
+    }
+
+    /// Appends an element, or a null for `None`.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some('a'));
+    /// v.push(None);
+    /// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [Some(&'a'), None]);
+    /// ```
+    pub fn push(&mut self, elem: Option<T>)
+    where
+        T: Default,
+    {
+        match elem {
+            Some(value) => self.push_value(value),
+            None => self.push_null(),
+        }
+    }
+
+    /// Appends a value.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push_value(1);
+    /// assert_eq!(v.get(0), Some(&1));
+    /// ```
+    pub fn push_value(&mut self, value: T) {
+        self.values.push(value);
+        self.validity.push(true);
+    }
+
+    /// Appends a null.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::NullableVec<i32> = vec::NullableVec::new();
+    /// v.push_null();
+    /// assert!(v.is_null(0));
+    /// ```
+    pub fn push_null(&mut self)
+    where
+        T: Default,
+    {
+        self.values.push(T::default());
+        self.validity.push(false);
+        self.null_count += 1;
+    }
+
+    /// Removes the last element and returns it, `Some(None)` for a null, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// v.push(None);
+    /// assert_eq!(v.pop(), Some(None));
+    /// assert_eq!(v.pop(), Some(Some(1)));
+    /// assert_eq!(v.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<Option<T>> {
+        let value = self.values.pop()?;
+
+        if self.validity.pop().unwrap() {
+            Some(Some(value))
+        } else {
+            self.null_count -= 1;
+            Some(None)
+        }
+    }
+
+    /// Returns a reference to the value at `index`, or `None` if it's null or out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// v.push(None);
+    /// assert_eq!(v.get(0), Some(&1));
+    /// assert_eq!(v.get(1), None);
+    /// assert_eq!(v.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if self.validity.get(index)? {
+            Some(&self.values[index])
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the value at `index`, or `None` if it's null or out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// *v.get_mut(0).unwrap() += 1;
+    /// assert_eq!(v.get(0), Some(&2));
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if self.validity.get(index)? {
+            Some(&mut self.values[index])
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the element at `index` is null.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// v.push(None);
+    /// assert!(!v.is_null(0));
+    /// assert!(v.is_null(1));
+    /// ```
+    pub fn is_null(&self, index: usize) -> bool {
+        !self.validity.get(index).expect("index out of bounds")
+    }
+
+    /// Replaces the element at `index`, setting it to null for `None`, and returns the old one.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// assert_eq!(v.set(0, None), Some(1));
+    /// assert_eq!(v.set(0, Some(2)), None);
+    /// assert_eq!(v.get(0), Some(&2));
+    /// ```
+    pub fn set(&mut self, index: usize, elem: Option<T>) -> Option<T>
+    where
+        T: Default,
+    {
+        let was_valid = !self.is_null(index);
+        let is_valid = elem.is_some();
+
+        let old = mem::replace(&mut self.values[index], elem.unwrap_or_default());
+        self.validity.set(index, is_valid);
+        self.null_count = self.null_count + was_valid as usize - is_valid as usize;
+
+        if was_valid {
+            Some(old)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value buffer, with `T::default()` in the null slots.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(3));
+    /// v.push(None);
+    /// assert_eq!(v.values(), &[3, 0]);
+    /// ```
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the validity bitmap, with bit `i` set if element `i` isn't null.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(3));
+    /// v.push(None);
+    /// assert_eq!(v.validity().iter().collect::<std::vec::Vec<_>>(), [true, false]);
+    /// ```
+    pub fn validity(&self) -> &BitVec {
+        &self.validity
+    }
+
+    /// Returns an iterator over the elements, `None` for the nulls.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// v.push(None);
+    /// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [Some(&1), None]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Option<&T>> + '_ {
+        self.values
+            .iter()
+            .zip(self.validity.iter())
+            .map(|(value, valid)| if valid { Some(value) } else { None })
+    }
+
+    /// Returns an iterator over the values that aren't null, with their indices.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(None);
+    /// v.push(Some('a'));
+    /// assert_eq!(v.iter_valid().collect::<std::vec::Vec<_>>(), [(1, &'a')]);
+    /// ```
+    pub fn iter_valid(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        self.validity
+            .iter_ones()
+            .map(move |index| (index, &self.values[index]))
+    }
+
+    /// Removes every element, keeping the value buffer's allocation.
+    /// # Example
+    /// ```
+    /// let mut v = vec::NullableVec::new();
+    /// v.push(Some(1));
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.validity.clear();
+        self.null_count = 0;
+    }
+}
+
+impl<T> Default for NullableVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Default> Extend<Option<T>> for NullableVec<T> {
+    fn extend<I: IntoIterator<Item = Option<T>>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Default> FromIterator<Option<T>> for NullableVec<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+// Compares the elements only, null slots can hold anything
+impl<T: PartialEq> PartialEq for NullableVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for NullableVec<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for NullableVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
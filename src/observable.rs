@@ -0,0 +1,360 @@
+use std::{fmt, mem, ops::Deref, sync::mpsc};
+
+use crate::Vec;
+
+/// Change made to an [`ObservableVec`], passed to its subscribers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// An element was inserted at `index`, moving the ones after it.
+    Inserted {
+        /// Index of the new element.
+        index: usize,
+    },
+    /// The element at `index` was removed, moving the ones after it.
+    Removed {
+        /// Index the element had.
+        index: usize,
+    },
+    /// The element at `index` was replaced or modified in place.
+    Replaced {
+        /// Index of the element.
+        index: usize,
+    },
+    /// Every element was removed.
+    Cleared,
+}
+
+/// Handle identifying a subscription to an [`ObservableVec`], to cancel it with
+/// [`unsubscribe`](ObservableVec::unsubscribe).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subscription(usize);
+
+// Subscriber callback, returning `false` once it doesn't want more events
+type Observer<T> = Box<dyn FnMut(Event, &[T]) -> bool>;
+
+/// Vector notifying subscribers of every change made to it, e.g. so a GUI list can update the rows that changed.
+///
+/// Each mutating method emits [`Event`]s after the change, in order, to callbacks registered with
+/// [`subscribe`](ObservableVec::subscribe) or to channels created by [`channel`](ObservableVec::channel).
+/// Elements are only given out as shared references, so every change goes through the vector.
+/// # Example
+/// ```
+/// use std::{cell::RefCell, rc::Rc};
+/// use vec::{ObservableEvent, ObservableVec};
+///
+/// let log = Rc::new(RefCell::new(std::vec::Vec::new()));
+/// let mut v = ObservableVec::new();
+///
+/// let sink = log.clone();
+/// v.subscribe(move |event, _| sink.borrow_mut().push(event));
+///
+/// v.push("a");
+/// v.insert(0, "b");
+/// v.set(1, "c");
+/// v.remove(0);
+///
+/// assert_eq!(
+///     *log.borrow(),
+///     [
+///         ObservableEvent::Inserted { index: 0 },
+///         ObservableEvent::Inserted { index: 0 },
+///         ObservableEvent::Replaced { index: 1 },
+///         ObservableEvent::Removed { index: 0 },
+///     ]
+/// );
+/// ```
+pub struct ObservableVec<T> {
+    vec: Vec<T>,
+    observers: Vec<(Subscription, Observer<T>)>,
+    next_id: usize,
+}
+
+impl<T> ObservableVec<T> {
+    /// Creates an empty vector with no subscribers, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::ObservableVec<i32> = vec::ObservableVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::from(Vec::new())
+    }
+
+    /// Registers a callback called with every event and the elements after the change, until it's
+    /// [unsubscribed](ObservableVec::unsubscribe).
+    /// # Example
+    /// ```
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// let total = Rc::new(Cell::new(0));
+    /// let mut v = vec::ObservableVec::new();
+    ///
+    /// let sum = total.clone();
+    /// v.subscribe(move |_, elems: &[i32]| sum.set(elems.iter().sum()));
+    ///
+    /// v.push(2);
+    /// v.push(3);
+    /// assert_eq!(total.get(), 5);
+    /// ```
+    pub fn subscribe<F: FnMut(Event, &[T]) + 'static>(&mut self, mut f: F) -> Subscription {
+        self.add_observer(Box::new(move |event, elems| {
+            f(event, elems);
+            true
+        }))
+    }
+
+    /// Returns a channel receiving every event. The subscription ends when the receiver is dropped.
+    /// # Example
+    /// ```
+    /// use vec::ObservableEvent;
+    ///
+    /// let mut v = vec::ObservableVec::new();
+    /// let events = v.channel();
+    ///
+    /// v.push(1);
+    /// v.clear();
+    /// assert_eq!(events.try_iter().collect::<std::vec::Vec<_>>(), [
+    ///     ObservableEvent::Inserted { index: 0 },
+    ///     ObservableEvent::Cleared,
+    /// ]);
+    /// ```
+    pub fn channel(&mut self) -> mpsc::Receiver<Event> {
+        let (sender, receiver) = mpsc::channel();
+        self.add_observer(Box::new(move |event, _| sender.send(event).is_ok()));
+        receiver
+    }
+
+    /// Removes a subscription, returning `false` if it had already ended.
+    /// # Example
+    /// ```
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// let count = Rc::new(Cell::new(0));
+    /// let mut v = vec::ObservableVec::new();
+    ///
+    /// let counter = count.clone();
+    /// let sub = v.subscribe(move |_, _| counter.set(counter.get() + 1));
+    /// v.push(1);
+    /// assert!(v.unsubscribe(sub));
+    /// v.push(2);
+    /// assert_eq!(count.get(), 1);
+    /// ```
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        match self
+            .observers
+            .iter()
+            .position(|(id, _)| *id == subscription)
+        {
+            Some(index) => {
+                drop(self.observers.remove(index));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Appends an element, emitting [`Event::Inserted`].
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.insert(self.vec.len, elem)
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty. Emits [`Event::Removed`] if an
+    /// element was removed.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.pop(), Some(1));
+    /// assert_eq!(v.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.vec.len.checked_sub(1)?;
+        Some(self.remove(last))
+    }
+
+    /// Inserts an element at `index`, moving the ones after it. Emits [`Event::Inserted`].
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(2);
+    /// v.insert(0, 1);
+    /// assert_eq!(&*v, &[1, 2]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        self.vec.insert(index, elem);
+        self.emit(Event::Inserted { index });
+    }
+
+    /// Removes and returns the element at `index`, moving the ones after it. Emits [`Event::Removed`].
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// assert_eq!(v.remove(0), 1);
+    /// assert_eq!(&*v, &[2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        let elem = self.vec.remove(index);
+        self.emit(Event::Removed { index });
+        elem
+    }
+
+    /// Replaces the element at `index` and returns the old one. Emits [`Event::Replaced`].
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.set(0, 2), 1);
+    /// assert_eq!(&*v, &[2]);
+    /// ```
+    pub fn set(&mut self, index: usize, elem: T) -> T {
+        let old = mem::replace(&mut self.vec[index], elem);
+        self.emit(Event::Replaced { index });
+        old
+    }
+
+    /// Calls `f` on the element at `index` and returns its result. Emits [`Event::Replaced`].
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// v.update(0, |x| *x += 1);
+    /// assert_eq!(&*v, &[2]);
+    /// ```
+    pub fn update<R, F: FnOnce(&mut T) -> R>(&mut self, index: usize, f: F) -> R {
+        let result = f(&mut self.vec[index]);
+        self.emit(Event::Replaced { index });
+        result
+    }
+
+    /// Swaps two elements. Emits [`Event::Replaced`] for both, unless they are the same.
+    /// # Panics
+    /// Panics if `a` or `b` are out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// v.swap(0, 1);
+    /// assert_eq!(&*v, &[2, 1]);
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.vec.swap(a, b);
+
+        if a != b {
+            self.emit(Event::Replaced { index: a });
+            self.emit(Event::Replaced { index: b });
+        }
+    }
+
+    /// Shortens the vector to `len` elements, doing nothing if it's not longer. Emits [`Event::Removed`] for every
+    /// element removed, last to first.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// v.truncate(1);
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        while self.vec.len > len {
+            self.pop();
+        }
+    }
+
+    /// Removes every element, emitting [`Event::Cleared`] if there were any.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        if self.vec.len > 0 {
+            self.vec.clear();
+            self.emit(Event::Cleared);
+        }
+    }
+
+    /// Returns the elements, dropping the subscribers.
+    /// # Example
+    /// ```
+    /// let mut v = vec::ObservableVec::new();
+    /// v.push(1);
+    /// assert_eq!(&*v.into_vec(), &[1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+
+    fn add_observer(&mut self, observer: Observer<T>) -> Subscription {
+        let id = Subscription(self.next_id);
+        self.next_id += 1;
+        self.observers.push((id, observer));
+        id
+    }
+
+    // Calls every observer, dropping the ones that are done
+    fn emit(&mut self, event: Event) {
+        let elems = &self.vec;
+        let mut i = 0;
+
+        while i < self.observers.len {
+            if (self.observers[i].1)(event, elems) {
+                i += 1;
+            } else {
+                drop(self.observers.remove(i));
+            }
+        }
+    }
+}
+
+impl<T> Default for ObservableVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<T>> for ObservableVec<T> {
+    /// Wraps a vector, with no subscribers.
+    fn from(vec: Vec<T>) -> Self {
+        Self {
+            vec,
+            observers: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<T> Deref for ObservableVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ObservableVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,55 @@
+use std::{alloc::Layout, sync::RwLock};
+
+/// Out-of-memory handler, see [`set_oom_handler`].
+pub type OomHandler = fn(Layout) -> OomAction;
+
+// Handler installed by `set_oom_handler`, consulted on every failed buffer allocation
+static HANDLER: RwLock<Option<OomHandler>> = RwLock::new(None);
+
+/// Decision of the out-of-memory handler installed with [`set_oom_handler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OomAction {
+    /// Try the allocation again, after the handler freed some memory (e.g. evicted a cache).
+    Retry,
+    /// Report the failure: the fallible methods return [`TryReserveError::AllocError`](crate::TryReserveError)
+    /// and the others abort through [`handle_alloc_error`](std::alloc::handle_alloc_error) (the default).
+    Fail,
+    /// Panic instead, so the failure unwinds and can be caught, even from the fallible methods.
+    Panic,
+}
+
+/// Installs a process-wide handler called with the layout of every allocation a vector fails to get, replacing
+/// the previous one. It can log the failure, free memory and retry, or turn the abort into a panic.
+///
+/// The handler runs inside the allocating call, it must not allocate from a vector nor install another handler.
+/// # Example
+/// ```
+/// use std::{alloc::Layout, panic};
+/// use vec::{set_oom_handler, OomAction, Vec};
+///
+/// fn unwind(layout: Layout) -> OomAction {
+///     eprintln!("out of memory allocating {} bytes", layout.size());
+///     OomAction::Panic
+/// }
+///
+/// set_oom_handler(Some(unwind));
+///
+/// // The allocation can't be served, but the panic can be caught
+/// let result = panic::catch_unwind(|| Vec::<u8>::with_capacity(isize::MAX as usize));
+/// assert!(result.is_err());
+///
+/// set_oom_handler(None);
+/// ```
+pub fn set_oom_handler(handler: Option<OomHandler>) {
+    *HANDLER.write().unwrap_or_else(|err| err.into_inner()) = handler;
+}
+
+// Asks the installed handler, if any, what to do about a failed allocation of `layout`
+pub(crate) fn handle(layout: Layout) -> OomAction {
+    let handler = *HANDLER.read().unwrap_or_else(|err| err.into_inner());
+
+    match handler {
+        Some(handler) => handler(layout),
+        None => OomAction::Fail,
+    }
+}
@@ -0,0 +1,605 @@
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{self, FromIterator},
+    mem,
+    ops::Index,
+};
+
+use crate::Vec;
+
+// Empty bucket of the hash index
+const EMPTY: usize = 0;
+
+#[derive(Clone)]
+struct Bucket<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+/// Hash map remembering the insertion order of its entries, like the `indexmap` crate.
+///
+/// Entries are stored densely in a [`Vec`], in insertion order, so they can be iterated quickly and accessed by
+/// position in O(1). An open addressing hash index of positions finds the entry of a key. Removing an entry either
+/// moves the last one into its place with [`swap_remove`](OrderedMap::swap_remove) in O(1), or shifts the following
+/// ones down with [`shift_remove`](OrderedMap::shift_remove) in O(n) to keep the order.
+/// # Example
+/// ```
+/// use vec::OrderedMap;
+///
+/// let mut map = OrderedMap::new();
+/// map.insert("b", 2);
+/// map.insert("a", 1);
+/// map.insert("c", 3);
+///
+/// assert_eq!(map["a"], 1);
+/// assert_eq!(map.get_index(0), Some((&"b", &2)));
+/// assert_eq!(map.keys().copied().collect::<String>(), "bac");
+///
+/// map.shift_remove("b");
+/// assert_eq!(map.keys().copied().collect::<String>(), "ac");
+/// map.swap_remove("a");
+/// assert_eq!(map.keys().copied().collect::<String>(), "c");
+/// ```
+#[derive(Clone)]
+pub struct OrderedMap<K, V, S = RandomState> {
+    entries: Vec<Bucket<K, V>>,
+    // linear probing table of entry positions plus one, `EMPTY` for an empty bucket, with a power of two length and
+    // at most half full
+    index: Vec<usize>,
+    hasher: S,
+}
+
+impl<K, V> OrderedMap<K, V> {
+    /// Creates an empty map, without allocating.
+    /// # Example
+    /// ```
+    /// let map: vec::OrderedMap<&str, i32> = vec::OrderedMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K, V, S> OrderedMap<K, V, S> {
+    /// Creates an empty map hashing keys with `hasher`, without allocating.
+    /// # Example
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut map = vec::OrderedMap::with_hasher(RandomState::new());
+    /// map.insert(1, 'a');
+    /// assert_eq!(map[&1], 'a');
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            entries: Vec::new(),
+            index: Vec::new(),
+            hasher,
+        }
+    }
+
+    /// Returns the number of entries.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len
+    }
+
+    /// Returns `true` if the map holds no entries.
+    /// # Example
+    /// ```
+    /// let map: vec::OrderedMap<i32, i32> = vec::OrderedMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.len == 0
+    }
+
+    /// Returns the entry at position `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('x', 1);
+    /// assert_eq!(map.get_index(0), Some((&'x', &1)));
+    /// assert_eq!(map.get_index(1), None);
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.entries.get(index).map(|b| (&b.key, &b.value))
+    }
+
+    /// Returns the entry at position `index` with its value mutably, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('x', 1);
+    /// *map.get_index_mut(0).unwrap().1 += 1;
+    /// assert_eq!(map[&'x'], 2);
+    /// ```
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.entries.get_mut(index).map(|b| (&b.key, &mut b.value))
+    }
+
+    /// Returns the first entry, or `None` if the map is empty.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert(2, 'b');
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.first(), Some((&2, &'b')));
+    /// ```
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    /// Returns the last entry, or `None` if the map is empty.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert(2, 'b');
+    /// map.insert(1, 'a');
+    /// assert_eq!(map.last(), Some((&1, &'a')));
+    /// ```
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.get_index(self.entries.len.checked_sub(1)?)
+    }
+
+    /// Returns an iterator over the entries, in order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('b', 2);
+    /// map.insert('a', 1);
+    /// assert_eq!(map.iter().collect::<std::vec::Vec<_>>(), [(&'b', &2), (&'a', &1)]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator + '_ {
+        self.entries.iter().map(|b| (&b.key, &b.value))
+    }
+
+    /// Returns an iterator over the entries with their values mutably, in order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 2);
+    /// map.iter_mut().for_each(|(_, v)| *v *= 10);
+    /// assert_eq!(map.values().copied().collect::<std::vec::Vec<_>>(), [10, 20]);
+    /// ```
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &mut V)> + ExactSizeIterator + '_ {
+        self.entries.iter_mut().map(|b| (&b.key, &mut b.value))
+    }
+
+    /// Returns an iterator over the keys, in order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('b', 2);
+    /// map.insert('a', 1);
+    /// assert_eq!(map.keys().collect::<String>(), "ba");
+    /// ```
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator + '_ {
+        self.entries.iter().map(|b| &b.key)
+    }
+
+    /// Returns an iterator over the values, in order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('b', 2);
+    /// map.insert('a', 1);
+    /// assert_eq!(map.values().copied().collect::<std::vec::Vec<_>>(), [2, 1]);
+    /// ```
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator + '_ {
+        self.entries.iter().map(|b| &b.value)
+    }
+
+    /// Returns an iterator over the values mutably, in order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('a', 1);
+    /// map.values_mut().for_each(|v| *v += 1);
+    /// assert_eq!(map[&'a'], 2);
+    /// ```
+    pub fn values_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut V> + ExactSizeIterator + '_ {
+        self.entries.iter_mut().map(|b| &mut b.value)
+    }
+
+    /// Removes every entry, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert('a', 1);
+    /// map.clear();
+    /// assert!(map.is_empty() && !map.contains_key(&'a'));
+    /// ```
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.index.fill(EMPTY);
+    }
+
+    // Returns the bucket of the index holding `pos`, which is in the map and hashes to `hash`
+    fn bucket_of(&self, hash: u64, pos: usize) -> usize {
+        let mask = self.index.len - 1;
+        let mut bucket = hash as usize & mask;
+
+        while self.index[bucket] != pos + 1 {
+            bucket = (bucket + 1) & mask;
+        }
+        bucket
+    }
+
+    // Empties a bucket, moving the following ones of its cluster back so every position stays reachable from the
+    // bucket of its hash
+    fn erase_bucket(&mut self, mut hole: usize) {
+        let mask = self.index.len - 1;
+        let mut bucket = hole;
+
+        loop {
+            bucket = (bucket + 1) & mask;
+            let slot = self.index[bucket];
+            if slot == EMPTY {
+                break;
+            }
+
+            let ideal = self.entries[slot - 1].hash as usize & mask;
+            // The entry can move back to the hole if the hole isn't before its ideal bucket
+            if (bucket.wrapping_sub(ideal) & mask) >= (bucket.wrapping_sub(hole) & mask) {
+                self.index[hole] = slot;
+                hole = bucket;
+            }
+        }
+
+        self.index[hole] = EMPTY;
+    }
+
+    // Rebuilds the index with room for `len` entries
+    fn rebuild_index(&mut self, len: usize) {
+        let buckets = (len * 2).next_power_of_two().max(8);
+        let mask = buckets - 1;
+
+        let mut index = Vec::with_capacity(buckets);
+        index.extend(iter::repeat_n(EMPTY, buckets));
+
+        for (pos, entry) in self.entries.iter().enumerate() {
+            let mut bucket = entry.hash as usize & mask;
+            while index[bucket] != EMPTY {
+                bucket = (bucket + 1) & mask;
+            }
+            index[bucket] = pos + 1;
+        }
+
+        self.index = index;
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> OrderedMap<K, V, S> {
+    /// Reserves room for at least `additional` more entries.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut map: vec::OrderedMap<i32, i32> = vec::OrderedMap::new();
+    /// map.reserve(10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+
+        let needed = self.entries.len + additional;
+        if needed * 2 > self.index.len {
+            self.rebuild_index(needed);
+        }
+    }
+
+    /// Inserts `value` under `key`, returning the value previously stored there, if any. A new key goes at the end,
+    /// while a replaced one keeps its position.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// assert_eq!(map.insert('a', 1), None);
+    /// map.insert('b', 2);
+    /// assert_eq!(map.insert('a', 3), Some(1));
+    /// assert_eq!(map.get_index(0), Some((&'a', &3)));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_full(key, value).1
+    }
+
+    /// Inserts `value` under `key` like [`insert`](OrderedMap::insert), and returns the position of the entry along
+    /// with the value previously stored there, if any.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// assert_eq!(map.insert_full('a', 1), (0, None));
+    /// assert_eq!(map.insert_full('b', 2), (1, None));
+    /// assert_eq!(map.insert_full('a', 3), (0, Some(1)));
+    /// ```
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        self.reserve(1);
+
+        let hash = self.hasher.hash_one(&key);
+        match self.find(hash, &key) {
+            Ok((_, pos)) => {
+                let old = mem::replace(&mut self.entries[pos].value, value);
+                (pos, Some(old))
+            }
+            Err(bucket) => {
+                let pos = self.entries.len;
+                self.entries.push(Bucket { hash, key, value });
+                self.index[bucket] = pos + 1;
+                (pos, None)
+            }
+        }
+    }
+
+    /// Returns `true` if the map holds an entry for `key`.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert("a", 1);
+    /// assert!(map.contains_key("a"));
+    /// assert!(!map.contains_key("b"));
+    /// ```
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get_index_of(key).is_some()
+    }
+
+    /// Returns the position of the entry of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// assert_eq!(map.get_index_of("b"), Some(1));
+    /// ```
+    pub fn get_index_of<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        if self.entries.len == 0 {
+            return None;
+        }
+
+        let hash = self.hasher.hash_one(key);
+        self.find(hash, key).ok().map(|(_, pos)| pos)
+    }
+
+    /// Returns a reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert("a", 1);
+    /// assert_eq!(map.get("a"), Some(&1));
+    /// assert_eq!(map.get("b"), None);
+    /// ```
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        Some(&self.entries[self.get_index_of(key)?].value)
+    }
+
+    /// Returns a mutable reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert("a", 1);
+    /// *map.get_mut("a").unwrap() += 1;
+    /// assert_eq!(map["a"], 2);
+    /// ```
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let pos = self.get_index_of(key)?;
+        Some(&mut self.entries[pos].value)
+    }
+
+    /// Removes the entry of `key` and returns its value, moving the last entry into its place. Takes O(1) but changes
+    /// the order.
+    /// # Example
+    /// ```
+    /// let mut map: vec::OrderedMap<_, _> = (0..4).map(|i| (i, i * 10)).collect();
+    /// assert_eq!(map.swap_remove(&1), Some(10));
+    /// assert_eq!(map.keys().copied().collect::<std::vec::Vec<_>>(), [0, 3, 2]);
+    /// ```
+    pub fn swap_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.swap_remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes the entry of `key` and returns it, moving the last entry into its place. Takes O(1) but changes the
+    /// order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert("a", 1);
+    /// assert_eq!(map.swap_remove_entry("a"), Some(("a", 1)));
+    /// ```
+    pub fn swap_remove_entry<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (bucket, pos) = self.locate(key)?;
+        self.erase_bucket(bucket);
+
+        let last = self.entries.len - 1;
+        if pos != last {
+            let moved = self.bucket_of(self.entries[last].hash, last);
+            self.index[moved] = pos + 1;
+            self.entries.swap(pos, last);
+        }
+
+        let Bucket { key, value, .. } = self.entries.pop().unwrap();
+        Some((key, value))
+    }
+
+    /// Removes the entry of `key` and returns its value, shifting the following entries down. Keeps the order but
+    /// takes O(n).
+    /// # Example
+    /// ```
+    /// let mut map: vec::OrderedMap<_, _> = (0..4).map(|i| (i, i * 10)).collect();
+    /// assert_eq!(map.shift_remove(&1), Some(10));
+    /// assert_eq!(map.keys().copied().collect::<std::vec::Vec<_>>(), [0, 2, 3]);
+    /// ```
+    pub fn shift_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.shift_remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes the entry of `key` and returns it, shifting the following entries down. Keeps the order but takes
+    /// O(n).
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert("a", 1);
+    /// assert_eq!(map.shift_remove_entry("a"), Some(("a", 1)));
+    /// ```
+    pub fn shift_remove_entry<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (bucket, pos) = self.locate(key)?;
+        self.erase_bucket(bucket);
+
+        for slot in self.index.iter_mut() {
+            if *slot > pos + 1 {
+                *slot -= 1;
+            }
+        }
+
+        let Bucket { key, value, .. } = self.entries.remove(pos);
+        Some((key, value))
+    }
+
+    /// Removes the last entry and returns it, or `None` if the map is empty.
+    /// # Example
+    /// ```
+    /// let mut map = vec::OrderedMap::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// assert_eq!(map.pop(), Some(("b", 2)));
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let last = self.entries.len.checked_sub(1)?;
+
+        let bucket = self.bucket_of(self.entries[last].hash, last);
+        self.erase_bucket(bucket);
+
+        let Bucket { key, value, .. } = self.entries.pop().unwrap();
+        Some((key, value))
+    }
+
+    // Returns the bucket and position of the entry of `key`, if any
+    fn locate<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+    {
+        if self.entries.len == 0 {
+            return None;
+        }
+
+        self.find(self.hasher.hash_one(key), key).ok()
+    }
+
+    // Returns the bucket and position of the entry of `key`, or the empty bucket it would go in. The index must not
+    // be full.
+    fn find<Q: Eq + ?Sized>(&self, hash: u64, key: &Q) -> Result<(usize, usize), usize>
+    where
+        K: Borrow<Q>,
+    {
+        let mask = self.index.len - 1;
+        let mut bucket = hash as usize & mask;
+
+        loop {
+            match self.index[bucket] {
+                EMPTY => return Err(bucket),
+                slot => {
+                    let entry = &self.entries[slot - 1];
+                    if entry.hash == hash && entry.key.borrow() == key {
+                        return Ok((bucket, slot - 1));
+                    }
+                }
+            }
+            bucket = (bucket + 1) & mask;
+        }
+    }
+}
+
+impl<K, V> Default for OrderedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, Q, V, S> Index<&Q> for OrderedMap<K, V, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not in the map")
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> Extend<(K, V)> for OrderedMap<K, V, S> {
+    /// Inserts every entry of an iterator, later values replacing earlier ones.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for OrderedMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+// Compares the entries like a map, whatever their order
+impl<K: Hash + Eq, V: PartialEq, S: BuildHasher> PartialEq for OrderedMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq, S: BuildHasher> Eq for OrderedMap<K, V, S> {}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for OrderedMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
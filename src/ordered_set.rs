@@ -0,0 +1,332 @@
+use std::{cmp::Ordering, fmt, iter::FromIterator, ops::Deref};
+
+use crate::{IntoIter, SortedVec, Vec};
+
+/// Set of unique elements kept in ascending order in a [`SortedVec`].
+///
+/// Lookups are binary searches over one flat buffer, which beats a `BTreeSet` for small sets. Inserting and removing
+/// move the elements after the affected position, so it's best suited to sets that are read more than written.
+/// # Example
+/// ```
+/// use vec::OrderedSet;
+///
+/// let a: OrderedSet<i32> = [3, 1, 2, 1].iter().copied().collect();
+/// let b: OrderedSet<i32> = [2, 3, 4].iter().copied().collect();
+///
+/// assert_eq!(&*a, &[1, 2, 3]);
+/// assert!(a.contains(&2));
+/// assert_eq!(&*a.union(&b), &[1, 2, 3, 4]);
+/// assert_eq!(&*a.intersection(&b), &[2, 3]);
+/// assert_eq!(&*a.difference(&b), &[1]);
+/// ```
+pub struct OrderedSet<T> {
+    // sorted, without duplicates
+    vec: SortedVec<T>,
+}
+
+impl<T: Ord> OrderedSet<T> {
+    /// Creates an empty set, without allocating.
+    /// # Example
+    /// ```
+    /// let set: vec::OrderedSet<i32> = vec::OrderedSet::new();
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            vec: SortedVec::new(),
+        }
+    }
+
+    /// Adds an element, returning `false` if an equal one was already there, in which case `elem` is dropped.
+    /// # Example
+    /// ```
+    /// let mut set = vec::OrderedSet::new();
+    /// assert!(set.insert(1));
+    /// assert!(!set.insert(1));
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn insert(&mut self, elem: T) -> bool {
+        self.vec.insert_unique(elem).is_ok()
+    }
+
+    /// Removes the element equal to `elem`, returning `false` if there was none.
+    /// # Example
+    /// ```
+    /// let mut set = vec::OrderedSet::new();
+    /// set.insert(1);
+    /// assert!(set.remove(&1));
+    /// assert!(!set.remove(&1));
+    /// ```
+    pub fn remove(&mut self, elem: &T) -> bool {
+        self.take(elem).is_some()
+    }
+
+    /// Removes the element equal to `elem` and returns it, or `None` if there was none.
+    /// # Example
+    /// ```
+    /// let mut set = vec::OrderedSet::new();
+    /// set.insert(1);
+    /// assert_eq!(set.take(&1), Some(1));
+    /// ```
+    pub fn take(&mut self, elem: &T) -> Option<T> {
+        self.vec.remove_item(elem)
+    }
+
+    /// Returns `true` if the set holds an element equal to `elem`.
+    /// # Example
+    /// ```
+    /// let set: vec::OrderedSet<i32> = [1, 2].iter().copied().collect();
+    /// assert!(set.contains(&1));
+    /// assert!(!set.contains(&3));
+    /// ```
+    pub fn contains(&self, elem: &T) -> bool {
+        self.vec.contains(elem)
+    }
+
+    /// Returns a reference to the element equal to `elem`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let set: vec::OrderedSet<i32> = [1, 2].iter().copied().collect();
+    /// assert_eq!(set.get(&2), Some(&2));
+    /// ```
+    pub fn get(&self, elem: &T) -> Option<&T> {
+        let index = self.vec.binary_search(elem).ok()?;
+        Some(&self.vec[index])
+    }
+
+    /// Returns `true` if every element of the set is also in `other`.
+    /// # Example
+    /// ```
+    /// let a: vec::OrderedSet<i32> = [1, 3].iter().copied().collect();
+    /// let b: vec::OrderedSet<i32> = [1, 2, 3].iter().copied().collect();
+    /// assert!(a.is_subset(&b));
+    /// assert!(!b.is_subset(&a));
+    /// ```
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let mut other = other.iter();
+        self.len() <= other.len() && self.iter().all(|elem| other.any(|x| x == elem))
+    }
+
+    /// Returns `true` if every element of `other` is also in the set.
+    /// # Example
+    /// ```
+    /// let a: vec::OrderedSet<i32> = [1, 2, 3].iter().copied().collect();
+    /// let b: vec::OrderedSet<i32> = [2].iter().copied().collect();
+    /// assert!(a.is_superset(&b));
+    /// ```
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if the sets have no element in common.
+    /// # Example
+    /// ```
+    /// let a: vec::OrderedSet<i32> = [1, 3].iter().copied().collect();
+    /// let b: vec::OrderedSet<i32> = [2, 4].iter().copied().collect();
+    /// assert!(a.is_disjoint(&b));
+    /// ```
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        let mut disjoint = true;
+        merge(self, other, |_, in_self, in_other| {
+            disjoint &= !(in_self && in_other)
+        });
+        disjoint
+    }
+
+    /// Returns the elements in either set.
+    /// # Example
+    /// ```
+    /// let a: vec::OrderedSet<i32> = [1, 3].iter().copied().collect();
+    /// let b: vec::OrderedSet<i32> = [2, 3].iter().copied().collect();
+    /// assert_eq!(&*a.union(&b), &[1, 2, 3]);
+    /// ```
+    pub fn union(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, |_, _| true)
+    }
+
+    /// Returns the elements in both sets.
+    /// # Example
+    /// ```
+    /// let a: vec::OrderedSet<i32> = [1, 3].iter().copied().collect();
+    /// let b: vec::OrderedSet<i32> = [2, 3].iter().copied().collect();
+    /// assert_eq!(&*a.intersection(&b), &[3]);
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, |in_self, in_other| in_self && in_other)
+    }
+
+    /// Returns the elements in the set but not in `other`.
+    /// # Example
+    /// ```
+    /// let a: vec::OrderedSet<i32> = [1, 3].iter().copied().collect();
+    /// let b: vec::OrderedSet<i32> = [2, 3].iter().copied().collect();
+    /// assert_eq!(&*a.difference(&b), &[1]);
+    /// ```
+    pub fn difference(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, |in_self, in_other| in_self && !in_other)
+    }
+
+    /// Returns the elements in exactly one of the sets.
+    /// # Example
+    /// ```
+    /// let a: vec::OrderedSet<i32> = [1, 3].iter().copied().collect();
+    /// let b: vec::OrderedSet<i32> = [2, 3].iter().copied().collect();
+    /// assert_eq!(&*a.symmetric_difference(&b), &[1, 2]);
+    /// ```
+    pub fn symmetric_difference(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, |in_self, in_other| in_self != in_other)
+    }
+
+    // Set of the elements of either set for which `keep(in self, in other)` returns `true`
+    fn combine<F: Fn(bool, bool) -> bool>(&self, other: &Self, keep: F) -> Self
+    where
+        T: Clone,
+    {
+        let mut vec = Vec::new();
+        merge(self, other, |elem, in_self, in_other| {
+            if keep(in_self, in_other) {
+                vec.push(elem.clone());
+            }
+        });
+
+        // Already in order, so the sort only checks it
+        Self { vec: vec.into() }
+    }
+}
+
+impl<T> OrderedSet<T> {
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut set: vec::OrderedSet<i32> = [1, 2].iter().copied().collect();
+    /// set.clear();
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Returns the elements as a sorted vector without duplicates, without copying.
+    /// # Example
+    /// ```
+    /// let set: vec::OrderedSet<i32> = [2, 1, 2].iter().copied().collect();
+    /// assert_eq!(&*set.into_sorted_vec(), &[1, 2]);
+    /// ```
+    pub fn into_sorted_vec(self) -> SortedVec<T> {
+        self.vec
+    }
+}
+
+// Walks both sets in order, calling `f(elem, in a, in b)` once for every distinct element
+fn merge<'a, T: Ord, F: FnMut(&'a T, bool, bool)>(a: &'a [T], b: &'a [T], mut f: F) {
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                f(&a[i], true, false);
+                i += 1;
+            }
+            Ordering::Greater => {
+                f(&b[j], false, true);
+                j += 1;
+            }
+            Ordering::Equal => {
+                f(&a[i], true, true);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    a[i..].iter().for_each(|elem| f(elem, true, false));
+    b[j..].iter().for_each(|elem| f(elem, false, true));
+}
+
+impl<T: Ord> Default for OrderedSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for OrderedSet<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for OrderedSet<T> {
+    /// Sorts a vector in place and removes its duplicates.
+    /// # Example
+    /// ```
+    /// use vec::{OrderedSet, Vec};
+    /// let mut vec = Vec::new();
+    /// vec.extend([3, 1, 3, 2]);
+    /// assert_eq!(&*OrderedSet::from(vec), &[1, 2, 3]);
+    /// ```
+    fn from(vec: Vec<T>) -> Self {
+        let mut vec = SortedVec::from(vec);
+        vec.dedup();
+        Self { vec }
+    }
+}
+
+impl<T: Ord> Extend<T> for OrderedSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.vec.extend(iter);
+        self.vec.dedup();
+    }
+}
+
+impl<T: Ord> FromIterator<T> for OrderedSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T> IntoIterator for OrderedSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<T: Clone> Clone for OrderedSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OrderedSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for OrderedSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for OrderedSet<T> {}
@@ -0,0 +1,136 @@
+use std::{alloc::Allocator, cmp::Ordering, collections::LinkedList};
+
+use rayon::{
+    iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+
+use crate::{growth::GrowthPolicy, Vec};
+
+impl<T: Send> FromParallelIterator<T> for Vec<T> {
+    /// Collects a parallel iterator into a vector, keeping the order of an indexed iterator. See
+    /// [`Vec::par_extend`](ParallelExtend::par_extend).
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    ///
+    /// let vec: vec::Vec<u64> = (0..1000u64).into_par_iter().map(|x| x * x).collect();
+    /// assert_eq!(vec.len(), 1000);
+    /// assert_eq!(vec[999], 999 * 999);
+    /// ```
+    fn from_par_iter<I: IntoParallelIterator<Item = T>>(par_iter: I) -> Self {
+        let mut vec = Vec::new();
+        vec.par_extend(par_iter);
+        vec
+    }
+}
+
+impl<T: Send, A: Allocator, G: GrowthPolicy> ParallelExtend<T> for Vec<T, A, G> {
+    /// Extends the vector with the items of a parallel iterator, keeping the order of an indexed iterator.
+    ///
+    /// Every worker fills its own chunk, and the chunks are moved into the vector at the end after a single
+    /// reservation for all of them.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use rayon::prelude::*;
+    ///
+    /// let mut vec = vec::Vec::new();
+    /// vec.push(0);
+    /// vec.par_extend((1..5).into_par_iter());
+    /// assert_eq!(&*vec, &[0, 1, 2, 3, 4]);
+    /// ```
+    fn par_extend<I: IntoParallelIterator<Item = T>>(&mut self, par_iter: I) {
+        // The chunks are standard vectors, which can be sent across threads
+        let chunks: LinkedList<std::vec::Vec<T>> = par_iter
+            .into_par_iter()
+            .fold(std::vec::Vec::new, |mut chunk, elem| {
+                chunk.push(elem);
+                chunk
+            })
+            .map(|chunk| LinkedList::from([chunk]))
+            .reduce(LinkedList::new, |mut left, mut right| {
+                left.append(&mut right);
+                left
+            });
+
+        self.reserve(chunks.iter().map(|chunk| chunk.len()).sum());
+        for chunk in chunks {
+            self.extend(chunk);
+        }
+    }
+}
+
+impl<T: Send, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Sorts the vector on all cores, preserving the order of equal elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend((0..10_000).rev());
+    /// vec.par_sort();
+    /// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+    /// ```
+    pub fn par_sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.as_mut_slice().par_sort()
+    }
+
+    /// Sorts the vector on all cores with a comparator function, preserving the order of equal elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..10_000);
+    /// vec.par_sort_by(|a, b| b.cmp(a));
+    /// assert_eq!(vec[0], 9_999);
+    /// ```
+    pub fn par_sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&mut self, compare: F) {
+        self.as_mut_slice().par_sort_by(compare)
+    }
+
+    /// Sorts the vector on all cores with a key extraction function, preserving the order of equal elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend([-3i32, 1, -2]);
+    /// vec.par_sort_by_key(|x| x.abs());
+    /// assert_eq!(&*vec, &[1, -2, -3]);
+    /// ```
+    pub fn par_sort_by_key<K: Ord, F: Fn(&T) -> K + Sync>(&mut self, f: F) {
+        self.as_mut_slice().par_sort_by_key(f)
+    }
+
+    /// Sorts the vector on all cores, without preserving the order of equal elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend((0..10_000).map(|x| x % 7));
+    /// vec.par_sort_unstable();
+    /// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+    /// ```
+    pub fn par_sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        self.as_mut_slice().par_sort_unstable()
+    }
+
+    /// Sorts the vector on all cores with a comparator function, without preserving the order of equal elements.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::Vec::new();
+    /// vec.extend(0..10_000);
+    /// vec.par_sort_unstable_by(|a, b| b.cmp(a));
+    /// assert_eq!(vec[9_999], 0);
+    /// ```
+    pub fn par_sort_unstable_by<F: Fn(&T, &T) -> Ordering + Sync>(&mut self, compare: F) {
+        self.as_mut_slice().par_sort_unstable_by(compare)
+    }
+
+    // The sorts of rayon are implemented on slices
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
@@ -0,0 +1,477 @@
+use std::{fmt, iter::FromIterator, ops::Index, sync::Arc};
+
+use crate::Vec;
+
+// Every node of the trie has up to `WIDTH` children, so each level consumes `BITS` bits of an index
+const BITS: usize = 5;
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+
+/// Immutable vector where every modification returns a new version, sharing most of its memory with the old one.
+///
+/// Elements are kept in a trie of 32-wide nodes plus a tail of up to 32 elements. Appending to the tail, reading
+/// and updating cost O(log32 n), which is at most 7 levels on 64-bit targets. Cloning a version is O(1), so
+/// keeping every past state, as an undo history does, is cheap.
+/// # Example
+/// ```
+/// use vec::PersistentVec;
+///
+/// let v1: PersistentVec<i32> = (0..1000).collect();
+/// let v2 = v1.update(500, -1);
+/// let v3 = v2.push(1000);
+///
+/// // Older versions are untouched
+/// assert_eq!((v1[500], v2[500], v3[500]), (500, -1, -1));
+/// assert_eq!((v1.len(), v3.len()), (1000, 1001));
+/// ```
+pub struct PersistentVec<T> {
+    len: usize,
+    // number of index bits below the root level
+    shift: usize,
+    root: Arc<Node<T>>,
+    // last 1 to 32 elements when not empty, kept out of the trie so pushes are cheap
+    tail: Arc<Vec<T>>,
+}
+
+enum Node<T> {
+    Branch(Vec<Arc<Node<T>>>),
+    // always holds exactly `WIDTH` elements
+    Leaf(Vec<T>),
+}
+
+// Versions are never mutated in place, every change copies the nodes it touches
+unsafe impl<T: Send + Sync> Send for PersistentVec<T> {}
+unsafe impl<T: Send + Sync> Sync for PersistentVec<T> {}
+
+impl<T> Node<T> {
+    fn children(&self) -> &Vec<Arc<Node<T>>> {
+        match self {
+            Node::Branch(children) => children,
+            Node::Leaf(_) => unreachable!("leaf found above the last level"),
+        }
+    }
+}
+
+// Chain of single-child branches from `level` down to `leaf`
+fn new_path<T>(level: usize, leaf: Arc<Node<T>>) -> Arc<Node<T>> {
+    if level == 0 {
+        return leaf;
+    }
+
+    let mut children = Vec::with_capacity(1);
+    children.push(new_path(level - BITS, leaf));
+    Arc::new(Node::Branch(children))
+}
+
+impl<T> PersistentVec<T> {
+    /// Creates an empty vector.
+    /// # Example
+    /// ```
+    /// let v: vec::PersistentVec<i32> = vec::PersistentVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            shift: BITS,
+            root: Arc::new(Node::Branch(Vec::new())),
+            tail: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let v = vec::PersistentVec::new().push(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::PersistentVec<i32> = vec::PersistentVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if both versions share all of their memory, which is the case for clones.
+    /// # Example
+    /// ```
+    /// let v = vec::PersistentVec::new().push(1);
+    /// assert!(v.ptr_eq(&v.clone()));
+    /// assert!(!v.ptr_eq(&v.push(2)));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.root, &other.root) && Arc::ptr_eq(&self.tail, &other.tail)
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let v = vec::PersistentVec::new().push(1);
+    /// assert_eq!(v.get(0), Some(&1));
+    /// assert_eq!(v.get(1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            Some(&self.leaf(index)[index & MASK])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the first element, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let v = vec::PersistentVec::new().push(1).push(2);
+    /// assert_eq!(v.first(), Some(&1));
+    /// ```
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns the last element, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let v = vec::PersistentVec::new().push(1).push(2);
+    /// assert_eq!(v.last(), Some(&2));
+    /// ```
+    pub fn last(&self) -> Option<&T> {
+        self.tail.last()
+    }
+
+    /// Returns an iterator over the elements.
+    /// # Example
+    /// ```
+    /// let v: vec::PersistentVec<i32> = (1..=100).collect();
+    /// assert_eq!(v.iter().sum::<i32>(), 5050);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len)
+            .step_by(WIDTH)
+            .flat_map(move |index| self.leaf(index).iter())
+    }
+
+    // Index of the first element of the tail
+    fn tail_offset(&self) -> usize {
+        if self.len == 0 {
+            0
+        } else {
+            (self.len - 1) & !MASK
+        }
+    }
+
+    // Elements of the leaf or tail holding `index`, which must be in bounds
+    fn leaf(&self, index: usize) -> &[T] {
+        if index >= self.tail_offset() {
+            return &self.tail;
+        }
+
+        let mut node = &self.root;
+        let mut level = self.shift;
+
+        loop {
+            match &**node {
+                Node::Branch(children) => node = &children[(index >> level) & MASK],
+                Node::Leaf(values) => return values,
+            }
+            level -= BITS;
+        }
+    }
+}
+
+impl<T: Clone> PersistentVec<T> {
+    /// Returns a new version with `elem` appended.
+    /// # Example
+    /// ```
+    /// let v1 = vec::PersistentVec::new().push(1);
+    /// let v2 = v1.push(2);
+    /// assert_eq!((v1.len(), v2.len()), (1, 2));
+    /// ```
+    pub fn push(&self, elem: T) -> Self {
+        // Room left in the tail, only the tail gets copied
+        if self.len - self.tail_offset() < WIDTH {
+            let mut tail = Vec::with_capacity(self.tail.len() + 1);
+            tail.extend(self.tail.iter().cloned());
+            tail.push(elem);
+
+            return Self {
+                len: self.len + 1,
+                shift: self.shift,
+                root: self.root.clone(),
+                tail: Arc::new(tail),
+            };
+        }
+
+        // The full tail moves into the trie as a leaf, and the element starts a new tail
+        let leaf = Arc::new(Node::Leaf((*self.tail).clone()));
+        let (root, shift) = if (self.len >> BITS) > (1 << self.shift) {
+            // The trie is full, grow it by a level
+            let mut children = Vec::with_capacity(2);
+            children.push(self.root.clone());
+            children.push(new_path(self.shift, leaf));
+
+            (Arc::new(Node::Branch(children)), self.shift + BITS)
+        } else {
+            (self.push_leaf(self.shift, &self.root, leaf), self.shift)
+        };
+
+        let mut tail = Vec::with_capacity(WIDTH);
+        tail.push(elem);
+
+        Self {
+            len: self.len + 1,
+            shift,
+            root,
+            tail: Arc::new(tail),
+        }
+    }
+
+    /// Returns a new version with the element at `index` replaced by `elem`.
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    /// # Example
+    /// ```
+    /// let v1: vec::PersistentVec<i32> = (0..100).collect();
+    /// let v2 = v1.update(10, -1);
+    /// assert_eq!((v1[10], v2[10]), (10, -1));
+    /// ```
+    pub fn update(&self, index: usize, elem: T) -> Self {
+        assert!(index < self.len, "index out of bounds");
+
+        if index >= self.tail_offset() {
+            let mut tail = (*self.tail).clone();
+            tail[index & MASK] = elem;
+
+            return Self {
+                tail: Arc::new(tail),
+                ..self.clone()
+            };
+        }
+
+        Self {
+            root: Self::update_in(self.shift, &self.root, index, elem),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a new version without the last element, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let v: vec::PersistentVec<i32> = (0..100).collect();
+    /// let shorter = v.pop().unwrap();
+    /// assert_eq!((v.len(), shorter.len()), (100, 99));
+    /// assert_eq!(shorter.last(), Some(&98));
+    /// ```
+    pub fn pop(&self) -> Option<Self> {
+        match self.len {
+            0 => return None,
+            1 => return Some(Self::new()),
+            _ => {}
+        }
+
+        // More than one element left in the tail, only the tail gets copied
+        if self.len - self.tail_offset() > 1 {
+            let mut tail = (*self.tail).clone();
+            tail.pop();
+
+            return Some(Self {
+                len: self.len - 1,
+                tail: Arc::new(tail),
+                ..self.clone()
+            });
+        }
+
+        // The tail empties, the last leaf of the trie becomes the new tail
+        let mut tail = Vec::with_capacity(WIDTH);
+        tail.extend(self.leaf(self.len - 2).iter().cloned());
+
+        let mut root = self
+            .pop_leaf(self.shift, &self.root)
+            .unwrap_or_else(|| Arc::new(Node::Branch(Vec::new())));
+        let mut shift = self.shift;
+
+        // Drop a level once the root has a single child
+        if shift > BITS && root.children().len() == 1 {
+            root = root.children()[0].clone();
+            shift -= BITS;
+        }
+
+        Some(Self {
+            len: self.len - 1,
+            shift,
+            root,
+            tail: Arc::new(tail),
+        })
+    }
+
+    /// Copies the elements into a mutable vector.
+    /// # Example
+    /// ```
+    /// let v: vec::PersistentVec<i32> = (0..3).collect();
+    /// assert_eq!(&*v.to_vec(), &[0, 1, 2]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len);
+        vec.extend(self.iter().cloned());
+        vec
+    }
+
+    // Copy of `node`, at `level`, with `leaf` added after its last leaf
+    fn push_leaf(&self, level: usize, node: &Node<T>, leaf: Arc<Node<T>>) -> Arc<Node<T>> {
+        let mut children = node.children().clone();
+        let index = ((self.len - 1) >> level) & MASK;
+
+        let child = if level == BITS {
+            leaf
+        } else if let Some(child) = children.get(index) {
+            self.push_leaf(level - BITS, child, leaf)
+        } else {
+            new_path(level - BITS, leaf)
+        };
+
+        if index < children.len() {
+            children[index] = child;
+        } else {
+            children.push(child);
+        }
+
+        Arc::new(Node::Branch(children))
+    }
+
+    // Copy of `node`, at `level`, without its last leaf, or `None` if that leaves it empty
+    fn pop_leaf(&self, level: usize, node: &Node<T>) -> Option<Arc<Node<T>>> {
+        let index = ((self.len - 2) >> level) & MASK;
+        let mut children = node.children().clone();
+
+        if level > BITS {
+            match self.pop_leaf(level - BITS, &children[index]) {
+                Some(child) => children[index] = child,
+                None if index == 0 => return None,
+                None => {
+                    children.pop();
+                }
+            }
+        } else if index == 0 {
+            return None;
+        } else {
+            children.pop();
+        }
+
+        Some(Arc::new(Node::Branch(children)))
+    }
+
+    // Copy of the path from `node`, at `level`, to element `index`, with that element replaced
+    fn update_in(level: usize, node: &Node<T>, index: usize, elem: T) -> Arc<Node<T>> {
+        match node {
+            Node::Branch(children) => {
+                let mut children = children.clone();
+                let slot = (index >> level) & MASK;
+
+                children[slot] = Self::update_in(level - BITS, &children[slot], index, elem);
+                Arc::new(Node::Branch(children))
+            }
+            Node::Leaf(values) => {
+                let mut values = values.clone();
+                values[index & MASK] = elem;
+                Arc::new(Node::Leaf(values))
+            }
+        }
+    }
+}
+
+impl<T> Clone for PersistentVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            shift: self.shift,
+            root: self.root.clone(),
+            tail: self.tail.clone(),
+        }
+    }
+}
+
+impl<T> Default for PersistentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for PersistentVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> From<Vec<T>> for PersistentVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len;
+        if len == 0 {
+            return Self::new();
+        }
+
+        // Everything but the last 1 to 32 elements goes into full leaves
+        let mut elems = vec.into_iter();
+        let mut nodes = Vec::with_capacity((len - 1) / WIDTH);
+        for _ in 0..(len - 1) / WIDTH {
+            let mut values = Vec::with_capacity(WIDTH);
+            values.extend(elems.by_ref().take(WIDTH));
+            nodes.push(Arc::new(Node::Leaf(values)));
+        }
+
+        let mut tail = Vec::with_capacity(WIDTH);
+        tail.extend(elems);
+
+        // Group the nodes level by level until they fit under the root
+        let mut shift = BITS;
+        while nodes.len > WIDTH {
+            let mut parents = Vec::with_capacity(nodes.len.div_ceil(WIDTH));
+            let mut children_left = nodes.into_iter().peekable();
+
+            while children_left.peek().is_some() {
+                let mut children = Vec::with_capacity(WIDTH);
+                children.extend(children_left.by_ref().take(WIDTH));
+                parents.push(Arc::new(Node::Branch(children)));
+            }
+
+            nodes = parents;
+            shift += BITS;
+        }
+
+        Self {
+            len,
+            shift,
+            root: Arc::new(Node::Branch(nodes)),
+            tail: Arc::new(tail),
+        }
+    }
+}
+
+impl<T> FromIterator<T> for PersistentVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Vec::new();
+        vec.extend(iter);
+        vec.into()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for PersistentVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for PersistentVec<T> {}
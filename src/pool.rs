@@ -0,0 +1,184 @@
+use std::{
+    alloc::{AllocError, Allocator, Layout},
+    cell::{Cell, UnsafeCell},
+    mem::{self, MaybeUninit},
+    ptr::NonNull,
+};
+
+// Marks the end of the free list
+const NONE: usize = usize::MAX;
+
+// Single block of the pool, aligned so any primitive type can be stored in it
+#[repr(C, align(16))]
+struct Block<const SIZE: usize>([MaybeUninit<u8>; SIZE]);
+
+/// Fixed-size block allocator with inline storage for `N` blocks of `SIZE` bytes each.
+///
+/// Every allocation takes exactly one block, so allocating and freeing are O(1), deterministic and
+/// can't fragment the pool. Requests bigger than `SIZE` bytes (or aligned to more than 16 bytes) fail,
+/// and growing a vector beyond one block is an allocation error.
+/// # Example
+/// ```
+/// #![feature(allocator_api)]
+/// use vec::{Pool, Vec};
+///
+/// let pool: Pool<64, 4> = Pool::new();
+/// let mut vec = Vec::new_in(&pool);
+///
+/// for i in 0..16u32 {
+///     vec.push(i);
+/// }
+///
+/// assert_eq!(vec[15], 15);
+/// assert_eq!(pool.available(), 3);
+/// ```
+pub struct Pool<const SIZE: usize, const N: usize> {
+    // backing storage of every block
+    blocks: UnsafeCell<[Block<SIZE>; N]>,
+    // free list links, `next[i]` is the block handed out after block `i`
+    next: [Cell<usize>; N],
+    // first block of the free list
+    head: Cell<usize>,
+    // blocks never handed out yet, which aren't linked in the free list
+    untouched: Cell<usize>,
+    // blocks currently available
+    available: Cell<usize>,
+}
+
+impl<const SIZE: usize, const N: usize> Pool<SIZE, N> {
+    // Distance in bytes between two consecutive blocks
+    const STRIDE: usize = mem::size_of::<Block<SIZE>>();
+
+    /// Creates a pool with all of its `N` blocks available. Usable in `const` contexts.
+    /// # Example
+    /// ```
+    /// use vec::Pool;
+    /// let pool: Pool<32, 8> = Pool::new();
+    /// assert_eq!(pool.available(), 8);
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            blocks: UnsafeCell::new([const { Block([MaybeUninit::uninit(); SIZE]) }; N]),
+            next: [const { Cell::new(NONE) }; N],
+            head: Cell::new(NONE),
+            untouched: Cell::new(0),
+            available: Cell::new(N),
+        }
+    }
+
+    /// Returns the number of blocks that can still be allocated.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Pool, Vec};
+    /// let pool: Pool<16, 2> = Pool::new();
+    /// let mut vec = Vec::new_in(&pool);
+    /// vec.push(1u8);
+    /// assert_eq!(pool.available(), 1);
+    /// drop(vec);
+    /// assert_eq!(pool.available(), 2);
+    /// ```
+    pub fn available(&self) -> usize {
+        self.available.get()
+    }
+
+    /// Returns the size in bytes of each block.
+    /// # Example
+    /// ```
+    /// use vec::Pool;
+    /// assert_eq!(Pool::<48, 2>::new().block_size(), 48);
+    /// ```
+    pub fn block_size(&self) -> usize {
+        SIZE
+    }
+
+    fn base(&self) -> *mut u8 {
+        self.blocks.get() as *mut u8
+    }
+
+    // Whether a block can hold the given layout
+    fn fits(layout: Layout) -> bool {
+        layout.size() <= SIZE && layout.align() <= mem::align_of::<Block<SIZE>>()
+    }
+
+    // Takes a block out of the pool, returning its index
+    fn take(&self) -> Option<usize> {
+        let index = match self.head.get() {
+            NONE if self.untouched.get() < N => {
+                let index = self.untouched.get();
+                self.untouched.set(index + 1);
+                index
+            }
+            NONE => return None,
+            head => {
+                self.head.set(self.next[head].get());
+                head
+            }
+        };
+
+        self.available.set(self.available.get() - 1);
+        Some(index)
+    }
+
+    // Gives the block at `index` back to the pool
+    fn give_back(&self, index: usize) {
+        self.next[index].set(self.head.get());
+        self.head.set(index);
+        self.available.set(self.available.get() + 1);
+    }
+}
+
+impl<const SIZE: usize, const N: usize> Default for Pool<SIZE, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const SIZE: usize, const N: usize> Allocator for &Pool<SIZE, N> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if !Pool::<SIZE, N>::fits(layout) {
+            return Err(AllocError);
+        }
+
+        let index = self.take().ok_or(AllocError)?;
+        let ptr = self.base().wrapping_add(index * Pool::<SIZE, N>::STRIDE);
+
+        // The whole block is usable, no matter how much was requested
+        Ok(NonNull::slice_from_raw_parts(
+            NonNull::new(ptr).ok_or(AllocError)?,
+            SIZE,
+        ))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, _layout: Layout) {
+        let offset = ptr.as_ptr().offset_from_unsigned(self.base());
+        self.give_back(offset / Pool::<SIZE, N>::STRIDE);
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        _old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // Blocks never move: either the new size still fits in the block or the pool can't serve it
+        if Pool::<SIZE, N>::fits(new_layout) {
+            Ok(NonNull::slice_from_raw_parts(ptr, SIZE))
+        } else {
+            Err(AllocError)
+        }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        _old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if Pool::<SIZE, N>::fits(new_layout) {
+            Ok(NonNull::slice_from_raw_parts(ptr, SIZE))
+        } else {
+            Err(AllocError)
+        }
+    }
+}
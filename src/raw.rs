@@ -1,16 +1,305 @@
 use std::{
     alloc::{handle_alloc_error, Allocator, Global, Layout},
-    mem,
-    ptr::{self, NonNull, Unique},
+    cmp, mem,
+    ops::{Bound, Range, RangeBounds},
+    ptr::{self, NonNull},
 };
 
+use crate::{
+    error::TryReserveError,
+    growth::{Doubling, GrowthPolicy},
+    oom::{self, OomAction},
+};
+
+#[cfg(feature = "alloc-hooks")]
+use crate::hooks::{self, AllocEvent, AllocKind};
+#[cfg(feature = "stats")]
+use crate::stats::VecStats;
+
+// Turns a failed reservation into the infallible API's behavior: panic on overflow, abort on OOM
+#[cold]
+#[inline(never)]
+pub fn handle_reserve_error(err: TryReserveError) -> ! {
+    match err {
+        TryReserveError::CapacityOverflow => panic!("capacity overflow"),
+        TryReserveError::AllocError { layout } => handle_alloc_error(layout),
+    }
+}
+
+// Resolves `range` against a length of `len`, panicking if it ends before it starts or past `len`. Bounds at
+// `usize::MAX` are out of bounds too, instead of wrapping around
+#[track_caller]
+pub fn resolve_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => Some(start),
+        Bound::Excluded(&start) => start.checked_add(1),
+        Bound::Unbounded => Some(0),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1),
+        Bound::Excluded(&end) => Some(end),
+        Bound::Unbounded => Some(len),
+    };
+
+    match (start, end) {
+        (Some(start), Some(end)) if start <= end && end <= len => start..end,
+        _ => panic!("range out of bounds"),
+    }
+}
+
+// Byte written over vacated memory in debug builds
+pub const POISON: u8 = 0xDD;
+
+// Overwrites `count` vacated slots with the poison byte in debug builds, so unsafe code reading through a stale
+// pointer sees an obviously wrong value instead of the old element
+pub unsafe fn poison<T>(ptr: *mut T, count: usize) {
+    if cfg!(debug_assertions) {
+        ptr::write_bytes(ptr, POISON, count);
+    }
+}
+
+// Lets the out-of-memory handler retry a failed allocation with `attempt`, or turn the failure into a panic
+fn handle_oom(
+    mut result: Result<NonNull<[u8]>, TryReserveError>,
+    mut attempt: impl FnMut() -> Result<NonNull<[u8]>, TryReserveError>,
+) -> Result<NonNull<[u8]>, TryReserveError> {
+    while let Err(TryReserveError::AllocError { layout }) = result {
+        match oom::handle(layout) {
+            OomAction::Retry => result = attempt(),
+            OomAction::Fail => break,
+            OomAction::Panic => panic!("memory allocation of {} bytes failed", layout.size()),
+        }
+    }
+
+    result
+}
+
+// Capacity to grow a buffer of `cap` elements to so it holds `len + additional`: the growth policy's pick for at
+// least `MIN_NON_ZERO_CAP` elements. Also returns the exact capacity needed, to fall back to when the allocator
+// can't fit the policy's pick. Shared by the vectors managing their own allocation
+pub fn grown_capacity<T, G: GrowthPolicy>(
+    growth: &G,
+    cap: usize,
+    len: usize,
+    additional: usize,
+) -> Result<(usize, usize), TryReserveError> {
+    let exact = len
+        .checked_add(additional)
+        .ok_or(TryReserveError::CapacityOverflow)?;
+    let required = cmp::max(exact, RawVec::<T>::MIN_NON_ZERO_CAP);
+    let new_cap = growth.next_capacity(cap, required);
+
+    assert!(
+        new_cap >= required,
+        "Growth policy returned a capacity smaller than required"
+    );
+
+    Ok((new_cap, exact))
+}
+
+// Reallocates a buffer to `new_cap` elements with `realloc`, which leaves the buffer untouched on failure. Bounded
+// allocators (a pool block, a fixed buffer) may not fit the policy's capacity while still having room for what's
+// needed, so it retries with the `exact` capacity before letting the out-of-memory handler decide. Returns the
+// new block and the capacity it was allocated for
+pub fn realloc_buffer(
+    new_cap: usize,
+    exact: usize,
+    mut realloc: impl FnMut(usize) -> Result<NonNull<[u8]>, TryReserveError>,
+) -> Result<(NonNull<[u8]>, usize), TryReserveError> {
+    let (result, new_cap) = match realloc(new_cap) {
+        Err(TryReserveError::AllocError { .. }) if new_cap > exact => (realloc(exact), exact),
+        result => (result, new_cap),
+    };
+    let ptr = handle_oom(result, || realloc(new_cap))?;
+
+    Ok((ptr, new_cap))
+}
+
+// Reports a buffer of `T`s grown from `old_cap` to `new_cap` elements to the allocation hook and to its statistics
+#[cfg_attr(
+    not(feature = "alloc-hooks"),
+    allow(unused_variables, clippy::extra_unused_type_parameters)
+)]
+pub fn record_growth<T>(
+    old_cap: usize,
+    new_cap: usize,
+    #[cfg(feature = "stats")] stats: &mut VecStats,
+) {
+    #[cfg(feature = "alloc-hooks")]
+    hooks::emit(AllocEvent::new::<T>(
+        if old_cap == 0 {
+            AllocKind::Allocate
+        } else {
+            AllocKind::Grow
+        },
+        old_cap,
+        new_cap,
+    ));
+
+    #[cfg(feature = "stats")]
+    stats.record(new_cap);
+}
+
+// Reports a freed buffer of `cap` `T`s to the allocation hook
+#[cfg_attr(not(feature = "alloc-hooks"), allow(unused_variables))]
+pub fn record_deallocation<T>(cap: usize) {
+    #[cfg(feature = "alloc-hooks")]
+    hooks::emit(AllocEvent::new::<T>(AllocKind::Deallocate, cap, 0));
+}
+
+// Shifts the `len - index` elements from `index` one slot to the right and writes `elem` in the gap. The buffer
+// must have room for `len + 1` elements
+pub unsafe fn insert_at<T>(ptr: *mut T, len: usize, index: usize, elem: T) {
+    if index < len {
+        // ptr::copy(source, dest, count) > Copy from 'source' to 'dest' 'count' elements
+        ptr::copy(ptr.add(index), ptr.add(index + 1), len - index);
+    }
+
+    ptr::write(ptr.add(index), elem);
+}
+
+// Moves the element at `index` out of a buffer of `len` elements, shifting the following ones over it and
+// poisoning the vacated last slot
+pub unsafe fn remove_at<T>(ptr: *mut T, len: usize, index: usize) -> T {
+    // Read the element out before the tail is shifted over it
+    let result = ptr::read(ptr.add(index));
+    ptr::copy(ptr.add(index + 1), ptr.add(index), len - index - 1);
+    poison(ptr.add(len - 1), 1);
+
+    result
+}
+
+// Drops the elements past `new_len`, if any
+pub unsafe fn truncate_at<T>(ptr: *mut T, len: &mut usize, new_len: usize) {
+    if new_len >= *len {
+        return;
+    }
+
+    let tail = ptr::slice_from_raw_parts_mut(ptr.add(new_len), *len - new_len);
+
+    // Update the length first, so a panicking destructor can't cause the tail to be dropped twice
+    *len = new_len;
+
+    if mem::needs_drop::<T>() {
+        ptr::drop_in_place(tail);
+    }
+
+    poison(tail as *mut T, tail.len());
+}
+
+// Removes, in a single left-to-right pass over the first `end` elements, every element whose index `remove` selects,
+// handing it to `removed` and shifting the kept ones left over the gaps. The elements past `end` are all kept
+pub unsafe fn remove_where<T>(
+    ptr: *mut T,
+    len: &mut usize,
+    end: usize,
+    mut remove: impl FnMut(usize) -> bool,
+    mut removed: impl FnMut(T),
+) {
+    // The vector is left empty while elements are moved out, and the guard closes the gaps and restores the
+    // length even if `remove` or `removed` panics
+    let mut guard = CompactOnDrop {
+        ptr,
+        original_len: *len,
+        processed: 0,
+        deleted: 0,
+        len,
+    };
+    *guard.len = 0;
+
+    while guard.processed < end {
+        let cur = ptr.add(guard.processed);
+
+        if remove(guard.processed) {
+            // Account for the element before handing it out, so a panic can't drop it twice
+            guard.processed += 1;
+            guard.deleted += 1;
+            removed(ptr::read(cur));
+        } else {
+            if guard.deleted > 0 {
+                ptr::copy_nonoverlapping(cur, cur.sub(guard.deleted), 1);
+            }
+            guard.processed += 1;
+        }
+    }
+}
+
+// Shifts the unprocessed tail of a `remove_where` pass over the gaps left by the removed elements, then writes the
+// final length back
+struct CompactOnDrop<'a, T> {
+    ptr: *mut T,
+    len: &'a mut usize,
+    original_len: usize,
+    processed: usize,
+    deleted: usize,
+}
+
+impl<T> Drop for CompactOnDrop<'_, T> {
+    fn drop(&mut self) {
+        let new_len = self.original_len - self.deleted;
+
+        if self.deleted > 0 {
+            unsafe {
+                let tail = self.ptr.add(self.processed);
+                ptr::copy(
+                    tail,
+                    tail.sub(self.deleted),
+                    self.original_len - self.processed,
+                );
+                poison(self.ptr.add(new_len), self.deleted);
+            }
+        }
+
+        *self.len = new_len;
+    }
+}
+
+// Rounds a capacity of `T`s up to fill the size class an allocator serves the buffer from
+trait SizeClass {
+    fn size_class_capacity<T>(&self, cap: usize) -> usize;
+}
+
+impl<A: Allocator> SizeClass for A {
+    // Other allocators only report their size classes through the length of the blocks they return
+    default fn size_class_capacity<T>(&self, cap: usize) -> usize {
+        cap
+    }
+}
+
+impl SizeClass for Global {
+    fn size_class_capacity<T>(&self, cap: usize) -> usize {
+        // Common mallocs hand out blocks in steps of at least 16 bytes, the rest of the last step would be wasted
+        const STEP: usize = 16;
+
+        let rounded = cap
+            .checked_mul(mem::size_of::<T>())
+            .and_then(|size| size.checked_add(STEP - 1))
+            .map(|size| (size & !(STEP - 1)) / mem::size_of::<T>());
+
+        rounded.unwrap_or(cap)
+    }
+}
+
+// Whether a new buffer is left uninitialized or zeroed by the allocator
+enum AllocInit {
+    Uninitialized,
+    Zeroed,
+}
+
 // Type for abstracting the repeated allocation, growth and free logics
 #[derive(Debug)]
-pub struct RawVec<T> {
+pub struct RawVec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
     // pointer to the allocation
-    pub ptr: Unique<T>,
+    pub ptr: NonNull<T>,
     // size of allocation
     pub cap: usize,
+    // allocator that owns the buffer, used for every grow, shrink and free
+    alloc: A,
+    // decides the capacity of the buffer on every grow
+    growth: G,
+    // reallocation count and peak capacity
+    #[cfg(feature = "stats")]
+    stats: VecStats,
 }
 
 // Type for abstracting iterators logic
@@ -19,90 +308,338 @@ pub struct RawValIter<T> {
     end: *const T,
 }
 
-// Allocate, grow and free shared methods
-impl<T> RawVec<T> {
+// Writes the local length back to the vector when dropped, so elements written before a panic (in a clone or an
+// iterator) are still accounted for and dropped exactly once
+pub struct SetLenOnDrop<'a> {
+    len: &'a mut usize,
+    local_len: usize,
+}
+
+impl<'a> SetLenOnDrop<'a> {
+    pub fn new(len: &'a mut usize) -> Self {
+        Self {
+            local_len: *len,
+            len,
+        }
+    }
+
+    pub fn current_len(&self) -> usize {
+        self.local_len
+    }
+
+    pub fn increment_len(&mut self, increment: usize) {
+        self.local_len += increment;
+    }
+}
+
+impl Drop for SetLenOnDrop<'_> {
+    fn drop(&mut self) {
+        *self.len = self.local_len;
+    }
+}
+
+impl<T> RawVec<T, Global> {
     pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator> RawVec<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_growth_policy_in(Doubling, alloc)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> RawVec<mem::MaybeUninit<T>, A, G> {
+    // Reinterprets the buffer as holding `T`s, which have the same layout as `MaybeUninit<T>`
+    pub fn assume_init(self) -> RawVec<T, A, G> {
+        let this = mem::ManuallyDrop::new(self);
+
+        unsafe {
+            RawVec {
+                ptr: this.ptr.cast(),
+                cap: this.cap,
+                alloc: ptr::read(&this.alloc),
+                growth: ptr::read(&this.growth),
+                #[cfg(feature = "stats")]
+                stats: this.stats,
+            }
+        }
+    }
+}
+
+// Allocate, grow and free shared methods
+impl<T, A: Allocator, G: GrowthPolicy> RawVec<T, A, G> {
+    // Smallest capacity of a non-empty buffer, so tiny vectors don't reallocate on every early push.
+    // Same heuristic as std: 8 for bytes, 4 for elements up to 1 KiB and 1 for anything bigger.
+    pub const MIN_NON_ZERO_CAP: usize = if mem::size_of::<T>() == 1 {
+        8
+    } else if mem::size_of::<T>() <= 1024 {
+        4
+    } else {
+        1
+    };
+
+    pub fn with_growth_policy_in(growth: G, alloc: A) -> Self {
         // !0 == usize::MAX
         let cap = if mem::size_of::<T>() == 0 { !0 } else { 0 };
 
         Self {
-            ptr: Unique::dangling(),
+            ptr: NonNull::dangling(),
             cap,
+            alloc,
+            growth,
+            #[cfg(feature = "stats")]
+            stats: VecStats::default(),
         }
     }
 
-    pub fn grow(&mut self) {
-        unsafe {
-            let elem_size = mem::size_of::<T>();
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
 
-            assert!(elem_size != 0, "capacity overflow");
+    pub fn growth_policy(&self) -> &G {
+        &self.growth
+    }
 
-            let (new_cap, ptr) = if self.cap == 0 {
-                let ptr = Global.allocate(Layout::array::<T>(1).unwrap());
-                (1, ptr)
-            } else {
-                let new_cap = 2 * self.cap;
-                let old_num_bytes = self.cap * elem_size;
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> VecStats {
+        self.stats
+    }
 
-                assert!(
-                    old_num_bytes <= (isize::MAX as usize) / 2,
-                    "capacity overflow"
-                );
+    // Allocates room for exactly `cap` elements up front
+    pub fn try_with_capacity_in(cap: usize, growth: G, alloc: A) -> Result<Self, TryReserveError> {
+        Self::try_allocate_in(cap, AllocInit::Uninitialized, growth, alloc)
+    }
 
-                let c: NonNull<T> = self.ptr.into();
-                let ptr = Global.grow(
-                    c.cast(),
-                    Layout::array::<T>(self.cap).unwrap(),
-                    Layout::array::<T>(new_cap).unwrap(),
-                );
+    // Allocates room for exactly `cap` elements up front, with every byte set to zero
+    pub fn try_with_capacity_zeroed_in(
+        cap: usize,
+        growth: G,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        Self::try_allocate_in(cap, AllocInit::Zeroed, growth, alloc)
+    }
 
-                (new_cap, ptr)
+    fn try_allocate_in(
+        cap: usize,
+        init: AllocInit,
+        growth: G,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let mut buf = Self::with_growth_policy_in(growth, alloc);
+
+        // Zero-sized types and empty buffers never allocate
+        if mem::size_of::<T>() == 0 || cap == 0 {
+            return Ok(buf);
+        }
+
+        let layout = Layout::array::<T>(cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+        let attempt = || {
+            let ptr = match init {
+                AllocInit::Uninitialized => buf.alloc.allocate(layout),
+                AllocInit::Zeroed => buf.alloc.allocate_zeroed(layout),
             };
 
-            // Out of memory
-            if ptr.is_err() {
-                handle_alloc_error(Layout::from_size_align_unchecked(
-                    new_cap * elem_size,
-                    mem::align_of::<T>(),
-                ))
+            ptr.map_err(|_| TryReserveError::AllocError { layout })
+        };
+        let ptr = handle_oom(attempt(), attempt)?;
+
+        buf.ptr = ptr.cast();
+        buf.cap = cap;
+
+        #[cfg(feature = "stats")]
+        buf.stats.record(cap);
+
+        #[cfg(feature = "alloc-hooks")]
+        hooks::emit(AllocEvent::new::<T>(AllocKind::Allocate, 0, cap));
+
+        Ok(buf)
+    }
+
+    // Makes room for one more element in a full buffer. Out of line, so the capacity checks of `push` and `insert`
+    // stay a single branch in the caller
+    #[cold]
+    #[inline(never)]
+    pub fn try_grow(&mut self) -> Result<(), TryReserveError> {
+        self.grow(self.cap, 1)
+    }
+
+    // Makes room for `additional` more elements after the first `len` ones in a single reallocation, to at least the
+    // capacity the growth policy would pick
+    #[inline]
+    pub fn try_reserve(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        if self.cap.wrapping_sub(len) >= additional {
+            return Ok(());
+        }
+
+        self.grow(len, additional)
+    }
+
+    // The reallocation behind `try_reserve` and `try_grow`, kept out of the hot loops that only check the capacity
+    #[cold]
+    #[inline(never)]
+    fn grow(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        // Zero-sized types already have a capacity of usize::MAX, needing more room means the length would overflow
+        if mem::size_of::<T>() == 0 {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        let (new_cap, exact) = grown_capacity::<T, G>(&self.growth, self.cap, len, additional)?;
+
+        // Fill the size class the allocator would round the buffer up to anyway, as far as the policy allows
+        let limit = cmp::max(new_cap, self.growth.max_capacity());
+        let new_cap = cmp::min(self.alloc.size_class_capacity::<T>(new_cap), limit);
+
+        let (ptr, new_cap) = realloc_buffer(new_cap, exact, |cap| self.try_realloc(cap))?;
+
+        // The allocator may also hand back a bigger block than asked for, its excess is usable capacity too
+        let new_cap = cmp::min(cmp::max(new_cap, ptr.len() / mem::size_of::<T>()), limit);
+
+        record_growth::<T>(
+            self.cap,
+            new_cap,
+            #[cfg(feature = "stats")]
+            &mut self.stats,
+        );
+
+        self.ptr = ptr.cast();
+        self.cap = new_cap;
+
+        invariant!(
+            self.ptr.as_ptr().is_aligned(),
+            "Allocator returned a misaligned buffer"
+        );
+
+        Ok(())
+    }
+
+    // Allocates or grows the buffer to `new_cap` elements, leaving it untouched on failure
+    fn try_realloc(&self, new_cap: usize) -> Result<NonNull<[u8]>, TryReserveError> {
+        // Fails for allocations bigger than isize::MAX bytes
+        let new_layout =
+            Layout::array::<T>(new_cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+
+        let ptr = unsafe {
+            if self.cap == 0 {
+                self.alloc.allocate(new_layout)
+            } else {
+                self.alloc.grow(
+                    self.ptr.cast(),
+                    Layout::array::<T>(self.cap).unwrap(),
+                    new_layout,
+                )
             }
+        };
 
-            let ptr = ptr.unwrap();
+        // Out of memory
+        ptr.map_err(|_| TryReserveError::AllocError { layout: new_layout })
+    }
+
+    // Shrinks the allocation down to `cap` elements, freeing it entirely when `cap` is 0
+    pub fn shrink_to(&mut self, cap: usize) {
+        let elem_size = mem::size_of::<T>();
 
-            self.ptr = Unique::new_unchecked(ptr.as_ptr() as *mut _);
-            self.cap = new_cap;
+        assert!(cap <= self.cap, "Tried to shrink to a larger capacity");
+
+        // Zero-sized types never allocate
+        if elem_size == 0 || cap == self.cap {
+            return;
+        }
+
+        unsafe {
+            let old_layout = Layout::array::<T>(self.cap).unwrap();
+
+            poison(self.ptr.as_ptr().add(cap), self.cap - cap);
+
+            if cap == 0 {
+                self.alloc.deallocate(self.ptr.cast(), old_layout);
+                self.ptr = NonNull::dangling();
+            } else {
+                let new_layout = Layout::array::<T>(cap).unwrap();
+                let ptr = self.alloc.shrink(self.ptr.cast(), old_layout, new_layout);
+
+                // Out of memory
+                if ptr.is_err() {
+                    handle_alloc_error(new_layout)
+                }
+
+                self.ptr = ptr.unwrap().cast();
+
+                #[cfg(feature = "stats")]
+                self.stats.record(cap);
+            }
+
+            #[cfg(feature = "alloc-hooks")]
+            hooks::emit(AllocEvent::new::<T>(
+                if cap == 0 {
+                    AllocKind::Deallocate
+                } else {
+                    AllocKind::Shrink
+                },
+                self.cap,
+                cap,
+            ));
+
+            self.cap = cap;
         }
     }
 }
 
 // RawVec Deallocation (Drop trait -> https://doc.rust-lang.org/1.9.0/book/drop.html)
-impl<T> Drop for RawVec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for RawVec<T, A, G> {
     fn drop(&mut self) {
         let elem_size = mem::size_of::<T>();
 
         // Don't free zero-sizes allocations
         if self.cap != 0 && elem_size != 0 {
             unsafe {
-                let c: NonNull<T> = self.ptr.into();
-                Global.deallocate(c.cast(), Layout::array::<T>(self.cap).unwrap())
+                poison(self.ptr.as_ptr(), self.cap);
+                self.alloc
+                    .deallocate(self.ptr.cast(), Layout::array::<T>(self.cap).unwrap())
             }
+
+            record_deallocation::<T>(self.cap);
         }
     }
 }
-
 impl<T> RawValIter<T> {
-    pub unsafe fn new(slice: &[T]) -> Self {
+    // Takes a raw slice rather than a reference, as the elements are moved (and dropped) through these pointers
+    pub unsafe fn new(slice: *mut [T]) -> Self {
+        let start = slice as *const T;
+
         Self {
-            start: slice.as_ptr(),
+            start,
+            // Zero-sized types can't be offset, so the length is encoded in the address of `end`
             end: if mem::size_of::<T>() == 0 {
-                ((slice.as_ptr() as usize) + slice.len()) as *const _
-            } else if slice.len() == 0 {
-                slice.as_ptr()
+                start.wrapping_byte_add(slice.len())
             } else {
-                slice.as_ptr().offset(slice.len() as isize)
+                start.add(slice.len())
             },
         }
     }
+
+    // Drops every element that wasn't yielded, leaving the iterator empty. The drop glue of slices keeps dropping
+    // the other elements if one destructor panics, so nothing is leaked
+    pub fn drop_remaining(&mut self) {
+        let (len, _) = self.size_hint();
+        let remaining = ptr::slice_from_raw_parts_mut(self.start as *mut T, len);
+
+        self.end = self.start;
+
+        unsafe { ptr::drop_in_place(remaining) }
+    }
+
+    // The elements that weren't yielded yet
+    pub fn as_raw_slice(&self) -> *const [T] {
+        let (len, _) = self.size_hint();
+        ptr::slice_from_raw_parts(self.start, len)
+    }
+
+    // Leaves the iterator empty without dropping the remaining elements, once they have been moved out
+    pub unsafe fn forget_remaining(&mut self) {
+        self.end = self.start;
+    }
 }
 
 impl<T> Iterator for RawValIter<T> {
@@ -113,23 +650,25 @@ impl<T> Iterator for RawValIter<T> {
             None
         } else {
             unsafe {
-                let result = ptr::read(self.start);
-                self.start = if mem::size_of::<T>() == 0 {
-                    (self.start as usize + 1) as *const _
+                if mem::size_of::<T>() == 0 {
+                    // `start` is kept as is, so reads always go through the original aligned pointer
+                    self.end = self.end.wrapping_byte_sub(1);
+                    Some(ptr::read(self.start))
                 } else {
-                    self.start.offset(1)
-                };
-
-                Some(result)
+                    let result = ptr::read(self.start);
+                    self.start = self.start.add(1);
+                    Some(result)
+                }
             }
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let elem_size = mem::size_of::<T>();
-
-        let len =
-            (self.end as usize - self.start as usize) / if elem_size == 0 { 1 } else { elem_size };
+        let len = if mem::size_of::<T>() == 0 {
+            self.end.addr() - self.start.addr()
+        } else {
+            unsafe { self.end.offset_from_unsigned(self.start) }
+        };
 
         (len, Some(len))
     }
@@ -141,13 +680,13 @@ impl<T> DoubleEndedIterator for RawValIter<T> {
             None
         } else {
             unsafe {
-                self.start = if mem::size_of::<T>() == 0 {
-                    (self.end as usize - 1) as *const _
+                if mem::size_of::<T>() == 0 {
+                    self.end = self.end.wrapping_byte_sub(1);
+                    Some(ptr::read(self.start))
                 } else {
-                    self.end.offset(-1)
-                };
-
-                Some(ptr::read(self.end))
+                    self.end = self.end.sub(1);
+                    Some(ptr::read(self.end))
+                }
             }
         }
     }
@@ -0,0 +1,170 @@
+use std::{
+    alloc::Allocator,
+    ops::{Add, Mul},
+    simd::{
+        cmp::SimdOrd,
+        num::{SimdFloat, SimdInt, SimdUint},
+        Simd,
+    },
+};
+
+use crate::{growth::GrowthPolicy, Vec};
+
+/// Primitive numbers that [`Vec::sum`], [`Vec::min`], [`Vec::max`], [`Vec::mean`] and [`Vec::dot`] reduce with
+/// explicit SIMD, a whole vector of elements at a time, instead of relying on the autovectorization of iterator
+/// chains.
+///
+/// Integer sums and dot products wrap on overflow. Float sums add the lanes in a different order than a sequential
+/// loop, so their rounding may differ slightly, and float minimums and maximums ignore NaNs.
+pub trait Numeric: Copy + PartialOrd {
+    #[doc(hidden)]
+    fn sum(slice: &[Self]) -> Self;
+    #[doc(hidden)]
+    fn min(slice: &[Self]) -> Option<Self>;
+    #[doc(hidden)]
+    fn max(slice: &[Self]) -> Option<Self>;
+    #[doc(hidden)]
+    fn mean(slice: &[Self]) -> Option<f64>;
+    #[doc(hidden)]
+    fn dot(a: &[Self], b: &[Self]) -> Self;
+}
+
+// `$add` and `$mul` are the scalar operations matching the SIMD ones for the tails: wrapping for integers
+macro_rules! impl_numeric {
+    ( $add:ident, $mul:ident; $( $t:ty => $lanes:literal ),* ) => {
+        $(
+            impl Numeric for $t {
+                fn sum(slice: &[Self]) -> Self {
+                    let chunks = slice.chunks_exact($lanes);
+                    let tail = chunks.remainder();
+
+                    let acc = chunks.fold(Simd::<$t, $lanes>::splat(0 as $t), |acc, chunk| {
+                        acc + Simd::from_slice(chunk)
+                    });
+                    tail.iter().fold(acc.reduce_sum(), |acc, &x| <$t>::$add(acc, x))
+                }
+
+                fn min(slice: &[Self]) -> Option<Self> {
+                    let chunks = slice.chunks_exact($lanes);
+                    let tail = chunks.remainder();
+
+                    let acc = chunks
+                        .map(Simd::<$t, $lanes>::from_slice)
+                        .reduce(|acc, chunk| acc.simd_min(chunk))
+                        .map(|acc| acc.reduce_min());
+                    tail.iter().fold(acc, |acc, &x| Some(acc.map_or(x, |acc| acc.min(x))))
+                }
+
+                fn max(slice: &[Self]) -> Option<Self> {
+                    let chunks = slice.chunks_exact($lanes);
+                    let tail = chunks.remainder();
+
+                    let acc = chunks
+                        .map(Simd::<$t, $lanes>::from_slice)
+                        .reduce(|acc, chunk| acc.simd_max(chunk))
+                        .map(|acc| acc.reduce_max());
+                    tail.iter().fold(acc, |acc, &x| Some(acc.map_or(x, |acc| acc.max(x))))
+                }
+
+                fn mean(slice: &[Self]) -> Option<f64> {
+                    if slice.is_empty() {
+                        return None;
+                    }
+
+                    // Summed as floats, so integers can't overflow
+                    let chunks = slice.chunks_exact($lanes);
+                    let tail = chunks.remainder();
+
+                    let acc = chunks.fold(Simd::<f64, $lanes>::splat(0.0), |acc, chunk| {
+                        acc + Simd::<$t, $lanes>::from_slice(chunk).cast::<f64>()
+                    });
+                    let sum = tail.iter().fold(acc.reduce_sum(), |acc, &x| acc + x as f64);
+
+                    Some(sum / slice.len() as f64)
+                }
+
+                fn dot(a: &[Self], b: &[Self]) -> Self {
+                    let a = a.chunks_exact($lanes);
+                    let b = b.chunks_exact($lanes);
+                    let tail = a.remainder().iter().zip(b.remainder());
+
+                    let acc = a.zip(b).fold(Simd::<$t, $lanes>::splat(0 as $t), |acc, (a, b)| {
+                        acc + Simd::from_slice(a) * Simd::from_slice(b)
+                    });
+                    tail.fold(acc.reduce_sum(), |acc, (&a, &b)| <$t>::$add(acc, <$t>::$mul(a, b)))
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric!(wrapping_add, wrapping_mul; u8 => 32, i8 => 32, u16 => 16, i16 => 16, u32 => 8, i32 => 8);
+impl_numeric!(wrapping_add, wrapping_mul; u64 => 4, i64 => 4, usize => 4, isize => 4);
+impl_numeric!(add, mul; f32 => 8, f64 => 4);
+
+impl<T: Numeric, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Returns the sum of the elements, zero if there are none. See [`Numeric`] for overflow and rounding.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend(1..=100u32);
+    /// assert_eq!(v.sum(), 5050);
+    /// ```
+    pub fn sum(&self) -> T {
+        T::sum(self)
+    }
+
+    /// Returns the smallest element, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([3.5f32, -1.0, 2.0]);
+    /// assert_eq!(v.min(), Some(-1.0));
+    /// ```
+    pub fn min(&self) -> Option<T> {
+        T::min(self)
+    }
+
+    /// Returns the largest element, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([3i8, -1, 2]);
+    /// assert_eq!(v.max(), Some(3));
+    /// ```
+    pub fn max(&self) -> Option<T> {
+        T::max(self)
+    }
+
+    /// Returns the arithmetic mean of the elements as a float, or `None` if the vector is empty. The elements are
+    /// summed as `f64`, so integer sums can't overflow.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([u8::MAX; 4]);
+    /// v.push(0);
+    /// assert_eq!(v.mean(), Some(204.0));
+    /// ```
+    pub fn mean(&self) -> Option<f64> {
+        T::mean(self)
+    }
+
+    /// Returns the dot product of the vector with a slice of the same length: the sum of the products of their
+    /// elements pairwise.
+    /// # Panics
+    /// This function will panic if the lengths differ.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([1.0f64, 2.0, 3.0]);
+    /// assert_eq!(v.dot(&[4.0, 5.0, 6.0]), 32.0);
+    /// ```
+    pub fn dot(&self, other: &[T]) -> T {
+        assert_eq!(
+            self.len,
+            other.len(),
+            "dot product of slices of different lengths"
+        );
+        T::dot(self, other)
+    }
+}
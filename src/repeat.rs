@@ -0,0 +1,126 @@
+use std::{alloc::Allocator, iter, mem, ptr, slice};
+
+use crate::{growth::GrowthPolicy, raw::handle_reserve_error, TryReserveError, Vec};
+
+impl<T: Clone, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Returns a vector holding the elements of this one repeated `n` times, allocated once.
+    ///
+    /// Vectors of primitive integers, floats, `bool`s or `char`s are written by doubling: the part already written is
+    /// copied after itself until the vector is full, which takes O(log n) copies instead of `n`. Other elements are
+    /// cloned one by one.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([1, 2]);
+    /// assert_eq!(&*v.repeat(3), &[1, 2, 1, 2, 1, 2]);
+    /// ```
+    pub fn repeat(&self, n: usize) -> Vec<T> {
+        let total = self
+            .len
+            .checked_mul(n)
+            .unwrap_or_else(|| handle_reserve_error(TryReserveError::CapacityOverflow));
+
+        let mut repeated = Vec::with_capacity(total);
+        repeated.spec_repeat(self, n);
+        repeated
+    }
+}
+
+/// Creates a vector of `n` clones of `elem`, behind `custom_vec![elem; n]`.
+///
+/// The last element is `elem` itself, moved instead of cloned. Vectors of zeroes of a primitive type are zeroed by the
+/// allocator instead of being written element by element.
+#[doc(hidden)]
+pub fn from_elem<T: Clone>(elem: T, n: usize) -> Vec<T> {
+    T::spec_from_elem(elem, n)
+}
+
+trait SpecFromElem: Sized {
+    fn spec_from_elem(elem: Self, n: usize) -> Vec<Self>;
+}
+
+impl<T: Clone> SpecFromElem for T {
+    default fn spec_from_elem(elem: Self, n: usize) -> Vec<Self> {
+        let mut vec = Vec::with_capacity(n);
+        vec.extend(iter::repeat_n(elem, n));
+        vec
+    }
+}
+
+// Like copying, zeroing is only specialized for concrete types
+macro_rules! impl_spec_from_elem_zeroable {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl SpecFromElem for $t {
+                fn spec_from_elem(elem: Self, n: usize) -> Vec<Self> {
+                    // Compares the bytes rather than the values, `-0.0` isn't made of zeroes
+                    let bytes = unsafe {
+                        slice::from_raw_parts(&elem as *const Self as *const u8, mem::size_of::<Self>())
+                    };
+                    if bytes.iter().all(|&byte| byte == 0) {
+                        return Vec::from_zeroes(n);
+                    }
+
+                    let mut vec = Vec::with_capacity(n);
+                    vec.extend(iter::repeat_n(elem, n));
+                    vec
+                }
+            }
+        )*
+    };
+}
+
+impl_spec_from_elem_zeroable!(
+    u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64, bool, char
+);
+
+// Fills an empty vector with the capacity for `n` copies of `src`, copying instead of cloning when possible
+trait SpecRepeat<T> {
+    fn spec_repeat(&mut self, src: &[T], n: usize);
+}
+
+impl<T: Clone> SpecRepeat<T> for Vec<T> {
+    default fn spec_repeat(&mut self, src: &[T], n: usize) {
+        self.extend(iter::repeat_n(src, n).flatten().cloned());
+    }
+}
+
+// Copying is only specialized for concrete types, `Copy` itself can't be specialized on
+macro_rules! impl_spec_repeat_copy {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl SpecRepeat<$t> for Vec<$t> {
+                fn spec_repeat(&mut self, src: &[$t], n: usize) {
+                    repeat_by_doubling(self, src, n)
+                }
+            }
+        )*
+    };
+}
+
+impl_spec_repeat_copy!(
+    u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64, bool, char
+);
+
+fn repeat_by_doubling<T: Copy>(vec: &mut Vec<T>, src: &[T], n: usize) {
+    let total = src.len() * n;
+    if total == 0 {
+        return;
+    }
+
+    unsafe {
+        let dst = vec.as_mut_ptr();
+        ptr::copy_nonoverlapping(src.as_ptr(), dst, src.len());
+
+        let mut written = src.len();
+        while written < total {
+            let count = (total - written).min(written);
+            ptr::copy_nonoverlapping(dst, dst.add(written), count);
+            written += count;
+        }
+
+        vec.set_len(total);
+    }
+}
@@ -0,0 +1,542 @@
+use std::{
+    fmt::{self, Write},
+    ops::{Range, RangeBounds},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{raw::resolve_range, String, Vec};
+
+// Largest chunk built from inserted text. Chunks split by edits may be smaller, and in-place inserts fill them back up
+const MAX_CHUNK: usize = 1024;
+
+/// Text stored as a balanced tree of UTF-8 chunks, each one a [`String`] (so a [`Vec<u8>`](Vec)) of at most 1 KiB.
+///
+/// Inserting, removing, splitting and concatenating take O(log n) instead of moving the whole text, which makes it
+/// suited to editing multi-megabyte documents. Positions are byte indices, with conversions from and to char indices.
+/// # Example
+/// ```
+/// use vec::Rope;
+///
+/// let mut rope = Rope::from("hello world");
+/// rope.insert(5, ",");
+/// rope.remove(7..);
+/// rope.append(Rope::from("rope"));
+///
+/// assert_eq!(rope, "hello, rope");
+/// assert_eq!(rope.char_to_byte(7), 7);
+/// ```
+#[derive(Clone, Default)]
+pub struct Rope {
+    root: Option<Box<Node>>,
+}
+
+// Treap node: in-order traversal gives the chunks in text order, and priorities form a max-heap so the tree stays
+// balanced with high probability whatever the order of edits
+#[derive(Clone)]
+struct Node {
+    chunk: String,
+    priority: u64,
+    // totals of the whole subtree
+    bytes: usize,
+    chars: usize,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+impl Node {
+    fn leaf(text: &str) -> Box<Node> {
+        Box::new(Node {
+            chunk: String::from(text),
+            priority: next_priority(),
+            bytes: text.len(),
+            chars: text.chars().count(),
+            left: None,
+            right: None,
+        })
+    }
+
+    // Recomputes the totals after a child changed
+    fn update(&mut self) {
+        self.bytes = bytes(&self.left) + self.chunk.len() + bytes(&self.right);
+        self.chars = chars(&self.left) + self.chunk.chars().count() + chars(&self.right);
+    }
+}
+
+fn bytes(node: &Option<Box<Node>>) -> usize {
+    node.as_ref().map_or(0, |n| n.bytes)
+}
+
+fn chars(node: &Option<Box<Node>>) -> usize {
+    node.as_ref().map_or(0, |n| n.chars)
+}
+
+// Pseudo-random priorities from a shared counter, scrambled with splitmix64
+fn next_priority() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut z = COUNTER
+        .fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)
+        .wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Joins two trees, every chunk of `left` coming before those of `right`
+fn merge(left: Option<Box<Node>>, right: Option<Box<Node>>) -> Option<Box<Node>> {
+    match (left, right) {
+        (None, node) | (node, None) => node,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+// Splits a tree into the text before byte `at` and the text from it, cutting a chunk in two if needed
+fn split(node: Option<Box<Node>>, at: usize) -> (Option<Box<Node>>, Option<Box<Node>>) {
+    let Some(mut node) = node else {
+        return (None, None);
+    };
+
+    let left_bytes = bytes(&node.left);
+    let chunk_end = left_bytes + node.chunk.len();
+
+    if at <= left_bytes {
+        let (left, right) = split(node.left.take(), at);
+        node.left = right;
+        node.update();
+        (left, Some(node))
+    } else if at >= chunk_end {
+        let (left, right) = split(node.right.take(), at - chunk_end);
+        node.right = left;
+        node.update();
+        (Some(node), right)
+    } else {
+        // The node keeps the head of its chunk and its left subtree, the tail joins the right subtree
+        let offset = at - left_bytes;
+        let tail = Node::leaf(&node.chunk[offset..]);
+        node.chunk.truncate(offset);
+
+        let right = merge(Some(tail), node.right.take());
+        node.update();
+        (Some(node), right)
+    }
+}
+
+// Builds a tree from text, cut into chunks on char boundaries
+fn build(text: &str) -> Option<Box<Node>> {
+    let mut root = None;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let mut end = rest.len().min(MAX_CHUNK);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        root = merge(root, Some(Node::leaf(&rest[..end])));
+        rest = &rest[end..];
+    }
+
+    root
+}
+
+// Inserts text inside the chunk holding byte `at` if it fits there, updating the totals on the way back up
+fn insert_in_place(node: &mut Node, at: usize, text: &str) -> bool {
+    let left_bytes = bytes(&node.left);
+    let chunk_end = left_bytes + node.chunk.len();
+
+    let done = if at < left_bytes {
+        node.left
+            .as_deref_mut()
+            .is_some_and(|left| insert_in_place(left, at, text))
+    } else if at <= chunk_end {
+        let fits = node.chunk.len() + text.len() <= MAX_CHUNK;
+        if fits {
+            node.chunk.insert_str(at - left_bytes, text);
+        }
+        fits
+    } else {
+        node.right
+            .as_deref_mut()
+            .is_some_and(|right| insert_in_place(right, at - chunk_end, text))
+    };
+
+    if done {
+        node.bytes += text.len();
+        node.chars += text.chars().count();
+    }
+
+    done
+}
+
+impl Rope {
+    /// Creates an empty rope, without allocating.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::new();
+    /// assert!(rope.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    /// Returns the length of the text in bytes.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("héllo");
+    /// assert_eq!(rope.len_bytes(), 6);
+    /// ```
+    pub fn len_bytes(&self) -> usize {
+        bytes(&self.root)
+    }
+
+    /// Returns the length of the text in chars.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("héllo");
+    /// assert_eq!(rope.len_chars(), 5);
+    /// ```
+    pub fn len_chars(&self) -> usize {
+        chars(&self.root)
+    }
+
+    /// Returns `true` if the rope holds no text.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("");
+    /// assert!(rope.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Inserts text at byte `index`.
+    /// # Panics
+    /// Panics if `index` is greater than the length or isn't on a char boundary.
+    /// # Example
+    /// ```
+    /// let mut rope = vec::Rope::from("held");
+    /// rope.insert(2, "llo wor");
+    /// assert_eq!(rope, "hello world");
+    /// ```
+    pub fn insert(&mut self, index: usize, text: &str) {
+        self.assert_char_boundary(index);
+
+        if text.is_empty() {
+            return;
+        }
+
+        if let Some(root) = self.root.as_deref_mut() {
+            if insert_in_place(root, index, text) {
+                return;
+            }
+        }
+
+        let (left, right) = split(self.root.take(), index);
+        self.root = merge(merge(left, build(text)), right);
+    }
+
+    /// Removes the text in the byte `range`.
+    /// # Panics
+    /// Panics if the range is out of bounds or its ends aren't on char boundaries.
+    /// # Example
+    /// ```
+    /// let mut rope = vec::Rope::from("hello world");
+    /// rope.remove(1..5);
+    /// assert_eq!(rope, "h world");
+    /// rope.remove(..2);
+    /// assert_eq!(rope, "world");
+    /// ```
+    pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+        let Range { start, end } = resolve_range(range, self.len_bytes());
+        self.assert_char_boundary(start);
+        self.assert_char_boundary(end);
+
+        let (left, rest) = split(self.root.take(), start);
+        let (_, right) = split(rest, end - start);
+        self.root = merge(left, right);
+    }
+
+    /// Splits the rope in two at byte `index`, keeping the text before it and returning the rest.
+    /// # Panics
+    /// Panics if `index` is greater than the length or isn't on a char boundary.
+    /// # Example
+    /// ```
+    /// let mut rope = vec::Rope::from("hello world");
+    /// let tail = rope.split_off(5);
+    /// assert_eq!(rope, "hello");
+    /// assert_eq!(tail, " world");
+    /// ```
+    pub fn split_off(&mut self, index: usize) -> Rope {
+        self.assert_char_boundary(index);
+
+        let (left, right) = split(self.root.take(), index);
+        self.root = left;
+
+        Rope { root: right }
+    }
+
+    /// Appends the text of another rope, without copying it.
+    /// # Example
+    /// ```
+    /// let mut rope = vec::Rope::from("hello");
+    /// rope.append(vec::Rope::from(" world"));
+    /// assert_eq!(rope, "hello world");
+    /// ```
+    pub fn append(&mut self, other: Rope) {
+        self.root = merge(self.root.take(), other.root);
+    }
+
+    /// Returns the byte at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("hi");
+    /// assert_eq!(rope.byte(1), Some(b'i'));
+    /// assert_eq!(rope.byte(2), None);
+    /// ```
+    pub fn byte(&self, index: usize) -> Option<u8> {
+        let (chunk, offset) = self.chunk_at_byte(index)?;
+        Some(chunk.as_bytes()[offset])
+    }
+
+    /// Returns the char at char `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("héllo");
+    /// assert_eq!(rope.char(1), Some('é'));
+    /// assert_eq!(rope.char(5), None);
+    /// ```
+    pub fn char(&self, index: usize) -> Option<char> {
+        let (chunk, offset) = self.chunk_at_char(index)?;
+        chunk.chars().nth(offset)
+    }
+
+    /// Returns the byte index of the char at char `index`. The char length maps to the byte length.
+    /// # Panics
+    /// Panics if `index` is greater than the length in chars.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("héllo");
+    /// assert_eq!(rope.char_to_byte(2), 3);
+    /// assert_eq!(rope.char_to_byte(5), 6);
+    /// ```
+    pub fn char_to_byte(&self, index: usize) -> usize {
+        assert!(index <= self.len_chars(), "index out of bounds");
+
+        let mut node = self.root.as_deref();
+        let (mut index, mut base) = (index, 0);
+
+        while let Some(n) = node {
+            let (left_chars, chunk_chars) = (chars(&n.left), n.chunk.chars().count());
+
+            if index < left_chars {
+                node = n.left.as_deref();
+            } else if index < left_chars + chunk_chars {
+                let offset = n.chunk.char_indices().nth(index - left_chars).unwrap().0;
+                return base + bytes(&n.left) + offset;
+            } else {
+                index -= left_chars + chunk_chars;
+                base += bytes(&n.left) + n.chunk.len();
+                node = n.right.as_deref();
+            }
+        }
+
+        base
+    }
+
+    /// Returns the char index of the char starting at byte `index`. The byte length maps to the char length.
+    /// # Panics
+    /// Panics if `index` is greater than the length or isn't on a char boundary.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("héllo");
+    /// assert_eq!(rope.byte_to_char(3), 2);
+    /// assert_eq!(rope.byte_to_char(6), 5);
+    /// ```
+    pub fn byte_to_char(&self, index: usize) -> usize {
+        self.assert_char_boundary(index);
+
+        let mut node = self.root.as_deref();
+        let (mut index, mut base) = (index, 0);
+
+        while let Some(n) = node {
+            let left_bytes = bytes(&n.left);
+
+            if index < left_bytes {
+                node = n.left.as_deref();
+            } else if index < left_bytes + n.chunk.len() {
+                let offset = n.chunk[..index - left_bytes].chars().count();
+                return base + chars(&n.left) + offset;
+            } else {
+                index -= left_bytes + n.chunk.len();
+                base += chars(&n.left) + n.chunk.chars().count();
+                node = n.right.as_deref();
+            }
+        }
+
+        base
+    }
+
+    /// Returns an iterator over the chunks of text, in order, borrowing them without copying.
+    /// # Example
+    /// ```
+    /// let mut rope = vec::Rope::from("hello");
+    /// rope.append(vec::Rope::from(" world"));
+    /// assert_eq!(rope.chunks().collect::<String>(), "hello world");
+    /// ```
+    pub fn chunks(&self) -> Chunks<'_> {
+        let mut chunks = Chunks { stack: Vec::new() };
+        chunks.push_left_spine(self.root.as_deref());
+        chunks
+    }
+
+    /// Returns an iterator over the chars of the text.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("héllo");
+    /// assert_eq!(rope.chars().nth(1), Some('é'));
+    /// ```
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.chunks().flat_map(str::chars)
+    }
+
+    /// Returns an iterator over the bytes of the text.
+    /// # Example
+    /// ```
+    /// let rope = vec::Rope::from("hi");
+    /// assert_eq!(rope.bytes().collect::<std::vec::Vec<_>>(), b"hi");
+    /// ```
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.chunks().flat_map(str::bytes)
+    }
+
+    // Chunk holding byte `index`, with the offset of the byte in it
+    fn chunk_at_byte(&self, mut index: usize) -> Option<(&str, usize)> {
+        let mut node = self.root.as_deref();
+
+        while let Some(n) = node {
+            let left_bytes = bytes(&n.left);
+
+            if index < left_bytes {
+                node = n.left.as_deref();
+            } else if index < left_bytes + n.chunk.len() {
+                return Some((&n.chunk, index - left_bytes));
+            } else {
+                index -= left_bytes + n.chunk.len();
+                node = n.right.as_deref();
+            }
+        }
+
+        None
+    }
+
+    // Chunk holding char `index`, with the offset in chars of the char in it
+    fn chunk_at_char(&self, mut index: usize) -> Option<(&str, usize)> {
+        let mut node = self.root.as_deref();
+
+        while let Some(n) = node {
+            let (left_chars, chunk_chars) = (chars(&n.left), n.chunk.chars().count());
+
+            if index < left_chars {
+                node = n.left.as_deref();
+            } else if index < left_chars + chunk_chars {
+                return Some((&n.chunk, index - left_chars));
+            } else {
+                index -= left_chars + chunk_chars;
+                node = n.right.as_deref();
+            }
+        }
+
+        None
+    }
+
+    fn assert_char_boundary(&self, index: usize) {
+        assert!(index <= self.len_bytes(), "index out of bounds");
+
+        // Chunks always start on a char boundary, so only an index inside a chunk needs checking
+        if let Some((chunk, offset)) = self.chunk_at_byte(index) {
+            assert!(
+                chunk.is_char_boundary(offset),
+                "index {} isn't on a char boundary",
+                index
+            );
+        }
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Self { root: build(text) }
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for chunk in self.chunks() {
+            write!(f, "{}", chunk.escape_debug())?;
+        }
+        f.write_char('"')
+    }
+}
+
+impl PartialEq for Rope {
+    fn eq(&self, other: &Self) -> bool {
+        self.len_bytes() == other.len_bytes() && self.bytes().eq(other.bytes())
+    }
+}
+
+impl Eq for Rope {}
+
+impl PartialEq<str> for Rope {
+    fn eq(&self, other: &str) -> bool {
+        self.len_bytes() == other.len() && self.bytes().eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for Rope {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+/// Iterator over the chunks of text of a [`Rope`], created by [`Rope::chunks`].
+pub struct Chunks<'a> {
+    // nodes whose chunk and right subtree are still to be visited, the next one on top
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Chunks<'a> {
+    fn push_left_spine(&mut self, mut node: Option<&'a Node>) {
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.left.as_deref();
+        }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right.as_deref());
+
+        Some(&node.chunk)
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    alloc::Global,
+    fmt,
+    ops::{Index, IndexMut},
+    ptr, slice,
+};
+
+use crate::{
+    growth::Doubling,
+    raw::{handle_reserve_error, RawVec},
+    Vec,
+};
+
+/// Vector storing its elements in separately allocated chunks of `N` elements (32 by default). Growing adds a chunk
+/// instead of reallocating, so elements never move once pushed, and indexing stays O(1).
+/// # Example
+/// ```
+/// use vec::SegmentedVec;
+///
+/// let mut v = SegmentedVec::<u32, 4>::new();
+/// v.extend(0..10);
+/// let first = &v[0] as *const u32;
+///
+/// v.extend(10..100);
+/// assert_eq!(&v[0] as *const u32, first);
+/// assert_eq!(v[42], 42);
+/// assert_eq!(v.chunks().count(), 25);
+/// ```
+pub struct SegmentedVec<T, const N: usize = 32> {
+    // every chunk holds room for exactly `N` elements, element `i` lives in chunk `i / N` at offset `i % N`
+    chunks: Vec<RawVec<T>>,
+    len: usize,
+}
+
+impl<T, const N: usize> SegmentedVec<T, N> {
+    /// Creates an empty vector, without allocating.
+    /// # Panics
+    /// Panics if `N` is zero.
+    /// # Example
+    /// ```
+    /// let v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        assert!(N > 0, "chunk size must be non-zero");
+
+        Self {
+            chunks: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the allocated chunks can hold.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SegmentedVec::<i32, 8>::new();
+    /// v.push(1);
+    /// assert_eq!(v.capacity(), 8);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.chunks.len * N
+    }
+
+    /// Appends an element, allocating a new chunk if the last one is full. Existing elements aren't moved.
+    /// # Panics
+    /// This function will panic if the chunk size exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// v.push(1);
+    /// assert_eq!(v[0], 1);
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        let (chunk, offset) = (self.len / N, self.len % N);
+
+        if chunk == self.chunks.len {
+            match RawVec::try_with_capacity_in(N, Doubling, Global) {
+                Ok(buf) => self.chunks.push(buf),
+                Err(err) => handle_reserve_error(err),
+            }
+        }
+
+        unsafe { ptr::write(self.chunks[chunk].ptr.as_ptr().add(offset), elem) };
+        self.len += 1;
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty. Emptied chunks are kept for
+    /// later pushes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.pop(), Some(1));
+    /// assert_eq!(v.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.slot(self.len))) }
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// v.push(1);
+    /// assert_eq!(v.get(0), Some(&1));
+    /// assert_eq!(v.get(1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            unsafe { Some(&*self.slot(index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// v.push(1);
+    /// *v.get_mut(0).unwrap() = 2;
+    /// assert_eq!(v[0], 2);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            unsafe { Some(&mut *self.slot(index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the filled part of every chunk, as slices.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SegmentedVec::<i32, 2>::new();
+    /// v.extend(0..3);
+    /// let chunks: std::vec::Vec<_> = v.chunks().collect();
+    /// assert_eq!(chunks, [&[0, 1][..], &[2][..]]);
+    /// ```
+    pub fn chunks(&self) -> impl Iterator<Item = &[T]> + '_ {
+        self.chunks
+            .iter()
+            .enumerate()
+            .map(move |(i, chunk)| unsafe {
+                slice::from_raw_parts(chunk.ptr.as_ptr(), filled::<N>(self.len, i))
+            })
+    }
+
+    /// Returns an iterator over the filled part of every chunk, as mutable slices.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SegmentedVec::<i32, 2>::new();
+    /// v.extend(0..3);
+    /// v.chunks_mut().for_each(|chunk| chunk.fill(7));
+    /// assert_eq!(v[2], 7);
+    /// ```
+    pub fn chunks_mut(&mut self) -> impl Iterator<Item = &mut [T]> + '_ {
+        let len = self.len;
+
+        self.chunks
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, chunk)| unsafe {
+                slice::from_raw_parts_mut(chunk.ptr.as_ptr(), filled::<N>(len, i))
+            })
+    }
+
+    /// Returns an iterator over the elements.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SegmentedVec::<i32, 2>::new();
+    /// v.extend(1..=3);
+    /// assert_eq!(v.iter().sum::<i32>(), 6);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.chunks().flatten()
+    }
+
+    /// Returns an iterator over mutable references to the elements.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SegmentedVec::<i32, 2>::new();
+    /// v.extend(1..=3);
+    /// v.iter_mut().for_each(|x| *x *= 10);
+    /// assert_eq!(v[2], 30);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.chunks_mut().flatten()
+    }
+
+    /// Removes every element, keeping the allocated chunks.
+    /// # Example
+    /// ```
+    /// let mut v: vec::SegmentedVec<i32> = vec::SegmentedVec::new();
+    /// v.push(1);
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.capacity(), 32);
+    /// ```
+    pub fn clear(&mut self) {
+        let len = self.len;
+
+        // Reset first, so a panicking destructor can't cause an element to be dropped twice
+        self.len = 0;
+
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            let filled = ptr::slice_from_raw_parts_mut(chunk.ptr.as_ptr(), filled::<N>(len, i));
+            unsafe { ptr::drop_in_place(filled) }
+        }
+    }
+
+    // Pointer to the slot of element `index`, whose chunk must be allocated
+    fn slot(&self, index: usize) -> *mut T {
+        unsafe { self.chunks[index / N].ptr.as_ptr().add(index % N) }
+    }
+}
+
+// Number of elements in chunk `i` of a vector of length `len`
+fn filled<const N: usize>(len: usize, i: usize) -> usize {
+    len.saturating_sub(i * N).min(N)
+}
+
+impl<T, const N: usize> Default for SegmentedVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for SegmentedVec<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for SegmentedVec<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SegmentedVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SegmentedVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut v = Self::new();
+        v.extend(self.iter().cloned());
+        v
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SegmentedVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for SegmentedVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SegmentedVec<T, N> {}
+
+impl<T, const N: usize> Drop for SegmentedVec<T, N> {
+    fn drop(&mut self) {
+        for chunk in self.chunks_mut() {
+            unsafe { ptr::drop_in_place(chunk) }
+        }
+        // Deallocation is handled by the RawVec of each chunk
+    }
+}
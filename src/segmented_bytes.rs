@@ -0,0 +1,321 @@
+use std::{
+    cmp, fmt,
+    io::{self, IoSlice},
+};
+
+use crate::{Vec, VecDeque};
+
+/// Queue of byte buffers read as one byte sequence, without concatenating them: a write queue for sockets.
+///
+/// Pushed buffers are kept as they are. [`io_slices`](SegmentedBytes::io_slices) describes the unread bytes for
+/// [`write_vectored`](io::Write::write_vectored), and [`advance`](SegmentedBytes::advance) consumes the written
+/// prefix, dropping the buffers it covers entirely.
+/// # Example
+/// ```
+/// use vec::SegmentedBytes;
+///
+/// let mut queue = SegmentedBytes::new();
+/// queue.push_slice(b"HTTP/1.1 200 OK\r\n");
+/// queue.push_slice(b"\r\n");
+/// queue.push_slice(b"hello");
+///
+/// let mut socket = std::vec::Vec::new();
+/// while !queue.is_empty() {
+///     queue.write_to(&mut socket).unwrap();
+/// }
+///
+/// assert_eq!(socket, b"HTTP/1.1 200 OK\r\n\r\nhello");
+/// assert_eq!(queue.segment_count(), 0);
+/// ```
+#[derive(Default)]
+pub struct SegmentedBytes {
+    // non-empty buffers, the first one read from `head`
+    segments: VecDeque<Vec<u8>>,
+    head: usize,
+    len: usize,
+}
+
+impl SegmentedBytes {
+    /// Creates an empty queue, without allocating.
+    /// # Example
+    /// ```
+    /// let queue = vec::SegmentedBytes::new();
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            segments: VecDeque::new(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of unread bytes.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.push_slice(b"de");
+    /// assert_eq!(queue.len(), 5);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no unread bytes.
+    /// # Example
+    /// ```
+    /// let queue = vec::SegmentedBytes::new();
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of buffers holding unread bytes.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.push_slice(b"");
+    /// assert_eq!(queue.segment_count(), 1);
+    /// ```
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Appends a buffer without copying it. Empty buffers are dropped.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut buf = vec::Vec::new();
+    /// buf.insert_slice(0, b"chunk");
+    ///
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push(buf);
+    /// assert_eq!(queue.len(), 5);
+    /// ```
+    pub fn push(&mut self, segment: Vec<u8>) {
+        if segment.len > 0 {
+            self.len += segment.len;
+            self.segments.push_back(segment);
+        }
+    }
+
+    /// Appends a copy of `bytes` as a new buffer.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// assert_eq!(queue.chunk(), b"abc");
+    /// ```
+    pub fn push_slice(&mut self, bytes: &[u8]) {
+        let mut segment = Vec::with_capacity(bytes.len());
+        segment.insert_slice(0, bytes);
+        self.push(segment);
+    }
+
+    /// Returns the unread bytes of the first buffer, empty if there are none.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.push_slice(b"de");
+    /// queue.advance(1);
+    /// assert_eq!(queue.chunk(), b"bc");
+    /// ```
+    pub fn chunk(&self) -> &[u8] {
+        match self.segments.front() {
+            Some(segment) => &segment[self.head..],
+            None => &[],
+        }
+    }
+
+    /// Returns an iterator over the unread bytes of every buffer, in order.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.push_slice(b"de");
+    /// queue.advance(2);
+    /// assert_eq!(queue.chunks().collect::<std::vec::Vec<_>>(), [&b"c"[..], &b"de"[..]]);
+    /// ```
+    pub fn chunks(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.segments.iter().enumerate().map(move |(i, segment)| {
+            if i == 0 {
+                &segment[self.head..]
+            } else {
+                &segment[..]
+            }
+        })
+    }
+
+    /// Fills `dst` with slices of the unread bytes, one per buffer from the first one, and returns how many were
+    /// written. Stops early when `dst` is full.
+    /// # Example
+    /// ```
+    /// use std::io::IoSlice;
+    ///
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.push_slice(b"de");
+    ///
+    /// let mut slices = [IoSlice::new(&[]); 4];
+    /// assert_eq!(queue.io_slices(&mut slices), 2);
+    /// assert_eq!(&*slices[1], b"de");
+    /// ```
+    pub fn io_slices<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let mut count = 0;
+        for (slot, chunk) in dst.iter_mut().zip(self.chunks()) {
+            *slot = IoSlice::new(chunk);
+            count += 1;
+        }
+        count
+    }
+
+    /// Consumes the first `count` bytes, dropping the buffers they cover entirely.
+    /// # Panics
+    /// Panics if `count > len`.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.push_slice(b"de");
+    /// queue.advance(4);
+    /// assert_eq!(queue.chunk(), b"e");
+    /// assert_eq!(queue.segment_count(), 1);
+    /// ```
+    pub fn advance(&mut self, mut count: usize) {
+        assert!(
+            count <= self.len,
+            "cannot advance past the end: {} > {}",
+            count,
+            self.len
+        );
+        self.len -= count;
+
+        while count > 0 {
+            let left = self.segments.front().unwrap().len - self.head;
+
+            if count < left {
+                self.head += count;
+                return;
+            }
+
+            count -= left;
+            self.segments.pop_front();
+            self.head = 0;
+        }
+    }
+
+    /// Writes the unread bytes to `writer` with a single [`write_vectored`](io::Write::write_vectored) call of up
+    /// to 64 slices, consumes what was written and returns its size.
+    /// # Errors
+    /// Returns the error of `writer`, consuming nothing.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.push_slice(b"de");
+    ///
+    /// let mut out = std::vec::Vec::new();
+    /// assert_eq!(queue.write_to(&mut out).unwrap(), 5);
+    /// assert_eq!(out, b"abcde");
+    /// ```
+    pub fn write_to<W: io::Write>(&mut self, writer: &mut W) -> io::Result<usize> {
+        let mut slices = [IoSlice::new(&[]); 64];
+        let count = self.io_slices(&mut slices);
+
+        let written = writer.write_vectored(&slices[..count])?;
+        self.advance(written);
+        Ok(written)
+    }
+
+    /// Copies the unread bytes to a single buffer, without consuming them.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"ab");
+    /// queue.push_slice(b"cd");
+    /// assert_eq!(&*queue.to_vec(), b"abcd");
+    /// ```
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut vec = Vec::with_capacity(self.len);
+        for chunk in self.chunks() {
+            vec.insert_slice(vec.len, chunk);
+        }
+        vec
+    }
+
+    /// Drops every buffer.
+    /// # Example
+    /// ```
+    /// let mut queue = vec::SegmentedBytes::new();
+    /// queue.push_slice(b"abc");
+    /// queue.clear();
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+impl From<Vec<u8>> for SegmentedBytes {
+    fn from(segment: Vec<u8>) -> Self {
+        let mut queue = Self::new();
+        queue.push(segment);
+        queue
+    }
+}
+
+impl Extend<Vec<u8>> for SegmentedBytes {
+    fn extend<I: IntoIterator<Item = Vec<u8>>>(&mut self, iter: I) {
+        for segment in iter {
+            self.push(segment);
+        }
+    }
+}
+
+impl io::Write for SegmentedBytes {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Read for SegmentedBytes {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk = self.chunk();
+        let count = cmp::min(buf.len(), chunk.len());
+
+        buf[..count].copy_from_slice(&chunk[..count]);
+        self.advance(count);
+        Ok(count)
+    }
+}
+
+// Only the unread bytes count, however they are split
+impl PartialEq for SegmentedBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.chunks().flatten().eq(other.chunks().flatten())
+    }
+}
+
+impl Eq for SegmentedBytes {}
+
+impl fmt::Debug for SegmentedBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.chunks()).finish()
+    }
+}
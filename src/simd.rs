@@ -0,0 +1,94 @@
+use std::{
+    alloc::Allocator,
+    simd::{cmp::SimdPartialEq, Simd},
+};
+
+use crate::{growth::GrowthPolicy, Vec};
+
+/// Primitive integers that [`Vec::contains`], [`Vec::position`] and [`Vec::fill`] scan with SIMD, comparing or writing
+/// 32 bytes at a time instead of one element at a time.
+///
+/// Vectors of these types pick up the accelerated methods over the generic slice ones without any change to the
+/// calling code.
+pub trait Primitive: Copy + PartialEq {
+    #[doc(hidden)]
+    fn position(slice: &[Self], value: Self) -> Option<usize>;
+    #[doc(hidden)]
+    fn fill(slice: &mut [Self], value: Self);
+}
+
+macro_rules! impl_primitive {
+    ( $( $t:ty => $lanes:literal ),* ) => {
+        $(
+            impl Primitive for $t {
+                fn position(slice: &[Self], value: Self) -> Option<usize> {
+                    // Scalar loops for the unaligned head and tail, whole vectors in between
+                    let (head, body, tail) = slice.as_simd::<$lanes>();
+                    if let Some(i) = head.iter().position(|&x| x == value) {
+                        return Some(i);
+                    }
+
+                    let splat = Simd::splat(value);
+                    for (i, chunk) in body.iter().enumerate() {
+                        if let Some(lane) = chunk.simd_eq(splat).first_set() {
+                            return Some(head.len() + i * $lanes + lane);
+                        }
+                    }
+
+                    let i = tail.iter().position(|&x| x == value)?;
+                    Some(head.len() + body.len() * $lanes + i)
+                }
+
+                fn fill(slice: &mut [Self], value: Self) {
+                    let (head, body, tail) = slice.as_simd_mut::<$lanes>();
+                    head.iter_mut().for_each(|x| *x = value);
+                    body.iter_mut().for_each(|x| *x = Simd::splat(value));
+                    tail.iter_mut().for_each(|x| *x = value);
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(u8 => 32, i8 => 32, u16 => 16, i16 => 16, u32 => 8, i32 => 8, u64 => 4, i64 => 4);
+impl_primitive!(usize => 4, isize => 4);
+
+impl<T: Primitive, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Returns `true` if the vector contains an element equal to `value`, comparing a whole SIMD vector of elements
+    /// at a time.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend(0..1000u32);
+    /// assert!(v.contains(&999));
+    /// assert!(!v.contains(&1000));
+    /// ```
+    pub fn contains(&self, value: &T) -> bool {
+        T::position(self, *value).is_some()
+    }
+
+    /// Returns the index of the first element equal to `value`, or `None` if there is none, comparing a whole SIMD
+    /// vector of elements at a time.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend(b"hello, world".iter().copied());
+    /// assert_eq!(v.position(b','), Some(5));
+    /// assert_eq!(v.position(b'!'), None);
+    /// ```
+    pub fn position(&self, value: T) -> Option<usize> {
+        T::position(self, value)
+    }
+
+    /// Sets every element to `value`, writing a whole SIMD vector of elements at a time.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend(0..100i64);
+    /// v.fill(-1);
+    /// assert!(v.iter().all(|&x| x == -1));
+    /// ```
+    pub fn fill(&mut self, value: T) {
+        T::fill(self, value)
+    }
+}
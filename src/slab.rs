@@ -0,0 +1,283 @@
+use std::{
+    fmt, mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// Storage handing out `usize` keys that stay valid until their entry is removed. Freed slots are reused by later
+/// insertions through a free list, so the keys stay small and dense.
+/// # Example
+/// ```
+/// use vec::Slab;
+///
+/// let mut slab = Slab::new();
+/// let a = slab.insert("a");
+/// let b = slab.insert("b");
+///
+/// assert_eq!(slab.remove(a), "a");
+/// assert_eq!(slab[b], "b");
+///
+/// // The freed slot is reused
+/// assert_eq!(slab.insert("c"), a);
+/// ```
+#[derive(Clone)]
+pub struct Slab<T> {
+    entries: Vec<Entry<T>>,
+    // first vacant slot, `entries.len` if there is none
+    next: usize,
+    len: usize,
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+    Occupied(T),
+    // index of the next vacant slot in the free list
+    Vacant(usize),
+}
+
+impl<T> Slab<T> {
+    /// Creates an empty slab, without allocating.
+    /// # Example
+    /// ```
+    /// let slab: vec::Slab<i32> = vec::Slab::new();
+    /// assert!(slab.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Creates an empty slab with room for `capacity` entries.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let slab: vec::Slab<i32> = vec::Slab::with_capacity(8);
+    /// assert_eq!(slab.capacity(), 8);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values in the slab.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// slab.insert(1);
+    /// assert_eq!(slab.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the slab holds no values.
+    /// # Example
+    /// ```
+    /// let slab: vec::Slab<i32> = vec::Slab::new();
+    /// assert!(slab.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of entries the slab can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// slab.insert(1u64);
+    /// assert_eq!(slab.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Stores a value and returns its key, reusing the most recently freed slot if there is one.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// let key = slab.insert("value");
+    /// assert_eq!(slab[key], "value");
+    /// ```
+    pub fn insert(&mut self, value: T) -> usize {
+        let key = self.next;
+
+        if key == self.entries.len {
+            self.entries.push(Entry::Occupied(value));
+            self.next = key + 1;
+        } else {
+            match mem::replace(&mut self.entries[key], Entry::Occupied(value)) {
+                Entry::Vacant(next) => self.next = next,
+                Entry::Occupied(_) => unreachable!("free list points to an occupied slot"),
+            }
+        }
+
+        self.len += 1;
+        key
+    }
+
+    /// Removes the value stored under `key` and returns it, freeing the slot.
+    /// # Panics
+    /// Panics if `key` isn't in the slab.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// let key = slab.insert(1);
+    /// assert_eq!(slab.remove(key), 1);
+    /// assert!(!slab.contains(key));
+    /// ```
+    pub fn remove(&mut self, key: usize) -> T {
+        self.try_remove(key).expect("invalid slab key")
+    }
+
+    /// Removes the value stored under `key` and returns it, or `None` if the key isn't in the slab.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// let key = slab.insert(1);
+    /// assert_eq!(slab.try_remove(key), Some(1));
+    /// assert_eq!(slab.try_remove(key), None);
+    /// ```
+    pub fn try_remove(&mut self, key: usize) -> Option<T> {
+        let entry = self.entries.get_mut(key)?;
+
+        if let Entry::Vacant(_) = entry {
+            return None;
+        }
+
+        match mem::replace(entry, Entry::Vacant(self.next)) {
+            Entry::Occupied(value) => {
+                self.next = key;
+                self.len -= 1;
+                Some(value)
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if a value is stored under `key`.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// let key = slab.insert(1);
+    /// assert!(slab.contains(key));
+    /// assert!(!slab.contains(key + 1));
+    /// ```
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value stored under `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// let key = slab.insert(1);
+    /// assert_eq!(slab.get(key), Some(&1));
+    /// ```
+    pub fn get(&self, key: usize) -> Option<&T> {
+        match self.entries.get(key)? {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value stored under `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// let key = slab.insert(1);
+    /// *slab.get_mut(key).unwrap() = 2;
+    /// assert_eq!(slab[key], 2);
+    /// ```
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        match self.entries.get_mut(key)? {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    /// Returns an iterator over the keys and values, in key order, skipping the vacant slots.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// let a = slab.insert('a');
+    /// slab.insert('b');
+    /// slab.remove(a);
+    /// assert_eq!(slab.iter().collect::<std::vec::Vec<_>>(), [(1, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(key, entry)| match entry {
+                Entry::Occupied(value) => Some((key, value)),
+                Entry::Vacant(_) => None,
+            })
+    }
+
+    /// Returns an iterator over the keys and mutable references to the values, in key order.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// slab.insert(1);
+    /// slab.insert(2);
+    /// slab.iter_mut().for_each(|(_, value)| *value *= 10);
+    /// assert_eq!(slab[1], 20);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> + '_ {
+        self.entries
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(key, entry)| match entry {
+                Entry::Occupied(value) => Some((key, value)),
+                Entry::Vacant(_) => None,
+            })
+    }
+
+    /// Removes every value, keeping the allocated capacity. Every key becomes invalid.
+    /// # Example
+    /// ```
+    /// let mut slab = vec::Slab::new();
+    /// slab.insert(1);
+    /// slab.clear();
+    /// assert!(slab.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.next = 0;
+        self.len = 0;
+    }
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for Slab<T> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("invalid slab key")
+    }
+}
+
+impl<T> IndexMut<usize> for Slab<T> {
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        self.get_mut(key).expect("invalid slab key")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Slab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,549 @@
+use std::{
+    fmt, mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+// Generation of a slot that has run out of generations. It's never handed out in a key and the slot is never reused
+const RETIRED: u32 = u32::MAX;
+
+/// Handle to a value of a [`SlotMap`], pairing a slot index with the generation of the slot when the value was
+/// inserted. Once the value is removed the slot's generation moves on, so the key no longer matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Key {
+    index: u32,
+    generation: u32,
+}
+
+impl Key {
+    /// Returns the slot index of the key, shared with the keys of values previously or later stored in that slot.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert(1);
+    /// assert_eq!(key.index(), 0);
+    /// ```
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+/// Storage handing out generational [`Key`]s: removing a value frees its slot for reuse, and the keys of the removed
+/// value are detected as stale instead of reaching the new one.
+///
+/// Handy for entity systems, where handles outlive the objects they point to. Values can be attached to the same
+/// keys in a [`SecondaryMap`].
+///
+/// A slot freed for the `u32::MAX`th time is retired instead of reused, so a key can never match again.
+/// # Example
+/// ```
+/// use vec::SlotMap;
+///
+/// let mut map = SlotMap::new();
+/// let player = map.insert("player");
+/// map.remove(player);
+///
+/// let enemy = map.insert("enemy");
+/// // Same slot, but the old key doesn't reach the new value
+/// assert_eq!(enemy.index(), player.index());
+/// assert_eq!(map.get(player), None);
+/// assert_eq!(map[enemy], "enemy");
+/// ```
+#[derive(Clone)]
+pub struct SlotMap<T> {
+    slots: Vec<Slot<T>>,
+    // first vacant slot, `slots.len` if there is none
+    next: usize,
+    len: usize,
+}
+
+#[derive(Clone)]
+struct Slot<T> {
+    generation: u32,
+    value: SlotValue<T>,
+}
+
+#[derive(Clone)]
+enum SlotValue<T> {
+    Occupied(T),
+    // index of the next vacant slot in the free list
+    Vacant(usize),
+}
+
+impl<T> SlotMap<T> {
+    /// Creates an empty slot map, without allocating.
+    /// # Example
+    /// ```
+    /// let map: vec::SlotMap<i32> = vec::SlotMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values in the map.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// map.insert(1);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map holds no values.
+    /// # Example
+    /// ```
+    /// let map: vec::SlotMap<i32> = vec::SlotMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Stores a value and returns its key, reusing a freed slot if there is one.
+    /// # Panics
+    /// Panics if the map would need more than `u32::MAX` slots.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert("value");
+    /// assert_eq!(map[key], "value");
+    /// ```
+    pub fn insert(&mut self, value: T) -> Key {
+        let index = self.next;
+
+        let generation = if index == self.slots.len {
+            assert!(index < u32::MAX as usize, "SlotMap is full");
+
+            self.slots.push(Slot {
+                generation: 0,
+                value: SlotValue::Occupied(value),
+            });
+            self.next = index + 1;
+
+            0
+        } else {
+            let slot = &mut self.slots[index];
+
+            match mem::replace(&mut slot.value, SlotValue::Occupied(value)) {
+                SlotValue::Vacant(next) => self.next = next,
+                SlotValue::Occupied(_) => unreachable!("free list points to an occupied slot"),
+            }
+
+            slot.generation
+        };
+
+        self.len += 1;
+
+        Key {
+            index: index as u32,
+            generation,
+        }
+    }
+
+    /// Removes the value reached by `key` and returns it, or `None` if the key is stale.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert(1);
+    /// assert_eq!(map.remove(key), Some(1));
+    /// assert_eq!(map.remove(key), None);
+    /// ```
+    pub fn remove(&mut self, key: Key) -> Option<T> {
+        self.get(key)?;
+
+        let slot = &mut self.slots[key.index()];
+        // Stale keys of this slot must not match the next value stored there. Occupied slots are never retired, so
+        // this can't overflow
+        slot.generation += 1;
+
+        match mem::replace(&mut slot.value, SlotValue::Vacant(self.next)) {
+            SlotValue::Occupied(value) => {
+                if slot.generation != RETIRED {
+                    self.next = key.index();
+                }
+                self.len -= 1;
+                Some(value)
+            }
+            SlotValue::Vacant(_) => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if `key` reaches a value.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert(1);
+    /// assert!(map.contains_key(key));
+    /// map.remove(key);
+    /// assert!(!map.contains_key(key));
+    /// ```
+    pub fn contains_key(&self, key: Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value reached by `key`, or `None` if the key is stale.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert(1);
+    /// assert_eq!(map.get(key), Some(&1));
+    /// ```
+    pub fn get(&self, key: Key) -> Option<&T> {
+        match self.slots.get(key.index())? {
+            Slot {
+                generation,
+                value: SlotValue::Occupied(value),
+            } if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value reached by `key`, or `None` if the key is stale.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert(1);
+    /// *map.get_mut(key).unwrap() = 2;
+    /// assert_eq!(map[key], 2);
+    /// ```
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        match self.slots.get_mut(key.index())? {
+            Slot {
+                generation,
+                value: SlotValue::Occupied(value),
+            } if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the keys and values, in slot order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let a = map.insert('a');
+    /// let b = map.insert('b');
+    /// map.remove(a);
+    /// assert_eq!(map.iter().collect::<std::vec::Vec<_>>(), [(b, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &T)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match &slot.value {
+                SlotValue::Occupied(value) => Some((
+                    Key {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    value,
+                )),
+                SlotValue::Vacant(_) => None,
+            })
+    }
+
+    /// Returns an iterator over the keys and mutable references to the values, in slot order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert(1);
+    /// map.iter_mut().for_each(|(_, value)| *value *= 10);
+    /// assert_eq!(map[key], 10);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Key, &mut T)> + '_ {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| match &mut slot.value {
+                SlotValue::Occupied(value) => Some((
+                    Key {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    value,
+                )),
+                SlotValue::Vacant(_) => None,
+            })
+    }
+
+    /// Removes every value, keeping the slots so every key in use becomes stale.
+    /// # Example
+    /// ```
+    /// let mut map = vec::SlotMap::new();
+    /// let key = map.insert(1);
+    /// map.clear();
+    /// assert!(map.is_empty());
+    ///
+    /// let other = map.insert(2);
+    /// assert_eq!(map.get(key), None);
+    /// assert_eq!(map[other], 2);
+    /// ```
+    pub fn clear(&mut self) {
+        // Rebuild the free list in slot order, bumping the generations of the slots in use and leaving out the
+        // retired ones
+        let mut next = self.slots.len;
+
+        for (index, slot) in self.slots.iter_mut().enumerate().rev() {
+            if let SlotValue::Occupied(_) = slot.value {
+                slot.generation += 1;
+            }
+
+            slot.value = SlotValue::Vacant(next);
+            if slot.generation != RETIRED {
+                next = index;
+            }
+        }
+
+        self.next = next;
+        self.len = 0;
+    }
+}
+
+impl<T> Default for SlotMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Key> for SlotMap<T> {
+    type Output = T;
+
+    fn index(&self, key: Key) -> &T {
+        self.get(key).expect("stale slot map key")
+    }
+}
+
+impl<T> IndexMut<Key> for SlotMap<T> {
+    fn index_mut(&mut self, key: Key) -> &mut T {
+        self.get_mut(key).expect("stale slot map key")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlotMap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Values attached to the keys of a [`SlotMap`], stored densely by slot index. A key only reaches the value inserted
+/// with that exact key, not one inserted for another generation of the slot.
+/// # Example
+/// ```
+/// use vec::{SecondaryMap, SlotMap};
+///
+/// let mut names = SlotMap::new();
+/// let mut health = SecondaryMap::new();
+///
+/// let player = names.insert("player");
+/// health.insert(player, 100);
+///
+/// names.remove(player);
+/// let enemy = names.insert("enemy");
+/// assert_eq!(health.get(enemy), None);
+/// assert_eq!(health.get(player), Some(&100));
+/// ```
+#[derive(Clone)]
+pub struct SecondaryMap<V> {
+    slots: Vec<Option<(u32, V)>>,
+    len: usize,
+}
+
+impl<V> SecondaryMap<V> {
+    /// Creates an empty secondary map, without allocating.
+    /// # Example
+    /// ```
+    /// let map: vec::SecondaryMap<i32> = vec::SecondaryMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values in the map.
+    /// # Example
+    /// ```
+    /// let mut keys = vec::SlotMap::new();
+    /// let mut map = vec::SecondaryMap::new();
+    /// map.insert(keys.insert(()), 1);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map holds no values.
+    /// # Example
+    /// ```
+    /// let map: vec::SecondaryMap<i32> = vec::SecondaryMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Attaches a value to `key`, returning the value previously attached to that exact key, if any. A value
+    /// attached to an older generation of the slot is dropped, while a stale `key` is ignored, leaving the value
+    /// of the newer generation in place and dropping `value`.
+    /// # Example
+    /// ```
+    /// let mut keys = vec::SlotMap::new();
+    /// let mut map = vec::SecondaryMap::new();
+    /// let key = keys.insert(());
+    /// assert_eq!(map.insert(key, 1), None);
+    /// assert_eq!(map.insert(key, 2), Some(1));
+    ///
+    /// keys.remove(key);
+    /// let reused = keys.insert(());
+    /// assert_eq!(map.insert(reused, 3), None);
+    /// assert_eq!(map.insert(key, 4), None);
+    /// assert_eq!((map.get(key), map[reused]), (None, 3));
+    /// ```
+    pub fn insert(&mut self, key: Key, value: V) -> Option<V> {
+        let index = key.index();
+
+        if index >= self.slots.len {
+            self.slots.reserve(index + 1 - self.slots.len);
+            while self.slots.len <= index {
+                self.slots.push(None);
+            }
+        }
+
+        match &mut self.slots[index] {
+            Some((generation, _)) if *generation > key.generation => None,
+            Some((generation, old)) if *generation == key.generation => {
+                Some(mem::replace(old, value))
+            }
+            slot @ Some(_) => {
+                *slot = Some((key.generation, value));
+                None
+            }
+            slot @ None => {
+                *slot = Some((key.generation, value));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Detaches the value of `key` and returns it, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut keys = vec::SlotMap::new();
+    /// let mut map = vec::SecondaryMap::new();
+    /// let key = keys.insert(());
+    /// map.insert(key, 1);
+    /// assert_eq!(map.remove(key), Some(1));
+    /// assert_eq!(map.remove(key), None);
+    /// ```
+    pub fn remove(&mut self, key: Key) -> Option<V> {
+        self.get(key)?;
+
+        self.len -= 1;
+        self.slots[key.index()].take().map(|(_, value)| value)
+    }
+
+    /// Returns `true` if a value is attached to `key`.
+    /// # Example
+    /// ```
+    /// let mut keys = vec::SlotMap::new();
+    /// let mut map = vec::SecondaryMap::new();
+    /// let key = keys.insert(());
+    /// map.insert(key, 1);
+    /// assert!(map.contains_key(key));
+    /// ```
+    pub fn contains_key(&self, key: Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value attached to `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut keys = vec::SlotMap::new();
+    /// let mut map = vec::SecondaryMap::new();
+    /// let key = keys.insert(());
+    /// map.insert(key, 1);
+    /// assert_eq!(map.get(key), Some(&1));
+    /// ```
+    pub fn get(&self, key: Key) -> Option<&V> {
+        match self.slots.get(key.index())? {
+            Some((generation, value)) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value attached to `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut keys = vec::SlotMap::new();
+    /// let mut map = vec::SecondaryMap::new();
+    /// let key = keys.insert(());
+    /// map.insert(key, 1);
+    /// *map.get_mut(key).unwrap() += 1;
+    /// assert_eq!(map[key], 2);
+    /// ```
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut V> {
+        match self.slots.get_mut(key.index())? {
+            Some((generation, value)) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the keys and values, in slot order.
+    /// # Example
+    /// ```
+    /// let mut keys = vec::SlotMap::new();
+    /// let mut map = vec::SecondaryMap::new();
+    /// let key = keys.insert(());
+    /// map.insert(key, 'a');
+    /// assert_eq!(map.iter().collect::<std::vec::Vec<_>>(), [(key, &'a')]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &V)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.as_ref().map(|(generation, value)| {
+                (
+                    Key {
+                        index: index as u32,
+                        generation: *generation,
+                    },
+                    value,
+                )
+            })
+        })
+    }
+}
+
+impl<V> Default for SecondaryMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Index<Key> for SecondaryMap<V> {
+    type Output = V;
+
+    fn index(&self, key: Key) -> &V {
+        self.get(key).expect("no value attached to the key")
+    }
+}
+
+impl<V> IndexMut<Key> for SecondaryMap<V> {
+    fn index_mut(&mut self, key: Key) -> &mut V {
+        self.get_mut(key).expect("no value attached to the key")
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for SecondaryMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
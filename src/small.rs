@@ -0,0 +1,593 @@
+use std::{
+    alloc::Global,
+    fmt, iter,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    raw::{handle_reserve_error, insert_at, poison, remove_at, truncate_at, RawValIter, RawVec},
+    TryReserveError, Vec,
+};
+
+/// Vector storing up to `N` elements inline, moving them to a heap buffer once it grows past that.
+///
+/// Handy when most vectors are tiny: those never allocate, while the big ones keep working as a regular [`Vec`].
+/// # Example
+/// ```
+/// use vec::SmallVec;
+///
+/// let mut vec: SmallVec<u32, 4> = SmallVec::new();
+/// vec.extend(0..4);
+/// assert!(!vec.spilled());
+///
+/// vec.push(4);
+/// assert!(vec.spilled());
+/// assert_eq!(&*vec, &[0, 1, 2, 3, 4]);
+/// ```
+pub struct SmallVec<T, const N: usize> {
+    len: usize,
+    data: Data<T, N>,
+}
+
+// Where the elements currently live, the inline array is never used again once spilled
+enum Data<T, const N: usize> {
+    Inline([MaybeUninit<T>; N]),
+    Heap(RawVec<T>),
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    /// Creates an empty vector, using the inline storage.
+    /// # Example
+    /// ```
+    /// let vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// assert_eq!(vec.capacity(), 4);
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            len: 0,
+            data: Data::Inline([const { MaybeUninit::uninit() }; N]),
+        }
+    }
+
+    /// Creates an empty vector with room for `capacity` elements, only allocating if it exceeds `N`.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let vec: vec::SmallVec<i32, 4> = vec::SmallVec::with_capacity(16);
+    /// assert!(vec.spilled());
+    /// assert_eq!(vec.capacity(), 16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= N {
+            return Self::new();
+        }
+
+        match RawVec::try_with_capacity_in(capacity, Doubling, Global) {
+            Ok(buf) => Self {
+                len: 0,
+                data: Data::Heap(buf),
+            },
+            Err(err) => handle_reserve_error(err),
+        }
+    }
+
+    /// Creates a vector holding the elements of an array, behind `small_vec![a, b, c]`. Only allocates if the array
+    /// is longer than `N`.
+    #[doc(hidden)]
+    pub fn from_array<const M: usize>(array: [T; M]) -> Self {
+        let mut vec = Self::with_capacity(M);
+        vec.extend(array);
+        vec
+    }
+
+    /// Creates a vector of `n` clones of `elem`, behind `small_vec![elem; n]`. Only allocates if `n` is greater than
+    /// `N`.
+    #[doc(hidden)]
+    pub fn from_elem(elem: T, n: usize) -> Self
+    where
+        T: Clone,
+    {
+        let mut vec = Self::with_capacity(n);
+        vec.extend(iter::repeat_n(elem, n));
+        vec
+    }
+
+    /// Returns the number of elements in the vector.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the vector can hold without (re)allocating.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<u64, 2> = vec::SmallVec::new();
+    /// assert_eq!(vec.capacity(), 2);
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline(_) => N,
+            Data::Heap(buf) => buf.cap,
+        }
+    }
+
+    /// Returns `true` if the elements were moved to the heap.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 1> = vec::SmallVec::new();
+    /// vec.push(1);
+    /// assert!(!vec.spilled());
+    /// vec.push(2);
+    /// assert!(vec.spilled());
+    /// ```
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    /// Appends an element to the back of the vector, spilling to the heap if the inline storage is full.
+    /// # Panics
+    /// Panics if the capacity overflows, aborts if the allocator fails.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<char, 2> = vec::SmallVec::new();
+    /// vec.push('a');
+    /// assert_eq!(vec[0], 'a');
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        self.reserve_one();
+
+        unsafe { ptr::write(self.as_mut_ptr().add(self.len), elem) };
+        self.len += 1;
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty. The elements stay on the heap once
+    /// spilled.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 2> = vec::SmallVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.pop(), Some(1));
+    /// assert_eq!(vec.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+
+        unsafe {
+            let elem = ptr::read(self.as_mut_ptr().add(self.len));
+            poison(self.as_mut_ptr().add(self.len), 1);
+            Some(elem)
+        }
+    }
+
+    /// Inserts an element at `index`, shifting all the elements after it to the right.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 2> = vec::SmallVec::new();
+    /// vec.push(1);
+    /// vec.push(3);
+    /// vec.insert(1, 2);
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        assert!(index <= self.len, "Index out of bounds");
+
+        self.reserve_one();
+
+        unsafe { insert_at(self.as_mut_ptr(), self.len, index, elem) };
+        self.len += 1;
+    }
+
+    /// Inserts a copy of every element of `slice` at `index`, shifting all the elements after it to the right.
+    /// # Panics
+    /// Panics if `index > len` or the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.extend([1, 5]);
+    /// vec.insert_slice(1, &[2, 3, 4]);
+    /// assert_eq!(&*vec, &[1, 2, 3, 4, 5]);
+    /// assert!(vec.spilled());
+    /// ```
+    pub fn insert_slice(&mut self, index: usize, slice: &[T])
+    where
+        T: Copy,
+    {
+        assert!(index <= self.len, "Index out of bounds");
+
+        self.reserve(slice.len());
+
+        unsafe {
+            let gap = self.as_mut_ptr().add(index);
+
+            ptr::copy(gap, gap.add(slice.len()), self.len - index);
+            ptr::copy_nonoverlapping(slice.as_ptr(), gap, slice.len());
+        }
+
+        self.len += slice.len();
+    }
+
+    /// Reserves room for at least `additional` more elements, spilling to the heap if they don't fit inline.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<u8, 16> = vec::SmallVec::new();
+    /// vec.reserve(8);
+    /// assert!(!vec.spilled());
+    /// vec.reserve(32);
+    /// assert!(vec.capacity() >= 32);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            handle_reserve_error(err)
+        }
+    }
+
+    /// Removes and returns the element at `index`, shifting all the elements after it to the left.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.remove(1), 1);
+    /// assert_eq!(&*vec, &[0, 2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        let elem = unsafe { remove_at(self.as_mut_ptr(), self.len, index) };
+        self.len -= 1;
+
+        elem
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if `len` is greater than the length.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.extend(0..5);
+    /// vec.truncate(2);
+    /// assert_eq!(&*vec, &[0, 1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        let ptr = self.as_mut_ptr();
+
+        unsafe { truncate_at(ptr, &mut self.len, len) }
+    }
+
+    /// Removes every element, keeping the current storage.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.extend(0..5);
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Removes every element, returning them in an iterator. Elements that aren't consumed are dropped along with
+    /// the iterator.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.drain().sum::<i32>(), 3);
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, N> {
+        unsafe {
+            let iter = RawValIter::new(ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), self.len));
+
+            self.len = 0;
+
+            Drain {
+                iter,
+                marker: PhantomData,
+            }
+        }
+    }
+
+    /// Turns the vector into a [`Vec`], reusing the heap buffer if the elements were spilled.
+    /// # Example
+    /// ```
+    /// let mut small: vec::SmallVec<i32, 2> = vec::SmallVec::new();
+    /// small.extend(0..3);
+    ///
+    /// let vec = small.into_vec();
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        let mut small = mem::ManuallyDrop::new(self);
+        let len = small.len;
+
+        match &mut small.data {
+            Data::Heap(buf) => unsafe { Vec::from_raw_parts(ptr::read(buf), len) },
+            Data::Inline(data) => {
+                let mut vec = Vec::with_capacity(len);
+
+                unsafe {
+                    ptr::copy_nonoverlapping(data.as_ptr() as *const T, vec.as_mut_ptr(), len);
+                    vec.len = len;
+                }
+
+                vec
+            }
+        }
+    }
+
+    fn as_ptr(&self) -> *const T {
+        match &self.data {
+            Data::Inline(data) => data.as_ptr() as *const T,
+            Data::Heap(buf) => buf.ptr.as_ptr(),
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        match &mut self.data {
+            Data::Inline(data) => data.as_mut_ptr() as *mut T,
+            Data::Heap(buf) => buf.ptr.as_ptr(),
+        }
+    }
+
+    fn reserve_one(&mut self) {
+        self.reserve(1)
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if self.capacity() - self.len >= additional {
+            return Ok(());
+        }
+
+        match &mut self.data {
+            Data::Heap(buf) => buf.try_reserve(self.len, additional),
+            Data::Inline(data) => {
+                let required = self
+                    .len
+                    .checked_add(additional)
+                    .ok_or(TryReserveError::CapacityOverflow)?;
+
+                // Grow as if the inline array was the previous buffer
+                let cap = Doubling.next_capacity(N, required);
+                let buf = RawVec::try_with_capacity_in(cap, Doubling, Global)?;
+
+                unsafe {
+                    ptr::copy_nonoverlapping(data.as_ptr() as *const T, buf.ptr.as_ptr(), self.len)
+                };
+                self.data = Data::Heap(buf);
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SmallVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> From<Vec<T>> for SmallVec<T, N> {
+    /// Turns a vector into a spilled `SmallVec`, reusing its allocation.
+    /// # Example
+    /// ```
+    /// use vec::{SmallVec, Vec};
+    /// let mut vec = Vec::new();
+    /// vec.push(1);
+    /// let small: SmallVec<i32, 4> = SmallVec::from(vec);
+    /// assert_eq!(&*small, &[1]);
+    /// ```
+    fn from(vec: Vec<T>) -> Self {
+        let (buf, len) = vec.into_raw_parts();
+
+        Self {
+            len,
+            data: Data::Heap(buf),
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVec<T, N> {
+    /// Appends every element of an iterator.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::with_capacity(self.len);
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for SmallVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallVec<T, N> {}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    fn drop(&mut self) {
+        if mem::needs_drop::<T>() {
+            unsafe { ptr::drop_in_place(&mut **self as *mut [T]) }
+        }
+        // The heap buffer, if any, is freed by RawVec
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    /// Consumes the vector into an iterator over its elements, without moving them out of the current storage.
+    /// # Example
+    /// ```
+    /// let mut vec: vec::SmallVec<i32, 4> = vec::SmallVec::new();
+    /// vec.extend(0..3);
+    /// let mut iter = vec.into_iter();
+    /// assert_eq!(iter.next(), Some(0));
+    /// assert_eq!(iter.next_back(), Some(2));
+    /// ```
+    fn into_iter(mut self) -> IntoIter<T, N> {
+        let end = self.len;
+
+        // The elements are now owned by the iterator, the vector only keeps the storage
+        self.len = 0;
+
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over a [`SmallVec`], created by its `IntoIterator` implementation.
+pub struct IntoIter<T, const N: usize> {
+    // emptied vector holding the storage, the elements in `start..end` are initialized
+    vec: SmallVec<T, N>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.start += 1;
+        Some(unsafe { ptr::read(self.vec.as_ptr().add(self.start - 1)) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { ptr::read(self.vec.as_ptr().add(self.end)) })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.vec.as_mut_ptr().add(self.start) },
+            self.end - self.start,
+        );
+
+        unsafe { ptr::drop_in_place(remaining) }
+    }
+}
+
+/// Draining iterator over a [`SmallVec`], created by [`SmallVec::drain`].
+pub struct Drain<'a, T, const N: usize> {
+    iter: RawValIter<T>,
+    marker: PhantomData<&'a mut SmallVec<T, N>>,
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back()
+    }
+}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        self.iter.drop_remaining();
+    }
+}
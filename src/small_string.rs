@@ -0,0 +1,261 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    str,
+};
+
+use crate::SmallVec;
+
+/// UTF-8 string storing up to `N` bytes inline (23 by default) and moving to the heap past that, built on
+/// [`SmallVec<u8, N>`](SmallVec).
+///
+/// Short identifiers and keys then never allocate, while offering the same `str` API as [`String`](crate::String).
+/// # Example
+/// ```
+/// use vec::SmallString;
+///
+/// let mut s = SmallString::<23>::from("user_id");
+/// assert!(!s.spilled());
+///
+/// s.push_str(" with a much longer suffix");
+/// assert!(s.spilled());
+/// assert!(s.starts_with("user_id"));
+/// ```
+pub struct SmallString<const N: usize = 23> {
+    vec: SmallVec<u8, N>,
+}
+
+impl<const N: usize> SmallString<N> {
+    /// Creates an empty string, using the inline storage.
+    /// # Example
+    /// ```
+    /// let s: vec::SmallString = vec::SmallString::new();
+    /// assert_eq!(s.capacity(), 23);
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            vec: SmallVec::new(),
+        }
+    }
+
+    /// Returns `true` if the bytes were moved to the heap.
+    /// # Example
+    /// ```
+    /// let mut s = vec::SmallString::<4>::from("abcd");
+    /// assert!(!s.spilled());
+    /// s.push('e');
+    /// assert!(s.spilled());
+    /// ```
+    pub fn spilled(&self) -> bool {
+        self.vec.spilled()
+    }
+
+    /// Returns the number of bytes the string can hold without (re)allocating.
+    /// # Example
+    /// ```
+    /// let s: vec::SmallString<8> = vec::SmallString::new();
+    /// assert_eq!(s.capacity(), 8);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the string as a `str` slice.
+    /// # Example
+    /// ```
+    /// let s: vec::SmallString = "hi".into();
+    /// assert_eq!(s.as_str(), "hi");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+
+    /// Returns the string as a mutable `str` slice.
+    /// # Example
+    /// ```
+    /// let mut s: vec::SmallString = "hi".into();
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(&*s, "HI");
+    /// ```
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+
+    /// Appends a string slice.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut s: vec::SmallString = "foo".into();
+    /// s.push_str("bar");
+    /// assert_eq!(&*s, "foobar");
+    /// ```
+    pub fn push_str(&mut self, string: &str) {
+        self.vec.insert_slice(self.vec.len(), string.as_bytes())
+    }
+
+    /// Appends a character, encoded as UTF-8.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut s: vec::SmallString = vec::SmallString::new();
+    /// s.push('é');
+    /// assert_eq!(s.len(), 2);
+    /// ```
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Removes the last character and returns it, or `None` if the string is empty.
+    /// # Example
+    /// ```
+    /// let mut s: vec::SmallString = "aé".into();
+    /// assert_eq!(s.pop(), Some('é'));
+    /// assert_eq!(&*s, "a");
+    /// ```
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        self.vec.truncate(self.vec.len() - ch.len_utf8());
+
+        Some(ch)
+    }
+
+    /// Inserts a string slice at byte `index`.
+    /// # Panics
+    /// Panics if `index` is greater than the length or isn't on a character boundary.
+    /// # Example
+    /// ```
+    /// let mut s: vec::SmallString = "held".into();
+    /// s.insert_str(2, "llo wor");
+    /// assert_eq!(&*s, "hello world");
+    /// ```
+    pub fn insert_str(&mut self, index: usize, string: &str) {
+        assert!(
+            self.is_char_boundary(index),
+            "index {} isn't on a character boundary",
+            index
+        );
+
+        self.vec.insert_slice(index, string.as_bytes())
+    }
+
+    /// Shortens the string to `len` bytes. Does nothing if `len` is greater than the length.
+    /// # Panics
+    /// Panics if `len` isn't on a character boundary.
+    /// # Example
+    /// ```
+    /// let mut s: vec::SmallString = "hello".into();
+    /// s.truncate(2);
+    /// assert_eq!(&*s, "he");
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            assert!(
+                self.is_char_boundary(len),
+                "length {} isn't on a character boundary",
+                len
+            );
+
+            self.vec.truncate(len)
+        }
+    }
+
+    /// Removes every character, keeping the current storage.
+    /// # Example
+    /// ```
+    /// let mut s: vec::SmallString = "hello".into();
+    /// s.clear();
+    /// assert!(s.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+}
+
+impl<const N: usize> Default for SmallString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for SmallString<N> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for SmallString<N> {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> From<&str> for SmallString<N> {
+    fn from(string: &str) -> Self {
+        let mut s = Self::new();
+        s.push_str(string);
+        s
+    }
+}
+
+impl<const N: usize> fmt::Write for SmallString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<const N: usize> Extend<char> for SmallString<N> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<const N: usize> Clone for SmallString<N> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<const N: usize> fmt::Display for SmallString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for SmallString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> PartialEq for SmallString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for SmallString<N> {}
+
+impl<const N: usize> PartialEq<str> for SmallString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for SmallString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
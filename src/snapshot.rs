@@ -0,0 +1,310 @@
+use std::{fmt, iter::FromIterator, mem, ops::Deref};
+
+use crate::Vec;
+
+/// Point a [`SnapshotVec`] can be rolled back to, returned by [`start_snapshot`](SnapshotVec::start_snapshot).
+///
+/// Snapshots must be rolled back or committed in the reverse order they were started.
+#[must_use = "a snapshot must be rolled back or committed"]
+#[derive(Debug)]
+pub struct Snapshot {
+    // length of the undo log when the snapshot started
+    undo_len: usize,
+    // number of snapshots open once this one started
+    depth: usize,
+}
+
+// Action undoing a change made while a snapshot is open
+enum Undo<T> {
+    Push,
+    Pop(T),
+    Set(usize, T),
+}
+
+/// Vector recording how to undo its changes while a snapshot is open, so backtracking algorithms (unification,
+/// search) can try something and cheaply roll it back.
+///
+/// [`start_snapshot`](SnapshotVec::start_snapshot) opens a snapshot, [`rollback_to`](SnapshotVec::rollback_to) undoes
+/// every change made since then, and [`commit`](SnapshotVec::commit) keeps them. Snapshots nest: the changes kept by
+/// committing an inner snapshot are still undone by rolling back an outer one. Outside of snapshots nothing is
+/// recorded.
+/// # Example
+/// ```
+/// use vec::SnapshotVec;
+///
+/// let mut v = SnapshotVec::new();
+/// v.push(1);
+///
+/// let outer = v.start_snapshot();
+/// v.push(2);
+///
+/// let inner = v.start_snapshot();
+/// v.set(0, 10);
+/// v.commit(inner);
+/// assert_eq!(&*v, &[10, 2]);
+///
+/// v.rollback_to(outer);
+/// assert_eq!(&*v, &[1]);
+/// ```
+pub struct SnapshotVec<T> {
+    values: Vec<T>,
+    undo_log: Vec<Undo<T>>,
+    open_snapshots: usize,
+}
+
+impl<T> SnapshotVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::SnapshotVec<i32> = vec::SnapshotVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            undo_log: Vec::new(),
+            open_snapshots: 0,
+        }
+    }
+
+    /// Creates an empty vector with room for `capacity` elements.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v: vec::SnapshotVec<i32> = vec::SnapshotVec::with_capacity(8);
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Appends an element and returns its index.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// assert_eq!(v.push('a'), 0);
+    /// assert_eq!(v.push('b'), 1);
+    /// ```
+    pub fn push(&mut self, elem: T) -> usize {
+        self.values.push(elem);
+        self.record(|| Undo::Push);
+        self.values.len - 1
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty. A copy of it is kept in the undo log
+    /// while a snapshot is open.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// v.push(1);
+    /// let snapshot = v.start_snapshot();
+    /// assert_eq!(v.pop(), Some(1));
+    /// assert!(v.is_empty());
+    /// v.rollback_to(snapshot);
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn pop(&mut self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let elem = self.values.pop()?;
+        self.record(|| Undo::Pop(elem.clone()));
+        Some(elem)
+    }
+
+    /// Replaces the element at `index`, keeping the old one in the undo log while a snapshot is open.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// v.push('a');
+    /// v.set(0, 'b');
+    /// assert_eq!(v[0], 'b');
+    /// ```
+    pub fn set(&mut self, index: usize, elem: T) {
+        let old = mem::replace(&mut self.values[index], elem);
+        self.record(|| Undo::Set(index, old));
+    }
+
+    /// Updates the element at `index` in place with `f`, saving a copy of it first while a snapshot is open.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// v.push(std::vec::Vec::new());
+    /// let snapshot = v.start_snapshot();
+    /// v.update(0, |list| list.push(1));
+    /// assert_eq!(v[0], [1]);
+    /// v.rollback_to(snapshot);
+    /// assert!(v[0].is_empty());
+    /// ```
+    pub fn update<F: FnOnce(&mut T)>(&mut self, index: usize, f: F)
+    where
+        T: Clone,
+    {
+        if self.in_snapshot() {
+            let old = self.values[index].clone();
+            self.undo_log.push(Undo::Set(index, old));
+        }
+
+        f(&mut self.values[index]);
+    }
+
+    /// Returns `true` if a snapshot is open.
+    /// # Example
+    /// ```
+    /// let mut v: vec::SnapshotVec<i32> = vec::SnapshotVec::new();
+    /// let snapshot = v.start_snapshot();
+    /// assert!(v.in_snapshot());
+    /// v.commit(snapshot);
+    /// assert!(!v.in_snapshot());
+    /// ```
+    pub fn in_snapshot(&self) -> bool {
+        self.open_snapshots > 0
+    }
+
+    /// Opens a snapshot, recording every change from now on until it's rolled back or committed.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// let snapshot = v.start_snapshot();
+    /// v.push(1);
+    /// v.rollback_to(snapshot);
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn start_snapshot(&mut self) -> Snapshot {
+        self.open_snapshots += 1;
+
+        Snapshot {
+            undo_len: self.undo_log.len,
+            depth: self.open_snapshots,
+        }
+    }
+
+    /// Undoes every change made since `snapshot` was started, including the ones of committed inner snapshots, and
+    /// closes it.
+    /// # Panics
+    /// Panics if `snapshot` isn't the innermost open snapshot.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// v.push(1);
+    /// let snapshot = v.start_snapshot();
+    /// v.set(0, 2);
+    /// v.push(3);
+    /// v.rollback_to(snapshot);
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn rollback_to(&mut self, snapshot: Snapshot) {
+        self.close(&snapshot);
+
+        while self.undo_log.len > snapshot.undo_len {
+            match self.undo_log.pop().unwrap() {
+                Undo::Push => {
+                    self.values.pop();
+                }
+                Undo::Pop(elem) => self.values.push(elem),
+                Undo::Set(index, elem) => self.values[index] = elem,
+            }
+        }
+    }
+
+    /// Keeps every change made since `snapshot` was started, and closes it. An outer snapshot can still roll them
+    /// back.
+    /// # Panics
+    /// Panics if `snapshot` isn't the innermost open snapshot.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// let snapshot = v.start_snapshot();
+    /// v.push(1);
+    /// v.commit(snapshot);
+    /// assert_eq!(&*v, &[1]);
+    /// ```
+    pub fn commit(&mut self, snapshot: Snapshot) {
+        self.close(&snapshot);
+
+        // Nothing can be rolled back anymore
+        if self.open_snapshots == 0 {
+            self.undo_log.clear();
+        }
+    }
+
+    /// Returns the elements in a vector, dropping the undo log.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SnapshotVec::new();
+    /// v.push(1);
+    /// assert_eq!(&*v.into_vec(), &[1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+
+    fn record<F: FnOnce() -> Undo<T>>(&mut self, undo: F) {
+        if self.in_snapshot() {
+            self.undo_log.push(undo());
+        }
+    }
+
+    fn close(&mut self, snapshot: &Snapshot) {
+        assert!(
+            snapshot.depth == self.open_snapshots && snapshot.undo_len <= self.undo_log.len,
+            "snapshot is not the innermost open one"
+        );
+        self.open_snapshots -= 1;
+    }
+}
+
+impl<T> Default for SnapshotVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for SnapshotVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl<T> From<Vec<T>> for SnapshotVec<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self {
+            values,
+            ..Self::new()
+        }
+    }
+}
+
+impl<T> Extend<T> for SnapshotVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for SnapshotVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SnapshotVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
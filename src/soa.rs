@@ -0,0 +1,424 @@
+use std::{marker::PhantomData, ptr, slice};
+
+use crate::raw::{handle_reserve_error, RawVec};
+
+/// Struct that can be stored field by field in a [`SoaVec`], one column per field. Implemented by
+/// `#[derive(Soa)]`, which also generates the reference and slice types.
+///
+/// # Safety
+/// Every method must act on all the columns alike, so they always hold the same number of initialized elements, and
+/// the references and slices returned must point into the columns.
+pub unsafe trait Soa: Sized {
+    #[doc(hidden)]
+    type Columns;
+    /// References to the fields of one element.
+    type Ref<'a>;
+    /// Mutable references to the fields of one element.
+    type RefMut<'a>;
+    /// One slice per column.
+    type Slices<'a>;
+    /// One mutable slice per column.
+    type SlicesMut<'a>;
+
+    #[doc(hidden)]
+    fn columns() -> Self::Columns;
+    #[doc(hidden)]
+    fn capacity(columns: &Self::Columns) -> usize;
+    #[doc(hidden)]
+    fn reserve(columns: &mut Self::Columns, len: usize, additional: usize);
+    #[doc(hidden)]
+    unsafe fn write(columns: &mut Self::Columns, index: usize, value: Self);
+    #[doc(hidden)]
+    unsafe fn read(columns: &Self::Columns, index: usize) -> Self;
+    #[doc(hidden)]
+    unsafe fn copy(columns: &mut Self::Columns, src: usize, dst: usize);
+    #[doc(hidden)]
+    unsafe fn get(columns: &Self::Columns, index: usize) -> Self::Ref<'_>;
+    #[doc(hidden)]
+    unsafe fn get_mut(columns: &Self::Columns, index: usize) -> Self::RefMut<'_>;
+    #[doc(hidden)]
+    unsafe fn slices(columns: &Self::Columns, len: usize) -> Self::Slices<'_>;
+    #[doc(hidden)]
+    unsafe fn slices_mut(columns: &mut Self::Columns, len: usize) -> Self::SlicesMut<'_>;
+    #[doc(hidden)]
+    unsafe fn drop_in_place(columns: &mut Self::Columns, len: usize);
+}
+
+/// Buffer holding one field of every element of a [`SoaVec`], used by the code `#[derive(Soa)]` generates.
+#[doc(hidden)]
+pub struct Column<T> {
+    buf: RawVec<T>,
+}
+
+impl<T> Column<T> {
+    pub fn new() -> Self {
+        Self { buf: RawVec::new() }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.cap
+    }
+
+    pub fn reserve(&mut self, len: usize, additional: usize) {
+        if let Err(err) = self.buf.try_reserve(len, additional) {
+            handle_reserve_error(err)
+        }
+    }
+
+    pub fn ptr(&self) -> *mut T {
+        self.buf.ptr.as_ptr()
+    }
+
+    /// # Safety
+    /// `index` must be less than the capacity, and the slot uninitialized.
+    pub unsafe fn write(&mut self, index: usize, value: T) {
+        ptr::write(self.ptr().add(index), value)
+    }
+
+    /// # Safety
+    /// The slot at `index` must be initialized, and is left logically uninitialized.
+    pub unsafe fn read(&self, index: usize) -> T {
+        ptr::read(self.ptr().add(index))
+    }
+
+    /// # Safety
+    /// The slot at `src` must be initialized and the one at `dst` within the capacity.
+    pub unsafe fn copy(&mut self, src: usize, dst: usize) {
+        ptr::copy(self.ptr().add(src), self.ptr().add(dst), 1)
+    }
+
+    /// # Safety
+    /// The first `len` slots must be initialized.
+    pub unsafe fn slice(&self, len: usize) -> &[T] {
+        slice::from_raw_parts(self.ptr(), len)
+    }
+
+    /// # Safety
+    /// The first `len` slots must be initialized.
+    pub unsafe fn slice_mut(&mut self, len: usize) -> &mut [T] {
+        slice::from_raw_parts_mut(self.ptr(), len)
+    }
+
+    /// # Safety
+    /// The first `len` slots must be initialized, and are left logically uninitialized.
+    pub unsafe fn drop_in_place(&mut self, len: usize) {
+        ptr::drop_in_place(self.slice_mut(len))
+    }
+}
+
+impl<T> Default for Column<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure-of-arrays vector: every field of `T` is stored in its own column, so iterating over a few fields only
+/// touches their memory. `T` implements [`Soa`] through `#[derive(Soa)]`.
+///
+/// Elements are pushed and removed whole, keeping the columns in sync, and read back as the generated
+/// `<T>Ref`/`<T>RefMut` types, or as one slice per column.
+/// # Example
+/// ```
+/// use vec::{Soa, SoaVec};
+///
+/// #[derive(Soa)]
+/// struct Particle {
+///     pos: f32,
+///     vel: f32,
+/// }
+///
+/// let mut particles = SoaVec::new();
+/// particles.push(Particle { pos: 0.0, vel: 1.0 });
+/// particles.push(Particle { pos: 5.0, vel: -1.0 });
+///
+/// for p in particles.iter_mut() {
+///     *p.pos += *p.vel;
+/// }
+///
+/// assert_eq!(particles.slices().pos, &[1.0, 4.0]);
+/// assert_eq!(*particles.get(1).unwrap().vel, -1.0);
+/// ```
+pub struct SoaVec<T: Soa> {
+    columns: T::Columns,
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T: Soa> SoaVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let v: vec::SoaVec<Point> = vec::SoaVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            columns: T::columns(),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates an empty vector with room for `capacity` elements in every column.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let v: vec::SoaVec<Point> = vec::SoaVec::with_capacity(10);
+    /// assert!(v.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut v = Self::new();
+        v.reserve(capacity);
+        v
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let v: vec::SoaVec<Point> = vec::SoaVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating any column.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// assert_eq!(v.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        T::capacity(&self.columns)
+    }
+
+    /// Makes room for at least `additional` more elements in every column.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v: vec::SoaVec<Point> = vec::SoaVec::new();
+    /// v.reserve(10);
+    /// assert!(v.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        T::reserve(&mut self.columns, self.len, additional)
+    }
+
+    /// Appends an element, writing each field to its column.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// assert_eq!(*v.get(0).unwrap().y, 2);
+    /// ```
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+
+        unsafe { T::write(&mut self.columns, self.len, value) };
+        self.len += 1;
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// assert_eq!(v.pop().map(|p| p.x), Some(1));
+    /// assert!(v.pop().is_none());
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(T::read(&self.columns, self.len)) }
+    }
+
+    /// Removes the element at `index` and returns it, moving the last element into its place in every column.
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 0 });
+    /// v.push(Point { x: 2, y: 0 });
+    /// v.push(Point { x: 3, y: 0 });
+    ///
+    /// assert_eq!(v.swap_remove(0).x, 1);
+    /// assert_eq!(v.slices().x, &[3, 2]);
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        unsafe {
+            let value = T::read(&self.columns, index);
+            self.len -= 1;
+
+            if index != self.len {
+                T::copy(&mut self.columns, self.len, index);
+            }
+
+            value
+        }
+    }
+
+    /// Returns references to the fields of the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// assert_eq!(*v.get(0).unwrap().x, 1);
+    /// assert!(v.get(1).is_none());
+    /// ```
+    pub fn get(&self, index: usize) -> Option<T::Ref<'_>> {
+        if index < self.len {
+            unsafe { Some(T::get(&self.columns, index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns mutable references to the fields of the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// *v.get_mut(0).unwrap().x = 3;
+    /// assert_eq!(*v.get(0).unwrap().x, 3);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<T::RefMut<'_>> {
+        if index < self.len {
+            unsafe { Some(T::get_mut(&self.columns, index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the elements, each one as references zipped from every column.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// v.push(Point { x: 3, y: 4 });
+    /// assert_eq!(v.iter().map(|p| p.x * p.y).sum::<i32>(), 14);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = T::Ref<'_>> + ExactSizeIterator + '_ {
+        (0..self.len).map(move |i| unsafe { T::get(&self.columns, i) })
+    }
+
+    /// Returns an iterator over the elements, each one as mutable references zipped from every column.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// v.iter_mut().for_each(|p| *p.x += *p.y);
+    /// assert_eq!(*v.get(0).unwrap().x, 3);
+    /// ```
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = T::RefMut<'_>> + ExactSizeIterator + '_ {
+        let columns = &self.columns;
+
+        // Every index is yielded once, so the mutable references never alias
+        (0..self.len).map(move |i| unsafe { T::get_mut(columns, i) })
+    }
+
+    /// Returns the columns, one slice per field.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// v.push(Point { x: 3, y: 4 });
+    /// assert_eq!(v.slices().y, &[2, 4]);
+    /// ```
+    pub fn slices(&self) -> T::Slices<'_> {
+        unsafe { T::slices(&self.columns, self.len) }
+    }
+
+    /// Returns the columns, one mutable slice per field.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// v.slices_mut().x.fill(0);
+    /// assert_eq!(*v.get(0).unwrap().x, 0);
+    /// ```
+    pub fn slices_mut(&mut self) -> T::SlicesMut<'_> {
+        unsafe { T::slices_mut(&mut self.columns, self.len) }
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// # #[derive(vec::Soa)] struct Point { x: i32, y: i32 }
+    /// let mut v = vec::SoaVec::new();
+    /// v.push(Point { x: 1, y: 2 });
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        let len = self.len;
+
+        // Reset first, so a panicking destructor can't cause an element to be dropped twice
+        self.len = 0;
+        unsafe { T::drop_in_place(&mut self.columns, len) }
+    }
+}
+
+impl<T: Soa> Default for SoaVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Soa> Extend<T> for SoaVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Soa> Drop for SoaVec<T> {
+    fn drop(&mut self) {
+        unsafe { T::drop_in_place(&mut self.columns, self.len) }
+        // Deallocation is handled by the RawVec of each column
+    }
+}
@@ -0,0 +1,905 @@
+use std::{
+    alloc::Allocator,
+    cmp::{self, Ordering},
+    mem::{self, MaybeUninit},
+    ptr, slice,
+};
+
+use crate::{growth::GrowthPolicy, Vec};
+
+// Slices up to this length are insertion sorted
+const INSERTION_LEN: usize = 20;
+// Slices from this length pick their pivot as the median of three medians of three
+const NINTHER_LEN: usize = 50;
+// Past this many swaps while choosing the pivot, the slice is most likely descending and gets reversed
+const MAX_SWAPS: usize = 4 * 3;
+// Natural runs shorter than this are extended with an insertion sort before being merged
+const MIN_RUN: usize = 10;
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Sorts the vector without preserving the order of equal elements, in place and without allocating.
+    ///
+    /// The sort is a pattern-defeating quicksort: O(n log n) in the worst case, and linear on input that is already
+    /// sorted, descending or made of few distinct values.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([5, -3, 4, 1, -3]);
+    /// v.sort_unstable();
+    /// assert_eq!(&*v, &[-3, -3, 1, 4, 5]);
+    /// ```
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        quicksort(self, T::lt)
+    }
+
+    /// Sorts the vector with a comparator function, without preserving the order of equal elements. See
+    /// [`Vec::sort_unstable`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([5, 4, 1, 3, 2]);
+    /// v.sort_unstable_by(|a, b| b.cmp(a));
+    /// assert_eq!(&*v, &[5, 4, 3, 2, 1]);
+    /// ```
+    pub fn sort_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        quicksort(self, |a, b| compare(a, b) == Ordering::Less)
+    }
+
+    /// Sorts the vector with a key extraction function, without preserving the order of equal elements. The key is
+    /// extracted again on every comparison. See [`Vec::sort_unstable`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([-5i32, 4, 1, -3, 2]);
+    /// v.sort_unstable_by_key(|k| k.abs());
+    /// assert_eq!(&*v, &[1, 2, -3, 4, -5]);
+    /// ```
+    pub fn sort_unstable_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        quicksort(self, |a, b| f(a).lt(&f(b)))
+    }
+
+    /// Sorts the vector, preserving the order of equal elements.
+    ///
+    /// The sort merges the runs already present in the vector, so it is O(n log n) in the worst case and linear on
+    /// sorted or descending input. Its scratch buffer of half the length is allocated from the vector's own
+    /// allocator.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([5, -3, 4, 1, -3]);
+    /// v.sort();
+    /// assert_eq!(&*v, &[-3, -3, 1, 4, 5]);
+    /// ```
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        let (v, alloc) = self.split_allocator();
+        merge_sort(v, T::lt, alloc)
+    }
+
+    /// Sorts the vector with a comparator function, preserving the order of equal elements. See [`Vec::sort`].
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')]);
+    /// v.sort_by(|a, b| a.0.cmp(&b.0));
+    /// assert_eq!(&*v, &[(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c')]);
+    /// ```
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        let (v, alloc) = self.split_allocator();
+        merge_sort(v, |a, b| compare(a, b) == Ordering::Less, alloc)
+    }
+
+    /// Sorts the vector with a key extraction function called once per element, preserving the order of equal
+    /// elements. The keys are kept in a buffer allocated from the vector's own allocator, which pays off over
+    /// [`Vec::sort_unstable_by_key`] when the key is expensive to compute.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([-5i32, 4, 1, -3, 2, -1]);
+    /// v.sort_by_cached_key(|k| k.to_string().len());
+    /// assert_eq!(&*v, &[4, 1, 2, -5, -3, -1]);
+    /// ```
+    pub fn sort_by_cached_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        let (v, alloc) = self.split_allocator();
+        if v.len() < 2 {
+            return;
+        }
+
+        // Each key is paired with its element's index, which makes every pair distinct and the unstable sort stable
+        let mut indices = Vec::with_capacity_in(v.len(), alloc);
+        indices.extend(v.iter().map(f).enumerate().map(|(i, key)| (key, i)));
+        indices.sort_unstable();
+
+        // Apply the permutation with swaps: an index smaller than `i` points at an element already swapped away,
+        // so follow where it went
+        for i in 0..v.len() {
+            let mut index = indices[i].1;
+            while index < i {
+                index = indices[index].1;
+            }
+
+            indices[i].1 = index;
+            v.swap(i, index);
+        }
+    }
+
+    /// Inserts an element into an already sorted vector, after the ones equal to it, and returns its index. The
+    /// position is found with a binary search, for keeping a vector sorted without the [`SortedVec`] wrapper.
+    ///
+    /// [`SortedVec`]: crate::SortedVec
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([10, 20, 30]);
+    /// assert_eq!(v.insert_sorted(25), 2);
+    /// assert_eq!(v.insert_sorted(20), 2);
+    /// assert_eq!(&*v, &[10, 20, 20, 25, 30]);
+    /// ```
+    pub fn insert_sorted(&mut self, elem: T) -> usize
+    where
+        T: Ord,
+    {
+        self.insert_sorted_by(elem, T::cmp)
+    }
+
+    /// Inserts an element into a vector sorted by a comparator function, after the ones equal to it, and returns
+    /// its index. See [`Vec::insert_sorted`].
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([30, 20, 10]);
+    /// assert_eq!(v.insert_sorted_by(15, |a, b| b.cmp(a)), 2);
+    /// assert_eq!(&*v, &[30, 20, 15, 10]);
+    /// ```
+    pub fn insert_sorted_by<F: FnMut(&T, &T) -> Ordering>(
+        &mut self,
+        elem: T,
+        mut compare: F,
+    ) -> usize {
+        let index = self.partition_point(|x| compare(x, &elem) != Ordering::Greater);
+        self.insert(index, elem);
+
+        index
+    }
+
+    /// Inserts an element into a vector sorted by a key extraction function, after the ones with an equal key, and
+    /// returns its index. See [`Vec::insert_sorted`].
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([(1, "a"), (3, "b")]);
+    /// assert_eq!(v.insert_sorted_by_key((1, "c"), |&(k, _)| k), 1);
+    /// assert_eq!(&*v, &[(1, "a"), (1, "c"), (3, "b")]);
+    /// ```
+    pub fn insert_sorted_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, elem: T, mut f: F) -> usize {
+        let key = f(&elem);
+        let index = self.partition_point(|x| f(x) <= key);
+        self.insert(index, elem);
+
+        index
+    }
+
+    /// Returns clones of the `k` largest elements, from the largest down, or of all of them if there are fewer.
+    ///
+    /// The elements are selected in linear time and only the `k` selected ones are sorted, which is cheaper than
+    /// sorting the whole vector when `k` is small. Only references are moved around, the vector is left untouched.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([4, 9, 1, 7, 3, 9]);
+    /// assert_eq!(&*v.top_k(3), &[9, 9, 7]);
+    /// ```
+    pub fn top_k(&self, k: usize) -> Vec<T>
+    where
+        T: Ord + Clone,
+    {
+        self.top_k_by(k, T::cmp)
+    }
+
+    /// Returns clones of the `k` greatest elements according to a comparator function, from the greatest down. A
+    /// reversed comparator selects the `k` smallest ones instead. See [`Vec::top_k`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([4, 9, 1, 7, 3, 9]);
+    /// assert_eq!(&*v.top_k_by(2, |a, b| b.cmp(a)), &[1, 3]);
+    /// ```
+    pub fn top_k_by<F: FnMut(&T, &T) -> Ordering>(&self, k: usize, mut compare: F) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut refs = Vec::with_capacity(self.len);
+        refs.extend(self.iter());
+
+        let refs = refs.into_top_k_by(k, |a, b| compare(a, b));
+        let mut top = Vec::with_capacity(refs.len());
+        top.extend(refs.iter().map(|&elem| elem.clone()));
+
+        top
+    }
+
+    /// Keeps only the `k` largest elements, from the largest down, dropping the others. Like [`Vec::top_k`], but
+    /// without cloning.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend(["pear", "apple", "fig", "plum"]);
+    /// assert_eq!(&*v.into_top_k(2), &["plum", "pear"]);
+    /// ```
+    pub fn into_top_k(self, k: usize) -> Self
+    where
+        T: Ord,
+    {
+        self.into_top_k_by(k, T::cmp)
+    }
+
+    /// Keeps only the `k` greatest elements according to a comparator function, from the greatest down, dropping
+    /// the others. See [`Vec::top_k_by`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend(["pear", "apple", "fig", "plum"]);
+    /// assert_eq!(&*v.into_top_k_by(1, |a, b| a.len().cmp(&b.len())), &["apple"]);
+    /// ```
+    pub fn into_top_k_by<F: FnMut(&T, &T) -> Ordering>(mut self, k: usize, mut compare: F) -> Self {
+        let mut descending = |a: &T, b: &T| compare(b, a);
+
+        // Partition the `k` greatest elements to the front, then sort only them
+        if k == 0 {
+            self.clear();
+        } else if k < self.len {
+            self.select_nth_unstable_by(k - 1, &mut descending);
+            self.truncate(k);
+        }
+
+        self.sort_unstable_by(descending);
+        self
+    }
+
+    /// Returns the permutation that sorts the vector: the index of its smallest element first, then of the next one
+    /// and so on. The vector is left untouched, and the order of the indices of equal elements is unspecified.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([30, 10, 20]);
+    /// assert_eq!(&*v.argsort(), &[1, 2, 0]);
+    /// ```
+    pub fn argsort(&self) -> Vec<usize>
+    where
+        T: Ord,
+    {
+        self.argsort_by(T::cmp)
+    }
+
+    /// Returns the permutation that sorts the vector according to a comparator function. See [`Vec::argsort`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([30, 10, 20]);
+    /// assert_eq!(&*v.argsort_by(|a, b| b.cmp(a)), &[0, 2, 1]);
+    /// ```
+    pub fn argsort_by<F: FnMut(&T, &T) -> Ordering>(&self, mut compare: F) -> Vec<usize> {
+        let mut indices = self.indices();
+        indices.sort_unstable_by(|&a, &b| compare(&self[a], &self[b]));
+        indices
+    }
+
+    /// Returns the permutation that sorts the vector according to a key extraction function. See
+    /// [`Vec::argsort`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([-3i32, 1, -2]);
+    /// assert_eq!(&*v.argsort_by_key(|x| x.abs()), &[1, 2, 0]);
+    /// ```
+    pub fn argsort_by_key<K: Ord, F: FnMut(&T) -> K>(&self, mut f: F) -> Vec<usize> {
+        self.argsort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    /// Returns the permutation that sorts the vector, keeping the indices of equal elements in increasing order.
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([2, 1, 2, 1]);
+    /// assert_eq!(&*v.stable_argsort(), &[1, 3, 0, 2]);
+    /// ```
+    pub fn stable_argsort(&self) -> Vec<usize>
+    where
+        T: Ord,
+    {
+        self.stable_argsort_by(T::cmp)
+    }
+
+    /// Returns the permutation that sorts the vector according to a comparator function, keeping the indices of
+    /// equal elements in increasing order. See [`Vec::stable_argsort`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend(["bb", "a", "cc", "d"]);
+    /// assert_eq!(&*v.stable_argsort_by(|a, b| a.len().cmp(&b.len())), &[1, 3, 0, 2]);
+    /// ```
+    pub fn stable_argsort_by<F: FnMut(&T, &T) -> Ordering>(&self, mut compare: F) -> Vec<usize> {
+        let mut indices = self.indices();
+        indices.sort_by(|&a, &b| compare(&self[a], &self[b]));
+        indices
+    }
+
+    /// Returns the permutation that sorts the vector according to a key extraction function, keeping the indices
+    /// of equal elements in increasing order. See [`Vec::stable_argsort`].
+    /// # Example
+    /// ```
+    /// let mut v = vec::Vec::new();
+    /// v.extend([-1i32, 2, 1, -2]);
+    /// assert_eq!(&*v.stable_argsort_by_key(|x| x.abs()), &[0, 2, 1, 3]);
+    /// ```
+    pub fn stable_argsort_by_key<K: Ord, F: FnMut(&T) -> K>(&self, mut f: F) -> Vec<usize> {
+        self.stable_argsort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    // The identity permutation, for the argsorts to sort
+    fn indices(&self) -> Vec<usize> {
+        let mut indices = Vec::with_capacity(self.len);
+        indices.extend(0..self.len);
+        indices
+    }
+
+    // Borrows the elements and the allocator at the same time, for sorts that allocate their scratch space from it
+    fn split_allocator(&mut self) -> (&mut [T], &A) {
+        let v = unsafe { slice::from_raw_parts_mut(self.ptr(), self.len) };
+        (v, self.buf.allocator())
+    }
+}
+
+// Sorts `v` with a pattern-defeating quicksort, after Orson Peters' pdqsort. Elements only ever move through swaps,
+// except in the insertion sort whose hole is filled back on panic, so a panicking comparison leaves every element in
+// the slice exactly once
+pub fn quicksort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], mut is_less: F) {
+    // Zero-sized values are all the same
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    // Past this many unbalanced partitions, the rest is heapsorted to keep the worst case at O(n log n)
+    let limit = usize::BITS - v.len().leading_zeros();
+    recurse(v, &mut is_less, None, limit);
+}
+
+// Sorts `v`, all of whose elements are greater or equal to `pred` if there is one
+fn recurse<'a, T, F: FnMut(&T, &T) -> bool>(
+    mut v: &'a mut [T],
+    is_less: &mut F,
+    mut pred: Option<&'a T>,
+    mut limit: u32,
+) {
+    let mut was_balanced = true;
+    let mut was_partitioned = true;
+
+    loop {
+        let len = v.len();
+
+        if len <= INSERTION_LEN {
+            insertion_sort(v, is_less);
+            return;
+        }
+
+        if limit == 0 {
+            heapsort(v, is_less);
+            return;
+        }
+
+        // An unbalanced partition hints at a pattern the pivot choice keeps hitting: shuffle some elements around
+        if !was_balanced {
+            break_patterns(v);
+            limit -= 1;
+        }
+
+        let (pivot, likely_sorted) = choose_pivot(v, is_less);
+
+        // The last partition moved nothing and the pivot choice found no disorder: try to finish with a few shifts
+        if was_balanced && was_partitioned && likely_sorted && partial_insertion_sort(v, is_less) {
+            return;
+        }
+
+        // A pivot equal to the predecessor is the smallest value of the slice: put all its copies first and skip
+        // them, which makes slices of few distinct values linear
+        if let Some(pred) = pred {
+            if !is_less(pred, &v[pivot]) {
+                let mid = partition_equal(v, pivot, is_less);
+                v = &mut v[mid..];
+                continue;
+            }
+        }
+
+        let (mid, partitioned) = partition(v, pivot, is_less);
+        was_balanced = cmp::min(mid, len - mid) >= len / 8;
+        was_partitioned = partitioned;
+
+        let (left, right) = v.split_at_mut(mid);
+        let (pivot, right) = right.split_at_mut(1);
+        let pivot = &pivot[0];
+
+        // Recurse into the shorter side and loop on the longer one, bounding the stack depth to O(log n)
+        if left.len() < right.len() {
+            recurse(left, is_less, pred, limit);
+            v = right;
+            pred = Some(pivot);
+        } else {
+            recurse(right, is_less, Some(pivot), limit);
+            v = left;
+        }
+    }
+}
+
+// Writes the element saved in `src` into the slot at `dest` when dropped, filling the hole left by an insertion even
+// if a comparison panics
+struct Hole<T> {
+    src: *const T,
+    dest: *mut T,
+}
+
+impl<T> Drop for Hole<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::copy_nonoverlapping(self.src, self.dest, 1) };
+    }
+}
+
+// Shifts the last element of `v` left until it is in place, assuming the others are sorted
+fn insert_tail<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    let len = v.len();
+    if len < 2 || !is_less(&v[len - 1], &v[len - 2]) {
+        return;
+    }
+
+    unsafe {
+        let arr = v.as_mut_ptr();
+        let tmp = mem::ManuallyDrop::new(ptr::read(arr.add(len - 1)));
+        ptr::copy_nonoverlapping(arr.add(len - 2), arr.add(len - 1), 1);
+
+        let mut hole = Hole {
+            src: &*tmp,
+            dest: arr.add(len - 2),
+        };
+
+        for i in (0..len - 2).rev() {
+            if !is_less(&*tmp, &*arr.add(i)) {
+                break;
+            }
+
+            ptr::copy_nonoverlapping(arr.add(i), arr.add(i + 1), 1);
+            hole.dest = arr.add(i);
+        }
+    }
+}
+
+// Shifts the first element of `v` right until it is in place, assuming the others are sorted
+fn insert_head<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    let len = v.len();
+    if len < 2 || !is_less(&v[1], &v[0]) {
+        return;
+    }
+
+    unsafe {
+        let arr = v.as_mut_ptr();
+        let tmp = mem::ManuallyDrop::new(ptr::read(arr));
+        ptr::copy_nonoverlapping(arr.add(1), arr, 1);
+
+        let mut hole = Hole {
+            src: &*tmp,
+            dest: arr.add(1),
+        };
+
+        for i in 2..len {
+            if !is_less(&*arr.add(i), &*tmp) {
+                break;
+            }
+
+            ptr::copy_nonoverlapping(arr.add(i), arr.add(i - 1), 1);
+            hole.dest = arr.add(i);
+        }
+    }
+}
+
+fn insertion_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    for i in 2..=v.len() {
+        insert_tail(&mut v[..i], is_less);
+    }
+}
+
+// Sorts `v` if it's only a few out of order pairs away from sorted, and returns whether it is sorted
+fn partial_insertion_sort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) -> bool {
+    // Out of order pairs fixed before giving up
+    const MAX_STEPS: usize = 5;
+
+    let len = v.len();
+    let mut i = 1;
+
+    for _ in 0..MAX_STEPS {
+        while i < len && !is_less(&v[i], &v[i - 1]) {
+            i += 1;
+        }
+
+        if i == len {
+            return true;
+        }
+
+        // Sink the smaller element of the pair into the sorted prefix; the greater one is checked by the next scan
+        v.swap(i - 1, i);
+        insert_tail(&mut v[..i], is_less);
+    }
+
+    false
+}
+
+fn heapsort<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) {
+    // Moves `node` down its max-heap until both children are smaller
+    fn sift_down<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], mut node: usize, is_less: &mut F) {
+        loop {
+            let mut child = 2 * node + 1;
+            if child >= v.len() {
+                break;
+            }
+
+            if child + 1 < v.len() && is_less(&v[child], &v[child + 1]) {
+                child += 1;
+            }
+
+            if !is_less(&v[node], &v[child]) {
+                break;
+            }
+
+            v.swap(node, child);
+            node = child;
+        }
+    }
+
+    for node in (0..v.len() / 2).rev() {
+        sift_down(v, node, is_less);
+    }
+
+    for end in (1..v.len()).rev() {
+        v.swap(0, end);
+        sift_down(&mut v[..end], 0, is_less);
+    }
+}
+
+// Picks a pivot index, reversing `v` if it looks descending, and returns whether `v` looks already sorted
+fn choose_pivot<T, F: FnMut(&T, &T) -> bool>(v: &mut [T], is_less: &mut F) -> (usize, bool) {
+    let len = v.len();
+    let mut a = len / 4;
+    let mut b = len / 4 * 2;
+    let mut c = len / 4 * 3;
+    let mut swaps = 0;
+
+    if len >= 8 {
+        let mut sort2 = |a: &mut usize, b: &mut usize| {
+            if is_less(&v[*b], &v[*a]) {
+                mem::swap(a, b);
+                swaps += 1;
+            }
+        };
+        let mut sort3 = |a: &mut usize, b: &mut usize, c: &mut usize| {
+            sort2(a, b);
+            sort2(b, c);
+            sort2(a, b);
+        };
+
+        if len >= NINTHER_LEN {
+            let mut sort_adjacent = |a: &mut usize| {
+                let mid = *a;
+                sort3(&mut (mid - 1), a, &mut (mid + 1));
+            };
+
+            sort_adjacent(&mut a);
+            sort_adjacent(&mut b);
+            sort_adjacent(&mut c);
+        }
+
+        sort3(&mut a, &mut b, &mut c);
+    }
+
+    if swaps < MAX_SWAPS {
+        (b, swaps == 0)
+    } else {
+        v.reverse();
+        (len - 1 - b, true)
+    }
+}
+
+// Moves the elements smaller than the pivot before it and the others after it, returning the final pivot index and
+// whether the slice was already partitioned
+fn partition<T, F: FnMut(&T, &T) -> bool>(
+    v: &mut [T],
+    pivot: usize,
+    is_less: &mut F,
+) -> (usize, bool) {
+    v.swap(0, pivot);
+
+    let (head, rest) = v.split_at_mut(1);
+    let pivot = &head[0];
+    let mut l = 0;
+    let mut r = rest.len();
+
+    while l < r && is_less(&rest[l], pivot) {
+        l += 1;
+    }
+    while l < r && !is_less(&rest[r - 1], pivot) {
+        r -= 1;
+    }
+    let was_partitioned = l >= r;
+
+    loop {
+        while l < r && is_less(&rest[l], pivot) {
+            l += 1;
+        }
+        while l < r && !is_less(&rest[r - 1], pivot) {
+            r -= 1;
+        }
+        if l >= r {
+            break;
+        }
+
+        r -= 1;
+        rest.swap(l, r);
+        l += 1;
+    }
+
+    // The last smaller element takes the pivot's place at the front
+    v.swap(0, l);
+    (l, was_partitioned)
+}
+
+// Moves the elements equal to the pivot, which no element of `v` is smaller than, to the front, and returns how many
+// there are
+fn partition_equal<T, F: FnMut(&T, &T) -> bool>(
+    v: &mut [T],
+    pivot: usize,
+    is_less: &mut F,
+) -> usize {
+    v.swap(0, pivot);
+
+    let (head, rest) = v.split_at_mut(1);
+    let pivot = &head[0];
+    let mut l = 0;
+    let mut r = rest.len();
+
+    loop {
+        while l < r && !is_less(pivot, &rest[l]) {
+            l += 1;
+        }
+        while l < r && is_less(pivot, &rest[r - 1]) {
+            r -= 1;
+        }
+        if l >= r {
+            break;
+        }
+
+        r -= 1;
+        rest.swap(l, r);
+        l += 1;
+    }
+
+    l + 1
+}
+
+// Swaps a few pseudo-random elements into the middle of `v`, where the next pivot is chosen
+fn break_patterns<T>(v: &mut [T]) {
+    let len = v.len();
+    if len < 8 {
+        return;
+    }
+
+    // Xorshift, seeded with the length to stay deterministic
+    let mut random = len;
+    let mut gen = || {
+        random ^= random << 13;
+        random ^= random >> 7;
+        random ^= random << 17;
+        random
+    };
+
+    let modulus = len.next_power_of_two();
+    let pos = len / 4 * 2;
+
+    for i in 0..3 {
+        let mut other = gen() & (modulus - 1);
+        if other >= len {
+            other -= len;
+        }
+
+        v.swap(pos - 1 + i, other);
+    }
+}
+
+// A sorted range of the slice waiting to be merged
+#[derive(Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+// Sorts `v` stably by merging its natural runs, like TimSort, with a scratch buffer taken from `alloc`
+fn merge_sort<T, F: FnMut(&T, &T) -> bool, A: Allocator>(v: &mut [T], mut is_less: F, alloc: &A) {
+    let len = v.len();
+
+    // Zero-sized values are all the same
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    if len <= INSERTION_LEN {
+        insertion_sort(v, &mut is_less);
+        return;
+    }
+
+    // A merge copies its shorter run out, which is at most half of the slice
+    let mut buf = Vec::<MaybeUninit<T>, &A>::with_capacity_in(len / 2, alloc);
+    let buf = buf.as_mut_ptr() as *mut T;
+    let mut runs = Vec::new_in(alloc);
+
+    // Walk backwards, finding runs and merging them as soon as the stack invariants break, which keeps the merges
+    // balanced and the stack logarithmic
+    let mut end = len;
+    while end > 0 {
+        let mut start = end - 1;
+        if start > 0 {
+            start -= 1;
+
+            // Only strictly descending runs are reversed, so equal elements keep their order
+            if is_less(&v[start + 1], &v[start]) {
+                while start > 0 && is_less(&v[start], &v[start - 1]) {
+                    start -= 1;
+                }
+                v[start..end].reverse();
+            } else {
+                while start > 0 && !is_less(&v[start], &v[start - 1]) {
+                    start -= 1;
+                }
+            }
+        }
+
+        while start > 0 && end - start < MIN_RUN {
+            start -= 1;
+            insert_head(&mut v[start..end], &mut is_less);
+        }
+
+        runs.push(Run {
+            start,
+            len: end - start,
+        });
+        end = start;
+
+        while let Some(r) = collapse(&runs) {
+            let left = runs[r + 1];
+            let right = runs[r];
+            unsafe {
+                merge(
+                    &mut v[left.start..right.start + right.len],
+                    left.len,
+                    buf,
+                    &mut is_less,
+                )
+            };
+
+            runs[r] = Run {
+                start: left.start,
+                len: left.len + right.len,
+            };
+            runs.remove(r + 1);
+        }
+    }
+}
+
+// Returns the index of the run to merge with the one after it, if the top of the stack breaks one of the TimSort
+// invariants or the whole slice has been scanned
+fn collapse(runs: &[Run]) -> Option<usize> {
+    let n = runs.len();
+
+    if n >= 2
+        && (runs[n - 1].start == 0
+            || runs[n - 2].len <= runs[n - 1].len
+            || (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+            || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len))
+    {
+        if n >= 3 && runs[n - 3].len < runs[n - 1].len {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else {
+        None
+    }
+}
+
+// Copies the elements still in the scratch buffer back into the slice when dropped, so a panicking comparison leaves
+// every element in the slice exactly once
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let len = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, len);
+        }
+    }
+}
+
+// Merges the sorted runs `v[..mid]` and `v[mid..]`, copying the shorter one into `buf`, which must have room for it
+unsafe fn merge<T, F: FnMut(&T, &T) -> bool>(
+    v: &mut [T],
+    mid: usize,
+    buf: *mut T,
+    is_less: &mut F,
+) {
+    let len = v.len();
+    let v = v.as_mut_ptr();
+    let v_mid = v.add(mid);
+    let v_end = v.add(len);
+
+    if mid <= len - mid {
+        // Merge forwards, taking from the right run only when it is strictly smaller
+        ptr::copy_nonoverlapping(v, buf, mid);
+        let mut hole = MergeHole {
+            start: buf,
+            end: buf.add(mid),
+            dest: v,
+        };
+        let mut right = v_mid;
+
+        while hole.start < hole.end && right < v_end {
+            let src = if is_less(&*right, &*hole.start) {
+                let src = right;
+                right = right.add(1);
+                src
+            } else {
+                let src = hole.start;
+                hole.start = hole.start.add(1);
+                src
+            };
+
+            ptr::copy_nonoverlapping(src, hole.dest, 1);
+            hole.dest = hole.dest.add(1);
+        }
+    } else {
+        // Merge backwards, taking from the left run only when it is strictly greater
+        ptr::copy_nonoverlapping(v_mid, buf, len - mid);
+        let mut hole = MergeHole {
+            start: buf,
+            end: buf.add(len - mid),
+            dest: v_mid,
+        };
+        let mut out = v_end;
+
+        while v < hole.dest && buf < hole.end {
+            out = out.sub(1);
+
+            if is_less(&*hole.end.sub(1), &*hole.dest.sub(1)) {
+                hole.dest = hole.dest.sub(1);
+                ptr::copy_nonoverlapping(hole.dest, out, 1);
+            } else {
+                hole.end = hole.end.sub(1);
+                ptr::copy_nonoverlapping(hole.end, out, 1);
+            }
+        }
+    }
+}
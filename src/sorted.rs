@@ -0,0 +1,294 @@
+use std::{
+    alloc::{Allocator, Global},
+    fmt,
+    ops::{Bound, Deref, RangeBounds},
+    ptr,
+};
+
+use crate::{
+    growth::{Doubling, GrowthPolicy},
+    IntoIter, Vec,
+};
+
+/// Vector keeping its elements in ascending order, so lookups are binary searches.
+///
+/// Only shared access to the elements is given out, as changing one could break the order. Equal elements are
+/// allowed, see [`SortedVec::insert_unique`] and [`SortedVec::dedup`] to keep a single copy.
+/// # Example
+/// ```
+/// use vec::SortedVec;
+///
+/// let mut vec = SortedVec::new();
+/// vec.insert(3);
+/// vec.insert(1);
+/// vec.insert(2);
+///
+/// assert_eq!(&*vec, &[1, 2, 3]);
+/// assert!(vec.contains(&2));
+/// ```
+pub struct SortedVec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    vec: Vec<T, A, G>,
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Creates an empty sorted vector, without allocating.
+    /// # Example
+    /// ```
+    /// let vec: vec::SortedVec<i32> = vec::SortedVec::new();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self { vec: Vec::new() }
+    }
+}
+
+impl<T: Ord> Default for SortedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> SortedVec<T, A, G> {
+    /// Inserts an element after the ones equal to it, and returns its index.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.insert(10);
+    /// vec.insert(30);
+    /// assert_eq!(vec.insert(20), 1);
+    /// assert_eq!(&*vec, &[10, 20, 30]);
+    /// ```
+    pub fn insert(&mut self, elem: T) -> usize {
+        let index = self.vec.partition_point(|x| x <= &elem);
+        self.vec.insert(index, elem);
+
+        index
+    }
+
+    /// Inserts an element unless an equal one is already there.
+    /// # Errors
+    /// Returns the index of the equal element if there is one, `elem` is dropped.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// assert_eq!(vec.insert_unique(1), Ok(0));
+    /// assert_eq!(vec.insert_unique(1), Err(0));
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn insert_unique(&mut self, elem: T) -> Result<usize, usize> {
+        match self.vec.binary_search(&elem) {
+            Ok(index) => Err(index),
+            Err(index) => {
+                self.vec.insert(index, elem);
+                Ok(index)
+            }
+        }
+    }
+
+    /// Returns `true` if the vector holds an element equal to `elem`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.insert(1);
+    /// assert!(vec.contains(&1));
+    /// assert!(!vec.contains(&2));
+    /// ```
+    pub fn contains(&self, elem: &T) -> bool {
+        self.vec.binary_search(elem).is_ok()
+    }
+
+    /// Returns the elements within `range`, found by binary search.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.extend([5, 1, 4, 2, 3]);
+    /// assert_eq!(vec.range(2..4), &[2, 3]);
+    /// assert_eq!(vec.range(4..), &[4, 5]);
+    /// assert_eq!(vec.range(..=1), &[1]);
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> &[T] {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.vec.partition_point(|x| x < start),
+            Bound::Excluded(start) => self.vec.partition_point(|x| x <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.vec.partition_point(|x| x <= end),
+            Bound::Excluded(end) => self.vec.partition_point(|x| x < end),
+            Bound::Unbounded => self.vec.len,
+        };
+
+        // An empty range (e.g. `3..1`) would end before it starts
+        &self.vec[start..end.max(start)]
+    }
+
+    /// Removes an element equal to `elem` and returns it, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.extend([1, 2, 3]);
+    /// assert_eq!(vec.remove_item(&2), Some(2));
+    /// assert_eq!(vec.remove_item(&2), None);
+    /// ```
+    pub fn remove_item(&mut self, elem: &T) -> Option<T> {
+        let index = self.vec.binary_search(elem).ok()?;
+        Some(self.vec.remove(index))
+    }
+
+    /// Removes every element equal to the one before it, so each value is kept once.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.extend([2, 1, 2, 1, 3]);
+    /// vec.dedup();
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn dedup(&mut self) {
+        let len = self.vec.len;
+        if len <= 1 {
+            return;
+        }
+
+        let ptr = self.vec.as_mut_ptr();
+
+        unsafe {
+            // If a comparison or a destructor panics the elements are leaked, rather than dropped twice
+            self.vec.set_len(0);
+
+            let mut write = 1;
+            for read in 1..len {
+                if *ptr.add(read) == *ptr.add(write - 1) {
+                    ptr::drop_in_place(ptr.add(read));
+                } else {
+                    ptr::copy(ptr.add(read), ptr.add(write), 1);
+                    write += 1;
+                }
+            }
+
+            self.vec.set_len(write);
+        }
+    }
+
+    /// Appends the elements of an iterator, then sorts the vector again. Equal elements keep their relative order.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.extend([3, 1, 2]);
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.vec.extend(iter);
+        self.vec.sort();
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> SortedVec<T, A, G> {
+    /// Removes the greatest element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.extend([2, 3, 1]);
+    /// assert_eq!(vec.pop(), Some(3));
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// Removes and returns the element at `index`.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.extend([2, 3, 1]);
+    /// assert_eq!(vec.remove(0), 1);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        self.vec.remove(index)
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::SortedVec::new();
+    /// vec.extend([2, 3, 1]);
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Returns the underlying vector, in ascending order, without copying.
+    /// # Example
+    /// ```
+    /// let mut sorted = vec::SortedVec::new();
+    /// sorted.extend([2, 3, 1]);
+    /// let vec = sorted.into_vec();
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T, A, G> {
+        self.vec
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> From<Vec<T, A, G>> for SortedVec<T, A, G> {
+    /// Sorts a vector in place and wraps it, keeping duplicates. Equal elements keep their relative order.
+    /// # Example
+    /// ```
+    /// use vec::{SortedVec, Vec};
+    /// let mut vec = Vec::new();
+    /// vec.extend([3, 1, 2, 1]);
+    ///
+    /// let sorted = SortedVec::from(vec);
+    /// assert_eq!(&*sorted, &[1, 1, 2, 3]);
+    /// ```
+    fn from(mut vec: Vec<T, A, G>) -> Self {
+        vec.sort();
+        Self { vec }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<SortedVec<T, A, G>> for Vec<T, A, G> {
+    fn from(sorted: SortedVec<T, A, G>) -> Self {
+        sorted.vec
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Deref for SortedVec<T, A, G> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for SortedVec<T, A, G> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for SortedVec<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> PartialEq for SortedVec<T, A, G> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for SortedVec<T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for SortedVec<T, A, G> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A, G>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    fmt, mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// Vector over a large index space where most positions are empty. Only the occupied entries are stored, sorted by
+/// index, so memory is proportional to their number while lookups by index take O(log n).
+/// # Example
+/// ```
+/// use vec::SparseVec;
+///
+/// let mut v = SparseVec::new();
+/// v.set(1_000_000, "a");
+/// v.set(7, "b");
+///
+/// assert_eq!(v.get(7), Some(&"b"));
+/// assert_eq!(v.get(8), None);
+/// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [(7, &"b"), (1_000_000, &"a")]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct SparseVec<T> {
+    // occupied indices in ascending order, `values[i]` is stored at `indices[i]`
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> SparseVec<T> {
+    /// Creates an empty sparse vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::SparseVec<i32> = vec::SparseVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Returns the number of occupied entries.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(100, 1);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.len
+    }
+
+    /// Returns `true` if no entry is occupied.
+    /// # Example
+    /// ```
+    /// let v: vec::SparseVec<i32> = vec::SparseVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.values.len == 0
+    }
+
+    /// Stores `value` at `index`, returning the value previously there, if any.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// assert_eq!(v.set(5, 'a'), None);
+    /// assert_eq!(v.set(5, 'b'), Some('a'));
+    /// ```
+    pub fn set(&mut self, index: usize, value: T) -> Option<T> {
+        match self.indices.binary_search(&index) {
+            Ok(pos) => Some(mem::replace(&mut self.values[pos], value)),
+            Err(pos) => {
+                self.indices.insert(pos, index);
+                self.values.insert(pos, value);
+                None
+            }
+        }
+    }
+
+    /// Clears the entry at `index` and returns its value, or `None` if it was empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(5, 'a');
+    /// assert_eq!(v.remove(5), Some('a'));
+    /// assert_eq!(v.remove(5), None);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let pos = self.indices.binary_search(&index).ok()?;
+
+        self.indices.remove(pos);
+        Some(self.values.remove(pos))
+    }
+
+    /// Returns `true` if the entry at `index` is occupied.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(5, 'a');
+    /// assert!(v.contains(5));
+    /// assert!(!v.contains(6));
+    /// ```
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.binary_search(&index).is_ok()
+    }
+
+    /// Returns a reference to the value at `index`, or `None` if the entry is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(5, 'a');
+    /// assert_eq!(v.get(5), Some(&'a'));
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let pos = self.indices.binary_search(&index).ok()?;
+        Some(&self.values[pos])
+    }
+
+    /// Returns a mutable reference to the value at `index`, or `None` if the entry is empty.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(5, 1);
+    /// *v.get_mut(5).unwrap() += 1;
+    /// assert_eq!(v[5], 2);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let pos = self.indices.binary_search(&index).ok()?;
+        Some(&mut self.values[pos])
+    }
+
+    /// Returns an iterator over the occupied indices and their values, in ascending index order.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(9, 'b');
+    /// v.set(3, 'a');
+    /// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [(3, &'a'), (9, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &T)> + ExactSizeIterator + '_ {
+        self.indices.iter().copied().zip(self.values.iter())
+    }
+
+    /// Returns an iterator over the occupied indices and mutable references to their values, in ascending index
+    /// order.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(3, 1);
+    /// v.iter_mut().for_each(|(index, value)| *value += index);
+    /// assert_eq!(v[3], 4);
+    /// ```
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (usize, &mut T)> + ExactSizeIterator + '_ {
+        self.indices.iter().copied().zip(self.values.iter_mut())
+    }
+
+    /// Returns the occupied indices, in ascending order.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(9, 'b');
+    /// v.set(3, 'a');
+    /// assert_eq!(v.indices(), &[3, 9]);
+    /// ```
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Empties every entry, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.set(3, 'a');
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.indices.clear();
+        self.values.clear();
+    }
+}
+
+impl<T> Default for SparseVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for SparseVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("no value at index")
+    }
+}
+
+impl<T> IndexMut<usize> for SparseVec<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("no value at index")
+    }
+}
+
+impl<T> Extend<(usize, T)> for SparseVec<T> {
+    /// Stores every `(index, value)` pair of an iterator, later pairs replacing earlier ones at the same index.
+    /// # Example
+    /// ```
+    /// let mut v = vec::SparseVec::new();
+    /// v.extend([(10, 'a'), (2, 'b'), (10, 'c')]);
+    /// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [(2, &'b'), (10, &'c')]);
+    /// ```
+    fn extend<I: IntoIterator<Item = (usize, T)>>(&mut self, iter: I) {
+        for (index, value) in iter {
+            self.set(index, value);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SparseVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    fmt,
+    iter::{self, FromIterator},
+    mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+// Sparse entry of a key that isn't in the set
+const NIL: usize = usize::MAX;
+
+/// Map from integer keys to values, with O(1) insertion, removal and lookup, and values stored densely.
+///
+/// A sparse array indexed by key holds the position of each value in the dense arrays of keys and values, which hold
+/// no gaps: removing a value moves the last one into its place. Iterating goes over contiguous memory, as entity
+/// component systems do with their components, at the cost of a sparse array as long as the largest key.
+/// # Example
+/// ```
+/// use vec::SparseSet;
+///
+/// let mut positions = SparseSet::new();
+/// positions.insert(7, (0, 0));
+/// positions.insert(2, (1, 1));
+/// positions.insert(40, (2, 2));
+///
+/// positions.remove(7);
+/// assert!(!positions.contains(7));
+/// assert_eq!(positions[40], (2, 2));
+/// // The last value took the place of the removed one
+/// assert_eq!(positions.keys(), &[40, 2]);
+/// assert_eq!(positions.values(), &[(2, 2), (1, 1)]);
+/// ```
+#[derive(Clone)]
+pub struct SparseSet<T> {
+    // position in `keys` and `values` of every key, `NIL` for the keys not in the set
+    sparse: Vec<usize>,
+    keys: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> SparseSet<T> {
+    /// Creates an empty set, without allocating.
+    /// # Example
+    /// ```
+    /// let set: vec::SparseSet<i32> = vec::SparseSet::new();
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            keys: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Creates an empty set with room for `capacity` values, and keys up to `capacity`.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::with_capacity(16);
+    /// set.insert(15, 'a');
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            sparse: Vec::with_capacity(capacity),
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of values.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(100, 'a');
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.len
+    }
+
+    /// Returns `true` if the set holds no values.
+    /// # Example
+    /// ```
+    /// let set: vec::SparseSet<i32> = vec::SparseSet::new();
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.values.len == 0
+    }
+
+    /// Stores `value` under `key`, returning the value previously stored there, if any. New values go at the end of
+    /// the dense arrays.
+    /// # Panics
+    /// Panics if `key` is `usize::MAX`, or if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// assert_eq!(set.insert(3, 'a'), None);
+    /// assert_eq!(set.insert(3, 'b'), Some('a'));
+    /// assert_eq!(set[3], 'b');
+    /// ```
+    pub fn insert(&mut self, key: usize, value: T) -> Option<T> {
+        if let Some(pos) = self.position(key) {
+            return Some(mem::replace(&mut self.values[pos], value));
+        }
+
+        assert!(key != NIL, "key out of range");
+        if key >= self.sparse.len {
+            let missing = key + 1 - self.sparse.len;
+            self.sparse.extend(iter::repeat_n(NIL, missing));
+        }
+
+        self.sparse[key] = self.values.len;
+        self.keys.push(key);
+        self.values.push(value);
+        None
+    }
+
+    /// Removes the value of `key` and returns it, or `None` if there is none. The last value is moved into its place.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(1, 'a');
+    /// set.insert(2, 'b');
+    /// set.insert(3, 'c');
+    /// assert_eq!(set.remove(1), Some('a'));
+    /// assert_eq!(set.remove(1), None);
+    /// assert_eq!(set.values(), &['c', 'b']);
+    /// ```
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        let pos = self.position(key)?;
+        let last = self.values.len - 1;
+
+        self.keys.swap(pos, last);
+        self.values.swap(pos, last);
+        self.keys.pop();
+        self.sparse[key] = NIL;
+
+        if pos != last {
+            self.sparse[self.keys[pos]] = pos;
+        }
+        self.values.pop()
+    }
+
+    /// Returns `true` if the set holds a value for `key`.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(5, 'a');
+    /// assert!(set.contains(5));
+    /// assert!(!set.contains(6));
+    /// ```
+    pub fn contains(&self, key: usize) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Returns a reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(5, 'a');
+    /// assert_eq!(set.get(5), Some(&'a'));
+    /// assert_eq!(set.get(6), None);
+    /// ```
+    pub fn get(&self, key: usize) -> Option<&T> {
+        Some(&self.values[self.position(key)?])
+    }
+
+    /// Returns a mutable reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(5, 1);
+    /// *set.get_mut(5).unwrap() += 1;
+    /// assert_eq!(set[5], 2);
+    /// ```
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let pos = self.position(key)?;
+        Some(&mut self.values[pos])
+    }
+
+    /// Returns the keys, in the order of [`values`](SparseSet::values).
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(9, 'a');
+    /// set.insert(4, 'b');
+    /// assert_eq!(set.keys(), &[9, 4]);
+    /// ```
+    pub fn keys(&self) -> &[usize] {
+        &self.keys
+    }
+
+    /// Returns the values, packed without gaps.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(9, 'a');
+    /// set.insert(4, 'b');
+    /// assert_eq!(set.values(), &['a', 'b']);
+    /// ```
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the values mutably, packed without gaps.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(9, 1);
+    /// set.insert(4, 2);
+    /// set.values_mut().iter_mut().for_each(|v| *v *= 10);
+    /// assert_eq!(set.values(), &[10, 20]);
+    /// ```
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Returns an iterator over the keys and their values, in dense order.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(9, 'a');
+    /// set.insert(4, 'b');
+    /// assert_eq!(set.iter().collect::<std::vec::Vec<_>>(), [(9, &'a'), (4, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &T)> + ExactSizeIterator + '_ {
+        self.keys.iter().copied().zip(self.values.iter())
+    }
+
+    /// Returns an iterator over the keys and mutable references to their values, in dense order.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(9, 1);
+    /// set.insert(4, 2);
+    /// for (key, value) in set.iter_mut() {
+    ///     *value += key;
+    /// }
+    /// assert_eq!(set.values(), &[10, 6]);
+    /// ```
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (usize, &mut T)> + ExactSizeIterator + '_ {
+        self.keys.iter().copied().zip(self.values.iter_mut())
+    }
+
+    /// Swaps the values of `a` and `b` in the dense arrays, e.g. to group the values matching a query.
+    /// # Panics
+    /// Panics if either key has no value.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(1, 'a');
+    /// set.insert(2, 'b');
+    /// set.swap(1, 2);
+    /// assert_eq!(set.keys(), &[2, 1]);
+    /// assert_eq!(set[1], 'a');
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let pos_a = self.position(a).expect("key not in the set");
+        let pos_b = self.position(b).expect("key not in the set");
+
+        self.keys.swap(pos_a, pos_b);
+        self.values.swap(pos_a, pos_b);
+        self.sparse[a] = pos_b;
+        self.sparse[b] = pos_a;
+    }
+
+    /// Removes every value, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut set = vec::SparseSet::new();
+    /// set.insert(1, 'a');
+    /// set.clear();
+    /// assert!(set.is_empty() && !set.contains(1));
+    /// ```
+    pub fn clear(&mut self) {
+        for &key in self.keys.iter() {
+            self.sparse[key] = NIL;
+        }
+
+        self.keys.clear();
+        self.values.clear();
+    }
+
+    fn position(&self, key: usize) -> Option<usize> {
+        match self.sparse.get(key) {
+            Some(&pos) if pos != NIL => Some(pos),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for SparseSet<T> {
+    type Output = T;
+
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("key not in the set")
+    }
+}
+
+impl<T> IndexMut<usize> for SparseSet<T> {
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        self.get_mut(key).expect("key not in the set")
+    }
+}
+
+impl<T> Extend<(usize, T)> for SparseSet<T> {
+    /// Inserts every key and value of an iterator, later values replacing earlier ones.
+    fn extend<I: IntoIterator<Item = (usize, T)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<T> FromIterator<(usize, T)> for SparseSet<T> {
+    fn from_iter<I: IntoIterator<Item = (usize, T)>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+// Compares the keys and their values, whatever their dense order
+impl<T: PartialEq> PartialEq for SparseSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<T: Eq> Eq for SparseSet<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for SparseSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
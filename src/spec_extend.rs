@@ -0,0 +1,81 @@
+use std::{alloc::Allocator, iter::TrustedLen, ptr, slice};
+
+use crate::{growth::GrowthPolicy, raw::SetLenOnDrop, IntoIter, TryReserveError, Vec};
+
+// Picks the fastest way to extend a vector from an iterator of `I` items, falling back to the generic loop of
+// `Vec::extend_desugared`
+pub(crate) trait SpecExtend<I, Iter> {
+    fn spec_extend(&mut self, iter: Iter) -> Result<(), TryReserveError>;
+}
+
+impl<T, I: Iterator<Item = T>, A: Allocator, G: GrowthPolicy> SpecExtend<T, I> for Vec<T, A, G> {
+    default fn spec_extend(&mut self, iter: I) -> Result<(), TryReserveError> {
+        self.extend_desugared(iter)
+    }
+}
+
+// The exact length is known up front: a single reservation, then writes without capacity checks
+impl<T, I: TrustedLen<Item = T>, A: Allocator, G: GrowthPolicy> SpecExtend<T, I> for Vec<T, A, G> {
+    default fn spec_extend(&mut self, iter: I) -> Result<(), TryReserveError> {
+        // `TrustedLen` iterators without an upper bound have more than `usize::MAX` elements
+        let additional = iter
+            .size_hint()
+            .1
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.buf.try_reserve(self.len, additional)?;
+
+        // The guard keeps the length right if the iterator panics
+        let ptr = self.ptr();
+        let mut len = SetLenOnDrop::new(&mut self.len);
+
+        iter.for_each(|elem| unsafe {
+            ptr::write(ptr.add(len.current_len()), elem);
+            len.increment_len(1);
+        });
+
+        Ok(())
+    }
+}
+
+// Moves the remaining elements of another vector with a single copy, leaving its buffer to be freed empty
+impl<T, A: Allocator, G: GrowthPolicy> SpecExtend<T, IntoIter<T>> for Vec<T, A, G> {
+    fn spec_extend(&mut self, mut iter: IntoIter<T>) -> Result<(), TryReserveError> {
+        let remaining = iter.iter.as_raw_slice();
+        self.buf.try_reserve(self.len, remaining.len())?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(
+                remaining as *const T,
+                self.ptr().add(self.len),
+                remaining.len(),
+            );
+            iter.iter.forget_remaining();
+        }
+
+        self.len += remaining.len();
+        Ok(())
+    }
+}
+
+impl<'a, T: Copy + 'a, I: Iterator<Item = &'a T>, A: Allocator, G: GrowthPolicy>
+    SpecExtend<&'a T, I> for Vec<T, A, G>
+{
+    default fn spec_extend(&mut self, iter: I) -> Result<(), TryReserveError> {
+        self.spec_extend(iter.copied())
+    }
+}
+
+// Copies the whole slice at once
+impl<'a, T: Copy + 'a, A: Allocator, G: GrowthPolicy> SpecExtend<&'a T, slice::Iter<'a, T>>
+    for Vec<T, A, G>
+{
+    fn spec_extend(&mut self, iter: slice::Iter<'a, T>) -> Result<(), TryReserveError> {
+        let slice = iter.as_slice();
+        self.buf.try_reserve(self.len, slice.len())?;
+
+        unsafe { ptr::copy_nonoverlapping(slice.as_ptr(), self.ptr().add(self.len), slice.len()) };
+
+        self.len += slice.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,708 @@
+use std::{
+    borrow::Borrow,
+    cmp,
+    convert::TryFrom,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    mem,
+    ops::Deref,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{IntoIter, String, Vec};
+
+// Makes spill file names unique inside the process
+static NEXT_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Types that can be written to and read back from a spill file. Implemented for the primitive types, and for
+/// vectors, strings, arrays and tuples of spillable types.
+/// # Example
+/// ```
+/// use std::io::{self, Read, Write};
+/// use vec::Spill;
+///
+/// struct Point(i32, i32);
+///
+/// impl Spill for Point {
+///     const SIZE: Option<usize> = Some(8);
+///
+///     fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+///         self.0.spill(writer)?;
+///         self.1.spill(writer)
+///     }
+///
+///     fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+///         Ok(Point(i32::unspill(reader)?, i32::unspill(reader)?))
+///     }
+/// }
+/// ```
+pub trait Spill: Sized {
+    /// Number of bytes every value is written as, if it's the same for all of them. Spilled elements of such a type
+    /// are read back without decoding the ones before them.
+    const SIZE: Option<usize> = None;
+
+    /// Writes the value to `writer`.
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+    /// Reads a value previously written by [`Spill::spill`] from `reader`.
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_spill {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl Spill for $t {
+                const SIZE: Option<usize> = Some(mem::size_of::<$t>());
+
+                fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut bytes = [0; mem::size_of::<$t>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$t>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_spill!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl Spill for bool {
+    const SIZE: Option<usize> = Some(1);
+
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).spill(writer)
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::unspill(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(io::ErrorKind::InvalidData.into()),
+        }
+    }
+}
+
+impl Spill for char {
+    const SIZE: Option<usize> = Some(4);
+
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u32).spill(writer)
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        char::from_u32(u32::unspill(reader)?).ok_or_else(|| io::ErrorKind::InvalidData.into())
+    }
+}
+
+impl Spill for () {
+    const SIZE: Option<usize> = Some(0);
+
+    fn spill<W: Write>(&self, _: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn unspill<R: Read>(_: &mut R) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+// Sequences are written as their length, as a `u64`, followed by their elements
+fn spill_len<W: Write>(len: usize, writer: &mut W) -> io::Result<()> {
+    (len as u64).spill(writer)
+}
+
+fn unspill_len<R: Read>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(u64::unspill(reader)?).map_err(|_| io::ErrorKind::InvalidData.into())
+}
+
+impl<T: Spill> Spill for Vec<T> {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        spill_len(self.len, writer)?;
+        self.iter().try_for_each(|elem| elem.spill(writer))
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = unspill_len(reader)?;
+        // The length comes from the file, so the buffer only grows as elements are actually read
+        let mut vec = Vec::new();
+        for _ in 0..len {
+            vec.push(T::unspill(reader)?);
+        }
+
+        Ok(vec)
+    }
+}
+
+impl<T: Spill> Spill for std::vec::Vec<T> {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        spill_len(self.len(), writer)?;
+        self.iter().try_for_each(|elem| elem.spill(writer))
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = unspill_len(reader)?;
+        (0..len).map(|_| T::unspill(reader)).collect()
+    }
+}
+
+impl Spill for String {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        spill_len(self.len(), writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let bytes = Vec::<u8>::unspill(reader)?;
+        String::from_utf8(bytes).map_err(|_| io::ErrorKind::InvalidData.into())
+    }
+}
+
+impl Spill for std::string::String {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        spill_len(self.len(), writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let bytes = std::vec::Vec::<u8>::unspill(reader)?;
+        std::string::String::from_utf8(bytes).map_err(|_| io::ErrorKind::InvalidData.into())
+    }
+}
+
+impl<T: Spill, const N: usize> Spill for [T; N] {
+    const SIZE: Option<usize> = match T::SIZE {
+        Some(size) => size.checked_mul(N),
+        None => None,
+    };
+
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.iter().try_for_each(|elem| elem.spill(writer))
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        std::array::try_from_fn(|_| T::unspill(reader))
+    }
+}
+
+// Size of two fields written one after the other
+const fn add_sizes(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.checked_add(b),
+        _ => None,
+    }
+}
+
+macro_rules! impl_spill_tuple {
+    ( $( ( $( $t:ident $i:tt ),+ ) ),* ) => {
+        $(
+            impl<$( $t: Spill ),+> Spill for ($( $t, )+) {
+                const SIZE: Option<usize> = {
+                    let size = Some(0);
+                    $( let size = add_sizes(size, $t::SIZE); )+
+                    size
+                };
+
+                fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    $( self.$i.spill(writer)?; )+
+                    Ok(())
+                }
+
+                fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    Ok(($( $t::unspill(reader)?, )+))
+                }
+            }
+        )*
+    };
+}
+
+impl_spill_tuple!(
+    (A 0),
+    (A 0, B 1),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+    (A 0, B 1, C 2, D 3, E 4),
+    (A 0, B 1, C 2, D 3, E 4, F 5)
+);
+
+/// Vector keeping at most a memory budget worth of elements in memory, spilling older elements to a
+/// temporary file once the budget is exceeded and streaming them back during iteration.
+/// # Example
+/// ```
+/// use vec::SpillVec;
+///
+/// // Keep at most 64 bytes (16 `u32`s) in memory
+/// let mut vec = SpillVec::with_budget(64);
+/// for i in 0..1000u32 {
+///     vec.push(i).unwrap();
+/// }
+///
+/// assert_eq!(vec.len(), 1000);
+/// assert!(vec.spilled_len() > 0);
+///
+/// let sum: u32 = vec.iter().map(|elem| *elem.unwrap()).sum();
+/// assert_eq!(sum, (0..1000).sum());
+/// ```
+pub struct SpillVec<T: Spill> {
+    // newest elements, never more than `mem_cap`
+    memory: Vec<T>,
+    // maximum number of elements kept in memory
+    mem_cap: usize,
+    // file holding the oldest elements, created on the first spill and opened for reading and appending
+    file: Option<(PathBuf, File)>,
+    // number of elements written to the file
+    spilled: usize,
+    // file offset of every spill, each one wrote exactly `mem_cap` elements
+    offsets: Vec<u64>,
+}
+
+impl<T: Spill> SpillVec<T> {
+    /// Creates an empty vector keeping at most `bytes` bytes worth of elements in memory (at least one element).
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let vec: SpillVec<u64> = SpillVec::with_budget(1 << 20);
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn with_budget(bytes: usize) -> Self {
+        Self {
+            memory: Vec::new(),
+            mem_cap: cmp::max(bytes / cmp::max(mem::size_of::<T>(), 1), 1),
+            file: None,
+            spilled: 0,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Returns the total number of elements, both in memory and spilled.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(4);
+    /// vec.push(1u8).unwrap();
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.spilled + self.memory.len()
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let vec: SpillVec<u8> = SpillVec::with_budget(4);
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements currently stored in the spill file.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(2);
+    /// vec.push(1u8).unwrap();
+    /// vec.push(2u8).unwrap();
+    /// assert_eq!(vec.spilled_len(), 0);
+    /// vec.push(3u8).unwrap();
+    /// assert_eq!(vec.spilled_len(), 2);
+    /// ```
+    pub fn spilled_len(&self) -> usize {
+        self.spilled
+    }
+
+    /// Appends an element, spilling the in-memory segment to disk first if the budget is exhausted.
+    /// # Errors
+    /// Returns the I/O error if the spill file can't be created or written.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(1);
+    /// vec.push(7u8).unwrap();
+    /// vec.push(8u8).unwrap();
+    /// assert_eq!(vec.len(), 2);
+    /// ```
+    pub fn push(&mut self, elem: T) -> io::Result<()> {
+        if self.memory.len() == self.mem_cap {
+            self.spill()?;
+        }
+
+        self.memory.push(elem);
+        Ok(())
+    }
+
+    /// Appends every element of an iterator, spilling to disk whenever the budget is exhausted.
+    /// # Errors
+    /// Returns the I/O error if the spill file can't be created or written, the elements pushed before are kept.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(2);
+    /// vec.extend(0..5u8).unwrap();
+    /// assert_eq!(vec.len(), 5);
+    /// assert_eq!(vec.spilled_len(), 4);
+    /// ```
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> io::Result<()> {
+        for elem in iter {
+            self.push(elem)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty. Once the elements in memory are
+    /// exhausted, the last spill is read back from disk.
+    /// # Errors
+    /// Returns the I/O error if the spill file can't be read or truncated, the vector is left unchanged.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(2);
+    /// vec.extend(0..3u8).unwrap();
+    ///
+    /// assert_eq!(vec.pop().unwrap(), Some(2));
+    /// assert_eq!(vec.pop().unwrap(), Some(1));
+    /// assert_eq!(vec.spilled_len(), 0);
+    /// assert_eq!(vec.pop().unwrap(), Some(0));
+    /// assert_eq!(vec.pop().unwrap(), None);
+    /// ```
+    pub fn pop(&mut self) -> io::Result<Option<T>> {
+        if self.memory.is_empty() {
+            self.unspill_last()?;
+        }
+
+        Ok(self.memory.pop())
+    }
+
+    /// Returns a copy of the element at `index`, or `None` if it's out of bounds. A spilled element is read back
+    /// from disk, directly if [`Spill::SIZE`] is known and by decoding the elements spilled along with it otherwise.
+    /// # Errors
+    /// Returns the I/O error if the spill file can't be read.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(2);
+    /// vec.extend(10..15u8).unwrap();
+    ///
+    /// assert_eq!(vec.get(1).unwrap(), Some(11));
+    /// assert_eq!(vec.get(4).unwrap(), Some(14));
+    /// assert_eq!(vec.get(5).unwrap(), None);
+    /// ```
+    pub fn get(&self, index: usize) -> io::Result<Option<T>>
+    where
+        T: Clone,
+    {
+        if index >= self.spilled {
+            return Ok(self.memory.get(index - self.spilled).cloned());
+        }
+
+        let offset = self.offsets[index / self.mem_cap];
+        let skipped = index % self.mem_cap;
+
+        if let Some(size) = T::SIZE {
+            let mut reader = self.reader_at(offset + (skipped * size) as u64)?;
+            return T::unspill(&mut reader).map(Some);
+        }
+
+        let mut reader = BufReader::new(self.reader_at(offset)?);
+        for _ in 0..skipped {
+            T::unspill(&mut reader)?;
+        }
+
+        T::unspill(&mut reader).map(Some)
+    }
+
+    /// Removes every element, emptying the spill file.
+    /// # Errors
+    /// Returns the I/O error if the spill file can't be truncated, the spilled elements are kept.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(2);
+    /// vec.extend(0..5u8).unwrap();
+    /// vec.clear().unwrap();
+    /// assert!(vec.is_empty());
+    /// assert_eq!(vec.spilled_len(), 0);
+    /// ```
+    pub fn clear(&mut self) -> io::Result<()> {
+        if let Some((_, file)) = &self.file {
+            file.set_len(0)?;
+        }
+
+        self.spilled = 0;
+        self.offsets.clear();
+        self.memory.clear();
+        Ok(())
+    }
+
+    /// Returns an iterator over every element, reading the spilled ones back from disk and borrowing the ones in
+    /// memory. Errors while reading are yielded by the iterator.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(2);
+    /// for i in 0..5u16 {
+    ///     vec.push(i).unwrap();
+    /// }
+    /// let items: Result<std::vec::Vec<_>, _> = vec.iter().map(|elem| elem.map(|elem| *elem)).collect();
+    /// assert_eq!(items.unwrap(), [0, 1, 2, 3, 4]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            reader: self
+                .file
+                .as_ref()
+                .map(|(_, file)| BufReader::new(ReadAt { file, position: 0 })),
+            remaining: self.spilled,
+            memory: self.memory.iter(),
+        }
+    }
+
+    /// Consumes the vector, returning an iterator yielding every element by value.
+    /// # Errors
+    /// Returns the I/O error if the spill file handle can't be duplicated. Errors while reading are yielded by the
+    /// iterator.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(1);
+    /// vec.push(1u32).unwrap();
+    /// vec.push(2u32).unwrap();
+    /// let mut iter = vec.into_stream().unwrap();
+    /// assert_eq!(iter.next().unwrap().unwrap(), 1);
+    /// assert_eq!(iter.next().unwrap().unwrap(), 2);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn into_stream(mut self) -> io::Result<SpillIntoIter<T>> {
+        let reader = match &self.file {
+            Some((_, file)) => Some(BufReader::new(ReadAt {
+                file: file.try_clone()?,
+                position: 0,
+            })),
+            None => None,
+        };
+
+        Ok(SpillIntoIter {
+            reader,
+            remaining: self.spilled,
+            memory: mem::replace(&mut self.memory, Vec::new()).into_iter(),
+            _file: self,
+        })
+    }
+
+    // Appends every in-memory element to the spill file
+    fn spill(&mut self) -> io::Result<()> {
+        if self.file.is_none() {
+            let path = std::env::temp_dir().join(format!(
+                "vec-spill-{}-{}",
+                process::id(),
+                NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed)
+            ));
+            // Appending, so truncating the file moves the next write back too. Reads go through `ReadAt`, which
+            // doesn't move the cursor writes start from
+            let file = OpenOptions::new()
+                .read(true)
+                .append(true)
+                .create_new(true)
+                .open(&path)?;
+
+            self.file = Some((path, file));
+        }
+
+        if let Some((_, file)) = &mut self.file {
+            let offset = file.metadata()?.len();
+
+            // A failed spill is rolled back, the elements stay in memory and the file holds whole spills only
+            if let Err(err) = write_records(file, &self.memory) {
+                return file.set_len(offset).and(Err(err));
+            }
+
+            self.offsets.push(offset);
+        }
+
+        self.spilled += self.memory.len();
+        self.memory = Vec::new();
+
+        Ok(())
+    }
+
+    // Moves the last spill back into memory, which must be empty, and cuts it off the file
+    fn unspill_last(&mut self) -> io::Result<()> {
+        let (offset, file) = match (self.offsets.last(), &self.file) {
+            (Some(&offset), Some((_, file))) => (offset, file),
+            _ => return Ok(()),
+        };
+
+        let mut reader = BufReader::new(self.reader_at(offset)?);
+        let mut memory = Vec::with_capacity(self.mem_cap);
+        for _ in 0..self.mem_cap {
+            memory.push(T::unspill(&mut reader)?);
+        }
+
+        file.set_len(offset)?;
+        self.offsets.pop();
+        self.spilled -= memory.len();
+        self.memory = memory;
+        Ok(())
+    }
+
+    // Reads the spill file from `offset`
+    fn reader_at(&self, offset: u64) -> io::Result<ReadAt<&File>> {
+        match &self.file {
+            Some((_, file)) => Ok(ReadAt {
+                file,
+                position: offset,
+            }),
+            None => Err(io::ErrorKind::NotFound.into()),
+        }
+    }
+}
+
+// Reads a file from a position of its own, without moving the file's cursor, so any number of readers can share
+// the file handle
+struct ReadAt<F> {
+    file: F,
+    position: u64,
+}
+
+impl<F: Borrow<File>> Read for ReadAt<F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = read_at(self.file.borrow(), buf, self.position)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(unix)]
+fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::read_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::windows::fs::FileExt::seek_read(file, buf, offset)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_at(_: &File, _: &mut [u8], _: u64) -> io::Result<usize> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+// Writes every element to the file, the buffered writer is gone (and flushed) once this returns
+fn write_records<T: Spill>(file: &mut File, elems: &[T]) -> io::Result<()> {
+    let mut writer = BufWriter::new(file);
+
+    for elem in elems {
+        elem.spill(&mut writer)?;
+    }
+
+    writer.flush()
+}
+
+// Removes the spill file from disk
+impl<T: Spill> Drop for SpillVec<T> {
+    fn drop(&mut self) {
+        if let Some((path, _)) = self.file.take() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Element yielded by [`SpillVec::iter`]: read back from disk if it was spilled, borrowed from the vector
+/// otherwise.
+#[derive(Debug)]
+pub enum SpillRef<'a, T> {
+    /// Element read back from the spill file.
+    Spilled(T),
+    /// Element still in memory.
+    Memory(&'a T),
+}
+
+impl<T: Clone> SpillRef<'_, T> {
+    /// Returns the element by value, cloning it if it's borrowed.
+    /// # Example
+    /// ```
+    /// use vec::SpillVec;
+    /// let mut vec = SpillVec::with_budget(1);
+    /// vec.extend([1u8, 2]).unwrap();
+    ///
+    /// let items: std::vec::Vec<u8> = vec.iter().map(|elem| elem.unwrap().into_owned()).collect();
+    /// assert_eq!(items, [1, 2]);
+    /// ```
+    pub fn into_owned(self) -> T {
+        match self {
+            Self::Spilled(elem) => elem,
+            Self::Memory(elem) => elem.clone(),
+        }
+    }
+}
+
+impl<T> Deref for SpillRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Self::Spilled(elem) => elem,
+            Self::Memory(elem) => elem,
+        }
+    }
+}
+
+/// Borrowing iterator over a [`SpillVec`], created by [`SpillVec::iter`].
+pub struct Iter<'a, T: Spill> {
+    reader: Option<BufReader<ReadAt<&'a File>>>,
+    remaining: usize,
+    memory: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T: Spill> Iterator for Iter<'a, T> {
+    type Item = io::Result<SpillRef<'a, T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.reader {
+            Some(reader) if self.remaining > 0 => {
+                self.remaining -= 1;
+                Some(T::unspill(reader).map(SpillRef::Spilled))
+            }
+            _ => self.memory.next().map(|elem| Ok(SpillRef::Memory(elem))),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining + self.memory.len();
+        (len, Some(len))
+    }
+}
+
+/// Owning iterator over a [`SpillVec`], created by [`SpillVec::into_stream`].
+pub struct SpillIntoIter<T: Spill> {
+    reader: Option<BufReader<ReadAt<File>>>,
+    remaining: usize,
+    memory: IntoIter<T>,
+    // keeps the spill file alive until iteration ends
+    _file: SpillVec<T>,
+}
+
+impl<T: Spill> Iterator for SpillIntoIter<T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.reader {
+            Some(reader) if self.remaining > 0 => {
+                self.remaining -= 1;
+                Some(T::unspill(reader))
+            }
+            _ => self.memory.next().map(Ok),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, _) = self.memory.size_hint();
+        (self.remaining + len, Some(self.remaining + len))
+    }
+}
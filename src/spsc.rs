@@ -0,0 +1,363 @@
+use std::{
+    alloc::Global,
+    cmp, fmt, ptr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    growth::Doubling,
+    raw::{handle_reserve_error, RawVec},
+};
+
+/// Creates a bounded single-producer/single-consumer ring holding up to `capacity` elements, and returns its two
+/// ends. Both can be sent to different threads.
+///
+/// The buffer is allocated here once; pushing and popping never allocate, lock or block, which suits real-time
+/// threads such as audio callbacks. The producer and consumer indices live on separate cache lines, so the two
+/// threads don't keep invalidating each other's line.
+/// # Panics
+/// Panics if `capacity` is zero or above `usize::MAX / 4`, or the buffer exceeds `isize::MAX` bytes.
+/// # Example
+/// ```
+/// use std::thread;
+///
+/// let (mut tx, mut rx) = vec::spsc_ring::<f32>(256);
+///
+/// let worker = thread::spawn(move || {
+///     let mut received = 0;
+///     let mut block = [0.0; 64];
+///     while received < 1024 {
+///         received += rx.read_into(&mut block);
+///     }
+///     received
+/// });
+///
+/// let samples = [0.5; 64];
+/// let mut sent = 0;
+/// while sent < 1024 {
+///     sent += tx.write_from(&samples[..(1024 - sent).min(64)]);
+/// }
+///
+/// assert_eq!(worker.join().unwrap(), 1024);
+/// ```
+pub fn spsc_ring<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "capacity must be non-zero");
+    assert!(capacity <= usize::MAX / 4, "capacity overflow");
+
+    let buf = match RawVec::try_with_capacity_in(capacity, Doubling, Global) {
+        Ok(buf) => buf,
+        Err(err) => handle_reserve_error(err),
+    };
+    let shared = Arc::new(Shared {
+        buf,
+        capacity,
+        head: CachePadded(AtomicUsize::new(0)),
+        tail: CachePadded(AtomicUsize::new(0)),
+    });
+
+    (
+        Producer {
+            shared: shared.clone(),
+            tail: 0,
+            cached_head: 0,
+        },
+        Consumer {
+            shared,
+            head: 0,
+            cached_tail: 0,
+        },
+    )
+}
+
+// Keeps the value on its own cache line
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+struct Shared<T> {
+    buf: RawVec<T>,
+    // the buffer of a ZST reports a capacity of `usize::MAX`, so the requested one is kept here
+    capacity: usize,
+    // index of the oldest element, written only by the consumer. Indices run modulo `2 * capacity`, so a full ring
+    // can be told apart from an empty one
+    head: CachePadded<AtomicUsize>,
+    // index of the next free slot, written only by the producer
+    tail: CachePadded<AtomicUsize>,
+}
+
+impl<T> Shared<T> {
+    fn slot(&self, index: usize) -> *mut T {
+        unsafe { self.buf.ptr.as_ptr().add(index % self.capacity) }
+    }
+
+    // Index `n` slots after `index`, with `n <= capacity`
+    fn advance(&self, index: usize, n: usize) -> usize {
+        (index + n) % (2 * self.capacity)
+    }
+
+    // Number of elements between `head` and `tail`
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        (tail + 2 * self.capacity - head) % (2 * self.capacity)
+    }
+
+    // Length of the run of slots starting at `index` that doesn't wrap around, capped at `len`
+    fn contiguous(&self, index: usize, len: usize) -> usize {
+        cmp::min(len, self.capacity - index % self.capacity)
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let (head, tail) = (*self.head.0.get_mut(), *self.tail.0.get_mut());
+
+        for index in 0..self.distance(head, tail) {
+            unsafe { ptr::drop_in_place(self.slot(head + index)) }
+        }
+        // Deallocation is handled by RawVec
+    }
+}
+
+/// Writing end of a ring created by [`spsc_ring`].
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    // local copy of `shared.tail`, which only this end writes
+    tail: usize,
+    // last head seen, so the consumer's cache line is only read when the ring looks full
+    cached_head: usize,
+}
+
+/// Reading end of a ring created by [`spsc_ring`].
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+    // local copy of `shared.head`, which only this end writes
+    head: usize,
+    // last tail seen, so the producer's cache line is only read when the ring looks empty
+    cached_tail: usize,
+}
+
+// Each end only touches the slots the indices hand over to it
+unsafe impl<T: Send> Send for Producer<T> {}
+unsafe impl<T: Send> Send for Consumer<T> {}
+
+impl<T> Producer<T> {
+    /// Returns the number of elements the ring can hold.
+    /// # Example
+    /// ```
+    /// let (tx, _rx) = vec::spsc_ring::<u8>(16);
+    /// assert_eq!(tx.capacity(), 16);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Returns the number of free slots. The consumer may free more at any time.
+    /// # Example
+    /// ```
+    /// let (mut tx, _rx) = vec::spsc_ring(4);
+    /// tx.push(1).unwrap();
+    /// assert_eq!(tx.free(), 3);
+    /// ```
+    pub fn free(&mut self) -> usize {
+        self.cached_head = self.shared.head.0.load(Ordering::Acquire);
+        self.capacity() - self.shared.distance(self.cached_head, self.tail)
+    }
+
+    /// Returns `true` if the ring has no free slot.
+    /// # Example
+    /// ```
+    /// let (mut tx, _rx) = vec::spsc_ring(1);
+    /// tx.push(1).unwrap();
+    /// assert!(tx.is_full());
+    /// ```
+    pub fn is_full(&mut self) -> bool {
+        self.free() == 0
+    }
+
+    /// Appends an element, or gives it back if the ring is full.
+    /// # Example
+    /// ```
+    /// let (mut tx, mut rx) = vec::spsc_ring(1);
+    /// assert_eq!(tx.push(1), Ok(()));
+    /// assert_eq!(tx.push(2), Err(2));
+    /// assert_eq!(rx.pop(), Some(1));
+    /// ```
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.reserve(1) == 0 {
+            return Err(value);
+        }
+
+        unsafe { ptr::write(self.shared.slot(self.tail), value) };
+        self.publish(1);
+        Ok(())
+    }
+
+    /// Copies as many elements of `src` as fit, and returns how many were written.
+    /// # Example
+    /// ```
+    /// let (mut tx, mut rx) = vec::spsc_ring(4);
+    /// assert_eq!(tx.write_from(&[1, 2, 3, 4, 5, 6]), 4);
+    ///
+    /// let mut dst = [0; 8];
+    /// assert_eq!(rx.read_into(&mut dst), 4);
+    /// assert_eq!(dst[..4], [1, 2, 3, 4]);
+    /// ```
+    pub fn write_from(&mut self, src: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let len = self.reserve(src.len());
+
+        // Up to the end of the buffer, then from its start
+        let first = self.shared.contiguous(self.tail, len);
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), self.shared.slot(self.tail), first);
+            ptr::copy_nonoverlapping(
+                src.as_ptr().add(first),
+                self.shared.slot(self.tail + first),
+                len - first,
+            );
+        }
+
+        self.publish(len);
+        len
+    }
+
+    // Number of slots, up to `wanted`, that can be written from the tail
+    fn reserve(&mut self, wanted: usize) -> usize {
+        let capacity = self.capacity();
+
+        if capacity - self.shared.distance(self.cached_head, self.tail) < wanted {
+            self.cached_head = self.shared.head.0.load(Ordering::Acquire);
+        }
+
+        cmp::min(
+            wanted,
+            capacity - self.shared.distance(self.cached_head, self.tail),
+        )
+    }
+
+    // Hands `len` written slots over to the consumer
+    fn publish(&mut self, len: usize) {
+        self.tail = self.shared.advance(self.tail, len);
+        self.shared.tail.0.store(self.tail, Ordering::Release);
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Returns the number of elements the ring can hold.
+    /// # Example
+    /// ```
+    /// let (_tx, rx) = vec::spsc_ring::<u8>(16);
+    /// assert_eq!(rx.capacity(), 16);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Returns the number of elements ready to be read. The producer may add more at any time.
+    /// # Example
+    /// ```
+    /// let (mut tx, mut rx) = vec::spsc_ring(4);
+    /// tx.push(1).unwrap();
+    /// assert_eq!(rx.len(), 1);
+    /// ```
+    pub fn len(&mut self) -> usize {
+        self.cached_tail = self.shared.tail.0.load(Ordering::Acquire);
+        self.shared.distance(self.head, self.cached_tail)
+    }
+
+    /// Returns `true` if no element is ready to be read.
+    /// # Example
+    /// ```
+    /// let (_tx, mut rx) = vec::spsc_ring::<u8>(4);
+    /// assert!(rx.is_empty());
+    /// ```
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the oldest element and returns it, or `None` if the ring is empty.
+    /// # Example
+    /// ```
+    /// let (mut tx, mut rx) = vec::spsc_ring(2);
+    /// tx.push(1).unwrap();
+    /// tx.push(2).unwrap();
+    /// assert_eq!(rx.pop(), Some(1));
+    /// assert_eq!(rx.pop(), Some(2));
+    /// assert_eq!(rx.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.available(1) == 0 {
+            return None;
+        }
+
+        let value = unsafe { ptr::read(self.shared.slot(self.head)) };
+        self.release(1);
+        Some(value)
+    }
+
+    /// Copies as many of the oldest elements as fit into `dst`, removing them, and returns how many were read.
+    /// # Example
+    /// ```
+    /// let (mut tx, mut rx) = vec::spsc_ring(4);
+    /// tx.write_from(&[1, 2, 3]);
+    ///
+    /// let mut dst = [0; 2];
+    /// assert_eq!(rx.read_into(&mut dst), 2);
+    /// assert_eq!(dst, [1, 2]);
+    /// assert_eq!(rx.pop(), Some(3));
+    /// ```
+    pub fn read_into(&mut self, dst: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let len = self.available(dst.len());
+
+        // Up to the end of the buffer, then from its start
+        let first = self.shared.contiguous(self.head, len);
+        unsafe {
+            ptr::copy_nonoverlapping(self.shared.slot(self.head), dst.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(
+                self.shared.slot(self.head + first),
+                dst.as_mut_ptr().add(first),
+                len - first,
+            );
+        }
+
+        self.release(len);
+        len
+    }
+
+    // Number of elements, up to `wanted`, that can be read from the head
+    fn available(&mut self, wanted: usize) -> usize {
+        if self.shared.distance(self.head, self.cached_tail) < wanted {
+            self.cached_tail = self.shared.tail.0.load(Ordering::Acquire);
+        }
+
+        cmp::min(wanted, self.shared.distance(self.head, self.cached_tail))
+    }
+
+    // Hands `len` read slots back to the producer
+    fn release(&mut self, len: usize) {
+        self.head = self.shared.advance(self.head, len);
+        self.shared.head.0.store(self.head, Ordering::Release);
+    }
+}
+
+impl<T> fmt::Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,296 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// Vector whose removals leave holes behind, so the index of every other element never changes. Iteration follows
+/// insertion order, skipping the holes, until [`compact`](StableVec::compact) closes them on request.
+///
+/// Unlike a [`Slab`](crate::Slab), holes are never refilled: new elements always go at the end.
+/// # Example
+/// ```
+/// use vec::StableVec;
+///
+/// let mut v = StableVec::new();
+/// let a = v.push('a');
+/// let b = v.push('b');
+/// let c = v.push('c');
+///
+/// v.remove(b);
+/// assert_eq!(v[c], 'c');
+/// assert_eq!(v.values().collect::<String>(), "ac");
+///
+/// v.compact();
+/// assert_eq!(v[a], 'a');
+/// assert_eq!(v[1], 'c');
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct StableVec<T> {
+    slots: Vec<Option<T>>,
+    // number of occupied slots
+    len: usize,
+}
+
+impl<T> StableVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::StableVec<i32> = vec::StableVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates an empty vector with room for `capacity` slots.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let v: vec::StableVec<i32> = vec::StableVec::with_capacity(8);
+    /// assert_eq!(v.capacity(), 8);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements, not counting the holes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push(1);
+    /// v.push(2);
+    /// v.remove(a);
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::StableVec<i32> = vec::StableVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots, holes included. This is the index the next pushed element gets.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push(1);
+    /// v.remove(a);
+    /// assert_eq!(v.next_index(), 1);
+    /// ```
+    pub fn next_index(&self) -> usize {
+        self.slots.len
+    }
+
+    /// Returns the number of slots the vector can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// v.push(1u64);
+    /// assert_eq!(v.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Appends an element and returns its index.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// assert_eq!(v.push('a'), 0);
+    /// assert_eq!(v.push('b'), 1);
+    /// ```
+    pub fn push(&mut self, elem: T) -> usize {
+        self.slots.push(Some(elem));
+        self.len += 1;
+        self.slots.len - 1
+    }
+
+    /// Removes the element at `index` and returns it, leaving a hole. Returns `None` if there is no element there.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push(1);
+    /// assert_eq!(v.remove(a), Some(1));
+    /// assert_eq!(v.remove(a), None);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let elem = self.slots.get_mut(index)?.take()?;
+        self.len -= 1;
+        Some(elem)
+    }
+
+    /// Returns `true` if there is an element at `index`.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push(1);
+    /// assert!(v.contains(a));
+    /// v.remove(a);
+    /// assert!(!v.contains(a));
+    /// ```
+    pub fn contains(&self, index: usize) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if there is a hole or it's out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push(1);
+    /// assert_eq!(v.get(a), Some(&1));
+    /// assert_eq!(v.get(a + 1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if there is a hole or it's out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push(1);
+    /// *v.get_mut(a).unwrap() = 2;
+    /// assert_eq!(v[a], 2);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slots.get_mut(index)?.as_mut()
+    }
+
+    /// Returns an iterator over the indices and elements, in order, skipping the holes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push('a');
+    /// v.push('b');
+    /// v.remove(a);
+    /// assert_eq!(v.iter().collect::<std::vec::Vec<_>>(), [(1, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &T)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| Some((index, slot.as_ref()?)))
+    }
+
+    /// Returns an iterator over the indices and mutable references to the elements, in order, skipping the holes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push(1);
+    /// v.iter_mut().for_each(|(_, elem)| *elem *= 10);
+    /// assert_eq!(v[a], 10);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (usize, &mut T)> + '_ {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| Some((index, slot.as_mut()?)))
+    }
+
+    /// Returns an iterator over the elements, in order, skipping the holes.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// v.push(1);
+    /// let b = v.push(2);
+    /// v.push(3);
+    /// v.remove(b);
+    /// assert_eq!(v.values().sum::<i32>(), 4);
+    /// ```
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.slots.iter().flatten()
+    }
+
+    /// Closes every hole by moving the elements towards the front, keeping their order, and drops the trailing
+    /// slots. Indices of elements placed after a hole change.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// let a = v.push('a');
+    /// v.push('b');
+    /// v.remove(a);
+    ///
+    /// v.compact();
+    /// assert_eq!(v.next_index(), 1);
+    /// assert_eq!(v[0], 'b');
+    /// ```
+    pub fn compact(&mut self) {
+        let mut write = 0;
+
+        for read in 0..self.slots.len {
+            if self.slots[read].is_some() {
+                self.slots.swap(write, read);
+                write += 1;
+            }
+        }
+
+        // Only holes are left past `write`
+        self.slots.truncate(write);
+    }
+
+    /// Removes every element and hole, keeping the allocated capacity. Indices start from zero again.
+    /// # Example
+    /// ```
+    /// let mut v = vec::StableVec::new();
+    /// v.push(1);
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.push(2), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+    }
+}
+
+impl<T> Default for StableVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for StableVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("no element at index")
+    }
+}
+
+impl<T> IndexMut<usize> for StableVec<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("no element at index")
+    }
+}
+
+impl<T> Extend<T> for StableVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for StableVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,30 @@
+use std::cmp;
+
+/// Allocation statistics of a single vector, returned by [`Vec::stats`](crate::Vec::stats).
+/// # Example
+/// ```
+/// let mut vec = vec::Vec::new();
+/// for i in 0..100u32 {
+///     vec.push(i);
+/// }
+///
+/// let stats = vec.stats();
+/// // 4, 8, 16, 32, 64 and 128 elements
+/// assert_eq!(stats.reallocations, 6);
+/// assert_eq!(stats.peak_capacity, 128);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VecStats {
+    /// Number of times the buffer was allocated, grown or shrunk.
+    pub reallocations: usize,
+    /// Biggest capacity, in elements, the buffer ever had.
+    pub peak_capacity: usize,
+}
+
+impl VecStats {
+    // Accounts for a new buffer of `cap` elements
+    pub(crate) fn record(&mut self, cap: usize) {
+        self.reallocations += 1;
+        self.peak_capacity = cmp::max(self.peak_capacity, cap);
+    }
+}
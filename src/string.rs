@@ -0,0 +1,367 @@
+use std::{
+    alloc::{Allocator, Global},
+    fmt,
+    ops::{Deref, DerefMut},
+    str,
+};
+
+use crate::{
+    error::FromUtf8Error,
+    growth::{Doubling, GrowthPolicy},
+    Vec,
+};
+
+/// Growable UTF-8 string, stored as a [`Vec<u8>`](Vec) that always holds valid UTF-8.
+/// # Example
+/// ```
+/// use std::fmt::Write;
+/// use vec::String;
+///
+/// let mut s = String::from("hello");
+/// s.push(',');
+/// s.push_str(" world");
+/// write!(s, " {}", 42).unwrap();
+///
+/// assert_eq!(&*s, "hello, world 42");
+/// assert!(s.starts_with("hello"));
+/// ```
+pub struct String<A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    vec: Vec<u8, A, G>,
+}
+
+impl String {
+    /// Creates an empty string, without allocating.
+    /// # Example
+    /// ```
+    /// let s = vec::String::new();
+    /// assert!(s.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self { vec: Vec::new() }
+    }
+
+    /// Creates an empty string with room for `capacity` bytes.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let s = vec::String::with_capacity(16);
+    /// assert_eq!(s.capacity(), 16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
+}
+
+impl Default for String {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> String<A, G> {
+    /// Turns a byte vector into a string without copying, checking it holds valid UTF-8.
+    /// # Errors
+    /// Returns a [`FromUtf8Error`] holding the bytes if they aren't valid UTF-8.
+    /// # Example
+    /// ```
+    /// use vec::{String, Vec};
+    /// let mut bytes = Vec::new();
+    /// bytes.extend(*b"hi");
+    ///
+    /// let s = String::from_utf8(bytes).unwrap();
+    /// assert_eq!(&*s, "hi");
+    /// ```
+    pub fn from_utf8(vec: Vec<u8, A, G>) -> Result<Self, FromUtf8Error<A, G>> {
+        match str::from_utf8(&vec) {
+            Ok(_) => Ok(Self { vec }),
+            Err(error) => Err(FromUtf8Error { bytes: vec, error }),
+        }
+    }
+
+    /// Turns a byte vector into a string without copying nor checking its contents.
+    /// # Safety
+    /// The bytes must be valid UTF-8.
+    /// # Example
+    /// ```
+    /// use vec::{String, Vec};
+    /// let mut bytes = Vec::new();
+    /// bytes.extend(*b"hi");
+    ///
+    /// let s = unsafe { String::from_utf8_unchecked(bytes) };
+    /// assert_eq!(&*s, "hi");
+    /// ```
+    pub unsafe fn from_utf8_unchecked(vec: Vec<u8, A, G>) -> Self {
+        invariant!(
+            str::from_utf8(&vec).is_ok(),
+            "from_utf8_unchecked: bytes aren't valid UTF-8"
+        );
+
+        Self { vec }
+    }
+
+    /// Returns the underlying bytes, without copying.
+    /// # Example
+    /// ```
+    /// let s = vec::String::from("hi");
+    /// assert_eq!(&*s.into_bytes(), b"hi");
+    /// ```
+    pub fn into_bytes(self) -> Vec<u8, A, G> {
+        self.vec
+    }
+
+    /// Returns the string as a `str` slice.
+    /// # Example
+    /// ```
+    /// let s = vec::String::from("hi");
+    /// assert_eq!(s.as_str(), "hi");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+
+    /// Returns the string as a mutable `str` slice.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::from("hi");
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(&*s, "HI");
+    /// ```
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+
+    /// Returns the number of bytes the string can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::new();
+    /// s.push('a');
+    /// assert_eq!(s.capacity(), 16);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::new();
+    /// s.reserve(10);
+    /// assert!(s.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+
+    /// Appends a string slice.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::from("foo");
+    /// s.push_str("bar");
+    /// assert_eq!(&*s, "foobar");
+    /// ```
+    pub fn push_str(&mut self, string: &str) {
+        self.vec.insert_slice(self.vec.len, string.as_bytes())
+    }
+
+    /// Appends a character, encoded as UTF-8.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::new();
+    /// s.push('a');
+    /// s.push('é');
+    /// assert_eq!(&*s, "aé");
+    /// assert_eq!(s.len(), 3);
+    /// ```
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Removes the last character and returns it, or `None` if the string is empty.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::from("aé");
+    /// assert_eq!(s.pop(), Some('é'));
+    /// assert_eq!(&*s, "a");
+    /// ```
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        self.vec.truncate(self.vec.len - ch.len_utf8());
+
+        Some(ch)
+    }
+
+    /// Inserts a string slice at byte `index`.
+    /// # Panics
+    /// Panics if `index` is greater than the length or isn't on a character boundary.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::from("held");
+    /// s.insert_str(2, "llo wor");
+    /// assert_eq!(&*s, "hello world");
+    /// ```
+    pub fn insert_str(&mut self, index: usize, string: &str) {
+        assert!(
+            self.is_char_boundary(index),
+            "index {} isn't on a character boundary",
+            index
+        );
+
+        self.vec.insert_slice(index, string.as_bytes())
+    }
+
+    /// Inserts a character at byte `index`.
+    /// # Panics
+    /// Panics if `index` is greater than the length or isn't on a character boundary.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::from("ac");
+    /// s.insert(1, 'b');
+    /// assert_eq!(&*s, "abc");
+    /// ```
+    pub fn insert(&mut self, index: usize, ch: char) {
+        self.insert_str(index, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Shortens the string to `len` bytes. Does nothing if `len` is greater than the length.
+    /// # Panics
+    /// Panics if `len` isn't on a character boundary.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::from("hello");
+    /// s.truncate(2);
+    /// assert_eq!(&*s, "he");
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            assert!(
+                self.is_char_boundary(len),
+                "length {} isn't on a character boundary",
+                len
+            );
+
+            self.vec.truncate(len)
+        }
+    }
+
+    /// Removes every character, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::from("hello");
+    /// s.clear();
+    /// assert!(s.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> Deref for String<A, G> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> DerefMut for String<A, G> {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl From<&str> for String {
+    fn from(string: &str) -> Self {
+        let mut s = Self::with_capacity(string.len());
+        s.push_str(string);
+        s
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> From<String<A, G>> for Vec<u8, A, G> {
+    fn from(string: String<A, G>) -> Self {
+        string.vec
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> fmt::Write for String<A, G> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> Extend<char> for String<A, G> {
+    /// Appends every character of an iterator.
+    /// # Example
+    /// ```
+    /// let mut s = vec::String::new();
+    /// s.extend(['a', 'b']);
+    /// assert_eq!(&*s, "ab");
+    /// ```
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<'a, A: Allocator, G: GrowthPolicy> Extend<&'a str> for String<A, G> {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for string in iter {
+            self.push_str(string);
+        }
+    }
+}
+
+impl<A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for String<A, G> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> fmt::Display for String<A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> fmt::Debug for String<A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> PartialEq for String<A, G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> Eq for String<A, G> {}
+
+impl<A: Allocator, G: GrowthPolicy> PartialEq<str> for String<A, G> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<A: Allocator, G: GrowthPolicy> PartialEq<&str> for String<A, G> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
@@ -0,0 +1,457 @@
+use std::{
+    alloc::{Allocator, Global, Layout},
+    fmt, mem,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+};
+
+#[cfg(feature = "stats")]
+use crate::stats::VecStats;
+use crate::{
+    growth::Doubling,
+    raw::{
+        grown_capacity, handle_reserve_error, insert_at, poison, realloc_buffer,
+        record_deallocation, record_growth, remove_at, truncate_at,
+    },
+    TryReserveError,
+};
+
+// Length and capacity, stored at the start of the allocation right before the elements
+#[repr(C)]
+struct Header {
+    len: usize,
+    cap: usize,
+    #[cfg(feature = "stats")]
+    stats: VecStats,
+}
+
+// Shared by every empty `ThinVec`, never written to
+static EMPTY_HEADER: Header = Header {
+    len: 0,
+    cap: 0,
+    #[cfg(feature = "stats")]
+    stats: VecStats {
+        reallocations: 0,
+        peak_capacity: 0,
+    },
+};
+
+/// Vector taking a single pointer of space, its length and capacity live in the heap block, right before the
+/// elements. Empty vectors don't allocate.
+///
+/// Handy for structs holding many usually-empty vectors, at the cost of an extra indirection to read the length.
+/// # Example
+/// ```
+/// use std::mem;
+/// use vec::ThinVec;
+///
+/// assert_eq!(mem::size_of::<ThinVec<u64>>(), mem::size_of::<usize>());
+/// assert_eq!(mem::size_of::<Option<ThinVec<u64>>>(), mem::size_of::<usize>());
+///
+/// let mut vec = ThinVec::new();
+/// vec.push(1);
+/// vec.push(2);
+/// assert_eq!(&*vec, &[1, 2]);
+/// ```
+pub struct ThinVec<T> {
+    // header followed by the elements, or the shared empty header
+    ptr: NonNull<Header>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for ThinVec<T> {}
+unsafe impl<T: Sync> Sync for ThinVec<T> {}
+
+impl<T> ThinVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let vec: vec::ThinVec<i32> = vec::ThinVec::new();
+    /// assert_eq!(vec.capacity(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            ptr: NonNull::from(&EMPTY_HEADER),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Creates an empty vector with room for `capacity` elements.
+    /// # Panics
+    /// Panics if the capacity overflows, aborts if the allocator fails.
+    /// # Example
+    /// ```
+    /// let vec: vec::ThinVec<i32> = vec::ThinVec::with_capacity(10);
+    /// assert_eq!(vec.capacity(), 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut vec = Self::new();
+        // Zero-sized types get all the room they could ever need on the first allocation
+        let capacity = if mem::size_of::<T>() == 0 && capacity > 0 {
+            !0
+        } else {
+            capacity
+        };
+
+        if capacity > 0 {
+            if let Err(err) = vec.try_grow_to(capacity, capacity) {
+                handle_reserve_error(err)
+            }
+        }
+
+        vec
+    }
+
+    /// Returns the number of elements in the vector.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.header().len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let vec: vec::ThinVec<u8> = vec::ThinVec::new();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.push(1u32);
+    /// assert_eq!(vec.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.header().cap
+    }
+
+    /// Returns how many times the block was reallocated and the peak capacity it reached, like
+    /// [`Vec::stats`](crate::Vec::stats).
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.extend(0..5u32);
+    /// assert_eq!(vec.stats().reallocations, 2);
+    /// assert_eq!(vec.stats().peak_capacity, 8);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> VecStats {
+        self.header().stats
+    }
+
+    /// Appends an element to the back of the vector.
+    /// # Panics
+    /// Panics if the capacity overflows, aborts if the allocator fails.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.push('a');
+    /// assert_eq!(vec[0], 'a');
+    /// ```
+    pub fn push(&mut self, elem: T) {
+        let len = self.len();
+        self.reserve_one();
+
+        unsafe {
+            ptr::write(self.data().add(len), elem);
+            self.header_mut().len += 1;
+        }
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.pop(), Some(1));
+    /// assert_eq!(vec.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        unsafe {
+            self.header_mut().len -= 1;
+
+            let len = self.len();
+            let elem = ptr::read(self.data().add(len));
+            poison(self.data().add(len), 1);
+            Some(elem)
+        }
+    }
+
+    /// Inserts an element at `index`, shifting all the elements after it to the right.
+    /// # Panics
+    /// Panics if `index > len`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.push(1);
+    /// vec.push(3);
+    /// vec.insert(1, 2);
+    /// assert_eq!(&*vec, &[1, 2, 3]);
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        let len = self.len();
+        assert!(index <= len, "Index out of bounds");
+
+        self.reserve_one();
+
+        unsafe {
+            insert_at(self.data(), len, index, elem);
+            self.header_mut().len += 1;
+        }
+    }
+
+    /// Removes and returns the element at `index`, shifting all the elements after it to the left.
+    /// # Panics
+    /// Panics if `index >= len`.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(vec.remove(1), 1);
+    /// assert_eq!(&*vec, &[0, 2]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(index < len, "index out of bounds");
+
+        unsafe {
+            let elem = remove_at(self.data(), len, index);
+            self.header_mut().len -= 1;
+            elem
+        }
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if `len` is greater than the length.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.extend(0..5);
+    /// vec.truncate(2);
+    /// assert_eq!(&*vec, &[0, 1]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        // The shared empty header is never written to
+        if len >= self.len() {
+            return;
+        }
+
+        let data = self.data();
+        unsafe { truncate_at(data, &mut self.header_mut().len, len) }
+    }
+
+    /// Removes every element, keeping the allocated capacity.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.extend(0..5);
+    /// vec.clear();
+    /// assert!(vec.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    // Must not be called on the shared empty header
+    unsafe fn header_mut(&mut self) -> &mut Header {
+        self.ptr.as_mut()
+    }
+
+    // Whether the vector owns an allocation, as opposed to pointing to the shared empty header
+    fn is_allocated(&self) -> bool {
+        !ptr::eq(self.ptr.as_ptr(), &EMPTY_HEADER)
+    }
+
+    // Pointer to the first element
+    fn data(&self) -> *mut T {
+        if self.is_allocated() {
+            unsafe { self.ptr.as_ptr().byte_add(Self::offset()).cast() }
+        } else {
+            // The shared header isn't followed by anything, and may not be aligned for `T`
+            NonNull::dangling().as_ptr()
+        }
+    }
+
+    // Layout of an allocation holding `cap` elements
+    fn layout(cap: usize) -> Result<Layout, TryReserveError> {
+        let elems = Layout::array::<T>(cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+        let (layout, _) = Layout::new::<Header>()
+            .extend(elems)
+            .map_err(|_| TryReserveError::CapacityOverflow)?;
+
+        Ok(layout)
+    }
+
+    // Distance in bytes from the header to the first element
+    fn offset() -> usize {
+        let elems = Layout::new::<T>();
+        let (_, offset) = Layout::new::<Header>().extend(elems).unwrap();
+
+        offset
+    }
+
+    // Makes room for one more element
+    fn reserve_one(&mut self) {
+        let (len, cap) = (self.len(), self.capacity());
+
+        if len < cap {
+            return;
+        }
+
+        let result = if mem::size_of::<T>() == 0 {
+            // Zero-sized types never need more room than the first allocation
+            if cap == 0 {
+                self.try_grow_to(!0, !0)
+            } else {
+                Err(TryReserveError::CapacityOverflow)
+            }
+        } else {
+            grown_capacity::<T, _>(&Doubling, cap, len, 1)
+                .and_then(|(new_cap, exact)| self.try_grow_to(new_cap, exact))
+        };
+
+        if let Err(err) = result {
+            handle_reserve_error(err)
+        }
+    }
+
+    // Reallocates the block to hold `new_cap` elements, or `exact` if the allocator can't fit that, keeping the
+    // header and the elements
+    fn try_grow_to(&mut self, new_cap: usize, exact: usize) -> Result<(), TryReserveError> {
+        let old_cap = self.capacity();
+        let (ptr, cap) = realloc_buffer(new_cap, exact, |cap| {
+            let new_layout = Self::layout(cap)?;
+
+            let ptr = unsafe {
+                if self.is_allocated() {
+                    let old_layout = Self::layout(old_cap).unwrap();
+                    Global.grow(self.ptr.cast(), old_layout, new_layout)
+                } else {
+                    Global.allocate(new_layout).inspect(|ptr| {
+                        ptr.cast::<Header>().write(Header {
+                            len: 0,
+                            cap: 0,
+                            #[cfg(feature = "stats")]
+                            stats: VecStats::default(),
+                        });
+                    })
+                }
+            };
+
+            ptr.map_err(|_| TryReserveError::AllocError { layout: new_layout })
+        })?;
+
+        self.ptr = ptr.cast();
+        let header = unsafe { self.header_mut() };
+        header.cap = cap;
+
+        // Zero-sized types are never reported, like the buffers of `Vec`
+        if mem::size_of::<T>() != 0 {
+            record_growth::<T>(
+                old_cap,
+                cap,
+                #[cfg(feature = "stats")]
+                &mut header.stats,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Default for ThinVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for ThinVec<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.data(), self.len()) }
+    }
+}
+
+impl<T> DerefMut for ThinVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.data(), self.len()) }
+    }
+}
+
+impl<T> Extend<T> for ThinVec<T> {
+    /// Appends every element of an iterator.
+    /// # Example
+    /// ```
+    /// let mut vec = vec::ThinVec::new();
+    /// vec.extend(0..3);
+    /// assert_eq!(&*vec, &[0, 1, 2]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Clone> Clone for ThinVec<T> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::with_capacity(self.len());
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ThinVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for ThinVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for ThinVec<T> {}
+
+impl<T> Drop for ThinVec<T> {
+    fn drop(&mut self) {
+        if !self.is_allocated() {
+            return;
+        }
+
+        unsafe {
+            // Deallocate even if a destructor panics
+            struct Dealloc(NonNull<u8>, Layout);
+
+            impl Drop for Dealloc {
+                fn drop(&mut self) {
+                    unsafe { Global.deallocate(self.0, self.1) }
+                }
+            }
+
+            let _dealloc = Dealloc(self.ptr.cast(), Self::layout(self.capacity()).unwrap());
+            if mem::size_of::<T>() != 0 {
+                record_deallocation::<T>(self.capacity());
+            }
+            ptr::drop_in_place(&mut **self);
+        }
+    }
+}
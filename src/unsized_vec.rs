@@ -0,0 +1,407 @@
+use std::{
+    alloc::{Allocator, Global, Layout},
+    cmp, fmt,
+    marker::{PhantomData, Unsize},
+    mem,
+    ops::{Index, IndexMut},
+    ptr::{self, NonNull, Pointee},
+};
+
+use crate::{error::TryReserveError, raw::handle_reserve_error, Vec};
+
+/// Vector of unsized values, such as `dyn Trait`, `str` or `[T]`, packed one after another in a single
+/// allocation instead of each one behind its own `Box`.
+///
+/// A separate table keeps the offset and pointer metadata (vtable or length) of every element, so indexing is
+/// O(1). Values are never moved individually, only the whole buffer when it grows.
+/// # Example
+/// ```
+/// use std::fmt::Display;
+/// use vec::UnsizedVec;
+///
+/// let mut v: UnsizedVec<dyn Display> = UnsizedVec::new();
+/// v.push_unsize(1u8);
+/// v.push_unsize("two");
+/// v.push_unsize(3.5f64);
+///
+/// let shown: std::vec::Vec<String> = v.iter().map(|x| x.to_string()).collect();
+/// assert_eq!(shown, ["1", "two", "3.5"]);
+/// ```
+pub struct UnsizedVec<T: ?Sized> {
+    ptr: NonNull<u8>,
+    // capacity of the buffer in bytes
+    cap: usize,
+    // bytes used, including padding
+    used: usize,
+    // alignment of the buffer, the largest of every element pushed so far
+    align: usize,
+    // offset in the buffer and pointer metadata of every element
+    entries: Vec<(usize, <T as Pointee>::Metadata)>,
+    marker: PhantomData<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for UnsizedVec<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for UnsizedVec<T> {}
+
+impl<T: ?Sized> UnsizedVec<T> {
+    /// Creates an empty vector, without allocating.
+    /// # Example
+    /// ```
+    /// let v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            cap: 0,
+            used: 0,
+            align: 1,
+            entries: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("a");
+    /// assert_eq!(v.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    /// # Example
+    /// ```
+    /// let v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.len == 0
+    }
+
+    /// Returns the number of bytes the values take up in the buffer, including padding between them.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("abc");
+    /// v.push_str("de");
+    /// assert_eq!(v.byte_len(), 5);
+    /// ```
+    pub fn byte_len(&self) -> usize {
+        self.used
+    }
+
+    /// Appends a sized value, turning it into `T` like an unsizing coercion would (`u32` into `dyn Display`,
+    /// `[u8; 4]` into `[u8]`, ...).
+    /// # Panics
+    /// This function will panic if the buffer exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<[u8]> = vec::UnsizedVec::new();
+    /// v.push_unsize([1, 2, 3]);
+    /// v.push_unsize([4]);
+    /// assert_eq!(&v[0], &[1, 2, 3]);
+    /// assert_eq!(&v[1], &[4]);
+    /// ```
+    pub fn push_unsize<U: Unsize<T>>(&mut self, value: U) {
+        let metadata = ptr::metadata(&value as &T as *const T);
+        let value = mem::ManuallyDrop::new(value);
+
+        unsafe {
+            self.push_raw(
+                &*value as *const U as *const u8,
+                Layout::new::<U>(),
+                metadata,
+            )
+        }
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("a");
+    /// assert_eq!(v.get(0), Some("a"));
+    /// assert_eq!(v.get(1), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let &(offset, metadata) = self.entries.get(index)?;
+        unsafe { Some(&*self.element(offset, metadata)) }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if out of bounds.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<[i32]> = vec::UnsizedVec::new();
+    /// v.push_unsize([1, 2]);
+    /// v.get_mut(0).unwrap()[1] = 5;
+    /// assert_eq!(&v[0], &[1, 5]);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let &(offset, metadata) = self.entries.get(index)?;
+        unsafe { Some(&mut *self.element(offset, metadata)) }
+    }
+
+    /// Returns the last element, or `None` if the vector is empty.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("a");
+    /// v.push_str("b");
+    /// assert_eq!(v.last(), Some("b"));
+    /// ```
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.entries.len.checked_sub(1)?)
+    }
+
+    /// Returns an iterator over the elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("a");
+    /// v.push_str("b");
+    /// assert_eq!(v.iter().collect::<String>(), "ab");
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + '_ {
+        self.entries
+            .iter()
+            .map(move |&(offset, metadata)| unsafe { &*self.element(offset, metadata) })
+    }
+
+    /// Returns an iterator over mutable references to the elements.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<[i32]> = vec::UnsizedVec::new();
+    /// v.push_unsize([1, 2]);
+    /// v.push_unsize([3]);
+    /// v.iter_mut().for_each(|slice| slice.fill(0));
+    /// assert_eq!(&v[1], &[0]);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator + '_ {
+        let (ptr, entries) = (self.ptr, &self.entries);
+
+        // Every element lives in its own byte range, so the mutable references never overlap
+        entries.iter().map(move |&(offset, metadata)| unsafe {
+            &mut *ptr::from_raw_parts_mut::<T>(ptr.as_ptr().add(offset), metadata)
+        })
+    }
+
+    /// Removes and drops the last element, returning `false` if the vector was empty.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("a");
+    /// assert!(v.pop_drop());
+    /// assert!(!v.pop_drop());
+    /// ```
+    pub fn pop_drop(&mut self) -> bool {
+        let len = self.entries.len;
+        if len == 0 {
+            return false;
+        }
+
+        self.truncate(len - 1);
+        true
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if it's not longer.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("a");
+    /// v.push_str("b");
+    /// v.truncate(1);
+    /// assert_eq!(v.byte_len(), 1);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        while self.entries.len > len {
+            // Forget the element before dropping it, so a panicking destructor can't cause a double drop
+            let (offset, metadata) = self.entries.pop().unwrap();
+            self.used = offset;
+
+            unsafe { ptr::drop_in_place(self.element(offset, metadata)) }
+        }
+    }
+
+    /// Removes every element, keeping the allocation.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("a");
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Appends a value by copying its bytes from `src`, taking ownership of it.
+    /// # Safety
+    /// `src` and `metadata` must make up a valid pointer to a value of type `T` with layout `layout`. The value must
+    /// not be used or dropped afterwards.
+    /// # Panics
+    /// This function will panic if the buffer exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(ptr_metadata)]
+    /// use std::{alloc::Layout, mem::ManuallyDrop, ptr};
+    ///
+    /// let mut v: vec::UnsizedVec<[String]> = vec::UnsizedVec::new();
+    /// let value = ManuallyDrop::new(["a".to_string(), "b".to_string()]);
+    /// let slice: &[String] = &*value;
+    ///
+    /// unsafe { v.push_raw(slice.as_ptr() as *const u8, Layout::for_value(slice), ptr::metadata(slice)) };
+    /// assert_eq!(v[0], ["a", "b"]);
+    /// ```
+    pub unsafe fn push_raw(
+        &mut self,
+        src: *const u8,
+        layout: Layout,
+        metadata: <T as Pointee>::Metadata,
+    ) {
+        let offset = match self.try_reserve_for(layout) {
+            Ok(offset) => offset,
+            Err(err) => handle_reserve_error(err),
+        };
+
+        ptr::copy_nonoverlapping(src, self.ptr.as_ptr().add(offset), layout.size());
+        self.entries.push((offset, metadata));
+        self.used = offset + layout.size();
+    }
+
+    // Pointer to the element at `offset` with `metadata`
+    fn element(&self, offset: usize, metadata: <T as Pointee>::Metadata) -> *mut T {
+        unsafe { ptr::from_raw_parts_mut(self.ptr.as_ptr().add(offset), metadata) }
+    }
+
+    // Makes room for a value with `layout` after the used bytes, and returns the offset to write it at
+    fn try_reserve_for(&mut self, layout: Layout) -> Result<usize, TryReserveError> {
+        let offset = self
+            .used
+            .checked_next_multiple_of(layout.align())
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let required = offset
+            .checked_add(layout.size())
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let align = cmp::max(self.align, layout.align());
+
+        if required > self.cap {
+            self.try_realloc(cmp::max(cmp::max(self.cap * 2, required), 64), align)?;
+        } else if align > self.align {
+            // Elements keep their offsets, so a more aligned value needs a more aligned buffer
+            self.try_realloc(self.cap, align)?;
+        }
+
+        Ok(offset)
+    }
+
+    // Moves the buffer to an allocation of `cap` bytes aligned to `align`, both at least the current ones
+    fn try_realloc(&mut self, cap: usize, align: usize) -> Result<(), TryReserveError> {
+        // Fails for allocations bigger than isize::MAX bytes
+        let new_layout =
+            Layout::from_size_align(cap, align).map_err(|_| TryReserveError::CapacityOverflow)?;
+
+        if cap == 0 {
+            // Only zero-sized values so far, which just need an aligned pointer
+            self.ptr = NonNull::new(ptr::without_provenance_mut(align)).unwrap();
+            self.align = align;
+            return Ok(());
+        }
+
+        let ptr = unsafe {
+            if self.cap == 0 {
+                Global.allocate(new_layout)
+            } else {
+                let old_layout = Layout::from_size_align_unchecked(self.cap, self.align);
+                Global.grow(self.ptr, old_layout, new_layout)
+            }
+        };
+
+        self.ptr = ptr
+            .map_err(|_| TryReserveError::AllocError { layout: new_layout })?
+            .cast();
+        self.cap = cap;
+        self.align = align;
+        Ok(())
+    }
+}
+
+impl UnsizedVec<str> {
+    /// Appends a copy of `s`.
+    /// # Panics
+    /// This function will panic if the buffer exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<str> = vec::UnsizedVec::new();
+    /// v.push_str("hello");
+    /// assert_eq!(&v[0], "hello");
+    /// ```
+    pub fn push_str(&mut self, s: &str) {
+        unsafe { self.push_raw(s.as_ptr(), Layout::for_value(s), s.len()) }
+    }
+}
+
+impl<T: Copy> UnsizedVec<[T]> {
+    /// Appends a copy of `slice`.
+    /// # Panics
+    /// This function will panic if the buffer exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut v: vec::UnsizedVec<[u16]> = vec::UnsizedVec::new();
+    /// v.push_slice(&[1, 2]);
+    /// assert_eq!(&v[0], &[1, 2]);
+    /// ```
+    pub fn push_slice(&mut self, slice: &[T]) {
+        unsafe {
+            self.push_raw(
+                slice.as_ptr() as *const u8,
+                Layout::for_value(slice),
+                slice.len(),
+            )
+        }
+    }
+}
+
+impl<T: ?Sized> Default for UnsizedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> Index<usize> for UnsizedVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T: ?Sized> IndexMut<usize> for UnsizedVec<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for UnsizedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: ?Sized> Drop for UnsizedVec<T> {
+    fn drop(&mut self) {
+        self.clear();
+
+        if self.cap != 0 {
+            unsafe {
+                Global.deallocate(
+                    self.ptr,
+                    Layout::from_size_align_unchecked(self.cap, self.align),
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,815 @@
+use std::{
+    fmt,
+    iter::FromIterator,
+    mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+// End of the list, in place of a node index
+const NIL: usize = usize::MAX;
+
+/// Handle to an element of a [`VecList`], pairing its node index with the generation of the node. Once the element
+/// is removed the node's generation moves on, so the handle no longer matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle {
+    index: usize,
+    generation: u32,
+}
+
+/// Doubly-linked list whose nodes live in a [`Vec`] and link to each other by index.
+///
+/// Elements are reached through generational [`Handle`]s that stay valid until the element is removed, so removing or
+/// inserting next to a known element is O(1) while the nodes stay in one allocation. Freed nodes are reused by later
+/// insertions.
+/// # Example
+/// ```
+/// use vec::VecList;
+///
+/// let mut list = VecList::new();
+/// let a = list.push_back('a');
+/// let c = list.push_back('c');
+/// list.insert_after(a, 'b');
+/// list.push_front('_');
+///
+/// assert_eq!(list.iter().collect::<String>(), "_abc");
+/// assert_eq!(list.remove(c), Some('c'));
+/// assert_eq!(list.remove(c), None);
+/// assert_eq!(list.iter().collect::<String>(), "_ab");
+/// ```
+#[derive(Clone)]
+pub struct VecList<T> {
+    nodes: Vec<Node<T>>,
+    // first and last node of the list, `NIL` if it's empty
+    head: usize,
+    tail: usize,
+    // first vacant node, `nodes.len` if there is none
+    free: usize,
+    len: usize,
+}
+
+#[derive(Clone)]
+struct Node<T> {
+    generation: u32,
+    // `None` for a vacant node, whose `next` is the next vacant node
+    value: Option<T>,
+    prev: usize,
+    next: usize,
+}
+
+impl<T> VecList<T> {
+    /// Creates an empty list, without allocating.
+    /// # Example
+    /// ```
+    /// let list: vec::VecList<i32> = vec::VecList::new();
+    /// assert!(list.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            free: 0,
+            len: 0,
+        }
+    }
+
+    /// Creates an empty list with room for `capacity` elements.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let list: vec::VecList<i32> = vec::VecList::with_capacity(8);
+    /// assert!(list.is_empty());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            nodes: Vec::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the number of elements.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list holds no elements.
+    /// # Example
+    /// ```
+    /// let list: vec::VecList<i32> = vec::VecList::new();
+    /// assert!(list.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Prepends an element and returns its handle.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_front(2);
+    /// let one = list.push_front(1);
+    /// assert_eq!(list.front(), Some(&1));
+    /// assert_eq!(list[one], 1);
+    /// ```
+    pub fn push_front(&mut self, value: T) -> Handle {
+        self.link(value, NIL, self.head)
+    }
+
+    /// Appends an element and returns its handle.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.back(), Some(&2));
+    /// ```
+    pub fn push_back(&mut self, value: T) -> Handle {
+        self.link(value, self.tail, NIL)
+    }
+
+    /// Inserts an element right before the one of `handle`, and returns its handle.
+    /// # Panics
+    /// Panics if `handle` is stale, or if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let b = list.push_back('b');
+    /// list.insert_before(b, 'a');
+    /// assert_eq!(list.iter().collect::<String>(), "ab");
+    /// ```
+    pub fn insert_before(&mut self, handle: Handle, value: T) -> Handle {
+        let index = self.position(handle).expect("stale handle");
+        self.link(value, self.nodes[index].prev, index)
+    }
+
+    /// Inserts an element right after the one of `handle`, and returns its handle.
+    /// # Panics
+    /// Panics if `handle` is stale, or if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back('a');
+    /// list.insert_after(a, 'b');
+    /// assert_eq!(list.iter().collect::<String>(), "ab");
+    /// ```
+    pub fn insert_after(&mut self, handle: Handle, value: T) -> Handle {
+        let index = self.position(handle).expect("stale handle");
+        self.link(value, index, self.nodes[index].next)
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_front(), Some(1));
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        match self.head {
+            NIL => None,
+            head => Some(self.unlink(head)),
+        }
+    }
+
+    /// Removes the last element and returns it, or `None` if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_back(), Some(2));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        match self.tail {
+            NIL => None,
+            tail => Some(self.unlink(tail)),
+        }
+    }
+
+    /// Removes the element of `handle` and returns it, or `None` if the handle is stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back('a');
+    /// list.push_back('b');
+    /// assert_eq!(list.remove(a), Some('a'));
+    /// assert_eq!(list.remove(a), None);
+    /// ```
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let index = self.position(handle)?;
+        Some(self.unlink(index))
+    }
+
+    /// Moves the element of `handle` to the front of the list, e.g. to mark it as most recently used. Returns `false`
+    /// if the handle is stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back('a');
+    /// let b = list.push_back('b');
+    /// assert!(list.move_to_front(b));
+    /// assert_eq!(list.iter().collect::<String>(), "ba");
+    /// ```
+    pub fn move_to_front(&mut self, handle: Handle) -> bool {
+        let index = match self.position(handle) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        if index != self.head {
+            self.detach(index);
+            self.attach(index, NIL, self.head);
+        }
+        true
+    }
+
+    /// Returns `true` if `handle` reaches an element.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// assert!(list.contains(a));
+    /// list.remove(a);
+    /// assert!(!list.contains(a));
+    /// ```
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    /// Returns a reference to the element of `handle`, or `None` if the handle is stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// assert_eq!(list.get(a), Some(&1));
+    /// ```
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.nodes[self.position(handle)?].value.as_ref()
+    }
+
+    /// Returns a mutable reference to the element of `handle`, or `None` if the handle is stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// *list.get_mut(a).unwrap() = 2;
+    /// assert_eq!(list[a], 2);
+    /// ```
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        let index = self.position(handle)?;
+        self.nodes[index].value.as_mut()
+    }
+
+    /// Returns the first element, or `None` if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.front(), Some(&1));
+    /// ```
+    pub fn front(&self) -> Option<&T> {
+        self.value(self.head)
+    }
+
+    /// Returns the last element, or `None` if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.back(), Some(&2));
+    /// ```
+    pub fn back(&self) -> Option<&T> {
+        self.value(self.tail)
+    }
+
+    /// Returns the handle of the first element, or `None` if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// assert_eq!(list.front_handle(), Some(a));
+    /// ```
+    pub fn front_handle(&self) -> Option<Handle> {
+        self.handle(self.head)
+    }
+
+    /// Returns the handle of the last element, or `None` if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// let b = list.push_back(2);
+    /// assert_eq!(list.back_handle(), Some(b));
+    /// ```
+    pub fn back_handle(&self) -> Option<Handle> {
+        self.handle(self.tail)
+    }
+
+    /// Returns the handle of the element after the one of `handle`, or `None` if it's the last one or the handle is
+    /// stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// let b = list.push_back(2);
+    /// assert_eq!(list.next(a), Some(b));
+    /// assert_eq!(list.next(b), None);
+    /// ```
+    pub fn next(&self, handle: Handle) -> Option<Handle> {
+        self.handle(self.nodes[self.position(handle)?].next)
+    }
+
+    /// Returns the handle of the element before the one of `handle`, or `None` if it's the first one or the handle
+    /// is stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// let b = list.push_back(2);
+    /// assert_eq!(list.prev(b), Some(a));
+    /// assert_eq!(list.prev(a), None);
+    /// ```
+    pub fn prev(&self, handle: Handle) -> Option<Handle> {
+        self.handle(self.nodes[self.position(handle)?].prev)
+    }
+
+    /// Returns an iterator over the elements, from front to back.
+    /// # Example
+    /// ```
+    /// let list: vec::VecList<_> = (1..4).collect();
+    /// assert_eq!(list.iter().rev().collect::<std::vec::Vec<_>>(), [&3, &2, &1]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: self.head,
+            back: self.tail,
+            len: self.len,
+        }
+    }
+
+    /// Returns an iterator over the handles of the elements, from front to back.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// let b = list.push_front(0);
+    /// assert_eq!(list.handles().collect::<std::vec::Vec<_>>(), [b, a]);
+    /// ```
+    pub fn handles(&self) -> impl Iterator<Item = Handle> + '_ {
+        let mut index = self.head;
+
+        std::iter::from_fn(move || {
+            let handle = self.handle(index)?;
+            index = self.nodes[index].next;
+            Some(handle)
+        })
+    }
+
+    /// Returns a cursor on the first element, or on the "ghost" position past the end if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..4).collect();
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.move_next();
+    /// assert_eq!(cursor.remove_current(), Some(2));
+    /// assert_eq!(cursor.current(), Some(&mut 3));
+    /// ```
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.head,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor on the last element, or on the "ghost" position past the end if the list is empty.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..4).collect();
+    /// let cursor = list.cursor_back_mut();
+    /// assert_eq!(cursor.peek_prev(), Some(&2));
+    /// ```
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.tail,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor on the element of `handle`, or `None` if the handle is stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// let b = list.push_back(2);
+    /// let mut cursor = list.cursor_mut(b).unwrap();
+    /// cursor.insert_before(5);
+    /// assert_eq!(list.iter().copied().collect::<std::vec::Vec<_>>(), [1, 5, 2]);
+    /// ```
+    pub fn cursor_mut(&mut self, handle: Handle) -> Option<CursorMut<'_, T>> {
+        Some(CursorMut {
+            index: self.position(handle)?,
+            list: self,
+        })
+    }
+
+    /// Removes every element, keeping the nodes so every handle in use becomes stale.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// list.clear();
+    /// assert!(list.is_empty() && !list.contains(a));
+    /// ```
+    pub fn clear(&mut self) {
+        // Rebuild the free list in node order, bumping the generations of the nodes in use
+        let mut next = self.nodes.len;
+
+        for (index, node) in self.nodes.iter_mut().enumerate().rev() {
+            if node.value.take().is_some() {
+                node.generation = node.generation.wrapping_add(1);
+            }
+
+            node.next = next;
+            next = index;
+        }
+
+        self.free = next;
+        self.head = NIL;
+        self.tail = NIL;
+        self.len = 0;
+    }
+
+    fn position(&self, handle: Handle) -> Option<usize> {
+        match self.nodes.get(handle.index)? {
+            Node {
+                generation,
+                value: Some(_),
+                ..
+            } if *generation == handle.generation => Some(handle.index),
+            _ => None,
+        }
+    }
+
+    fn handle(&self, index: usize) -> Option<Handle> {
+        if index == NIL {
+            return None;
+        }
+
+        Some(Handle {
+            index,
+            generation: self.nodes[index].generation,
+        })
+    }
+
+    fn value(&self, index: usize) -> Option<&T> {
+        if index == NIL {
+            return None;
+        }
+
+        self.nodes[index].value.as_ref()
+    }
+
+    // Stores `value` in a vacant node linked between `prev` and `next`, which are adjacent or `NIL`
+    fn link(&mut self, value: T, prev: usize, next: usize) -> Handle {
+        let index = self.free;
+
+        if index == self.nodes.len {
+            self.nodes.push(Node {
+                generation: 0,
+                value: Some(value),
+                prev: NIL,
+                next: NIL,
+            });
+            self.free = index + 1;
+        } else {
+            let node = &mut self.nodes[index];
+            self.free = node.next;
+            node.value = Some(value);
+        }
+
+        self.attach(index, prev, next);
+        self.len += 1;
+
+        Handle {
+            index,
+            generation: self.nodes[index].generation,
+        }
+    }
+
+    // Takes the value out of an occupied node, and frees it
+    fn unlink(&mut self, index: usize) -> T {
+        self.detach(index);
+
+        let node = &mut self.nodes[index];
+        // Stale handles of this node must not match the next value stored there
+        node.generation = node.generation.wrapping_add(1);
+        node.next = mem::replace(&mut self.free, index);
+        self.len -= 1;
+
+        node.value.take().unwrap()
+    }
+
+    // Links a node between `prev` and `next`, which are adjacent or `NIL`
+    fn attach(&mut self, index: usize, prev: usize, next: usize) {
+        self.nodes[index].prev = prev;
+        self.nodes[index].next = next;
+
+        match prev {
+            NIL => self.head = index,
+            prev => self.nodes[prev].next = index,
+        }
+        match next {
+            NIL => self.tail = index,
+            next => self.nodes[next].prev = index,
+        }
+    }
+
+    // Links the neighbors of a node to each other
+    fn detach(&mut self, index: usize) {
+        let Node { prev, next, .. } = self.nodes[index];
+
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+}
+
+/// Cursor over a [`VecList`], pointing at an element or at a "ghost" position between the back and the front, that
+/// can insert and remove elements around it in O(1).
+pub struct CursorMut<'a, T> {
+    list: &'a mut VecList<T>,
+    // node the cursor is on, `NIL` for the ghost position
+    index: usize,
+}
+
+impl<T> CursorMut<'_, T> {
+    /// Returns the element the cursor is on, or `None` on the ghost position.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..3).collect();
+    /// let mut cursor = list.cursor_front_mut();
+    /// *cursor.current().unwrap() = 10;
+    /// assert_eq!(list.front(), Some(&10));
+    /// ```
+    pub fn current(&mut self) -> Option<&mut T> {
+        match self.index {
+            NIL => None,
+            index => self.list.nodes[index].value.as_mut(),
+        }
+    }
+
+    /// Returns the handle of the element the cursor is on, or `None` on the ghost position.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// let a = list.push_back(1);
+    /// assert_eq!(list.cursor_front_mut().handle(), Some(a));
+    /// ```
+    pub fn handle(&self) -> Option<Handle> {
+        self.list.handle(self.index)
+    }
+
+    /// Moves to the next element, or from the last element to the ghost position, or from the ghost position to the
+    /// first element.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..3).collect();
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&mut 2));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), None);
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&mut 1));
+    /// ```
+    pub fn move_next(&mut self) {
+        self.index = match self.index {
+            NIL => self.list.head,
+            index => self.list.nodes[index].next,
+        };
+    }
+
+    /// Moves to the previous element, or from the first element to the ghost position, or from the ghost position
+    /// to the last element.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..3).collect();
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), None);
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), Some(&mut 2));
+    /// ```
+    pub fn move_prev(&mut self) {
+        self.index = match self.index {
+            NIL => self.list.tail,
+            index => self.list.nodes[index].prev,
+        };
+    }
+
+    /// Returns the element after the cursor, the first one on the ghost position.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..3).collect();
+    /// assert_eq!(list.cursor_front_mut().peek_next(), Some(&2));
+    /// ```
+    pub fn peek_next(&self) -> Option<&T> {
+        match self.index {
+            NIL => self.list.value(self.list.head),
+            index => self.list.value(self.list.nodes[index].next),
+        }
+    }
+
+    /// Returns the element before the cursor, the last one on the ghost position.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..3).collect();
+    /// assert_eq!(list.cursor_back_mut().peek_prev(), Some(&1));
+    /// ```
+    pub fn peek_prev(&self) -> Option<&T> {
+        match self.index {
+            NIL => self.list.value(self.list.tail),
+            index => self.list.value(self.list.nodes[index].prev),
+        }
+    }
+
+    /// Inserts an element before the cursor, at the back of the list on the ghost position.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(2);
+    /// list.cursor_front_mut().insert_before(1);
+    /// assert_eq!(list.front(), Some(&1));
+    /// ```
+    pub fn insert_before(&mut self, value: T) -> Handle {
+        let prev = match self.index {
+            NIL => self.list.tail,
+            index => self.list.nodes[index].prev,
+        };
+        self.list.link(value, prev, self.index)
+    }
+
+    /// Inserts an element after the cursor, at the front of the list on the ghost position.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut list = vec::VecList::new();
+    /// list.push_back(1);
+    /// list.cursor_front_mut().insert_after(2);
+    /// assert_eq!(list.back(), Some(&2));
+    /// ```
+    pub fn insert_after(&mut self, value: T) -> Handle {
+        let next = match self.index {
+            NIL => self.list.head,
+            index => self.list.nodes[index].next,
+        };
+        self.list.link(value, self.index, next)
+    }
+
+    /// Removes the element the cursor is on and returns it, moving the cursor to the next element. Returns `None` on
+    /// the ghost position.
+    /// # Example
+    /// ```
+    /// let mut list: vec::VecList<_> = (1..4).collect();
+    /// let mut cursor = list.cursor_front_mut();
+    /// assert_eq!(cursor.remove_current(), Some(1));
+    /// assert_eq!(cursor.current(), Some(&mut 2));
+    /// ```
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.index == NIL {
+            return None;
+        }
+
+        let index = self.index;
+        self.index = self.list.nodes[index].next;
+        Some(self.list.unlink(index))
+    }
+}
+
+/// Iterator over the elements of a [`VecList`], returned by [`VecList::iter`].
+pub struct Iter<'a, T> {
+    list: &'a VecList<T>,
+    front: usize,
+    back: usize,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = &self.list.nodes[self.front];
+        self.front = node.next;
+        self.len -= 1;
+        node.value.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = &self.list.nodes[self.back];
+        self.back = node.prev;
+        self.len -= 1;
+        node.value.as_ref()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> Default for VecList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Handle> for VecList<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle) -> &T {
+        self.get(handle).expect("stale handle")
+    }
+}
+
+impl<T> IndexMut<Handle> for VecList<T> {
+    fn index_mut(&mut self, handle: Handle) -> &mut T {
+        self.get_mut(handle).expect("stale handle")
+    }
+}
+
+impl<T> Extend<T> for VecList<T> {
+    /// Appends every element of an iterator.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for VecList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+// Compares the elements in list order, not where their nodes are
+impl<T: PartialEq> PartialEq for VecList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for VecList<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for VecList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
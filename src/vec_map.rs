@@ -0,0 +1,489 @@
+use std::{
+    fmt,
+    iter::FromIterator,
+    mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::Vec;
+
+/// Map from small `usize` keys to values, stored in a vector with one slot per possible key up to the largest one
+/// inserted. Every operation is a plain index, trading memory for speed on dense key spaces such as opcodes or
+/// small IDs.
+/// # Example
+/// ```
+/// use vec::VecMap;
+///
+/// let mut handlers = VecMap::new();
+/// handlers.insert(0x01, "load");
+/// handlers.insert(0x10, "store");
+///
+/// assert_eq!(handlers.get(0x10), Some(&"store"));
+/// assert_eq!(handlers.get(0x02), None);
+///
+/// *handlers.entry(0x20).or_insert("nop") = "jump";
+/// assert_eq!(handlers.keys().collect::<std::vec::Vec<_>>(), [0x01, 0x10, 0x20]);
+/// ```
+#[derive(Clone)]
+pub struct VecMap<V> {
+    // slot `key` holds the value of `key`, if any
+    slots: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V> VecMap<V> {
+    /// Creates an empty map, without allocating.
+    /// # Example
+    /// ```
+    /// let map: vec::VecMap<i32> = vec::VecMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates an empty map with slots for the keys below `capacity`.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let map: vec::VecMap<i32> = vec::VecMap::with_capacity(16);
+    /// assert!(map.capacity() >= 16);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of keys the map can hold without reallocating.
+    /// # Example
+    /// ```
+    /// let map: vec::VecMap<i32> = vec::VecMap::new();
+    /// assert_eq!(map.capacity(), 0);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Returns the number of entries.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(5, 'a');
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map has no entries.
+    /// # Example
+    /// ```
+    /// let map: vec::VecMap<i32> = vec::VecMap::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value for `key`, returning the previous one if there was any.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// assert_eq!(map.insert(3, 'a'), None);
+    /// assert_eq!(map.insert(3, 'b'), Some('a'));
+    /// ```
+    pub fn insert(&mut self, key: usize, value: V) -> Option<V> {
+        let old = self.slot(key).replace(value);
+
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Removes the value of `key` and returns it, or `None` if there was none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(3, 'a');
+    /// assert_eq!(map.remove(3), Some('a'));
+    /// assert_eq!(map.remove(3), None);
+    /// ```
+    pub fn remove(&mut self, key: usize) -> Option<V> {
+        let old = self.slots.get_mut(key)?.take();
+
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Returns `true` if `key` has a value.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(3, 'a');
+    /// assert!(map.contains_key(3));
+    /// assert!(!map.contains_key(4));
+    /// ```
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(3, 'a');
+    /// assert_eq!(map.get(3), Some(&'a'));
+    /// assert_eq!(map.get(100), None);
+    /// ```
+    pub fn get(&self, key: usize) -> Option<&V> {
+        self.slots.get(key)?.as_ref()
+    }
+
+    /// Returns a mutable reference to the value of `key`, or `None` if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(3, 1);
+    /// *map.get_mut(3).unwrap() += 1;
+    /// assert_eq!(map[3], 2);
+    /// ```
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut V> {
+        self.slots.get_mut(key)?.as_mut()
+    }
+
+    /// Returns the entry of `key`, to insert or update its value in place.
+    /// # Example
+    /// ```
+    /// let mut counts = vec::VecMap::new();
+    /// for opcode in [1, 2, 1, 1] {
+    ///     *counts.entry(opcode).or_insert(0) += 1;
+    /// }
+    /// assert_eq!((counts[1], counts[2]), (3, 1));
+    /// ```
+    pub fn entry(&mut self, key: usize) -> Entry<'_, V> {
+        if self.contains_key(key) {
+            Entry::Occupied(OccupiedEntry { map: self, key })
+        } else {
+            Entry::Vacant(VacantEntry { map: self, key })
+        }
+    }
+
+    /// Returns an iterator over the entries, in increasing key order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(7, 'b');
+    /// map.insert(2, 'a');
+    /// assert_eq!(map.iter().collect::<std::vec::Vec<_>>(), [(2, &'a'), (7, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &V)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(key, slot)| Some((key, slot.as_ref()?)))
+    }
+
+    /// Returns an iterator over the entries with mutable references to the values, in increasing key order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(2, 1);
+    /// map.insert(7, 1);
+    /// map.iter_mut().for_each(|(key, value)| *value += key);
+    /// assert_eq!(map[7], 8);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (usize, &mut V)> + '_ {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(key, slot)| Some((key, slot.as_mut()?)))
+    }
+
+    /// Returns an iterator over the keys with a value, in increasing order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(7, 'b');
+    /// map.insert(2, 'a');
+    /// assert_eq!(map.keys().collect::<std::vec::Vec<_>>(), [2, 7]);
+    /// ```
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values, in increasing key order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(7, 'b');
+    /// map.insert(2, 'a');
+    /// assert_eq!(map.values().collect::<String>(), "ab");
+    /// ```
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over mutable references to the values, in increasing key order.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(2, 1);
+    /// map.values_mut().for_each(|value| *value *= 10);
+    /// assert_eq!(map[2], 10);
+    /// ```
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut V> + '_ {
+        self.iter_mut().map(|(_, value)| value)
+    }
+
+    /// Keeps only the entries for which `f` returns `true`.
+    /// # Example
+    /// ```
+    /// let mut map: vec::VecMap<usize> = (0..6).map(|key| (key, key * 10)).collect();
+    /// map.retain(|key, _| key % 2 == 0);
+    /// assert_eq!(map.keys().collect::<std::vec::Vec<_>>(), [0, 2, 4]);
+    /// ```
+    pub fn retain<F: FnMut(usize, &mut V) -> bool>(&mut self, mut f: F) {
+        for (key, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(value) = slot {
+                if !f(key, value) {
+                    *slot = None;
+                    self.len -= 1;
+                }
+            }
+        }
+    }
+
+    /// Removes every entry, keeping the allocation.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(2, 'a');
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+    }
+
+    // Slot of `key`, adding empty slots up to it
+    fn slot(&mut self, key: usize) -> &mut Option<V> {
+        if key >= self.slots.len {
+            self.slots.reserve(key + 1 - self.slots.len);
+
+            while self.slots.len <= key {
+                self.slots.push(None);
+            }
+        }
+
+        &mut self.slots[key]
+    }
+}
+
+/// Entry of a [`VecMap`] key, which may or may not have a value. Created by [`VecMap::entry`].
+pub enum Entry<'a, V> {
+    /// The key has a value.
+    Occupied(OccupiedEntry<'a, V>),
+    /// The key has no value.
+    Vacant(VacantEntry<'a, V>),
+}
+
+/// Entry of a [`VecMap`] key with a value.
+pub struct OccupiedEntry<'a, V> {
+    map: &'a mut VecMap<V>,
+    key: usize,
+}
+
+/// Entry of a [`VecMap`] key without a value.
+pub struct VacantEntry<'a, V> {
+    map: &'a mut VecMap<V>,
+    key: usize,
+}
+
+impl<'a, V> Entry<'a, V> {
+    /// Returns the key of the entry.
+    /// # Example
+    /// ```
+    /// let mut map: vec::VecMap<i32> = vec::VecMap::new();
+    /// assert_eq!(map.entry(3).key(), 3);
+    /// ```
+    pub fn key(&self) -> usize {
+        match self {
+            Entry::Occupied(entry) => entry.key,
+            Entry::Vacant(entry) => entry.key,
+        }
+    }
+
+    /// Returns the value, inserting `default` first if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// assert_eq!(*map.entry(3).or_insert(1), 1);
+    /// assert_eq!(*map.entry(3).or_insert(2), 1);
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Returns the value, inserting the result of `default` first if there is none.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.entry(3).or_insert_with(std::vec::Vec::new).push('a');
+    /// assert_eq!(map[3], ['a']);
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Returns the value, inserting the default value first if there is none.
+    /// # Example
+    /// ```
+    /// let mut map: vec::VecMap<u32> = vec::VecMap::new();
+    /// *map.entry(3).or_default() += 1;
+    /// assert_eq!(map[3], 1);
+    /// ```
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if there is one, and returns the entry.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.entry(3).and_modify(|v| *v += 1).or_insert(0);
+    /// map.entry(3).and_modify(|v| *v += 1).or_insert(0);
+    /// assert_eq!(map[3], 1);
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, V> OccupiedEntry<'a, V> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Returns a reference to the value.
+    pub fn get(&self) -> &V {
+        self.map.slots[self.key].as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.slots[self.key].as_mut().unwrap()
+    }
+
+    /// Returns a mutable reference to the value, borrowing the map for as long as the entry did.
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.slots[self.key].as_mut().unwrap()
+    }
+
+    /// Replaces the value and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the value and returns it.
+    /// # Example
+    /// ```
+    /// let mut map = vec::VecMap::new();
+    /// map.insert(3, 'a');
+    /// if let vec::VecMapEntry::Occupied(entry) = map.entry(3) {
+    ///     assert_eq!(entry.remove(), 'a');
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove(self) -> V {
+        self.map.remove(self.key).unwrap()
+    }
+}
+
+impl<'a, V> VacantEntry<'a, V> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Inserts a value for the key and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.len += 1;
+        self.map.slot(self.key).insert(value)
+    }
+}
+
+impl<V> Default for VecMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Index<usize> for VecMap<V> {
+    type Output = V;
+
+    fn index(&self, key: usize) -> &V {
+        self.get(key).expect("no value for key")
+    }
+}
+
+impl<V> IndexMut<usize> for VecMap<V> {
+    fn index_mut(&mut self, key: usize) -> &mut V {
+        self.get_mut(key).expect("no value for key")
+    }
+}
+
+impl<V> Extend<(usize, V)> for VecMap<V> {
+    fn extend<I: IntoIterator<Item = (usize, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<V> FromIterator<(usize, V)> for VecMap<V> {
+    fn from_iter<I: IntoIterator<Item = (usize, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+// Compares entries only, since trailing empty slots depend on past insertions
+impl<V: PartialEq> PartialEq for VecMap<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<V: Eq> Eq for VecMap<V> {}
+
+impl<V: fmt::Debug> fmt::Debug for VecMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
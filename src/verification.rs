@@ -0,0 +1,132 @@
+//! Kani model-checking harnesses for the unsafe core, run with `cargo kani --features verification`.
+//!
+//! Kani checks every access for bounds and use after free on its own; the harnesses drive bounded sequences of
+//! operations and compare them to a fixed-size model, counting drops to catch elements dropped twice or leaked.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{raw::RawVec, Vec};
+
+// Longest operation sequence explored, and so the most elements a vector can hold
+const MAX_OPS: usize = 4;
+
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+// An element that counts its creations and drops, sized so the vector allocates
+struct Tracked {
+    _value: u8,
+}
+
+impl Tracked {
+    fn new() -> Self {
+        CREATED.fetch_add(1, Ordering::Relaxed);
+        Tracked { _value: 0 }
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(6)]
+fn vec_operations_match_model() {
+    let mut vec = Vec::new();
+    let mut model = [0u32; MAX_OPS];
+    let mut len = 0;
+
+    for _ in 0..MAX_OPS {
+        let op: u8 = kani::any();
+        let value: u32 = kani::any();
+        let index: usize = kani::any();
+
+        match op % 4 {
+            0 => {
+                vec.push(value);
+                model[len] = value;
+                len += 1;
+            }
+            1 => {
+                kani::assume(index <= len);
+                vec.insert(index, value);
+                model.copy_within(index..len, index + 1);
+                model[index] = value;
+                len += 1;
+            }
+            2 => {
+                kani::assume(index < len);
+                assert_eq!(vec.remove(index), model[index]);
+                model.copy_within(index + 1..len, index);
+                len -= 1;
+            }
+            _ => {
+                let popped = vec.pop();
+                if len == 0 {
+                    assert!(popped.is_none());
+                } else {
+                    len -= 1;
+                    assert_eq!(popped, Some(model[len]));
+                }
+            }
+        }
+
+        assert_eq!(vec.len(), len);
+        assert!(vec.capacity() >= len);
+    }
+
+    assert_eq!(&*vec, &model[..len]);
+}
+
+#[kani::proof]
+#[kani::unwind(6)]
+fn vec_elements_are_dropped_once() {
+    let mut vec = Vec::new();
+
+    for _ in 0..MAX_OPS {
+        let op: u8 = kani::any();
+        let index: usize = kani::any();
+
+        match op % 4 {
+            0 => vec.push(Tracked::new()),
+            1 => {
+                kani::assume(index <= vec.len());
+                vec.insert(index, Tracked::new());
+            }
+            2 => {
+                kani::assume(index < vec.len());
+                drop(vec.remove(index));
+            }
+            _ => drop(vec.pop()),
+        }
+
+        let live = CREATED.load(Ordering::Relaxed) - DROPPED.load(Ordering::Relaxed);
+        assert_eq!(live, vec.len());
+    }
+
+    drop(vec);
+    assert_eq!(
+        CREATED.load(Ordering::Relaxed),
+        DROPPED.load(Ordering::Relaxed)
+    );
+}
+
+#[kani::proof]
+#[kani::unwind(4)]
+fn raw_vec_reserve_stays_in_bounds() {
+    let mut buf = RawVec::<u16>::new();
+    let mut len = 0;
+
+    for _ in 0..2 {
+        let additional: usize = kani::any();
+        kani::assume(additional > 0 && additional <= MAX_OPS);
+
+        buf.try_reserve(len, additional).unwrap();
+        assert!(buf.cap >= len + additional);
+
+        // Every slot up to the new length is writable
+        unsafe { buf.ptr.as_ptr().add(len + additional - 1).write(0) };
+        len += additional;
+    }
+}
@@ -0,0 +1,139 @@
+use std::alloc::{Allocator, Global};
+
+use crate::{handle_reserve_error, Doubling, RawVec, TryReserveError, Vec};
+
+/// Types for which a value with every byte set to zero is valid, so a zeroed buffer can be used as is.
+///
+/// # Safety
+/// The all-zero bit pattern must be a valid value of the type.
+/// # Example
+/// ```
+/// use vec::Zeroable;
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct Pixel {
+///     r: u8,
+///     g: u8,
+///     b: u8,
+/// }
+///
+/// unsafe impl Zeroable for Pixel {}
+///
+/// let pixels = vec::Vec::<Pixel>::from_zeroes(1920 * 1080);
+/// assert!(pixels.iter().all(|p| p.r == 0 && p.g == 0 && p.b == 0));
+/// ```
+pub unsafe trait Zeroable {}
+
+macro_rules! impl_zeroable {
+    ( $( $t:ty ),* ) => {
+        $(
+            unsafe impl Zeroable for $t {}
+        )*
+    };
+}
+
+impl_zeroable!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_zeroable!(f32, f64, bool, char, ());
+
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+
+impl<T> Vec<T> {
+    /// Creates a new Vector with room for `capacity` elements, whose memory is zeroed by the allocator instead of
+    /// being filled afterwards. The vector is empty, use [`Vec::set_len`] to expose the zeroed elements when
+    /// `T` isn't [`Zeroable`].
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// let mut vec: vec::Vec<Option<NonZeroU32>> = vec::Vec::with_capacity_zeroed(8);
+    /// assert_eq!(vec.len(), 0);
+    ///
+    /// // Safety: `None` is represented by zeroes
+    /// unsafe { vec.set_len(8) };
+    /// assert!(vec.iter().all(Option::is_none));
+    /// ```
+    pub fn with_capacity_zeroed(capacity: usize) -> Self {
+        Self::with_capacity_zeroed_in(capacity, Global)
+    }
+
+    /// Creates a vector of `len` zeroes, zeroed by the allocator (usually for free on fresh pages) instead of being
+    /// written element by element.
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// let vec = vec::Vec::<u64>::from_zeroes(1000);
+    /// assert_eq!(vec.len(), 1000);
+    /// assert!(vec.iter().all(|&x| x == 0));
+    /// ```
+    pub fn from_zeroes(len: usize) -> Self
+    where
+        T: Zeroable,
+    {
+        Self::from_zeroes_in(len, Global)
+    }
+}
+
+impl<T, A: Allocator> Vec<T, A> {
+    /// Creates a new Vector with room for `capacity` elements allocated from `alloc`, whose memory is zeroed by the
+    /// allocator. The vector is empty, see [`Vec::with_capacity_zeroed`].
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// let vec: vec::Vec<u8, _> = vec::Vec::with_capacity_zeroed_in(16, Global);
+    /// assert_eq!(vec.capacity(), 16);
+    /// ```
+    pub fn with_capacity_zeroed_in(capacity: usize, alloc: A) -> Self {
+        match Self::try_with_capacity_zeroed_in(capacity, alloc) {
+            Ok(vec) => vec,
+            Err(err) => handle_reserve_error(err),
+        }
+    }
+
+    /// Creates a new Vector with room for `capacity` zeroed elements allocated from `alloc`, returning an error if
+    /// the allocation fails.
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use vec::{TryReserveError, Vec};
+    /// let err = Vec::<u32, _>::try_with_capacity_zeroed_in(usize::MAX, Global).unwrap_err();
+    /// assert_eq!(err, TryReserveError::CapacityOverflow);
+    /// ```
+    pub fn try_with_capacity_zeroed_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            buf: RawVec::try_with_capacity_zeroed_in(capacity, Doubling, alloc)?,
+            len: 0,
+        })
+    }
+
+    /// Creates a vector of `len` zeroes allocated from `alloc`, see [`Vec::from_zeroes`].
+    /// # Panics
+    /// This function will panic if the capacity exceeds `isize::MAX` bytes.
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use vec::{Aligned, Vec};
+    /// let vec: Vec<f32, _> = Vec::from_zeroes_in(256, Aligned::<64>::new());
+    /// assert_eq!(vec.as_ptr() as usize % 64, 0);
+    /// assert_eq!(vec[255], 0.0);
+    /// ```
+    pub fn from_zeroes_in(len: usize, alloc: A) -> Self
+    where
+        T: Zeroable,
+    {
+        let mut vec = Self::with_capacity_zeroed_in(len, alloc);
+
+        // Safety: the buffer holds `len` zeroed elements, which are valid values of `T`
+        unsafe { vec.set_len(len) };
+
+        vec
+    }
+}
@@ -0,0 +1,119 @@
+//! Checks the events reported by the allocation hook:
+//! `cargo test --features alloc-hooks --test alloc_hooks`
+#![cfg(feature = "alloc-hooks")]
+
+use std::cell::RefCell;
+
+use vec::{set_alloc_hook, AllocEvent, AllocKind, CompactVec, ThinVec, Vec};
+
+thread_local! {
+    // Events raised by the current test's thread, the hook itself is shared by every test
+    static EVENTS: RefCell<std::vec::Vec<AllocEvent>> = const { RefCell::new(std::vec::Vec::new()) };
+}
+
+fn record(event: &AllocEvent) {
+    EVENTS.with(|events| events.borrow_mut().push(*event));
+}
+
+fn recorded(f: impl FnOnce()) -> std::vec::Vec<AllocEvent> {
+    set_alloc_hook(Some(record));
+    EVENTS.with(|events| events.borrow_mut().clear());
+
+    f();
+
+    EVENTS.with(|events| events.borrow_mut().drain(..).collect())
+}
+
+fn event(kind: AllocKind, old_cap: usize, new_cap: usize) -> AllocEvent {
+    AllocEvent {
+        kind,
+        old_cap,
+        new_cap,
+        elem_size: 4,
+    }
+}
+
+#[test]
+fn reports_the_buffer_lifecycle() {
+    let events = recorded(|| {
+        let mut vec = Vec::new();
+        for i in 0..5u32 {
+            vec.push(i);
+        }
+        vec.truncate(2);
+        vec.shrink_to_fit();
+    });
+
+    assert_eq!(
+        events,
+        [
+            event(AllocKind::Allocate, 0, 4),
+            event(AllocKind::Grow, 4, 8),
+            event(AllocKind::Shrink, 8, 2),
+            event(AllocKind::Deallocate, 2, 0),
+        ]
+    );
+}
+
+#[test]
+fn reports_preallocated_buffers() {
+    let events = recorded(|| {
+        let mut vec: Vec<u32> = Vec::with_capacity(16);
+        vec.clear();
+        vec.shrink_to_fit();
+    });
+
+    assert_eq!(
+        events,
+        [
+            event(AllocKind::Allocate, 0, 16),
+            event(AllocKind::Deallocate, 16, 0),
+        ]
+    );
+}
+
+#[test]
+fn zero_sized_types_are_not_reported() {
+    let events = recorded(|| {
+        let mut vec = Vec::new();
+        vec.extend(std::iter::repeat_n((), 100));
+    });
+
+    assert!(events.is_empty());
+}
+
+#[test]
+fn thin_vec_reports_its_block() {
+    let events = recorded(|| {
+        let mut vec = ThinVec::new();
+        for i in 0..5u32 {
+            vec.push(i);
+        }
+    });
+
+    assert_eq!(
+        events,
+        [
+            event(AllocKind::Allocate, 0, 4),
+            event(AllocKind::Grow, 4, 8),
+            event(AllocKind::Deallocate, 8, 0),
+        ]
+    );
+}
+
+#[test]
+fn compact_vec_reports_its_buffer() {
+    let events = recorded(|| {
+        let mut vec = CompactVec::new();
+        vec.extend(0..5u32);
+    });
+
+    assert_eq!(
+        events,
+        [
+            event(AllocKind::Allocate, 0, 4),
+            event(AllocKind::Grow, 4, 8),
+            event(AllocKind::Deallocate, 8, 0),
+        ]
+    );
+}
@@ -0,0 +1,26 @@
+//! Misuse of the unsafe API is caught when the `debug-invariants` feature is enabled:
+//! `cargo test --features debug-invariants --test debug_invariants`
+#![cfg(feature = "debug-invariants")]
+
+use vec::Vec;
+
+#[test]
+#[should_panic(expected = "set_len: new length 5 exceeds capacity 4")]
+fn set_len_beyond_capacity() {
+    let mut vec: Vec<u32> = Vec::with_capacity(4);
+
+    unsafe { vec.set_len(5) };
+}
+
+#[test]
+fn valid_operations_pass() {
+    let mut vec = Vec::new();
+
+    vec.extend(0..100);
+    vec.insert(50, 1000);
+    assert_eq!(vec.remove(50), 1000);
+    vec.truncate(10);
+
+    unsafe { vec.set_len(5) };
+    assert_eq!(&*vec, &[0, 1, 2, 3, 4]);
+}
@@ -0,0 +1,92 @@
+//! Checks the code generated by the derive macros:
+//! `cargo test --features derive --test derive`
+#![cfg(feature = "derive")]
+
+use vec::{EnumIndex, EnumIndexedVec, Idx, IndexVec};
+
+#[derive(Idx, Clone, Copy, PartialEq, Eq, Debug)]
+struct NodeId(u32);
+
+#[derive(Idx, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BlockId(
+    /// Position in the block table
+    pub usize,
+);
+
+#[derive(Idx, Clone, Copy, PartialEq, Eq, Debug)]
+struct Tiny(u8);
+
+#[test]
+fn idx_round_trips() {
+    assert_eq!(NodeId::new(7), NodeId(7));
+    assert_eq!(NodeId(7).index(), 7);
+    assert_eq!(BlockId::new(usize::MAX).index(), usize::MAX);
+}
+
+#[test]
+fn index_vec_with_derived_indices() {
+    let mut nodes: IndexVec<NodeId, &str> = IndexVec::new();
+    let mut blocks: IndexVec<BlockId, std::vec::Vec<NodeId>> = IndexVec::new();
+
+    let entry = blocks.push(std::vec::Vec::new());
+    for name in ["a", "b", "c"] {
+        let node = nodes.push(name);
+        blocks[entry].push(node);
+    }
+
+    assert_eq!(blocks[entry], [NodeId(0), NodeId(1), NodeId(2)]);
+    assert_eq!(nodes[NodeId(2)], "c");
+    assert_eq!(nodes.indices().last(), Some(NodeId(2)));
+}
+
+#[test]
+#[should_panic(expected = "index 256 doesn't fit in `Tiny`")]
+fn idx_overflow_panics() {
+    let mut v: IndexVec<Tiny, ()> = IndexVec::new();
+    for _ in 0..=256 {
+        v.push(());
+    }
+}
+
+#[derive(EnumIndex, Clone, Copy, PartialEq, Eq, Debug)]
+enum Opcode {
+    Load,
+    /// Writes a register to memory
+    Store = 7,
+    #[allow(dead_code)]
+    Jump,
+}
+
+#[derive(EnumIndex, Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Never {}
+
+#[test]
+fn enum_index_numbers_variants_in_order() {
+    assert_eq!(Opcode::COUNT, 3);
+    assert_eq!(Opcode::Store.index(), 1);
+    assert_eq!(Opcode::from_index(2), Opcode::Jump);
+    assert_eq!(Never::COUNT, 0);
+}
+
+#[test]
+fn enum_indexed_vec_with_derived_keys() {
+    let mut counts: EnumIndexedVec<Opcode, u32> = EnumIndexedVec::new();
+    for op in [Opcode::Store, Opcode::Load, Opcode::Store] {
+        counts[op] += 1;
+    }
+
+    assert_eq!(
+        counts.iter().collect::<std::vec::Vec<_>>(),
+        [(Opcode::Load, &1), (Opcode::Store, &2), (Opcode::Jump, &0)]
+    );
+    assert_eq!(format!("{:?}", counts), "{Load: 1, Store: 2, Jump: 0}");
+
+    let empty: EnumIndexedVec<Never, u32> = EnumIndexedVec::new();
+    assert_eq!(empty.iter().count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "index 3 out of range for `Opcode`")]
+fn enum_index_out_of_range_panics() {
+    Opcode::from_index(3);
+}
@@ -0,0 +1,78 @@
+//! The construction macros must build this crate's containers whatever is in scope where they're expanded.
+use std::rc::Rc;
+
+use vec::{array_vec, custom_vec, small_vec, sorted_vec, vec_deque, ArrayVec, SmallVec};
+
+// Shadows the prelude's `Vec`, which `custom_vec!` must not pick up
+#[allow(dead_code)]
+struct Vec;
+
+#[test]
+fn custom_vec_builds_the_crate_vec() {
+    let list: vec::Vec<i32> = custom_vec![1, 2, 3];
+    assert_eq!(&*list, &[1, 2, 3]);
+
+    let empty: vec::Vec<i32> = custom_vec![];
+    assert!(empty.is_empty());
+
+    let repeated: vec::Vec<String> = custom_vec![String::from("a"); 2];
+    assert_eq!(&*repeated, &["a", "a"]);
+}
+
+#[test]
+fn custom_vec_allocates_once() {
+    let list = custom_vec!["alpha", "beta", "gamma", "delta", "epsilon",];
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.capacity(), 5);
+}
+
+#[test]
+fn array_vec_fills_up_to_the_capacity() {
+    let list: ArrayVec<i32, 4> = array_vec![1, 2, 3,];
+    assert_eq!(&*list, &[1, 2, 3]);
+
+    let empty: ArrayVec<i32, 4> = array_vec![];
+    assert!(empty.is_empty());
+
+    const LEN: usize = 4;
+    let full: ArrayVec<u8, LEN> = array_vec![7; LEN];
+    assert!(full.is_full());
+    assert_eq!(&*full, &[7; 4]);
+}
+
+#[test]
+fn repetitions_move_the_element_into_the_last_slot() {
+    let elem = Rc::new(0);
+
+    let array: ArrayVec<Rc<i32>, 4> = array_vec![elem.clone(); 3];
+    let small: SmallVec<Rc<i32>, 2> = small_vec![elem.clone(); 3];
+    assert_eq!(Rc::strong_count(&elem), 7);
+
+    drop((array, small));
+    assert_eq!(Rc::strong_count(&elem), 1);
+}
+
+#[test]
+fn small_vec_spills_only_past_the_inline_capacity() {
+    let inline: SmallVec<i32, 4> = small_vec![1, 2, 3, 4];
+    assert!(!inline.spilled());
+
+    let spilled: SmallVec<i32, 4> = small_vec![1, 2, 3, 4, 5];
+    assert!(spilled.spilled());
+    assert_eq!(spilled.capacity(), 5);
+    assert_eq!(&*spilled, &[1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn sorted_vec_and_vec_deque_accept_both_forms() {
+    let sorted = sorted_vec!["b", "c", "a",];
+    assert_eq!(&*sorted, &["a", "b", "c"]);
+    assert_eq!(&*sorted_vec![2; 3], &[2, 2, 2]);
+
+    let deque = vec_deque![1, 2, 3];
+    assert_eq!(
+        deque.iter().copied().collect::<std::vec::Vec<_>>(),
+        [1, 2, 3]
+    );
+    assert_eq!(vec_deque![0u8; 5].len(), 5);
+}
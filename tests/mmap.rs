@@ -0,0 +1,77 @@
+//! File-backed mappings all start at offset 0 of the file, so only one allocation may be live at a time:
+//! `cargo test --features mmap --test mmap`
+#![cfg(all(feature = "mmap", target_os = "linux"))]
+#![feature(allocator_api)]
+
+use std::{
+    alloc::{Allocator, Layout},
+    fs::{self, File, OpenOptions},
+    path::PathBuf,
+};
+
+use vec::{Mmap, Vec};
+
+fn temp_file(name: &str) -> (File, PathBuf) {
+    let path = std::env::temp_dir().join(format!("vec-mmap-test-{}-{}", name, std::process::id()));
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    (file, path)
+}
+
+#[test]
+fn file_backs_a_single_live_allocation() {
+    let (file, path) = temp_file("single");
+    let alloc = Mmap::file(file);
+    let layout = Layout::array::<u32>(1024).unwrap();
+
+    let first = alloc.allocate(layout).unwrap();
+    assert!(alloc.allocate(layout).is_err());
+
+    // Growing keeps the same allocation
+    let grown = unsafe {
+        alloc
+            .grow(first.cast(), layout, Layout::array::<u32>(1 << 16).unwrap())
+            .unwrap()
+    };
+    assert!(alloc.allocate(layout).is_err());
+
+    unsafe { alloc.deallocate(grown.cast(), Layout::array::<u32>(1 << 16).unwrap()) };
+    let again = alloc.allocate(layout).unwrap();
+    unsafe { alloc.deallocate(again.cast(), layout) };
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn vector_contents_survive_a_failed_second_allocation() {
+    let (file, path) = temp_file("vector");
+    let mut vec = Vec::new_in(Mmap::file(file));
+    vec.extend((0..2000u32).rev());
+
+    let layout = Layout::array::<u32>(2000).unwrap();
+    assert!(vec.allocator().allocate(layout).is_err());
+    assert!(vec.iter().copied().eq((0..2000).rev()));
+
+    drop(vec);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn anonymous_mappings_are_independent() {
+    let alloc = Mmap::anonymous();
+    let layout = Layout::array::<u64>(512).unwrap();
+
+    let a = alloc.allocate(layout).unwrap();
+    let b = alloc.allocate(layout).unwrap();
+    assert_ne!(a.cast::<u8>(), b.cast::<u8>());
+
+    unsafe {
+        alloc.deallocate(a.cast(), layout);
+        alloc.deallocate(b.cast(), layout);
+    }
+}
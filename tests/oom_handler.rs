@@ -0,0 +1,87 @@
+//! Checks the out-of-memory handler can free memory and retry, or turn the failure into a panic. The handler is
+//! process-wide, so everything runs in a single test.
+#![feature(allocator_api)]
+
+use std::{
+    alloc::{AllocError, Allocator, Global, Layout},
+    panic,
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use vec::{set_oom_handler, OomAction, TryReserveError, Vec};
+
+// Number of upcoming allocations `Flaky` fails
+static FAILURES: AtomicUsize = AtomicUsize::new(0);
+// Number of times the handler was called
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+// Allocator failing as long as `FAILURES` isn't 0
+#[derive(Clone, Copy)]
+struct Flaky;
+
+unsafe impl Allocator for Flaky {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let failing =
+            FAILURES.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+
+        match failing {
+            Ok(_) => Err(AllocError),
+            Err(_) => Global.allocate(layout),
+        }
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        Global.deallocate(ptr, layout)
+    }
+}
+
+fn retry(_: Layout) -> OomAction {
+    CALLS.fetch_add(1, Ordering::SeqCst);
+    OomAction::Retry
+}
+
+fn fail(_: Layout) -> OomAction {
+    CALLS.fetch_add(1, Ordering::SeqCst);
+    OomAction::Fail
+}
+
+fn unwind(_: Layout) -> OomAction {
+    OomAction::Panic
+}
+
+#[test]
+fn handler_decides_what_happens_on_failure() {
+    // Retried until the allocator recovers
+    set_oom_handler(Some(retry));
+    FAILURES.store(3, Ordering::SeqCst);
+
+    let mut vec = Vec::new_in(Flaky);
+    vec.push(1);
+    // The first failure is retried with the exact capacity before asking the handler
+    assert_eq!(CALLS.swap(0, Ordering::SeqCst), 2);
+
+    let vec = Vec::<u8, _>::with_capacity_in(64, Flaky);
+    assert_eq!(vec.capacity(), 64);
+    assert_eq!(CALLS.swap(0, Ordering::SeqCst), 0);
+
+    // Reported to the fallible methods
+    set_oom_handler(Some(fail));
+    FAILURES.store(1, Ordering::SeqCst);
+
+    let result = Vec::<u8, _>::try_with_capacity_in(64, Flaky);
+    assert!(matches!(result, Err(TryReserveError::AllocError { .. })));
+    assert_eq!(CALLS.swap(0, Ordering::SeqCst), 1);
+
+    // Unwinds instead of aborting
+    set_oom_handler(Some(unwind));
+    FAILURES.store(2, Ordering::SeqCst);
+
+    let result = panic::catch_unwind(|| {
+        let mut vec = Vec::new_in(Flaky);
+        vec.push(1);
+    });
+    assert!(result.is_err());
+
+    set_oom_handler(None);
+}
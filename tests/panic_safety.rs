@@ -0,0 +1,233 @@
+//! Checks that a panicking clone, iterator or destructor never leaves the vector with a gap counted in its length,
+//! double drops an element or leaks one.
+use std::{
+    cell::Cell,
+    ops::Bound,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use vec::{ArcBytes, ByteBuf, ByteBufError, DirtyRangeVec, Rope, Vec};
+
+// Counts its drops, and panics on drop (or clone) when asked to
+#[derive(Debug)]
+struct Bomb {
+    drops: Rc<Cell<usize>>,
+    clones: Rc<Cell<usize>>,
+    panic_on_drop: bool,
+    panic_on_clone: bool,
+}
+
+impl Bomb {
+    fn new(drops: &Rc<Cell<usize>>) -> Self {
+        Self {
+            drops: drops.clone(),
+            clones: Rc::new(Cell::new(0)),
+            panic_on_drop: false,
+            panic_on_clone: false,
+        }
+    }
+}
+
+impl Clone for Bomb {
+    fn clone(&self) -> Self {
+        if self.panic_on_clone {
+            panic!("clone");
+        }
+
+        self.clones.set(self.clones.get() + 1);
+        Self::new(&self.drops)
+    }
+}
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+
+        if self.panic_on_drop {
+            panic!("drop");
+        }
+    }
+}
+
+fn bombs(n: usize, drops: &Rc<Cell<usize>>) -> Vec<Bomb> {
+    let mut vec = Vec::new();
+
+    for _ in 0..n {
+        vec.push(Bomb::new(drops));
+    }
+
+    vec
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn insert_out_of_bounds() {
+    let mut vec = Vec::new();
+    vec.push(1);
+    vec.insert(2, 2);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn remove_out_of_bounds() {
+    let mut vec = Vec::new();
+    vec.push(1);
+    vec.remove(1);
+}
+
+#[test]
+#[should_panic(expected = "range out of bounds")]
+fn rope_remove_range_ending_at_usize_max() {
+    let mut rope = Rope::from("hello");
+    rope.remove(..=usize::MAX);
+}
+
+#[test]
+#[should_panic(expected = "range out of bounds")]
+fn arc_bytes_slice_starting_after_usize_max() {
+    let bytes = ArcBytes::from(&b"hello"[..]);
+    bytes.slice((Bound::Excluded(usize::MAX), Bound::Unbounded));
+}
+
+#[test]
+fn arc_bytes_truncate_to_usize_max_after_advance() {
+    let mut bytes = ArcBytes::from(&b"abc"[..]);
+    bytes.advance(1);
+    bytes.truncate(usize::MAX);
+    assert_eq!(&*bytes, b"bc");
+}
+
+#[test]
+fn byte_buf_split_to_past_the_end_underflows_without_allocating() {
+    let mut buf = ByteBuf::new();
+    buf.put_slice(b"abc");
+    assert_eq!(
+        buf.split_to(usize::MAX).unwrap_err(),
+        ByteBufError::Underflow {
+            needed: usize::MAX,
+            remaining: 3
+        }
+    );
+    assert_eq!(buf.chunk(), b"abc");
+}
+
+#[test]
+#[should_panic(expected = "range out of bounds")]
+fn dirty_range_vec_slice_ending_at_usize_max() {
+    let mut vec = DirtyRangeVec::new();
+    vec.extend([1, 2, 3]);
+    vec.slice_mut(..=usize::MAX);
+}
+
+#[test]
+fn extend_with_panicking_iterator_keeps_pushed_elements() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = bombs(2, &drops);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        vec.extend((0..10).map(|i| {
+            if i == 7 {
+                panic!("iterator");
+            }
+            Bomb::new(&drops)
+        }))
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(vec.len(), 9);
+    assert_eq!(drops.get(), 0);
+
+    drop(vec);
+    assert_eq!(drops.get(), 9);
+}
+
+#[test]
+fn clone_with_panicking_clone_drops_the_clones_once() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = bombs(5, &drops);
+    vec[3].panic_on_clone = true;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| vec.clone()));
+
+    assert!(result.is_err());
+    // The three elements cloned before the panic were dropped with the partial clone
+    assert_eq!(drops.get(), 3);
+
+    vec[3].panic_on_clone = false;
+    drop(vec);
+    assert_eq!(drops.get(), 8);
+}
+
+#[test]
+fn truncate_with_panicking_destructor_drops_the_rest() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = bombs(6, &drops);
+    vec[3].panic_on_drop = true;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| vec.truncate(2)));
+
+    assert!(result.is_err());
+    assert_eq!(vec.len(), 2);
+    assert_eq!(drops.get(), 4);
+
+    drop(vec);
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+fn remove_indices_with_panicking_destructor_keeps_the_rest() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = bombs(8, &drops);
+    vec[3].panic_on_drop = true;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| vec.remove_indices(&[1, 3, 5])));
+
+    assert!(result.is_err());
+    // The pass stopped at the panicking element: index 5 is still in the vector, with no gap left behind
+    assert_eq!(vec.len(), 6);
+    assert_eq!(drops.get(), 2);
+
+    drop(vec);
+    assert_eq!(drops.get(), 8);
+}
+
+#[test]
+fn dropping_with_panicking_destructor_drops_the_rest() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = bombs(4, &drops);
+    vec[0].panic_on_drop = true;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| drop(vec)));
+
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn into_iter_with_panicking_destructor_drops_the_rest() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = bombs(5, &drops);
+    vec[2].panic_on_drop = true;
+
+    let mut iter = vec.into_iter();
+    drop(iter.next());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| drop(iter)));
+
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn drain_with_panicking_destructor_drops_the_rest() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = bombs(5, &drops);
+    vec[1].panic_on_drop = true;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| drop(vec.drain())));
+
+    assert!(result.is_err());
+    assert_eq!(vec.len(), 0);
+    assert_eq!(drops.get(), 5);
+}
@@ -0,0 +1,103 @@
+//! Debug builds overwrite vacated memory with `0xDD`, checks every place doing so:
+//! `cargo test --test poison`
+#![cfg(debug_assertions)]
+#![feature(allocator_api)]
+
+use std::{
+    alloc::{AllocError, Allocator, Global, Layout},
+    cell::Cell,
+    ptr::NonNull,
+};
+
+use vec::Vec;
+
+const POISON: u8 = 0xDD;
+
+// Checks the whole buffer was poisoned before being freed. Reallocations go straight to `Global`, as the default
+// ones free the old buffer with its live elements
+#[derive(Default)]
+struct CheckFree {
+    freed: Cell<usize>,
+}
+
+unsafe impl Allocator for &CheckFree {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let bytes = std::slice::from_raw_parts(ptr.as_ptr(), layout.size());
+        assert!(bytes.iter().all(|&b| b == POISON));
+
+        self.freed.set(self.freed.get() + 1);
+        Global.deallocate(ptr, layout)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        Global.grow(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        Global.shrink(ptr, old_layout, new_layout)
+    }
+}
+
+// Reads the `i`th slot of the buffer as raw bytes, whether it holds an element or not
+fn slot(vec: &Vec<u32, impl Allocator>, i: usize) -> [u8; 4] {
+    unsafe { vec.as_ptr().add(i).cast::<[u8; 4]>().read() }
+}
+
+#[test]
+fn pop_poisons_the_vacated_slot() {
+    let mut vec = Vec::new();
+    vec.extend(0..4u32);
+
+    assert_eq!(vec.pop(), Some(3));
+    assert_eq!(slot(&vec, 3), [POISON; 4]);
+    assert_eq!(slot(&vec, 2), 2u32.to_ne_bytes());
+}
+
+#[test]
+fn remove_poisons_the_last_slot() {
+    let mut vec = Vec::new();
+    vec.extend(0..4u32);
+
+    assert_eq!(vec.remove(1), 1);
+    assert_eq!(&*vec, &[0, 2, 3]);
+    assert_eq!(slot(&vec, 3), [POISON; 4]);
+}
+
+#[test]
+fn truncate_poisons_the_tail() {
+    let mut vec = Vec::new();
+    vec.extend(0..8u32);
+
+    vec.truncate(5);
+    assert!((5..8).all(|i| slot(&vec, i) == [POISON; 4]));
+
+    vec.clear();
+    assert!((0..8).all(|i| slot(&vec, i) == [POISON; 4]));
+}
+
+#[test]
+fn buffers_are_poisoned_before_being_freed() {
+    let alloc = CheckFree::default();
+    let mut vec = Vec::new_in(&alloc);
+    vec.extend(0..100u32);
+
+    vec.truncate(10);
+    vec.shrink_to_fit();
+    drop(vec);
+
+    assert_eq!(alloc.freed.get(), 1);
+}
@@ -0,0 +1,138 @@
+//! A spill that fails partway must leave the file as it was, or the records read back would be misaligned.
+use std::{
+    cell::Cell,
+    io::{self, Read, Write},
+};
+
+use vec::{Spill, SpillVec};
+
+thread_local! {
+    // Every test runs on its own thread, so each one gets its own budget
+    static WRITES_LEFT: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+// Element whose writes start failing once the thread's budget of writes is spent
+#[derive(Debug, Clone, PartialEq)]
+struct Flaky(u32);
+
+impl Spill for Flaky {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let left = WRITES_LEFT.with(Cell::get);
+        if left == 0 {
+            return Err(io::Error::other("disk full"));
+        }
+
+        WRITES_LEFT.with(|writes| writes.set(left - 1));
+        self.0.spill(writer)
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        u32::unspill(reader).map(Flaky)
+    }
+}
+
+fn contents(vec: &SpillVec<Flaky>) -> Vec<u32> {
+    vec.iter().map(|elem| elem.unwrap().0).collect()
+}
+
+#[test]
+fn failed_spill_is_rolled_back() {
+    let mut vec = SpillVec::with_budget(16);
+    vec.extend((0..8).map(Flaky)).unwrap();
+
+    // The second spill fails after writing two of its four elements
+    WRITES_LEFT.with(|writes| writes.set(2));
+    assert!(vec.push(Flaky(8)).is_err());
+    assert_eq!(vec.len(), 8);
+    assert_eq!(vec.spilled_len(), 4);
+
+    WRITES_LEFT.with(|writes| writes.set(usize::MAX));
+    vec.extend((8..12).map(Flaky)).unwrap();
+    assert_eq!(contents(&vec), (0..12).collect::<Vec<_>>());
+    assert_eq!(vec.get(5).unwrap(), Some(Flaky(5)));
+}
+
+#[test]
+fn pop_reads_spills_back_in_order() {
+    let mut vec = SpillVec::with_budget(8);
+    vec.extend(0..10u32).unwrap();
+
+    let mut popped = Vec::new();
+    while let Some(elem) = vec.pop().unwrap() {
+        popped.push(elem);
+    }
+    assert_eq!(popped, (0..10).rev().collect::<Vec<_>>());
+
+    // The file was cut back, so pushing again spills from the start
+    vec.extend(20..25u32).unwrap();
+    let items: Vec<u32> = vec.iter().map(|elem| *elem.unwrap()).collect();
+    assert_eq!(items, [20, 21, 22, 23, 24]);
+}
+
+#[test]
+fn clear_empties_the_file() {
+    let mut vec = SpillVec::with_budget(4);
+    vec.extend(0..9u8).unwrap();
+    vec.clear().unwrap();
+    assert!(vec.is_empty());
+
+    vec.extend(0..6u8).unwrap();
+    for i in 0..6 {
+        assert_eq!(vec.get(i).unwrap(), Some(i as u8));
+    }
+}
+
+// Element that can only be moved, to check iteration doesn't need `Clone`
+#[derive(Debug, PartialEq)]
+struct Moved(u64);
+
+impl Spill for Moved {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.spill(writer)
+    }
+
+    fn unspill<R: Read>(reader: &mut R) -> io::Result<Self> {
+        u64::unspill(reader).map(Moved)
+    }
+}
+
+#[test]
+fn iter_without_clone() {
+    let mut vec = SpillVec::with_budget(16);
+    vec.extend((0..5).map(Moved)).unwrap();
+    assert_eq!(vec.spilled_len(), 4);
+
+    let items: Vec<u64> = vec.iter().map(|elem| elem.unwrap().0).collect();
+    assert_eq!(items, [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn sequences_and_tuples_round_trip() {
+    let elems: Vec<(u8, String, Vec<u16>)> = (0..7)
+        .map(|i| (i, "x".repeat(i as usize), (0..i as u16).collect()))
+        .collect();
+
+    let mut vec = SpillVec::with_budget(64);
+    vec.extend(elems.iter().cloned()).unwrap();
+    assert!(vec.spilled_len() > 0);
+
+    for (i, elem) in elems.iter().enumerate() {
+        assert_eq!(vec.get(i).unwrap().as_ref(), Some(elem));
+    }
+    let items: Vec<_> = vec.iter().map(|elem| elem.unwrap().into_owned()).collect();
+    assert_eq!(items, elems);
+}
+
+#[test]
+fn fixed_size_records_are_read_directly() {
+    let mut vec = SpillVec::with_budget(32);
+    vec.extend((0..100u32).map(|i| (i, i as u8, [i as u16; 3])))
+        .unwrap();
+
+    for i in (0..100).rev() {
+        assert_eq!(
+            vec.get(i).unwrap(),
+            Some((i as u32, i as u8, [i as u16; 3]))
+        );
+    }
+}
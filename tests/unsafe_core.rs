@@ -0,0 +1,1080 @@
+//! Exercises every operation going through the unsafe core of `Vec`.
+//!
+//! Run under Miri to catch undefined behavior and provenance regressions:
+//! `MIRIFLAGS=-Zmiri-strict-provenance cargo +nightly miri test --test unsafe_core`
+use std::{cell::Cell, mem::MaybeUninit, rc::Rc};
+
+use vec::{
+    AnyType, AnyVec, AppendOnlyVec, Arena, ArrayVec, BiVec, BinaryHeap, BoundedVec, CVec,
+    CompactVec, ConcurrentVec, Fixed, FrozenVec, GapBuffer, Pool, SegmentedVec, ShrinkBelow,
+    SmallVec, SortedVec, StaticBuffer, ThinVec, UnsizedVec, Vec, VecDeque,
+};
+
+// Counts how many times values sharing the same counter were dropped
+#[derive(Debug)]
+struct DropCounter(Rc<Cell<usize>>, usize);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn counted(n: usize) -> (Vec<DropCounter>, Rc<Cell<usize>>) {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = Vec::new();
+
+    for i in 0..n {
+        vec.push(DropCounter(drops.clone(), i));
+    }
+
+    (vec, drops)
+}
+
+#[test]
+fn push_and_pop() {
+    let mut vec = Vec::new();
+
+    for i in 0..100 {
+        vec.push(i.to_string());
+    }
+
+    for i in (0..100).rev() {
+        assert_eq!(vec.pop(), Some(i.to_string()));
+    }
+
+    assert_eq!(vec.pop(), None);
+}
+
+#[test]
+fn insert_front_middle_and_back() {
+    let mut vec = Vec::new();
+
+    vec.insert(0, 2);
+    vec.insert(0, 0);
+    vec.insert(1, 1);
+    vec.insert(3, 3);
+
+    assert_eq!(&*vec, &[0, 1, 2, 3]);
+}
+
+#[test]
+fn remove_returns_the_removed_element() {
+    let mut vec = Vec::new();
+    vec.extend(0..5);
+
+    assert_eq!(vec.remove(0), 0);
+    assert_eq!(vec.remove(1), 2);
+    assert_eq!(vec.remove(2), 4);
+    assert_eq!(&*vec, &[1, 3]);
+}
+
+#[test]
+fn remove_drops_each_element_once() {
+    let (mut vec, drops) = counted(10);
+
+    let removed = vec.remove(4);
+    assert_eq!(removed.1, 4);
+    drop(removed);
+
+    assert_eq!(drops.get(), 1);
+    drop(vec);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn truncate_and_clear_drop_the_tail() {
+    let (mut vec, drops) = counted(10);
+
+    vec.truncate(20);
+    assert_eq!(drops.get(), 0);
+
+    vec.truncate(6);
+    assert_eq!(drops.get(), 4);
+    assert_eq!(vec.len(), 6);
+    assert_eq!(vec[5].1, 5);
+
+    vec.clear();
+    assert_eq!(drops.get(), 10);
+    assert!(vec.capacity() >= 10);
+}
+
+#[test]
+fn dropping_the_vector_drops_every_element() {
+    let (vec, drops) = counted(33);
+
+    drop(vec);
+    assert_eq!(drops.get(), 33);
+}
+
+#[test]
+fn into_iter_from_both_ends() {
+    let mut vec = Vec::new();
+    vec.extend(0..6);
+
+    let mut iter = vec.into_iter();
+
+    assert_eq!(iter.size_hint(), (6, Some(6)));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(5));
+    assert_eq!(iter.next_back(), Some(4));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    assert_eq!(iter.next_back(), Some(3));
+    assert_eq!(iter.next(), Some(2));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn into_iter_drops_unconsumed_elements() {
+    let (vec, drops) = counted(8);
+
+    let mut iter = vec.into_iter();
+    drop(iter.next());
+    drop(iter.next_back());
+    assert_eq!(drops.get(), 2);
+
+    drop(iter);
+    assert_eq!(drops.get(), 8);
+}
+
+#[test]
+fn drain_yields_everything_and_empties_the_vector() {
+    let mut vec = Vec::new();
+    vec.extend(0..4);
+
+    let drained: std::vec::Vec<_> = vec.drain().collect();
+
+    assert_eq!(drained, [0, 1, 2, 3]);
+    assert_eq!(vec.len(), 0);
+
+    vec.push(10);
+    assert_eq!(&*vec, &[10]);
+}
+
+#[test]
+fn drain_drops_unconsumed_elements() {
+    let (mut vec, drops) = counted(5);
+
+    let mut drain = vec.drain();
+    drop(drain.next_back());
+    drop(drain);
+
+    assert_eq!(drops.get(), 5);
+    drop(vec);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn zero_sized_elements() {
+    let mut vec = Vec::new();
+
+    for _ in 0..10 {
+        vec.push(());
+    }
+    vec.insert(5, ());
+    assert_eq!(vec.remove(0), ());
+    assert_eq!(vec.len(), 10);
+
+    let mut iter = vec.into_iter();
+    assert_eq!(iter.size_hint(), (10, Some(10)));
+    assert_eq!(iter.next(), Some(()));
+    assert_eq!(iter.next_back(), Some(()));
+    assert_eq!(iter.count(), 8);
+}
+
+#[test]
+fn zero_sized_drain() {
+    let mut vec = Vec::new();
+    vec.extend(std::iter::repeat_n((), 4));
+
+    assert_eq!(vec.drain().count(), 4);
+    assert_eq!(vec.len(), 0);
+}
+
+#[test]
+fn shrink_to_fit_keeps_elements() {
+    let mut vec = Vec::with_capacity(64);
+    vec.extend(0..10);
+
+    vec.shrink_to_fit();
+    assert_eq!(vec.capacity(), 10);
+    assert_eq!(&*vec, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    while vec.pop().is_some() {}
+    vec.shrink_to_fit();
+    assert_eq!(vec.capacity(), 0);
+}
+
+#[test]
+fn custom_allocators() {
+    let arena = Arena::with_chunk_size(64);
+    let mut a = Vec::new_in(&arena);
+    let mut b = Vec::new_in(&arena);
+
+    for i in 0..50 {
+        a.push(i);
+        b.insert(0, i);
+    }
+
+    assert_eq!(a[49], 49);
+    assert_eq!(b[49], 0);
+
+    let pool: Pool<64, 2> = Pool::new();
+    let mut c = Vec::new_in(&pool);
+    c.extend(0..16u32);
+    assert_eq!(c.remove(0), 0);
+    assert_eq!(c.into_iter().sum::<u32>(), (1..16).sum());
+    assert_eq!(pool.available(), 2);
+}
+
+#[test]
+fn fixed_buffer() {
+    let mut buf: [MaybeUninit<String>; 10] = [const { MaybeUninit::uninit() }; 10];
+    let mut vec = Vec::new_in(Fixed::new(&mut buf));
+
+    for i in 0..10 {
+        vec.push(i.to_string());
+    }
+
+    assert_eq!(vec.capacity(), 10);
+    assert!(vec.try_push(String::new()).is_err());
+    assert_eq!(vec.remove(0), "0");
+
+    vec.shrink_to_fit();
+    vec.push(10.to_string());
+    assert_eq!(vec.last().map(String::as_str), Some("10"));
+}
+
+#[test]
+fn static_buffer() {
+    static BUF: StaticBuffer<String, 4> = StaticBuffer::new();
+
+    let mut vec = Vec::from_buffer(BUF.take().unwrap());
+    assert!(BUF.take().is_none());
+
+    vec.extend((0..4).map(|i| i.to_string()));
+    assert!(vec.try_push(String::new()).is_err());
+
+    // Shrinking keeps the buffer, so the vector can grow back into it
+    vec.truncate(1);
+    vec.shrink_to_fit();
+    vec.extend((1..4).map(|i| i.to_string()));
+    assert_eq!(&*vec, &["0", "1", "2", "3"]);
+    assert!(vec.try_push(String::new()).is_err());
+}
+
+#[test]
+fn thin_vec() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = ThinVec::new();
+    vec.truncate(0);
+    assert_eq!(vec.pop().map(|d: DropCounter| d.1), None);
+
+    for i in 0..20 {
+        vec.insert(i / 2, DropCounter(drops.clone(), i));
+    }
+
+    assert_eq!(vec.remove(0).1, 1);
+    assert_eq!(vec.pop().map(|d| d.1), Some(0));
+    vec.truncate(10);
+    assert_eq!(drops.get(), 10);
+
+    let copy = vec.iter().map(|d| d.1).collect::<std::vec::Vec<_>>();
+    assert_eq!(copy.len(), 10);
+
+    drop(vec);
+    assert_eq!(drops.get(), 20);
+
+    let mut units = ThinVec::with_capacity(3);
+    units.extend(std::iter::repeat_n((), 10));
+    assert_eq!(units.len(), 10);
+    assert_eq!(units.capacity(), usize::MAX);
+}
+
+#[test]
+fn compact_vec() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = CompactVec::new();
+
+    for i in 0..20 {
+        vec.insert(i / 2, DropCounter(drops.clone(), i));
+    }
+
+    assert_eq!(vec.remove(0).1, 1);
+    assert_eq!(vec.pop().map(|d| d.1), Some(0));
+    vec.truncate(10);
+    assert_eq!(drops.get(), 10);
+
+    drop(vec);
+    assert_eq!(drops.get(), 20);
+
+    let mut units = CompactVec::new();
+    units.extend(std::iter::repeat_n((), 10));
+    assert_eq!(units.len(), 10);
+    assert_eq!(units.capacity(), u32::MAX as usize);
+}
+
+#[test]
+fn shrink_policy() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec = Vec::with_growth_policy(ShrinkBelow::default());
+
+    for i in 0..64 {
+        vec.push(DropCounter(drops.clone(), i));
+    }
+
+    while vec.len() > 15 {
+        vec.pop();
+    }
+    assert_eq!(vec.capacity(), 30);
+    assert_eq!(vec[14].1, 14);
+
+    let mut drain = vec.drain();
+    assert_eq!(drain.next().map(|d| d.1), Some(0));
+    drop(drain);
+
+    assert_eq!(vec.capacity(), 0);
+    assert_eq!(drops.get(), 64);
+}
+
+#[test]
+fn bulk_insertions() {
+    let (mut a, drops) = counted(3);
+    let (mut b, _) = counted(0);
+    for i in 3..40 {
+        b.push(DropCounter(drops.clone(), i));
+    }
+
+    a.append(&mut b);
+    assert_eq!(a.len(), 40);
+    assert!(b.is_empty());
+    assert_eq!(a[39].1, 39);
+    drop(b);
+    drop(a);
+    assert_eq!(drops.get(), 40);
+
+    let mut vec = Vec::new();
+    vec.extend(0..4u64);
+    vec.insert_slice(2, &[10; 100]);
+    vec.insert_slice(102, &[]);
+    vec.insert_slice(104, &[20]);
+    assert_eq!(vec.len(), 105);
+    assert_eq!(&vec[..3], &[0, 1, 10]);
+    assert_eq!(&vec[101..], &[10, 2, 3, 20]);
+}
+
+#[test]
+fn uninit_buffers() {
+    let mut buf: Vec<MaybeUninit<String>> = Vec::with_capacity(8);
+    for i in 0..5 {
+        unsafe {
+            buf.as_mut_ptr()
+                .cast::<String>()
+                .add(i)
+                .write(i.to_string())
+        };
+    }
+
+    let vec = unsafe { Vec::from_uninit_parts(buf, 5) };
+    assert_eq!(vec.capacity(), 8);
+    assert_eq!(vec[4], "4");
+
+    let mut buf = Vec::new();
+    buf.push(MaybeUninit::new(String::from("a")));
+    let mut vec = unsafe { buf.assume_init() };
+    vec.push(String::from("b"));
+    assert_eq!(&*vec, &["a", "b"]);
+}
+
+#[test]
+fn vec_deque() {
+    let drops = Rc::new(Cell::new(0));
+    let mut deque = VecDeque::new();
+
+    // Wrap around the end of the buffer, then grow while wrapped
+    for i in 0..6 {
+        deque.push_back(DropCounter(drops.clone(), i));
+    }
+    for i in 0..3 {
+        assert_eq!(deque.pop_front().unwrap().1, i);
+    }
+    for i in 6..12 {
+        deque.push_back(DropCounter(drops.clone(), i));
+    }
+    deque.push_front(DropCounter(drops.clone(), 2));
+    assert_eq!(deque.len(), 10);
+    assert!(deque.iter().map(|d| d.1).eq(2..12));
+    assert!(deque.iter().rev().map(|d| d.1).eq((2..12).rev()));
+
+    deque.make_contiguous();
+    assert_eq!(deque.as_slices().1.len(), 0);
+    assert_eq!(deque[9].1, 11);
+
+    let mut vec = Vec::from(deque);
+    assert_eq!(vec.len(), 10);
+    assert_eq!(vec[0].1, 2);
+    vec.push(DropCounter(drops.clone(), 12));
+
+    let mut deque = VecDeque::from(vec);
+    deque.push_front(DropCounter(drops.clone(), 1));
+    let mut iter = deque.into_iter();
+    assert_eq!(iter.next().unwrap().1, 1);
+    assert_eq!(iter.next_back().unwrap().1, 12);
+    drop(iter);
+    assert_eq!(drops.get(), 15);
+
+    let mut deque = VecDeque::with_capacity(4);
+    for i in 0..3 {
+        deque.push_front(i.to_string());
+    }
+    deque.clear();
+    assert!(deque.is_empty());
+    deque.push_back(String::from("a"));
+    assert_eq!(Vec::from(deque)[0], "a");
+}
+
+#[test]
+fn array_vec() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec: ArrayVec<DropCounter, 8> = ArrayVec::new();
+
+    for i in 0..6 {
+        vec.push(DropCounter(drops.clone(), i));
+    }
+    vec.insert(0, DropCounter(drops.clone(), 6));
+    vec.insert(7, DropCounter(drops.clone(), 7));
+    assert!(vec.is_full());
+    assert_eq!(
+        vec.try_push(DropCounter(drops.clone(), 8))
+            .unwrap_err()
+            .element
+            .1,
+        8
+    );
+    assert_eq!(drops.get(), 1);
+
+    assert_eq!(vec.remove(0).1, 6);
+    assert_eq!(vec.pop().unwrap().1, 7);
+    vec.truncate(4);
+    assert_eq!(drops.get(), 5);
+
+    let mut drain = vec.drain();
+    assert_eq!(drain.next().unwrap().1, 0);
+    drop(drain);
+    assert!(vec.is_empty());
+    assert_eq!(drops.get(), 9);
+
+    vec.extend((0..3).map(|i| DropCounter(drops.clone(), i)));
+    let mut iter = vec.into_iter();
+    assert_eq!(iter.next_back().unwrap().1, 2);
+    drop(iter);
+    assert_eq!(drops.get(), 12);
+
+    let mut zsts: ArrayVec<(), 3> = ArrayVec::new();
+    zsts.extend([(), ()]);
+    assert_eq!(zsts.drain().count(), 2);
+
+    let strings: ArrayVec<String, 2> = {
+        let mut vec = ArrayVec::new();
+        vec.push(String::from("a"));
+        vec
+    };
+    assert_eq!(strings.clone(), strings);
+}
+
+#[test]
+fn small_vec() {
+    let drops = Rc::new(Cell::new(0));
+    let mut vec: SmallVec<DropCounter, 4> = SmallVec::new();
+
+    for i in 0..4 {
+        vec.push(DropCounter(drops.clone(), i));
+    }
+    assert!(!vec.spilled());
+    vec.insert(2, DropCounter(drops.clone(), 4));
+    assert!(vec.spilled());
+    assert_eq!(vec.capacity(), 8);
+    assert_eq!(vec.remove(2).1, 4);
+    assert_eq!(vec.pop().unwrap().1, 3);
+    assert_eq!(drops.get(), 2);
+
+    let vec = vec.into_vec();
+    assert_eq!(vec.len(), 3);
+    let mut vec = SmallVec::<_, 4>::from(vec);
+    vec.drain().next();
+    assert_eq!(drops.get(), 5);
+
+    let mut inline: SmallVec<DropCounter, 4> = SmallVec::new();
+    inline.extend((0..3).map(|i| DropCounter(drops.clone(), i)));
+    let mut iter = inline.into_iter();
+    assert_eq!(iter.next_back().unwrap().1, 2);
+    drop(iter);
+    let mut inline: SmallVec<DropCounter, 4> = SmallVec::new();
+    inline.extend((0..3).map(|i| DropCounter(drops.clone(), i)));
+    let vec = inline.into_vec();
+    assert_eq!(vec[2].1, 2);
+    drop(vec);
+    assert_eq!(drops.get(), 11);
+
+    let mut strings: SmallVec<String, 1> = SmallVec::new();
+    strings.push(String::from("a"));
+    strings.push(String::from("b"));
+    strings.truncate(1);
+    assert_eq!(strings.into_iter().collect::<std::vec::Vec<_>>(), ["a"]);
+
+    let mut zsts: SmallVec<(), 0> = SmallVec::new();
+    zsts.extend([(), ()]);
+    assert!(zsts.spilled());
+    assert_eq!(zsts.into_iter().count(), 2);
+}
+
+#[test]
+fn bounded_vec() {
+    let mut vec = BoundedVec::new(5);
+    vec.extend((0..3).map(|i| i.to_string())).unwrap();
+    vec.insert(0, String::from("x")).unwrap();
+    let err = vec.extend((3..10).map(|i| i.to_string())).unwrap_err();
+    assert_eq!(err.element, "4");
+    assert!(vec.is_full());
+    assert_eq!(vec.capacity(), 5);
+    assert_eq!(vec.remove(0), "x");
+    assert_eq!(vec.pop().as_deref(), Some("3"));
+    assert_eq!(
+        vec.into_iter().collect::<std::vec::Vec<_>>(),
+        ["0", "1", "2"]
+    );
+}
+
+#[test]
+fn sorted_vec() {
+    let mut vec = SortedVec::new();
+
+    for i in [3, 1, 3, 2, 1, 3] {
+        vec.insert(Rc::new(i));
+    }
+    vec.extend([2, 0].map(Rc::new));
+    assert_eq!(vec.range(Rc::new(1)..Rc::new(3)).len(), 4);
+
+    vec.dedup();
+    assert!(vec.iter().map(|i| **i).eq(0..4));
+    assert_eq!(vec.insert_unique(Rc::new(2)), Err(2));
+
+    let shared = Rc::new(());
+    let mut pairs = SortedVec::from({
+        let mut vec = Vec::new();
+        vec.extend((0..6).map(|i| (i % 3, shared.clone())));
+        vec
+    });
+    pairs.dedup();
+    assert_eq!(pairs.len(), 3);
+    assert_eq!(Rc::strong_count(&shared), 4);
+    assert_eq!(pairs.remove(0).0, 0);
+    drop(pairs);
+    assert_eq!(Rc::strong_count(&shared), 1);
+}
+
+#[test]
+fn binary_heap() {
+    let mut heap = BinaryHeap::new();
+    for i in [5, 1, 8, 3, 9, 2, 7] {
+        heap.push(i.to_string());
+    }
+    assert_eq!(heap.pop().as_deref(), Some("9"));
+
+    *heap.peek_mut().unwrap() = String::from("0");
+    assert_eq!(heap.peek().map(String::as_str), Some("7"));
+
+    let sorted = heap.clone().into_sorted_vec();
+    assert_eq!(&*sorted, &["0", "1", "2", "3", "5", "7"]);
+
+    let mut heap = BinaryHeap::from(sorted);
+    assert_eq!(heap.pop().as_deref(), Some("7"));
+    assert_eq!(heap.len(), 5);
+}
+
+#[test]
+fn utf8_string() {
+    let mut s = vec::String::from("héllo");
+    s.insert(0, '¡');
+    s.insert_str(s.len(), " wörld");
+    s.push('!');
+    assert_eq!(s, "¡héllo wörld!");
+    assert_eq!(s.pop(), Some('!'));
+    s.truncate(8);
+    assert_eq!(s, "¡héllo");
+
+    let bytes = s.clone().into_bytes();
+    assert_eq!(vec::String::from_utf8(bytes).unwrap(), s);
+
+    let mut bytes = s.into_bytes();
+    bytes.truncate(1);
+    let err = vec::String::from_utf8(bytes).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 0);
+    assert_eq!(err.into_bytes().len(), 1);
+}
+
+#[test]
+fn small_string() {
+    let mut s = vec::SmallString::<8>::from("ab");
+    s.insert_str(1, "éé");
+    assert_eq!(s, "aééb");
+    assert!(!s.spilled());
+
+    s.insert_str(0, "spilled ");
+    assert!(s.spilled());
+    s.push('!');
+    assert_eq!(s, "spilled aééb!");
+    assert_eq!(s.pop(), Some('!'));
+    s.truncate(9);
+    assert_eq!(s.clone(), "spilled a");
+}
+
+#[test]
+fn gap_buffer() {
+    let drops = Rc::new(Cell::new(0));
+    let mut buf = GapBuffer::new();
+
+    // Grow while elements sit on both sides of the gap
+    for i in 0..4 {
+        buf.insert(DropCounter(drops.clone(), i));
+    }
+    buf.move_gap_to(1);
+    for i in 4..10 {
+        buf.insert(DropCounter(drops.clone(), i));
+    }
+    assert_eq!(buf.len(), 10);
+    assert_eq!(buf.as_slices().1.len(), 3);
+    assert!(buf.iter().map(|d| d.1).eq([0, 4, 5, 6, 7, 8, 9, 1, 2, 3]));
+
+    assert_eq!(buf.delete_backward().unwrap().1, 9);
+    assert_eq!(buf.delete_forward().unwrap().1, 1);
+    buf.move_gap_to(0);
+    buf.move_gap_to(buf.len());
+    assert_eq!(buf[7].1, 3);
+
+    let vec = Vec::from(buf);
+    assert_eq!(vec.len(), 8);
+    let mut buf = GapBuffer::from(vec);
+    buf.move_gap_to(4);
+    drop(buf);
+    assert_eq!(drops.get(), 10);
+
+    let mut buf = GapBuffer::with_capacity(2);
+    buf.insert_slice(&["a".to_string(), "b".to_string(), "c".to_string()]);
+    buf.move_gap_to(1);
+    buf.clear();
+    assert!(buf.is_empty());
+    buf.insert("d".to_string());
+}
+
+#[test]
+fn bi_vec() {
+    let drops = Rc::new(Cell::new(0));
+    let mut v = BiVec::new();
+
+    // Grow from both ends, re-centering in place and after reallocating
+    for i in 0..10 {
+        v.push_front(DropCounter(drops.clone(), i));
+        v.push_back(DropCounter(drops.clone(), 100 + i));
+    }
+    for _ in 0..5 {
+        v.pop_back();
+    }
+    for i in 10..20 {
+        v.push_front(DropCounter(drops.clone(), i));
+    }
+    assert_eq!(v.len(), 25);
+    assert_eq!(v[0].1, 19);
+    assert_eq!(v[24].1, 104);
+    assert_eq!(drops.get(), 5);
+
+    assert_eq!(v.pop_front().unwrap().1, 19);
+    v.truncate(20);
+    assert_eq!(drops.get(), 10);
+    let vec = Vec::from(v);
+    assert_eq!(vec[0].1, 18);
+    let mut v = BiVec::from(vec);
+    v.push_front(DropCounter(drops.clone(), 20));
+    drop(v);
+    assert_eq!(drops.get(), 31);
+
+    let mut v = BiVec::with_capacity(3);
+    v.extend(["a".to_string(), "b".to_string()]);
+    v.push_front("c".to_string());
+    v.clear();
+    v.push_back("d".to_string());
+    assert_eq!(v.clone(), v);
+
+    let mut zst = BiVec::new();
+    zst.push_front(());
+    zst.push_back(());
+    assert_eq!(zst.len(), 2);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn soa_vec() {
+    use vec::{Soa, SoaVec};
+
+    #[derive(Soa)]
+    struct Entity {
+        name: String,
+        health: DropCounter,
+        pos: (f32, f32),
+    }
+
+    let drops = Rc::new(Cell::new(0));
+    let mut entities = SoaVec::new();
+
+    for i in 0..10 {
+        entities.push(Entity {
+            name: i.to_string(),
+            health: DropCounter(drops.clone(), i),
+            pos: (i as f32, 0.0),
+        });
+    }
+    assert_eq!(entities.len(), 10);
+
+    let removed = entities.swap_remove(2);
+    assert_eq!(removed.name, "2");
+    drop(removed);
+    assert_eq!(entities.get(2).unwrap().health.1, 9);
+    assert_eq!(entities.pop().unwrap().name, "8");
+
+    for e in entities.iter_mut() {
+        e.pos.1 = e.health.1 as f32;
+    }
+    let slices = entities.slices();
+    assert_eq!(slices.name.len(), 8);
+    assert!(slices.pos.iter().all(|p| p.0 == p.1));
+    assert_eq!(drops.get(), 2);
+
+    drop(entities);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn segmented_vec() {
+    let drops = Rc::new(Cell::new(0));
+    let mut v = SegmentedVec::<DropCounter, 3>::new();
+
+    for i in 0..10 {
+        v.push(DropCounter(drops.clone(), i));
+    }
+    let first = &v[0] as *const DropCounter;
+    for i in 10..20 {
+        v.push(DropCounter(drops.clone(), i));
+    }
+    assert_eq!(&v[0] as *const DropCounter, first);
+    assert_eq!(v.capacity(), 21);
+    assert!(v.iter().map(|d| d.1).eq(0..20));
+
+    assert_eq!(v.pop().unwrap().1, 19);
+    v.iter_mut().for_each(|d| d.1 *= 2);
+    assert_eq!(v[18].1, 36);
+
+    v.clear();
+    assert_eq!(drops.get(), 20);
+    v.push(DropCounter(drops.clone(), 0));
+    drop(v);
+    assert_eq!(drops.get(), 21);
+
+    let mut zst = SegmentedVec::<(), 4>::new();
+    zst.extend(std::iter::repeat_n((), 9));
+    assert_eq!(zst.len(), 9);
+    assert_eq!(zst.pop(), Some(()));
+}
+
+#[test]
+fn append_only_vec() {
+    use std::{sync::Arc, thread};
+
+    let v = Arc::new(AppendOnlyVec::new());
+
+    let handles: std::vec::Vec<_> = (0..3)
+        .map(|t| {
+            let v = v.clone();
+            thread::spawn(move || {
+                for i in 0..40 {
+                    let index = v.push((t * 40 + i).to_string());
+                    assert_eq!(v[index], (t * 40 + i).to_string());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(v.len(), 120);
+    let mut all: std::vec::Vec<usize> = v.iter().map(|s| s.parse().unwrap()).collect();
+    all.sort();
+    assert!(all.into_iter().eq(0..120));
+    assert_eq!(v.get(120), None);
+}
+
+#[test]
+fn concurrent_vec() {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+    };
+
+    let v = ConcurrentVec::with_shards(4);
+
+    thread::scope(|s| {
+        for t in 0..3 {
+            let v = &v;
+            s.spawn(move || {
+                for i in 0..40 {
+                    let index = v.push((t * 40 + i).to_string());
+                    assert_eq!(*v.get(index).unwrap(), (t * 40 + i).to_string());
+                }
+            });
+        }
+    });
+    assert_eq!(v.len(), 120);
+
+    let visited = AtomicUsize::new(0);
+    v.par_for_each(|index, s| {
+        assert_eq!(*v.get(index).unwrap(), *s);
+        visited.fetch_add(1, Ordering::Relaxed);
+    });
+    assert_eq!(visited.into_inner(), 120);
+
+    let mut all: std::vec::Vec<usize> = v.into_vec().iter().map(|s| s.parse().unwrap()).collect();
+    all.sort();
+    assert!(all.into_iter().eq(0..120));
+}
+
+#[test]
+fn spsc_ring() {
+    use std::thread;
+
+    let (mut tx, mut rx) = vec::spsc_ring::<u32>(5);
+
+    let consumer = thread::spawn(move || {
+        let mut received = std::vec::Vec::new();
+        let mut block = [0; 3];
+        while received.len() < 100 {
+            let n = rx.read_into(&mut block);
+            received.extend_from_slice(&block[..n]);
+            received.extend(rx.pop());
+        }
+        received
+    });
+
+    let data: std::vec::Vec<u32> = (0..100).collect();
+    let mut sent = 0;
+    while sent < 100 {
+        sent += tx.write_from(&data[sent..(sent + 4).min(100)]);
+        if sent < 100 && tx.push(data[sent]).is_ok() {
+            sent += 1;
+        }
+    }
+    assert_eq!(consumer.join().unwrap(), data);
+
+    // Elements left in the ring are dropped with it
+    let counter = Rc::new(Cell::new(0));
+    let (mut tx, mut rx) = vec::spsc_ring(3);
+    for _ in 0..3 {
+        assert!(tx.push(DropCounter(counter.clone(), 0)).is_ok());
+    }
+    assert!(tx.push(DropCounter(counter.clone(), 0)).is_err());
+    assert_eq!(counter.get(), 1);
+    drop(rx.pop());
+    assert!(tx.push(DropCounter(counter.clone(), 0)).is_ok());
+    drop((tx, rx));
+    assert_eq!(counter.get(), 5);
+}
+
+#[test]
+fn any_vec() {
+    let counter = Rc::new(Cell::new(0));
+    let ty = AnyType::of_cloneable::<String>();
+    let mut v = AnyVec::new(ty);
+
+    for i in 0..20 {
+        v.push(i.to_string());
+    }
+    assert_eq!(v.get::<String>(19).map(|s| s.as_str()), Some("19"));
+
+    v.swap_remove(0);
+    assert_eq!(v.get::<String>(0).map(|s| s.as_str()), Some("19"));
+
+    let mut other = AnyVec::new(ty);
+    v.swap_remove_into(1, &mut other);
+    assert_eq!(other.downcast_ref::<String>().unwrap(), ["1"]);
+
+    let copy = v.try_clone().unwrap();
+    v.truncate(5);
+    assert_eq!(copy.len(), 18);
+    assert_eq!(v.pop::<String>().as_deref(), Some("4"));
+    assert!(v.downcast_ref::<u8>().is_none());
+
+    // Every element is dropped exactly once, whichever way it leaves
+    let mut drops = AnyVec::new(AnyType::of::<DropCounter>());
+    for i in 0..10 {
+        drops.push(DropCounter(counter.clone(), i));
+    }
+    drops.swap_remove(3);
+    drops.truncate(6);
+    assert_eq!(counter.get(), 4);
+    drop(drops);
+    assert_eq!(counter.get(), 10);
+
+    // Zero-sized and over-aligned element types
+    let mut units = AnyVec::new(AnyType::of::<()>());
+    for _ in 0..100 {
+        units.push(());
+    }
+    units.swap_remove(50);
+    assert_eq!(units.len(), 99);
+
+    #[repr(align(64))]
+    struct Aligned(u8);
+    let mut aligned = AnyVec::new(AnyType::of::<Aligned>());
+    aligned.push(Aligned(1));
+    aligned.push(Aligned(2));
+    assert_eq!(aligned.as_ptr() as usize % 64, 0);
+    assert_eq!(aligned.get::<Aligned>(1).unwrap().0, 2);
+}
+
+#[test]
+fn unsized_vec() {
+    use std::fmt::Debug;
+
+    #[derive(Debug)]
+    #[repr(align(32))]
+    struct Aligned(u8);
+
+    let counter = Rc::new(Cell::new(0));
+    let mut v: UnsizedVec<dyn Debug> = UnsizedVec::new();
+
+    // Zero-sized values first, then mixed sizes and alignments forcing the buffer to grow and realign
+    v.push_unsize(());
+    for i in 0..20 {
+        v.push_unsize(i as u8);
+        v.push_unsize(i.to_string());
+        v.push_unsize(Aligned(i as u8));
+        v.push_unsize([i as u64; 3]);
+        v.push_unsize(DropCounter(counter.clone(), i));
+    }
+    assert_eq!(v.len(), 101);
+    assert_eq!(format!("{:?}", &v[3]), "Aligned(0)");
+    assert_eq!(format!("{:?}", &v[97]), "\"19\"");
+    assert_eq!(&v[98] as *const dyn Debug as *const u8 as usize % 32, 0);
+
+    v.truncate(50);
+    assert_eq!(counter.get(), 11);
+    drop(v);
+    assert_eq!(counter.get(), 20);
+
+    let mut strings: UnsizedVec<str> = UnsizedVec::new();
+    for i in 0..50 {
+        strings.push_str(&i.to_string());
+    }
+    assert_eq!(strings.iter().next_back(), Some("49"));
+    assert_eq!(strings.byte_len(), 90);
+
+    let mut slices: UnsizedVec<[u32]> = UnsizedVec::new();
+    slices.push_slice(&[]);
+    slices.push_unsize([1, 2, 3]);
+    slices.iter_mut().for_each(|s| s.reverse());
+    assert_eq!(&slices[1], &[3, 2, 1]);
+
+    // Over-aligned elements behind a slice pointer keep their values and alignment
+    let mut aligned: UnsizedVec<[Aligned]> = UnsizedVec::new();
+    aligned.push_unsize([Aligned(7)]);
+    aligned.push_unsize([Aligned(8), Aligned(9)]);
+    assert_eq!(aligned[1][1].0, 9);
+    assert_eq!(aligned[1].as_ptr() as usize % 32, 0);
+}
+
+#[test]
+fn c_vec() {
+    use std::{ffi::c_void, mem, ptr};
+
+    extern "C" {
+        fn malloc(size: usize) -> *mut c_void;
+        fn free(ptr: *mut c_void);
+    }
+
+    // Adopts `n` counters written into a malloc'd buffer
+    fn adopt(n: usize, counter: &Rc<Cell<usize>>) -> CVec<DropCounter> {
+        unsafe {
+            let ptr = malloc(n * mem::size_of::<DropCounter>()) as *mut DropCounter;
+            for i in 0..n {
+                ptr.add(i).write(DropCounter(counter.clone(), i));
+            }
+            CVec::new(ptr, n, free)
+        }
+    }
+
+    let counter = Rc::new(Cell::new(0));
+    let mut v = adopt(10, &counter);
+    v.swap(0, 9);
+    assert_eq!(v[0].1, 9);
+    drop(v);
+    assert_eq!(counter.get(), 10);
+
+    let vec = adopt(10, &counter).try_into_vec().unwrap();
+    assert_eq!(counter.get(), 10);
+    assert_eq!(vec[9].1, 9);
+    drop(vec);
+    assert_eq!(counter.get(), 20);
+
+    let empty = unsafe { CVec::<u64>::new(ptr::null_mut(), 0, free) };
+    assert_eq!(&*empty, &[]);
+    assert!(empty.try_into_vec().unwrap().is_empty());
+}
+
+#[test]
+fn frozen_vec() {
+    let v = FrozenVec::new();
+    let mut held = std::vec::Vec::new();
+
+    // Keep references to early elements while pushing enough to add chunks and grow the chunk table
+    for i in 0..200 {
+        held.push(v.push_get(i.to_string()));
+    }
+    assert!(held.iter().enumerate().all(|(i, s)| **s == i.to_string()));
+
+    let mut seen = 0;
+    for s in v.iter() {
+        if seen < 5 {
+            v.push(s.clone());
+        }
+        seen += 1;
+    }
+    assert_eq!(seen, 205);
+    assert_eq!(v[202], "2");
+
+    let mut v = v;
+    v.get_mut(0).unwrap().push('!');
+    assert_eq!(v.into_inner()[0], "0!");
+}
+
+#[test]
+fn equality() {
+    let a: Vec<u32> = (0..100).collect();
+    let mut b = a.clone();
+    assert_eq!(a, b);
+    b[99] = 0;
+    assert_ne!(a, b);
+    b.pop();
+    assert_ne!(a, b);
+
+    // Equality of the elements, not of their bytes
+    let mut nan = Vec::new();
+    nan.push(f64::NAN);
+    assert_ne!(nan, nan.clone());
+
+    let mut zero = Vec::new();
+    zero.push(0.0);
+    let mut negative_zero = Vec::new();
+    negative_zero.push(-0.0);
+    assert_eq!(zero, negative_zero);
+}
@@ -0,0 +1,177 @@
+//! Zero-sized elements never allocate and report a capacity of `usize::MAX`, every operation must cope with that.
+use std::{cell::Cell, mem};
+
+use vec::{TryReserveError, Vec};
+
+thread_local! {
+    // Every test runs on its own thread, so each one gets its own counter
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn drops() -> usize {
+    DROPS.with(Cell::get)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Unit;
+
+// Zero-sized type counting its drops
+#[derive(Clone)]
+struct Counted;
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+    }
+}
+
+#[test]
+fn capacity_is_unbounded_and_nothing_allocates() {
+    let mut vec = Vec::new();
+    assert_eq!(vec.capacity(), usize::MAX);
+
+    vec.extend(std::iter::repeat_n(Unit, 1000));
+    assert_eq!(vec.len(), 1000);
+    assert_eq!(vec.capacity(), usize::MAX);
+
+    vec.shrink_to_fit();
+    assert_eq!(vec.capacity(), usize::MAX);
+
+    let vec: Vec<Unit> = Vec::with_capacity(10);
+    assert_eq!(vec.capacity(), usize::MAX);
+}
+
+#[test]
+fn push_pop_insert_remove() {
+    let mut vec = Vec::new();
+
+    vec.push(Unit);
+    vec.insert(0, Unit);
+    vec.insert(2, Unit);
+    vec.insert(1, Unit);
+    assert_eq!(vec.len(), 4);
+
+    assert_eq!(vec.remove(3), Unit);
+    assert_eq!(vec.remove(0), Unit);
+    assert_eq!(vec.pop(), Some(Unit));
+    assert_eq!(vec.pop(), Some(Unit));
+    assert_eq!(vec.pop(), None);
+}
+
+#[test]
+fn try_push_at_max_length_fails() {
+    let mut vec = Vec::new();
+
+    unsafe { vec.set_len(usize::MAX) };
+
+    assert_eq!(vec.try_push(Unit), Err(TryReserveError::CapacityOverflow));
+    assert_eq!(
+        vec.try_insert(0, Unit),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    assert_eq!(vec.len(), usize::MAX);
+
+    unsafe { vec.set_len(0) };
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn push_at_max_length_panics() {
+    let mut vec = Vec::new();
+
+    unsafe { vec.set_len(usize::MAX) };
+
+    // Unit has no destructor, so leaking the fake elements during unwinding is harmless
+    vec.push(Unit);
+}
+
+#[test]
+fn drops_are_counted() {
+    assert_eq!(mem::size_of::<Counted>(), 0);
+
+    let mut vec = Vec::new();
+
+    for _ in 0..10 {
+        vec.push(Counted);
+    }
+
+    drop(vec.remove(4));
+    assert_eq!(drops(), 1);
+
+    vec.truncate(5);
+    assert_eq!(drops(), 5);
+
+    drop(vec);
+    assert_eq!(drops(), 10);
+}
+
+#[test]
+fn into_iter_and_drain() {
+    let mut vec = Vec::new();
+    vec.extend(std::iter::repeat_n(Counted, 6));
+
+    let mut drain = vec.drain();
+    assert_eq!(drain.size_hint(), (6, Some(6)));
+    drop(drain.next());
+    drop(drain.next_back());
+    assert_eq!(drain.size_hint(), (4, Some(4)));
+    drop(drain);
+    assert_eq!(drops(), 6);
+    assert_eq!(vec.len(), 0);
+
+    vec.extend(std::iter::repeat_n(Counted, 6));
+
+    let mut iter = vec.into_iter();
+    drop(iter.next_back());
+    assert_eq!(iter.size_hint(), (5, Some(5)));
+    drop(iter);
+    assert_eq!(drops(), 12);
+}
+
+#[test]
+fn clone_and_clear() {
+    let mut vec = Vec::new();
+    vec.extend(std::iter::repeat_n(Counted, 3));
+    // The element passed to repeat_n is moved into the vector, not dropped
+    assert_eq!(drops(), 0);
+
+    let copy = vec.clone();
+    assert_eq!(copy.len(), 3);
+
+    vec.clear();
+    assert_eq!(drops(), 3);
+
+    drop(copy);
+    assert_eq!(drops(), 6);
+}
+
+#[test]
+fn deref_as_slice() {
+    let mut vec = Vec::new();
+    vec.extend(std::iter::repeat_n(Unit, 5));
+
+    assert_eq!(vec.iter().count(), 5);
+    assert_eq!(vec.iter_mut().count(), 5);
+    assert_eq!(&vec[1..3], &[Unit, Unit]);
+}
+
+#[test]
+fn cursor_edits() {
+    let mut vec = Vec::new();
+    vec.extend(std::iter::repeat_n(Counted, 2));
+
+    let mut cursor = vec.cursor_mut(1);
+    for _ in 0..4 {
+        cursor.insert_before(Counted);
+    }
+    drop(cursor.remove_current());
+    cursor.move_prev();
+    assert!(cursor.current().is_some());
+    assert_eq!((cursor.index(), cursor.len()), (4, 5));
+    drop(cursor);
+
+    assert_eq!(drops(), 1);
+    assert_eq!(vec.len(), 5);
+    drop(vec);
+    assert_eq!(drops(), 6);
+}